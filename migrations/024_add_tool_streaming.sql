@@ -0,0 +1,3 @@
+-- Mark tools whose upstream response is streamed (SSE, NDJSON, chunked text)
+-- Streaming tools are consumed incrementally and report MCP progress notifications
+ALTER TABLE tools ADD COLUMN streaming BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,9 @@
+-- Multiple upstream base URLs with failover / load balancing
+-- JSON: {"base_urls": [...], "strategy": "primary-failover"|"round-robin"|"latency-based",
+--        "failover_status_codes": [502, 503, 504]}
+-- A tool without its own configuration inherits the toolkit's.
+ALTER TABLE tools ADD COLUMN endpoints TEXT;
+ALTER TABLE toolkits ADD COLUMN endpoints TEXT;
+
+-- Base URL actually used for an execution
+ALTER TABLE execution_history ADD COLUMN endpoint TEXT;
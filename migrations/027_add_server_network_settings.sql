@@ -0,0 +1,12 @@
+-- Per-server egress settings for outbound tool requests
+-- proxy_url, client_cert and client_key are encrypted with the master key
+-- (proxy URLs may carry credentials); ca_bundle holds PEM root certificates.
+CREATE TABLE IF NOT EXISTS server_network_settings (
+    server_id INTEGER PRIMARY KEY REFERENCES servers(id) ON DELETE CASCADE,
+    proxy_url TEXT,
+    ca_bundle TEXT,
+    client_cert TEXT,
+    client_key TEXT,
+    created_at INTEGER DEFAULT (unixepoch()),
+    updated_at INTEGER DEFAULT (unixepoch())
+);
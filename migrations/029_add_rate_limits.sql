@@ -0,0 +1,58 @@
+-- Token-bucket rate limits
+-- JSON: {"requests": 10, "period_seconds": 1, "burst": 20,
+--        "mode": "wait"|"reject", "max_wait_ms": 30000}
+-- Tool limits apply to the tool, toolkit limits to each upstream host the
+-- toolkit's tools call, server limits to every call made through the server.
+ALTER TABLE tools ADD COLUMN rate_limit TEXT;
+ALTER TABLE toolkits ADD COLUMN rate_limit TEXT;
+ALTER TABLE server_network_settings ADD COLUMN rate_limit TEXT;
+
+-- Allow 'throttled' as an execution status for calls refused by a rate limit,
+-- and record how long a call waited for its rate limits.
+-- SQLite cannot alter a CHECK constraint, so the table is rebuilt.
+
+CREATE TABLE execution_history_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INTEGER NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    instance_id INTEGER NOT NULL REFERENCES tool_instances(id) ON DELETE CASCADE,
+    tool_id INTEGER NOT NULL REFERENCES tools(id) ON DELETE CASCADE,
+
+    -- Execution timing
+    started_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TEXT,
+    duration_ms INTEGER,
+
+    -- Execution result
+    status TEXT NOT NULL CHECK (status IN ('success', 'error', 'timeout', 'cancelled', 'blocked', 'throttled')),
+    http_status_code INTEGER,
+    error_message TEXT,
+
+    -- Input/Output data
+    input_params TEXT,  -- JSON string of input parameters
+    response_body TEXT,  -- HTTP response body
+    response_headers TEXT,  -- JSON string of response headers
+
+    -- Optional detailed logging
+    request_url TEXT,
+    request_method TEXT,
+    response_size_bytes INTEGER,
+
+    -- Metadata
+    transport TEXT CHECK (transport IN ('http', 'sse')),
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    endpoint TEXT,
+    throttled_ms INTEGER
+);
+
+INSERT INTO execution_history_new
+SELECT *, NULL FROM execution_history;
+
+DROP TABLE execution_history;
+
+ALTER TABLE execution_history_new RENAME TO execution_history;
+
+CREATE INDEX idx_execution_history_server_id ON execution_history(server_id);
+CREATE INDEX idx_execution_history_instance_id ON execution_history(instance_id);
+CREATE INDEX idx_execution_history_started_at ON execution_history(started_at);
+CREATE INDEX idx_execution_history_status ON execution_history(status);
+CREATE INDEX idx_execution_history_tool_id ON execution_history(tool_id);
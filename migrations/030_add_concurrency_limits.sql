@@ -0,0 +1,10 @@
+-- Concurrency limits
+-- JSON: {"max_concurrent": 4, "max_queue": 10, "queue_timeout_ms": 10000}
+-- Server limits bound every call made through the server, instance limits
+-- the calls of one tool instance.
+ALTER TABLE server_network_settings ADD COLUMN concurrency_limit TEXT;
+ALTER TABLE tool_instances ADD COLUMN concurrency_limit TEXT;
+
+-- Time a call spent waiting for a concurrency slot, kept apart from the
+-- upstream latency
+ALTER TABLE execution_history ADD COLUMN queued_ms INTEGER;
@@ -0,0 +1,5 @@
+-- Documentation of tool parameters, shown to MCP clients in input schemas
+-- JSON: {"status": {"description": "Order status", "enum": ["open", "closed"]}}
+-- Keyed by parameter name; parameters without an entry keep the generic
+-- description.
+ALTER TABLE tools ADD COLUMN parameter_metadata TEXT;
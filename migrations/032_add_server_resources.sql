@@ -0,0 +1,19 @@
+-- MCP resources of a server
+-- A resource is either a static document (content set) or a GET tool
+-- instance published for clients to read without a tool call (instance_id
+-- set). Instances with exposed parameters are listed as resource templates.
+CREATE TABLE IF NOT EXISTS server_resources (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INTEGER NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    mime_type TEXT NOT NULL DEFAULT 'text/markdown',
+    content TEXT,
+    instance_id INTEGER REFERENCES tool_instances(id) ON DELETE CASCADE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(server_id, name),
+    CHECK ((content IS NULL) <> (instance_id IS NULL))
+);
+
+CREATE INDEX idx_server_resources_server_id ON server_resources(server_id);
@@ -0,0 +1,17 @@
+-- MCP prompts of a server
+-- The template is a single user message with {{type:name}} placeholders.
+-- Placeholders naming a plain server global are filled from it, the others
+-- are prompt arguments documented in `arguments` (JSON keyed by name).
+CREATE TABLE IF NOT EXISTS server_prompts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INTEGER NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    template TEXT NOT NULL,
+    arguments TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(server_id, name)
+);
+
+CREATE INDEX idx_server_prompts_server_id ON server_prompts(server_id);
@@ -0,0 +1,54 @@
+-- Tools with the MCP and MCP-SSE methods forward their calls to a tool of a
+-- remote MCP server. SQLite cannot change a CHECK constraint in place, so
+-- the table is rebuilt with foreign keys off: dropping it would otherwise
+-- cascade to the instances and execution history of every tool.
+--
+-- Foreign keys cannot be switched off inside a transaction, so the one sqlx
+-- runs migrations in is committed first and reopened at the end, for sqlx
+-- to record the migration in.
+COMMIT;
+
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+CREATE TABLE tools_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    toolkit_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    method VARCHAR(10) DEFAULT 'GET',
+    url TEXT,
+    headers TEXT,
+    body TEXT,
+    timeout_ms INTEGER DEFAULT 30000,
+    created_at INTEGER DEFAULT (unixepoch()),
+    updated_at INTEGER DEFAULT (unixepoch()),
+    streaming BOOLEAN NOT NULL DEFAULT FALSE,
+    endpoints TEXT,
+    rate_limit TEXT,
+    parameter_metadata TEXT,
+    FOREIGN KEY (toolkit_id) REFERENCES toolkits(id) ON DELETE CASCADE,
+    CHECK (method IN ('GET', 'POST', 'PUT', 'DELETE', 'PATCH', 'MCP', 'MCP-SSE'))
+);
+
+INSERT INTO tools_new (
+    id, toolkit_id, name, description, method, url, headers, body, timeout_ms,
+    created_at, updated_at, streaming, endpoints, rate_limit, parameter_metadata
+)
+SELECT
+    id, toolkit_id, name, description, method, url, headers, body, timeout_ms,
+    created_at, updated_at, streaming, endpoints, rate_limit, parameter_metadata
+FROM tools;
+
+DROP TABLE tools;
+ALTER TABLE tools_new RENAME TO tools;
+
+CREATE INDEX idx_tools_toolkit_id ON tools(toolkit_id);
+CREATE UNIQUE INDEX idx_tools_toolkit_name ON tools(toolkit_id, name);
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
+
+BEGIN;
@@ -0,0 +1,4 @@
+-- Bumped whenever a configuration document is applied to a server, the
+-- command line included. The web process polls it to reload servers whose
+-- configuration changed outside of it.
+ALTER TABLE servers ADD COLUMN config_version INTEGER NOT NULL DEFAULT 0;
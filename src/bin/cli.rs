@@ -1,14 +1,20 @@
 use clap::{Parser, Subcommand};
 use saramcp::{
     db,
+    models::{Server, ServerNetworkSettings},
     repositories::user_repository::SqliteUserRepository,
+    repositories::{SqliteToolRepository, SqliteToolkitRepository},
     services::user_service::{CreateUserRequest, UpdatePasswordRequest, UserService},
+    services::{
+        tool_service::ToolService, toolkit_service::ToolkitService, BundleTarget, InstanceService,
+        SecretsManager, ServerConfig, ServerService, ToolCatalog, ToolkitBundle,
+    },
 };
 use std::sync::Arc;
 
 #[derive(Parser)]
 #[command(name = "saramcp-cli")]
-#[command(about = "CLI tool for managing SaraMCP users", long_about = None)]
+#[command(about = "CLI tool for managing SaraMCP users, servers and toolkits", long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -21,6 +27,116 @@ enum Commands {
         #[command(subcommand)]
         command: UserCommands,
     },
+
+    /// Server management commands
+    Server {
+        #[command(subcommand)]
+        command: ServerCommands,
+    },
+
+    /// Toolkit bundle export and import
+    Toolkit {
+        #[command(subcommand)]
+        command: ToolkitCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ToolkitCommands {
+    /// Print a toolkit as a versioned bundle
+    Export {
+        /// Email address of the toolkit owner
+        #[arg(short, long)]
+        email: String,
+
+        /// Toolkit ID
+        #[arg(short, long)]
+        id: i64,
+
+        /// Output format: yaml or json
+        #[arg(short, long, default_value = "yaml")]
+        format: String,
+    },
+
+    /// Import a bundle as a new toolkit or into an existing one
+    Import {
+        /// Email address of the user who will own the tools
+        #[arg(short, long)]
+        email: String,
+
+        /// Bundle file (JSON or YAML)
+        #[arg(short, long)]
+        file: String,
+
+        /// Merge into this toolkit instead of creating a new one
+        #[arg(long)]
+        into: Option<i64>,
+
+        /// Replace tools that already exist when merging
+        #[arg(long)]
+        replace: bool,
+
+        /// Title of the new toolkit (defaults to the bundle's)
+        #[arg(short, long)]
+        title: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ServerCommands {
+    /// Print the configuration of a server as YAML
+    ///
+    /// Secret globals are listed by name only.
+    Export {
+        /// Server UUID
+        #[arg(short, long)]
+        server: String,
+    },
+
+    /// Reconcile a server to a configuration document
+    ///
+    /// The server is the one given by --server, else the user's server with
+    /// the document's name, which is created if it does not exist.
+    Apply {
+        /// Email address of the server owner
+        #[arg(short, long)]
+        email: String,
+
+        /// Configuration file (YAML or JSON)
+        #[arg(short, long)]
+        file: String,
+
+        /// Server UUID
+        #[arg(short, long)]
+        server: Option<String>,
+
+        /// Only print the changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show or replace the egress host rules of a server
+    ///
+    /// Rules are host names, *.domain wildcards, IP addresses or CIDR ranges.
+    /// Allowed hosts may be reached even on private networks; denied hosts
+    /// are always blocked. Without options the current rules are shown.
+    Egress {
+        /// Server UUID
+        #[arg(short, long)]
+        server: String,
+
+        /// Host rule to allow (repeatable)
+        #[arg(long)]
+        allow: Vec<String>,
+
+        /// Host rule to deny (repeatable)
+        #[arg(long)]
+        deny: Vec<String>,
+
+        /// Remove all rules of the server
+        #[arg(long)]
+        clear: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -259,6 +375,285 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         },
+
+        Commands::Toolkit { command } => {
+            let toolkit_repository = Arc::new(SqliteToolkitRepository::new(pool.clone()));
+            let tool_repository = Arc::new(SqliteToolRepository::new(pool.clone()));
+            let toolkit_service =
+                ToolkitService::new(toolkit_repository.clone(), tool_repository.clone());
+            let tool_service = ToolService::new(tool_repository, toolkit_repository);
+
+            let email = match &command {
+                ToolkitCommands::Export { email, .. } | ToolkitCommands::Import { email, .. } => {
+                    email
+                }
+            };
+            let user = match user_service.find_user_by_email(email).await {
+                Ok(Some(user)) => user,
+                Ok(None) => {
+                    eprintln!("❌ User '{}' not found", email);
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("❌ Failed to find user: {}", err);
+                    std::process::exit(1);
+                }
+            };
+
+            match command {
+                ToolkitCommands::Export { id, format, .. } => {
+                    let bundle = match toolkit_service.get_toolkit(id, user.id).await {
+                        Ok(toolkit) => match tool_service.list_tools(id, user.id).await {
+                            Ok(tools) => ToolkitBundle::from_toolkit(&toolkit, &tools),
+                            Err(err) => {
+                                eprintln!("❌ Failed to list tools: {}", err);
+                                std::process::exit(1);
+                            }
+                        },
+                        Err(err) => {
+                            eprintln!("❌ Failed to find toolkit: {}", err);
+                            std::process::exit(1);
+                        }
+                    };
+
+                    let document = match format.as_str() {
+                        "json" => bundle.to_json(),
+                        "yaml" | "yml" => bundle.to_yaml(),
+                        other => {
+                            eprintln!("❌ Unknown format '{}', use yaml or json", other);
+                            std::process::exit(1);
+                        }
+                    };
+                    match document {
+                        Ok(document) => println!("{}", document.trim_end()),
+                        Err(err) => {
+                            eprintln!("❌ {}", err);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+
+                ToolkitCommands::Import {
+                    file,
+                    into,
+                    replace,
+                    title,
+                    ..
+                } => {
+                    let text = std::fs::read_to_string(&file)?;
+                    let bundle = match ToolkitBundle::parse(&text) {
+                        Ok(bundle) => bundle,
+                        Err(err) => {
+                            eprintln!("❌ {}", err);
+                            std::process::exit(1);
+                        }
+                    };
+
+                    let target = match into {
+                        Some(toolkit_id) => BundleTarget::Merge {
+                            toolkit_id,
+                            replace,
+                        },
+                        None => BundleTarget::NewToolkit {
+                            title: title.unwrap_or_default(),
+                        },
+                    };
+
+                    match bundle
+                        .import(&toolkit_service, &tool_service, user.id, &target)
+                        .await
+                    {
+                        Ok(summary) => {
+                            println!("✅ Bundle imported into toolkit {}!", summary.toolkit_id);
+                            println!("  Created: {}", summary.created.len());
+                            println!("  Replaced: {}", summary.replaced.len());
+                            if !summary.skipped.is_empty() {
+                                println!(
+                                    "  Skipped (already exist): {}",
+                                    summary.skipped.join(", ")
+                                );
+                            }
+                        }
+                        Err(err) => {
+                            eprintln!("❌ Failed to import bundle: {}", err);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+            }
+        }
+
+        Commands::Server { command } => match command {
+            ServerCommands::Export { server } => {
+                let server = match Server::get_by_uuid(&pool, &server).await {
+                    Ok(Some(server)) => server,
+                    Ok(None) => {
+                        eprintln!("❌ Server '{}' not found", server);
+                        std::process::exit(1);
+                    }
+                    Err(err) => {
+                        eprintln!("❌ Failed to find server: {}", err);
+                        std::process::exit(1);
+                    }
+                };
+
+                let server_service = ServerService::new(pool.clone(), SecretsManager::new()?);
+                let instance_service = InstanceService::new(pool.clone(), SecretsManager::new()?);
+                let config = ServerConfig::export(
+                    &server_service,
+                    &instance_service,
+                    server.id.unwrap_or_default(),
+                    server.user_id,
+                )
+                .await?;
+                println!("{}", config.to_yaml()?.trim_end());
+            }
+
+            ServerCommands::Apply {
+                email,
+                file,
+                server,
+                dry_run,
+            } => {
+                let user = match user_service.find_user_by_email(&email).await {
+                    Ok(Some(user)) => user,
+                    Ok(None) => {
+                        eprintln!("❌ User '{}' not found", email);
+                        std::process::exit(1);
+                    }
+                    Err(err) => {
+                        eprintln!("❌ Failed to find user: {}", err);
+                        std::process::exit(1);
+                    }
+                };
+
+                let desired = match ServerConfig::parse(&std::fs::read_to_string(&file)?) {
+                    Ok(desired) => desired,
+                    Err(err) => {
+                        eprintln!("❌ {}", err);
+                        std::process::exit(1);
+                    }
+                };
+
+                let target = match server {
+                    Some(uuid) => match Server::get_by_uuid(&pool, &uuid).await? {
+                        Some(server) if server.user_id == user.id => Some(server),
+                        _ => {
+                            eprintln!("❌ Server '{}' not found", uuid);
+                            std::process::exit(1);
+                        }
+                    },
+                    None => Server::list_by_user(&pool, user.id)
+                        .await?
+                        .into_iter()
+                        .find(|s| s.name == desired.server.name),
+                };
+                let server_id = target.and_then(|s| s.id);
+
+                let server_service = ServerService::new(pool.clone(), SecretsManager::new()?);
+                let instance_service = InstanceService::new(pool.clone(), SecretsManager::new()?);
+                let toolkit_repository = Arc::new(SqliteToolkitRepository::new(pool.clone()));
+                let tool_repository = Arc::new(SqliteToolRepository::new(pool.clone()));
+                let toolkit_service =
+                    ToolkitService::new(toolkit_repository.clone(), tool_repository.clone());
+                let tool_service = ToolService::new(tool_repository, toolkit_repository);
+
+                let current = match server_id {
+                    Some(server_id) => Some(
+                        ServerConfig::export(
+                            &server_service,
+                            &instance_service,
+                            server_id,
+                            user.id,
+                        )
+                        .await?,
+                    ),
+                    None => None,
+                };
+                let catalog = ToolCatalog::load(&toolkit_service, &tool_service, user.id).await?;
+                let plan = desired.plan(current.as_ref(), &catalog);
+
+                for line in plan.diff() {
+                    println!("{}", line);
+                }
+                for warning in &plan.warnings {
+                    println!("ℹ️  {}", warning);
+                }
+                if !plan.is_valid() {
+                    for error in &plan.errors {
+                        eprintln!("❌ {}", error);
+                    }
+                    std::process::exit(1);
+                }
+                if plan.changes.is_empty() {
+                    println!(
+                        "✅ Server '{}' already matches the configuration",
+                        desired.server.name
+                    );
+                } else if dry_run {
+                    println!("{} change(s) not applied (dry run)", plan.changes.len());
+                } else {
+                    match plan.apply(&pool, user.id, server_id).await {
+                        Ok(_) => {
+                            println!(
+                                "✅ {} change(s) applied to '{}'!",
+                                plan.changes.len(),
+                                desired.server.name
+                            );
+                            // The registry lives in the web process, which picks
+                            // up the bumped configuration version on its own
+                            println!("ℹ️  A running SaraMCP reloads the server's MCP tools within a few seconds");
+                        }
+                        Err(err) => {
+                            eprintln!("❌ Failed to apply configuration: {}", err);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+            }
+
+            ServerCommands::Egress {
+                server,
+                allow,
+                deny,
+                clear,
+            } => {
+                let server = match Server::get_by_uuid(&pool, &server).await {
+                    Ok(Some(server)) => server,
+                    Ok(None) => {
+                        eprintln!("❌ Server '{}' not found", server);
+                        std::process::exit(1);
+                    }
+                    Err(err) => {
+                        eprintln!("❌ Failed to find server: {}", err);
+                        std::process::exit(1);
+                    }
+                };
+                let server_id = server.id.unwrap_or_default();
+
+                if clear || !allow.is_empty() || !deny.is_empty() {
+                    let server_service = ServerService::new(pool.clone(), SecretsManager::new()?);
+                    if let Err(err) = server_service
+                        .set_egress_host_rules(server_id, &allow, &deny)
+                        .await
+                    {
+                        eprintln!("❌ Failed to update egress rules: {}", err);
+                        std::process::exit(1);
+                    }
+                    println!("✅ Egress rules of '{}' updated!", server.name);
+                }
+
+                let settings = ServerNetworkSettings::get(&pool, server_id).await?;
+                let rules = |text: Option<String>| text.unwrap_or_else(|| "(none)".to_string());
+                println!("Allowed hosts:");
+                println!(
+                    "{}",
+                    rules(settings.as_ref().and_then(|s| s.allowed_hosts.clone()))
+                );
+                println!("Denied hosts:");
+                println!("{}", rules(settings.and_then(|s| s.denied_hosts)));
+            }
+        },
     }
 
     Ok(())
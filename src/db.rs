@@ -5,6 +5,16 @@ pub async fn create_pool() -> Result<SqlitePool, sqlx::Error> {
     let database_url = env::var("DATABASE_URL")
         .map_err(|_| sqlx::Error::Configuration("DATABASE_URL must be set".into()))?;
 
+    create_pool_at(&database_url).await
+}
+
+/// Connects to the database at a `sqlite://` URL or plain file path
+pub async fn create_pool_at(database_url: &str) -> Result<SqlitePool, sqlx::Error> {
+    let database_url = match database_url.starts_with("sqlite:") {
+        true => database_url.to_string(),
+        false => format!("sqlite://{}", database_url),
+    };
+
     // Ensure the data directory exists
     if let Some(parent) = std::path::Path::new(&database_url.replace("sqlite://", "")).parent() {
         std::fs::create_dir_all(parent).ok();
@@ -1,5 +1,8 @@
 use crate::models::ToolUsageStats;
-use crate::services::{DashboardService, DashboardStats, RecentExecution, ServerStats};
+use crate::services::{
+    CircuitBreakerRegistry, CircuitSnapshot, CircuitState, DashboardService, DashboardStats,
+    RecentExecution, ServerStats,
+};
 use crate::AppState;
 use askama::Template;
 use askama_web::WebTemplate;
@@ -14,6 +17,7 @@ struct DashboardTemplate {
     server_stats: Vec<ServerStats>,
     most_used_tools: Vec<ToolUsageStats>,
     recent_executions: Vec<RecentExecution>,
+    circuits: Vec<CircuitSnapshot>,
 }
 
 /// GET /dashboard - Show user dashboard
@@ -58,6 +62,14 @@ pub async fn dashboard_handler(
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    // Upstreams currently short-circuited for any of the user's servers
+    let server_ids: Vec<i64> = server_stats.iter().map(|s| s.server_id).collect();
+    let circuits = CircuitBreakerRegistry::shared()
+        .snapshots_for_servers(&server_ids)
+        .into_iter()
+        .filter(|circuit| circuit.state != CircuitState::Closed)
+        .collect();
+
     // Render template
     let template = DashboardTemplate {
         user_email,
@@ -65,6 +77,7 @@ pub async fn dashboard_handler(
         server_stats,
         most_used_tools,
         recent_executions,
+        circuits,
     };
 
     Ok(template.into_response())
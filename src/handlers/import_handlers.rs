@@ -0,0 +1,790 @@
+use crate::error::AppError;
+use crate::handlers::instance_handlers::reload_server_tools;
+use crate::models::{ConfigureInstanceForm, Server, ServerSummary, Toolkit};
+use crate::services::{
+    har_import, mcp_import, openapi_import, postman_import, BundlePlan, BundleTarget,
+    ImportOptions, McpSource, McpTransport, ToolImport, ToolkitBundle,
+};
+use crate::AppState;
+use askama::Template;
+use askama_web::WebTemplate;
+use axum::{
+    extract::{Path, State},
+    response::{Html, IntoResponse, Redirect, Response},
+    Form,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+/// Formats accepted by the import page, as (value, label)
+const IMPORT_FORMATS: [(&str, &str); 3] = [
+    ("openapi", "OpenAPI 3.0 / 3.1 (JSON or YAML)"),
+    ("postman", "Postman Collection v2.1 (JSON)"),
+    ("har", "HAR browser recording (JSON)"),
+];
+
+#[derive(Template, WebTemplate)]
+#[template(path = "toolkits/import.html")]
+struct ImportTemplate {
+    user_email: String,
+    csrf_token: String,
+    error: Option<String>,
+    formats: Vec<(&'static str, &'static str)>,
+    form: ImportForm,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "toolkits/import_preview.html")]
+struct ImportPreviewTemplate {
+    user_email: String,
+    csrf_token: String,
+    error: Option<String>,
+    form: ImportForm,
+    import: ToolImport,
+    servers: Vec<ServerSummary>,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "toolkits/import_bundle.html")]
+struct ImportBundleTemplate {
+    user_email: String,
+    csrf_token: String,
+    error: Option<String>,
+    form: BundleForm,
+    toolkits: Vec<Toolkit>,
+    plan: Option<BundlePlan>,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "servers/mcp_import.html")]
+struct McpImportTemplate {
+    user_email: String,
+    csrf_token: String,
+    error: Option<String>,
+    server: Server,
+    form: McpImportForm,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "servers/mcp_import_preview.html")]
+struct McpImportPreviewTemplate {
+    user_email: String,
+    csrf_token: String,
+    error: Option<String>,
+    server: Server,
+    form: McpImportForm,
+    import: ToolImport,
+}
+
+/// Fields of the import and preview forms
+///
+/// Parsed by hand since tool selections repeat the `tools` field and global
+/// values arrive as `global.<name>` fields.
+#[derive(Debug, Clone, Default)]
+struct ImportForm {
+    format: String,
+    document: String,
+    title: String,
+    include_optional: bool,
+    folders_as_toolkits: bool,
+    tools: Vec<String>,
+    server_id: Option<i64>,
+    globals: Vec<(String, String)>,
+}
+
+impl ImportForm {
+    fn parse(body: &str) -> Self {
+        let mut form = ImportForm::default();
+        for (key, value) in form_urlencoded::parse(body.as_bytes()) {
+            match key.as_ref() {
+                "format" => form.format = value.into_owned(),
+                "document" => form.document = value.into_owned(),
+                "title" => form.title = value.into_owned(),
+                "include_optional" => form.include_optional = true,
+                "folders_as_toolkits" => form.folders_as_toolkits = true,
+                "tools" => form.tools.push(value.into_owned()),
+                "server_id" => form.server_id = value.parse().ok(),
+                key => {
+                    if let Some(name) = key.strip_prefix("global.") {
+                        form.globals.push((name.to_string(), value.into_owned()));
+                    }
+                }
+            }
+        }
+        form
+    }
+
+    /// Reads the submitted document in the chosen format
+    fn read(&self) -> Result<ToolImport, String> {
+        if self.document.trim().is_empty() {
+            return Err("Paste or upload a document to import".to_string());
+        }
+
+        let options = ImportOptions {
+            include_optional: self.include_optional,
+            folders_as_toolkits: self.folders_as_toolkits,
+        };
+        match self.format.as_str() {
+            "openapi" => openapi_import::parse(&self.document, options).map_err(|e| e.to_string()),
+            "postman" => postman_import::parse(&self.document, options).map_err(|e| e.to_string()),
+            "har" => har_import::parse(&self.document, options).map_err(|e| e.to_string()),
+            _ => Err("Choose a supported format".to_string()),
+        }
+    }
+
+    fn is_selected(&self, key: &str) -> bool {
+        self.tools.iter().any(|k| k == key)
+    }
+
+    fn global_value(&self, name: &str) -> &str {
+        self.globals
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or("")
+    }
+}
+
+async fn session_user(session: &Session) -> Result<(i64, String), AppError> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| AppError::InternalError)?
+        .ok_or(AppError::AuthenticationFailed)?;
+
+    let user_email = session
+        .get::<String>("email")
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+
+    Ok((user_id, user_email))
+}
+
+fn render_form(
+    user_email: String,
+    error: Option<String>,
+    form: ImportForm,
+) -> Result<Response, AppError> {
+    let template = ImportTemplate {
+        user_email,
+        csrf_token: generate_csrf_token(),
+        error,
+        formats: IMPORT_FORMATS.to_vec(),
+        form,
+    };
+    Ok(Html(template.render().map_err(|_| AppError::InternalError)?).into_response())
+}
+
+async fn render_preview(
+    state: &AppState,
+    user_id: i64,
+    user_email: String,
+    error: Option<String>,
+    form: ImportForm,
+    import: ToolImport,
+) -> Result<Response, AppError> {
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let servers = server_service
+        .list_servers(user_id)
+        .await
+        .map_err(|_| AppError::InternalError)?;
+
+    let template = ImportPreviewTemplate {
+        user_email,
+        csrf_token: generate_csrf_token(),
+        error,
+        form,
+        import,
+        servers,
+    };
+    Ok(Html(template.render().map_err(|_| AppError::InternalError)?).into_response())
+}
+
+/// GET /toolkits/import - Display import form
+pub async fn import_toolkit_page(session: Session) -> Result<Response, AppError> {
+    let (_, user_email) = session_user(&session).await?;
+
+    render_form(
+        user_email,
+        None,
+        ImportForm {
+            format: "openapi".to_string(),
+            ..Default::default()
+        },
+    )
+}
+
+/// POST /toolkits/import/preview - Show the tools a document would create
+pub async fn preview_import_handler(
+    State(state): State<AppState>,
+    session: Session,
+    body: String,
+) -> Result<Response, AppError> {
+    let (user_id, user_email) = session_user(&session).await?;
+
+    // TODO: Validate CSRF token
+
+    let mut form = ImportForm::parse(&body);
+    let import = match form.read() {
+        Ok(import) => import,
+        Err(msg) => return render_form(user_email, Some(msg), form),
+    };
+
+    // First preview: everything selected, titled after the document, with
+    // the values the document holds for the suggested globals
+    if form.title.trim().is_empty() {
+        form.title = import.title.clone();
+        form.tools = import.tools.iter().map(|t| t.key.clone()).collect();
+        form.globals = import
+            .globals
+            .iter()
+            .map(|g| (g.name.clone(), g.value.clone()))
+            .collect();
+    }
+
+    render_preview(&state, user_id, user_email, None, form, import).await
+}
+
+/// POST /toolkits/import - Create a toolkit from the selected tools
+pub async fn import_toolkit_handler(
+    State(state): State<AppState>,
+    session: Session,
+    body: String,
+) -> Result<Response, AppError> {
+    let (user_id, user_email) = session_user(&session).await?;
+
+    // TODO: Validate CSRF token
+
+    let form = ImportForm::parse(&body);
+    let import = match form.read() {
+        Ok(import) => import,
+        Err(msg) => return render_form(user_email, Some(msg), form),
+    };
+
+    let toolkit_service = state
+        .toolkit_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let tool_service = state.tool_service.as_ref().ok_or(AppError::InternalError)?;
+
+    let toolkit_ids = match import
+        .create_toolkits(
+            toolkit_service,
+            tool_service,
+            user_id,
+            &form.title,
+            Some(&form.tools),
+        )
+        .await
+    {
+        Ok(ids) => ids,
+        Err(AppError::Validation(msg)) => {
+            return render_preview(&state, user_id, user_email, Some(msg), form, import).await
+        }
+        Err(e) => return Err(e),
+    };
+
+    // Optionally install on a server, filling in the suggested globals
+    if let Some(server_id) = form.server_id {
+        let server_service = state
+            .server_service
+            .as_ref()
+            .ok_or(AppError::InternalError)?;
+
+        let globals: Vec<(String, String, bool)> = import
+            .globals
+            .iter()
+            .filter_map(|global| {
+                let value = form.global_value(&global.name).trim();
+                (!value.is_empty()).then(|| (global.name.clone(), value.to_string(), global.secret))
+            })
+            .collect();
+
+        for toolkit_id in &toolkit_ids {
+            server_service
+                .add_toolkit_to_server(server_id, *toolkit_id, user_id)
+                .await
+                .map_err(|_| AppError::InternalError)?;
+        }
+        server_service
+            .set_server_globals(server_id, user_id, &globals)
+            .await
+            .map_err(|_| AppError::InternalError)?;
+    }
+
+    match toolkit_ids.as_slice() {
+        [toolkit_id] => Ok(Redirect::to(&format!("/toolkits/{}", toolkit_id)).into_response()),
+        _ => Ok(Redirect::to("/toolkits").into_response()),
+    }
+}
+
+/// Fields of the toolkit bundle import form
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BundleForm {
+    #[serde(default)]
+    pub csrf_token: String,
+    #[serde(default)]
+    pub document: String,
+    /// "new", or the id of the toolkit to merge into
+    #[serde(default)]
+    pub target: String,
+    #[serde(default)]
+    pub title: String,
+    /// Replace tools whose names already exist instead of skipping them
+    #[serde(default)]
+    pub replace: Option<String>,
+}
+
+impl BundleForm {
+    fn read(&self) -> Result<ToolkitBundle, String> {
+        if self.document.trim().is_empty() {
+            return Err("Paste or upload a toolkit bundle to import".to_string());
+        }
+        ToolkitBundle::parse(&self.document).map_err(|e| e.to_string())
+    }
+
+    fn to_target(&self) -> BundleTarget {
+        match self.target.parse() {
+            Ok(toolkit_id) => BundleTarget::Merge {
+                toolkit_id,
+                replace: self.replace.is_some(),
+            },
+            Err(_) => BundleTarget::NewToolkit {
+                title: self.title.clone(),
+            },
+        }
+    }
+
+    fn merge_into(&self, toolkit_id: &i64) -> bool {
+        self.target == toolkit_id.to_string()
+    }
+}
+
+async fn render_bundle_form(
+    state: &AppState,
+    user_id: i64,
+    user_email: String,
+    error: Option<String>,
+    form: BundleForm,
+    plan: Option<BundlePlan>,
+) -> Result<Response, AppError> {
+    let toolkit_service = state
+        .toolkit_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let toolkits = toolkit_service.list_toolkits(user_id).await?;
+
+    let template = ImportBundleTemplate {
+        user_email,
+        csrf_token: generate_csrf_token(),
+        error,
+        form,
+        toolkits,
+        plan,
+    };
+    Ok(Html(template.render().map_err(|_| AppError::InternalError)?).into_response())
+}
+
+/// GET /toolkits/import/bundle - Display toolkit bundle import form
+pub async fn import_bundle_page(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Response, AppError> {
+    let (user_id, user_email) = session_user(&session).await?;
+
+    let form = BundleForm {
+        target: "new".to_string(),
+        ..Default::default()
+    };
+    render_bundle_form(&state, user_id, user_email, None, form, None).await
+}
+
+/// POST /toolkits/import/bundle/preview - Validate a bundle and report conflicts
+pub async fn preview_bundle_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Form(mut form): Form<BundleForm>,
+) -> Result<Response, AppError> {
+    let (user_id, user_email) = session_user(&session).await?;
+
+    // TODO: Validate CSRF token
+
+    let bundle = match form.read() {
+        Ok(bundle) => bundle,
+        Err(msg) => {
+            return render_bundle_form(&state, user_id, user_email, Some(msg), form, None).await
+        }
+    };
+
+    if form.title.trim().is_empty() {
+        form.title = bundle.toolkit.title.clone();
+    }
+
+    let existing = match form.to_target() {
+        BundleTarget::Merge { toolkit_id, .. } => {
+            let tool_service = state.tool_service.as_ref().ok_or(AppError::InternalError)?;
+            tool_service.list_tools(toolkit_id, user_id).await?
+        }
+        BundleTarget::NewToolkit { .. } => Vec::new(),
+    };
+    let plan = bundle.plan(&existing);
+
+    render_bundle_form(&state, user_id, user_email, None, form, Some(plan)).await
+}
+
+/// POST /toolkits/import/bundle - Create or merge a toolkit from a bundle
+pub async fn import_bundle_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Form(form): Form<BundleForm>,
+) -> Result<Response, AppError> {
+    let (user_id, user_email) = session_user(&session).await?;
+
+    // TODO: Validate CSRF token
+
+    let bundle = match form.read() {
+        Ok(bundle) => bundle,
+        Err(msg) => {
+            return render_bundle_form(&state, user_id, user_email, Some(msg), form, None).await
+        }
+    };
+
+    let toolkit_service = state
+        .toolkit_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let tool_service = state.tool_service.as_ref().ok_or(AppError::InternalError)?;
+
+    match bundle
+        .import(toolkit_service, tool_service, user_id, &form.to_target())
+        .await
+    {
+        Ok(summary) => {
+            Ok(Redirect::to(&format!("/toolkits/{}", summary.toolkit_id)).into_response())
+        }
+        Err(AppError::Validation(msg)) => {
+            render_bundle_form(&state, user_id, user_email, Some(msg), form, None).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Fields of the remote MCP server import and preview forms
+///
+/// Parsed by hand since tool selections repeat the `tools` field and the
+/// per-tool choices arrive as `name.<tool>`, `description.<tool>` and
+/// `pin.<argument>.<tool>` fields.
+#[derive(Debug, Clone, Default)]
+struct McpImportForm {
+    transport: String,
+    url: String,
+    headers: String,
+    title: String,
+    include_optional: bool,
+    tools: Vec<String>,
+    names: Vec<(String, String)>,
+    descriptions: Vec<(String, String)>,
+    /// (tool key, argument, value)
+    pins: Vec<(String, String, String)>,
+}
+
+impl McpImportForm {
+    fn parse(body: &str) -> Self {
+        let mut form = McpImportForm::default();
+        for (key, value) in form_urlencoded::parse(body.as_bytes()) {
+            match key.as_ref() {
+                "transport" => form.transport = value.into_owned(),
+                "url" => form.url = value.into_owned(),
+                "headers" => form.headers = value.into_owned(),
+                "title" => form.title = value.into_owned(),
+                "include_optional" => form.include_optional = true,
+                "tools" => form.tools.push(value.into_owned()),
+                key => {
+                    if let Some(tool) = key.strip_prefix("name.") {
+                        form.names.push((tool.to_string(), value.into_owned()));
+                    } else if let Some(tool) = key.strip_prefix("description.") {
+                        form.descriptions
+                            .push((tool.to_string(), value.into_owned()));
+                    } else if let Some((argument, tool)) = key
+                        .strip_prefix("pin.")
+                        .and_then(|rest| rest.split_once('.'))
+                    {
+                        form.pins.push((
+                            tool.to_string(),
+                            argument.to_string(),
+                            value.into_owned(),
+                        ));
+                    }
+                }
+            }
+        }
+        form
+    }
+
+    fn source(&self) -> Result<McpSource, String> {
+        let transport = McpTransport::from_method(&self.transport)
+            .ok_or_else(|| "Choose a transport".to_string())?;
+        McpSource::parse(transport, &self.url, &self.headers).map_err(|e| e.to_string())
+    }
+
+    fn options(&self) -> ImportOptions {
+        ImportOptions {
+            include_optional: self.include_optional,
+            ..Default::default()
+        }
+    }
+
+    fn is_selected(&self, key: &str) -> bool {
+        self.tools.iter().any(|k| k == key)
+    }
+
+    fn name(&self, key: &str) -> &str {
+        Self::value_of(&self.names, key)
+    }
+
+    fn description(&self, key: &str) -> &str {
+        Self::value_of(&self.descriptions, key)
+    }
+
+    fn pin(&self, key: &str, argument: &str) -> &str {
+        self.pins
+            .iter()
+            .find(|(tool, arg, _)| tool == key && arg == argument)
+            .map(|(_, _, v)| v.as_str())
+            .unwrap_or("")
+    }
+
+    fn pins_of(&self, key: &str) -> Vec<(String, String)> {
+        self.pins
+            .iter()
+            .filter(|(tool, _, _)| tool == key)
+            .map(|(_, argument, value)| (argument.clone(), value.clone()))
+            .collect()
+    }
+
+    fn value_of<'a>(values: &'a [(String, String)], key: &str) -> &'a str {
+        values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or("")
+    }
+}
+
+async fn owned_server(state: &AppState, server_id: i64, user_id: i64) -> Result<Server, AppError> {
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    server_service
+        .get_server(server_id, user_id)
+        .await
+        .map_err(|_| AppError::InternalError)?
+        .ok_or(AppError::UserNotFound)
+}
+
+fn render_mcp_form(
+    user_email: String,
+    error: Option<String>,
+    server: Server,
+    form: McpImportForm,
+) -> Result<Response, AppError> {
+    let template = McpImportTemplate {
+        user_email,
+        csrf_token: generate_csrf_token(),
+        error,
+        server,
+        form,
+    };
+    Ok(Html(template.render().map_err(|_| AppError::InternalError)?).into_response())
+}
+
+fn render_mcp_preview(
+    user_email: String,
+    error: Option<String>,
+    server: Server,
+    form: McpImportForm,
+    import: ToolImport,
+) -> Result<Response, AppError> {
+    let template = McpImportPreviewTemplate {
+        user_email,
+        csrf_token: generate_csrf_token(),
+        error,
+        server,
+        form,
+        import,
+    };
+    Ok(Html(template.render().map_err(|_| AppError::InternalError)?).into_response())
+}
+
+/// Lists the remote tools the form points at, as a proposed toolkit
+async fn discover_mcp_tools(
+    state: &AppState,
+    server_id: i64,
+    form: &McpImportForm,
+) -> Result<ToolImport, String> {
+    let source = form.source()?;
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or_else(|| "Server service unavailable".to_string())?;
+    let remote = server_service
+        .list_remote_mcp_tools(server_id, &source)
+        .await
+        .map_err(|e| e.to_string())?;
+    mcp_import::build(&source, &remote, form.options()).map_err(|e| e.to_string())
+}
+
+/// GET /servers/{id}/mcp-import - Display the remote MCP server form
+pub async fn mcp_import_page(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+) -> Result<Response, AppError> {
+    let (user_id, user_email) = session_user(&session).await?;
+    let server = owned_server(&state, server_id, user_id).await?;
+
+    render_mcp_form(
+        user_email,
+        None,
+        server,
+        McpImportForm {
+            transport: McpTransport::StreamableHttp.method().to_string(),
+            ..Default::default()
+        },
+    )
+}
+
+/// POST /servers/{id}/mcp-import/preview - Show the tools of a remote MCP server
+pub async fn preview_mcp_import_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+    body: String,
+) -> Result<Response, AppError> {
+    let (user_id, user_email) = session_user(&session).await?;
+    let server = owned_server(&state, server_id, user_id).await?;
+
+    // TODO: Validate CSRF token
+
+    let mut form = McpImportForm::parse(&body);
+    let import = match discover_mcp_tools(&state, server_id, &form).await {
+        Ok(import) => import,
+        Err(msg) => return render_mcp_form(user_email, Some(msg), server, form),
+    };
+
+    // First preview: everything selected, titled after the remote server
+    if form.title.trim().is_empty() {
+        form.title = import.title.clone();
+        form.tools = import.tools.iter().map(|t| t.key.clone()).collect();
+    }
+
+    render_mcp_preview(user_email, None, server, form, import)
+}
+
+/// POST /servers/{id}/mcp-import - Add the selected remote tools as instances
+///
+/// The tools go to a new private toolkit installed on the server, with one
+/// instance each named, described and pinned as chosen in the preview.
+pub async fn mcp_import_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+    body: String,
+) -> Result<Response, AppError> {
+    let (user_id, user_email) = session_user(&session).await?;
+    let server = owned_server(&state, server_id, user_id).await?;
+
+    // TODO: Validate CSRF token
+
+    let form = McpImportForm::parse(&body);
+    let import = match discover_mcp_tools(&state, server_id, &form).await {
+        Ok(import) => import,
+        Err(msg) => return render_mcp_form(user_email, Some(msg), server, form),
+    };
+
+    let toolkit_service = state
+        .toolkit_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let tool_service = state.tool_service.as_ref().ok_or(AppError::InternalError)?;
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let instance_service = state
+        .instance_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+
+    let toolkit_ids = match import
+        .create_toolkits(
+            toolkit_service,
+            tool_service,
+            user_id,
+            &form.title,
+            Some(&form.tools),
+        )
+        .await
+    {
+        Ok(ids) => ids,
+        Err(AppError::Validation(msg)) => {
+            return render_mcp_preview(user_email, Some(msg), server, form, import)
+        }
+        Err(e) => return Err(e),
+    };
+
+    for toolkit_id in toolkit_ids {
+        server_service
+            .add_toolkit_to_server(server_id, toolkit_id, user_id)
+            .await
+            .map_err(|_| AppError::InternalError)?;
+
+        for tool in tool_service.list_tools(toolkit_id, user_id).await? {
+            let Some(imported) = import.tools.iter().find(|t| t.name == tool.name) else {
+                continue;
+            };
+            let name = match form.name(&imported.key).trim() {
+                "" => imported.name.as_str(),
+                name => name,
+            };
+            let instance_name = instance_service
+                .generate_instance_name(server_id, name)
+                .await
+                .map_err(|_| AppError::InternalError)?;
+
+            instance_service
+                .create_instance_with_config(
+                    server_id,
+                    ConfigureInstanceForm {
+                        instance_name,
+                        description: Some(form.description(&imported.key).trim().to_string()),
+                        tool_id: tool.id,
+                        param_configs: mcp_import::param_configs(
+                            imported,
+                            &form.pins_of(&imported.key),
+                        ),
+                        csrf_token: String::new(),
+                    },
+                )
+                .await
+                .map_err(|_| AppError::InternalError)?;
+        }
+    }
+
+    reload_server_tools(&state.pool, server_id, &state.mcp_registry).await;
+
+    Ok(Redirect::to(&format!("/servers/{}?tab=tools", server_id)).into_response())
+}
+
+fn generate_csrf_token() -> String {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    let random_bytes: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
+    hex::encode(random_bytes)
+}
@@ -1,5 +1,8 @@
 use crate::error::AppError;
-use crate::models::{ConfigureInstanceForm, ExtractedParameter, Server};
+use crate::models::{
+    ConcurrencyLimit, ConcurrencyLimitFields, ConfigureInstanceForm, ExtractedParameter,
+    InstanceLimitsForm, Server,
+};
 use crate::services::http_executor::ExecutionResult;
 use crate::services::instance_executor::InstanceExecutor;
 use crate::services::secrets_manager::SecretsManager;
@@ -81,6 +84,7 @@ struct EditInstanceTemplate {
     params_with_config: Vec<ParameterWithConfig>,
     signature: String,
     user_email: String,
+    concurrency: ConcurrencyLimitFields,
 }
 
 // Simple tool struct for template
@@ -362,6 +366,10 @@ pub async fn edit_instance_page(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    let concurrency = ConcurrencyLimitFields::from(ConcurrencyLimit::parse(
+        instance.concurrency_limit.as_deref(),
+    ));
+
     let template = EditInstanceTemplate {
         csrf_token,
         server,
@@ -369,6 +377,7 @@ pub async fn edit_instance_page(
         params_with_config,
         signature,
         user_email,
+        concurrency,
     };
 
     Ok(Html(
@@ -445,6 +454,54 @@ pub async fn update_instance_handler(
     )))
 }
 
+/// POST /servers/{id}/instances/{instance_id}/limits - Save the concurrency limit
+pub async fn update_instance_limits_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path((server_id, instance_id)): Path<(i64, i64)>,
+    Form(form): Form<InstanceLimitsForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = get_user_id(&session).await?;
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let instance_service = state
+        .instance_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+
+    if !server_service
+        .user_owns_server(server_id, user_id)
+        .await
+        .map_err(|_| AppError::InternalError)?
+    {
+        return Err(AppError::AuthenticationFailed);
+    }
+
+    let instance = instance_service
+        .get_instance(instance_id)
+        .await
+        .map_err(|_| AppError::InternalError)?;
+    if instance.is_none_or(|i| i.server_id != server_id) {
+        return Err(AppError::AuthenticationFailed);
+    }
+
+    instance_service
+        .update_concurrency_limit(instance_id, &form)
+        .await
+        .map_err(|e| match e.downcast::<AppError>() {
+            Ok(app_error) => app_error,
+            Err(_) => AppError::InternalError,
+        })?;
+
+    Ok(Redirect::to(&format!(
+        "/servers/{}/instances/{}",
+        server_id, instance_id
+    )))
+}
+
 pub async fn delete_instance_handler(
     State(state): State<AppState>,
     session: Session,
@@ -486,7 +543,7 @@ pub async fn delete_instance_handler(
 }
 
 /// Helper function to reload tools for a server in the MCP registry
-async fn reload_server_tools(
+pub(crate) async fn reload_server_tools(
     pool: &sqlx::SqlitePool,
     server_id: i64,
     mcp_registry: &Option<
@@ -506,6 +563,33 @@ async fn reload_server_tools(
     }
 }
 
+/// Helper function to tell a server's MCP clients that one of its lists changed
+pub(crate) async fn notify_server_list_changed(
+    pool: &sqlx::SqlitePool,
+    server_id: i64,
+    mcp_registry: &Option<
+        std::sync::Arc<tokio::sync::RwLock<crate::mcp::registry::McpServerRegistry>>,
+    >,
+    list: crate::mcp::ListChanged,
+) {
+    if let Some(ref registry) = mcp_registry {
+        if let Ok(Some(server)) = Server::get_by_id(pool, server_id).await {
+            if let Err(e) = registry
+                .read()
+                .await
+                .notify_list_changed(&server.uuid, list)
+                .await
+            {
+                tracing::error!(
+                    "Failed to notify MCP clients of server {}: {}",
+                    server.uuid,
+                    e
+                );
+            }
+        }
+    }
+}
+
 // Display structs for test template
 #[derive(serde::Serialize)]
 struct ExposedParameterDisplay {
@@ -869,5 +953,7 @@ async fn execute_instance_test(
         body: content_text,
         headers: HashMap::new(),
         curl_command: String::new(),
+        endpoint: None,
+        failovers: Vec::new(),
     })
 }
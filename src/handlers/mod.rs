@@ -1,5 +1,6 @@
 pub mod contact_handlers;
 pub mod dashboard_handlers;
+pub mod import_handlers;
 pub mod instance_handlers;
 pub mod oauth_handlers;
 pub mod server_handlers;
@@ -11,6 +12,7 @@ pub mod unified_auth_handlers;
 
 pub use contact_handlers::*;
 pub use dashboard_handlers::*;
+pub use import_handlers::*;
 pub use instance_handlers::*;
 pub use oauth_handlers::*;
 pub use server_handlers::*;
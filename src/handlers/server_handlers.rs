@@ -1,14 +1,25 @@
-use crate::models::{CreateServerForm, GlobalsForm, ServerGlobal, UpdateServerForm};
+use crate::handlers::instance_handlers::{notify_server_list_changed, reload_server_tools};
+use crate::mcp::{ListChanged, McpServerRegistry};
+use crate::models::{
+    ConcurrencyLimit, ConcurrencyLimitFields, CreateServerForm, GlobalsForm, NetworkSettingsForm,
+    PromptForm, PublishInstanceForm, RateLimitConfig, RateLimitFields, ResourceDocumentForm,
+    Server, ServerGlobal, UpdateServerForm,
+};
+use crate::services::{
+    ConfigPlan, EgressConfigError, OpenApiExport, PromptError, ResourceError, ServerConfig,
+    ToolCatalog,
+};
 use crate::AppState;
 use askama::Template;
 use askama_web::WebTemplate;
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::{Html, IntoResponse, Redirect},
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Redirect, Response},
     Form,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tower_sessions::Session;
 
 #[derive(Debug, Deserialize)]
@@ -44,12 +55,58 @@ struct ViewServerTemplate {
     active_tab: String,
     user_email: String,
     bindings: Vec<BindingRow>,
+    network: NetworkSettingsView,
+    network_error: Option<String>,
+    circuits: Vec<crate::services::CircuitSnapshot>,
+    resources: Vec<ResourceRow>,
+    resource_error: Option<String>,
+    prompts: Vec<PromptRow>,
+    prompt_error: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct ServerViewQuery {
     #[serde(default = "default_tab")]
     tab: String,
+    network_error: Option<String>,
+    resource_error: Option<String>,
+    prompt_error: Option<String>,
+}
+
+/// A server prompt as listed, and edited, on the prompts tab
+pub struct PromptRow {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub template: String,
+    /// Argument documentation in the edit form's line format
+    pub arguments_text: String,
+    /// Placeholders not filled by a plain global
+    pub arguments: Vec<String>,
+}
+
+/// A server resource as listed on the resources tab
+pub struct ResourceRow {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub mime_type: String,
+    /// URI, or URI template for tool instances taking parameters
+    pub uri: String,
+    /// Name of the tool instance read, None for documents
+    pub instance_name: Option<String>,
+}
+
+/// Network settings as shown on the settings tab; the proxy URL and
+/// certificate material are never sent back to the browser
+pub struct NetworkSettingsView {
+    pub has_proxy: bool,
+    pub ca_bundle: String,
+    pub has_client_identity: bool,
+    pub allowed_hosts: String,
+    pub denied_hosts: String,
+    pub rate_limit: RateLimitFields,
+    pub concurrency: ConcurrencyLimitFields,
 }
 
 fn default_tab() -> String {
@@ -96,6 +153,29 @@ fn build_binding_rows(
     bindings
 }
 
+#[derive(Template, WebTemplate)]
+#[template(path = "servers/config.html")]
+struct ServerConfigTemplate {
+    csrf_token: String,
+    server: crate::models::Server,
+    user_email: String,
+    document: String,
+    error: Option<String>,
+    plan: Option<ConfigPlan>,
+    applied: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ServerConfigForm {
+    #[serde(default)]
+    pub document: String,
+    /// Only show the changes the document would make
+    #[serde(default)]
+    pub dry_run: Option<String>,
+    #[serde(default)]
+    pub csrf_token: String,
+}
+
 #[derive(Template, WebTemplate)]
 #[template(path = "servers/edit.html")]
 struct EditServerTemplate {
@@ -312,12 +392,90 @@ pub async fn view_server_handler(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    // Plain globals fill prompt placeholders of the same name
+    let plain_global_keys: Vec<String> = globals
+        .iter()
+        .filter(|g| !g.is_secret.unwrap_or(false))
+        .map(|g| g.key.clone())
+        .collect();
+
     // Match discovered params with globals
     let bindings = build_binding_rows(discovered, globals);
 
+    let network = server_service
+        .get_network_settings_decrypted(server_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .unwrap_or_default();
+    let network = NetworkSettingsView {
+        has_client_identity: network.client_cert.is_some() && network.client_key.is_some(),
+        rate_limit: RateLimitFields::from(RateLimitConfig::parse(network.rate_limit.as_deref())),
+        concurrency: ConcurrencyLimitFields::from(ConcurrencyLimit::parse(
+            network.concurrency_limit.as_deref(),
+        )),
+        has_proxy: network.proxy_url.is_some(),
+        ca_bundle: network.ca_bundle.unwrap_or_default(),
+        allowed_hosts: network.allowed_hosts.unwrap_or_default(),
+        denied_hosts: network.denied_hosts.unwrap_or_default(),
+    };
+
+    let resources = server_service
+        .list_resources(server_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .map(|resource| {
+            let instance = resource
+                .instance_id
+                .and_then(|id| instances.iter().find(|i| i.id == id));
+            let exposed: Vec<&str> = instance
+                .map(|i| {
+                    i.params
+                        .iter()
+                        .filter(|p| p.source == "exposed")
+                        .map(|p| p.param_name.as_str())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let uri = match exposed.is_empty() {
+                true => resource.uri(),
+                false => format!("{}{{?{}}}", resource.uri(), exposed.join(",")),
+            };
+            ResourceRow {
+                id: resource.id.unwrap_or_default(),
+                description: resource.description.clone().unwrap_or_default(),
+                instance_name: instance.map(|i| i.instance_name.clone()),
+                uri,
+                name: resource.name,
+                mime_type: resource.mime_type,
+            }
+        })
+        .collect();
+
+    let prompts = server_service
+        .list_prompts(server_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .map(|prompt| PromptRow {
+            id: prompt.id.unwrap_or_default(),
+            description: prompt.description.clone().unwrap_or_default(),
+            arguments_text: prompt.arguments_text(),
+            arguments: prompt
+                .placeholders()
+                .into_iter()
+                .map(|(_, name)| name)
+                .filter(|name| !plain_global_keys.contains(name))
+                .collect(),
+            name: prompt.name,
+            template: prompt.template,
+        })
+        .collect();
+
     // Validate tab parameter
     let active_tab = match query.tab.as_str() {
-        "server" | "toolkits" | "tools" | "bindings" | "metadata" | "settings" => query.tab,
+        "server" | "toolkits" | "tools" | "bindings" | "metadata" | "settings" | "resources"
+        | "prompts" => query.tab,
         _ => "server".to_string(),
     };
 
@@ -331,6 +489,14 @@ pub async fn view_server_handler(
         active_tab,
         user_email,
         bindings,
+        network,
+        network_error: query.network_error,
+        circuits: crate::services::CircuitBreakerRegistry::shared()
+            .snapshots_for_servers(&[server_id]),
+        resources,
+        resource_error: query.resource_error,
+        prompts,
+        prompt_error: query.prompt_error,
     };
 
     Ok(Html(
@@ -443,6 +609,249 @@ pub async fn update_server_access_handler(
     )))
 }
 
+pub async fn update_server_network_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+    Form(form): Form<NetworkSettingsForm>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if let Err(e) = server_service
+        .save_network_settings(server_id, user_id, form)
+        .await
+    {
+        // Invalid proxy/certificates are shown on the settings tab
+        return match e.downcast_ref::<EgressConfigError>() {
+            Some(config_error) => Ok(Redirect::to(&format!(
+                "/servers/{}?tab=settings&network_error={}",
+                server_id,
+                urlencoding::encode(&config_error.to_string())
+            ))),
+            None => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        };
+    }
+
+    Ok(Redirect::to(&format!(
+        "/servers/{}?tab=settings",
+        server_id
+    )))
+}
+
+/// Redirect to the resources tab, showing a validation failure if any
+fn resources_redirect(server_id: i64, result: anyhow::Result<()>) -> Result<Redirect, StatusCode> {
+    match result {
+        Ok(()) => Ok(Redirect::to(&format!(
+            "/servers/{}?tab=resources",
+            server_id
+        ))),
+        Err(e) => match e.downcast_ref::<ResourceError>() {
+            Some(resource_error) => Ok(Redirect::to(&format!(
+                "/servers/{}?tab=resources&resource_error={}",
+                server_id,
+                urlencoding::encode(&resource_error.to_string())
+            ))),
+            None => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+/// POST /servers/{id}/resources - Attach or replace a document resource
+pub async fn save_resource_document_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+    Form(form): Form<ResourceDocumentForm>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    // TODO: Validate CSRF token
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let result = server_service
+        .save_resource_document(server_id, user_id, &form)
+        .await
+        .map(|_| ());
+    if result.is_ok() {
+        notify_server_list_changed(
+            &state.pool,
+            server_id,
+            &state.mcp_registry,
+            ListChanged::Resources,
+        )
+        .await;
+    }
+    resources_redirect(server_id, result)
+}
+
+/// POST /servers/{id}/resources/publish - Publish a GET tool instance as a resource
+pub async fn publish_instance_resource_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+    Form(form): Form<PublishInstanceForm>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    // TODO: Validate CSRF token
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let result = server_service
+        .publish_instance_resource(server_id, user_id, &form)
+        .await
+        .map(|_| ());
+    if result.is_ok() {
+        notify_server_list_changed(
+            &state.pool,
+            server_id,
+            &state.mcp_registry,
+            ListChanged::Resources,
+        )
+        .await;
+    }
+    resources_redirect(server_id, result)
+}
+
+/// POST /servers/{id}/resources/{resource_id}/delete - Remove a resource
+pub async fn delete_resource_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path((server_id, resource_id)): Path<(i64, i64)>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let result = server_service
+        .delete_resource(server_id, user_id, resource_id)
+        .await;
+    if result.is_ok() {
+        notify_server_list_changed(
+            &state.pool,
+            server_id,
+            &state.mcp_registry,
+            ListChanged::Resources,
+        )
+        .await;
+    }
+    resources_redirect(server_id, result)
+}
+
+/// Redirect to the prompts tab, showing a validation failure if any
+fn prompts_redirect(server_id: i64, result: anyhow::Result<()>) -> Result<Redirect, StatusCode> {
+    match result {
+        Ok(()) => Ok(Redirect::to(&format!("/servers/{}?tab=prompts", server_id))),
+        Err(e) => match e.downcast_ref::<PromptError>() {
+            Some(prompt_error) => Ok(Redirect::to(&format!(
+                "/servers/{}?tab=prompts&prompt_error={}",
+                server_id,
+                urlencoding::encode(&prompt_error.to_string())
+            ))),
+            None => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+/// POST /servers/{id}/prompts - Create a prompt or replace the one of the same name
+pub async fn save_prompt_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+    Form(form): Form<PromptForm>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    // TODO: Validate CSRF token
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let result = server_service
+        .save_prompt(server_id, user_id, &form)
+        .await
+        .map(|_| ());
+    if result.is_ok() {
+        notify_server_list_changed(
+            &state.pool,
+            server_id,
+            &state.mcp_registry,
+            ListChanged::Prompts,
+        )
+        .await;
+    }
+    prompts_redirect(server_id, result)
+}
+
+/// POST /servers/{id}/prompts/{prompt_id}/delete - Remove a prompt
+pub async fn delete_prompt_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path((server_id, prompt_id)): Path<(i64, i64)>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let result = server_service
+        .delete_prompt(server_id, user_id, prompt_id)
+        .await;
+    if result.is_ok() {
+        notify_server_list_changed(
+            &state.pool,
+            server_id,
+            &state.mcp_registry,
+            ListChanged::Prompts,
+        )
+        .await;
+    }
+    prompts_redirect(server_id, result)
+}
+
 pub async fn delete_server_handler(
     State(state): State<AppState>,
     session: Session,
@@ -643,6 +1052,16 @@ pub async fn save_bindings_handler(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    // Globals decide which parameters tools expose and fill prompt templates
+    reload_server_tools(&state.pool, server_id, &state.mcp_registry).await;
+    notify_server_list_changed(
+        &state.pool,
+        server_id,
+        &state.mcp_registry,
+        ListChanged::Prompts,
+    )
+    .await;
+
     Ok(Redirect::to(&format!(
         "/servers/{}?tab=bindings",
         server_id
@@ -771,3 +1190,335 @@ pub async fn root_message_handler(
             axum::http::StatusCode::INTERNAL_SERVER_ERROR
         })
 }
+
+/// Current configuration of a server as a YAML document
+async fn export_server_config(
+    state: &AppState,
+    server_id: i64,
+    user_id: i64,
+) -> Result<ServerConfig, StatusCode> {
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let instance_service = state
+        .instance_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    ServerConfig::export(server_service, instance_service, server_id, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// GET /servers/{id}/config.yaml - Download the server configuration
+pub async fn download_server_config_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+) -> Result<Response, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !server_service
+        .user_owns_server(server_id, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let config = export_server_config(&state, server_id, user_id).await?;
+    let document = config
+        .to_yaml()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let filename = match crate::services::tool_import::snake_case(&config.server.name).as_str() {
+        "" => "server".to_string(),
+        name => name.to_string(),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/yaml".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.saramcp-server.yaml\"", filename),
+            ),
+        ],
+        document,
+    )
+        .into_response())
+}
+
+/// GET /servers/{id}/config - Show the configuration document of a server
+pub async fn server_config_page(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+) -> Result<Html<String>, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let server = server_service
+        .get_server(server_id, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let document = export_server_config(&state, server_id, user_id)
+        .await?
+        .to_yaml()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    render_server_config(&session, server, document, None, None, false).await
+}
+
+/// POST /servers/{id}/config - Preview or apply a configuration document
+///
+/// With `dry_run` only the diff is shown. Otherwise the server is reconciled
+/// to the document and its tools are reloaded in the MCP registry.
+pub async fn apply_server_config_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+    Form(form): Form<ServerConfigForm>,
+) -> Result<Html<String>, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    // TODO: Validate CSRF token
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let toolkit_service = state
+        .toolkit_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let tool_service = state
+        .tool_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let server = server_service
+        .get_server(server_id, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let desired = match ServerConfig::parse(&form.document) {
+        Ok(desired) => desired,
+        Err(e) => {
+            return render_server_config(
+                &session,
+                server,
+                form.document,
+                Some(e.to_string()),
+                None,
+                false,
+            )
+            .await
+        }
+    };
+
+    let current = export_server_config(&state, server_id, user_id).await?;
+    let catalog = ToolCatalog::load(toolkit_service, tool_service, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let plan = desired.plan(Some(&current), &catalog);
+
+    if form.dry_run.is_some() || !plan.is_valid() {
+        return render_server_config(&session, server, form.document, None, Some(plan), false)
+            .await;
+    }
+
+    if let Err(e) = plan.apply(&state.pool, user_id, Some(server_id)).await {
+        tracing::error!(
+            "Failed to apply configuration to server {}: {}",
+            server_id,
+            e
+        );
+        return render_server_config(
+            &session,
+            server,
+            form.document,
+            Some("Applying the configuration failed, nothing was changed".to_string()),
+            None,
+            false,
+        )
+        .await;
+    }
+
+    if let Some(ref registry) = state.mcp_registry {
+        if let Err(e) = McpServerRegistry::reload_changed_configs(registry).await {
+            tracing::error!("Failed to reload MCP servers: {}", e);
+        }
+    }
+
+    // Show the server as it is now, renamed or not
+    let server = server_service
+        .get_server(server_id, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    render_server_config(&session, server, form.document, None, Some(plan), true).await
+}
+
+async fn render_server_config(
+    session: &Session,
+    server: crate::models::Server,
+    document: String,
+    error: Option<String>,
+    plan: Option<ConfigPlan>,
+    applied: bool,
+) -> Result<Html<String>, StatusCode> {
+    let user_email = session
+        .get::<String>("email")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let csrf_token = session
+        .get::<String>("csrf_token")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .unwrap_or_default();
+
+    let template = ServerConfigTemplate {
+        csrf_token,
+        server,
+        user_email,
+        document,
+        error,
+        plan,
+        applied,
+    };
+
+    Ok(Html(
+        template
+            .render()
+            .unwrap_or_else(|_| "Template error".to_string()),
+    ))
+}
+
+/// Response carrying an OpenAPI document
+///
+/// `?format=yaml` serves YAML instead of JSON and `?download=1` serves the
+/// document as a file named after `name`.
+pub(crate) fn openapi_response(
+    document: &serde_json::Value,
+    params: &HashMap<String, String>,
+    name: &str,
+) -> anyhow::Result<Response> {
+    let (body, content_type, extension) =
+        OpenApiExport::render(document, params.get("format").map(String::as_str))?;
+
+    let mut response = ([(header::CONTENT_TYPE, content_type)], body).into_response();
+    if params.contains_key("download") {
+        let stem = match crate::services::tool_import::snake_case(name).as_str() {
+            "" => "api".to_string(),
+            stem => stem.to_string(),
+        };
+        let disposition = format!("attachment; filename=\"{}.openapi.{}\"", stem, extension);
+        response.headers_mut().insert(
+            header::CONTENT_DISPOSITION,
+            header::HeaderValue::from_str(&disposition)?,
+        );
+    }
+    Ok(response)
+}
+
+async fn server_openapi(
+    state: &AppState,
+    server_id: i64,
+    user_id: i64,
+) -> Result<serde_json::Value, StatusCode> {
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let instance_service = state
+        .instance_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let tool_service = state
+        .tool_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    OpenApiExport::for_server(
+        server_service,
+        instance_service,
+        tool_service,
+        server_id,
+        user_id,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// GET /servers/{id}/openapi - OpenAPI document of the server's tool instances
+pub async fn server_openapi_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(server_id): Path<i64>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Response, StatusCode> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let server_service = state
+        .server_service
+        .as_ref()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let server = server_service
+        .get_server(server_id, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let document = server_openapi(&state, server_id, user_id).await?;
+    openapi_response(&document, &params, &server.name)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// GET /s/{uuid}/openapi.json - OpenAPI document at the server's MCP address
+///
+/// Guarded by the same access control as the MCP endpoint, so whoever may
+/// call the server's tools can read what it exposes.
+pub async fn mcp_openapi_handler(
+    State(state): State<AppState>,
+    Path(server_uuid): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Response, StatusCode> {
+    let server = Server::get_by_uuid(&state.pool, &server_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let server_id = server.id.ok_or(StatusCode::NOT_FOUND)?;
+
+    let document = server_openapi(&state, server_id, server.user_id).await?;
+    openapi_response(&document, &params, &server.name)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
@@ -1,7 +1,10 @@
 use crate::error::AppError;
-use crate::models::{CreateToolForm, ExtractedParameter, Tool, UpdateToolForm};
+use crate::handlers::instance_handlers::reload_server_tools;
+use crate::models::{
+    CreateToolForm, ExtractedParameter, RateLimitFields, Tool, ToolInstance, UpdateToolForm,
+};
 use crate::services::http_executor::ExecutionResult;
-use crate::services::tool_test_service;
+use crate::services::{curl_import, tool_test_service};
 use crate::AppState;
 use askama::Template;
 use askama_web::WebTemplate;
@@ -24,10 +27,17 @@ struct ToolDisplay {
     pub headers: String,
     pub body: String,
     pub timeout_ms: i32,
+    pub streaming: bool,
+    pub base_urls: String,
+    pub endpoint_strategy: String,
+    pub failover_status_codes: String,
+    pub rate_limit: RateLimitFields,
 }
 
 impl From<Tool> for ToolDisplay {
     fn from(t: Tool) -> Self {
+        let endpoints = t.endpoint_config();
+        let rate_limit = RateLimitFields::from(t.rate_limit_config());
         ToolDisplay {
             id: t.id,
             name: t.name,
@@ -37,6 +47,21 @@ impl From<Tool> for ToolDisplay {
             headers: t.headers.unwrap_or_else(|| "{}".to_string()),
             body: t.body.unwrap_or_else(|| "{}".to_string()),
             timeout_ms: t.timeout_ms,
+            streaming: t.streaming,
+            base_urls: endpoints
+                .as_ref()
+                .map(|e| e.base_urls_text())
+                .unwrap_or_default(),
+            endpoint_strategy: endpoints
+                .as_ref()
+                .map(|e| e.strategy.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            failover_status_codes: endpoints
+                .as_ref()
+                .map(|e| e.failover_status_codes_text())
+                .unwrap_or_else(|| "502, 503, 504".to_string()),
+            rate_limit,
         }
     }
 }
@@ -58,7 +83,70 @@ impl From<ExtractedParameter> for ParameterDisplay {
     }
 }
 
+/// Prefilled values of the new tool form
+struct ToolDraft {
+    pub name: String,
+    pub description: String,
+    pub method: String,
+    pub url: String,
+    pub headers: String,
+    pub body: String,
+}
+
+impl Default for ToolDraft {
+    fn default() -> Self {
+        ToolDraft {
+            name: String::new(),
+            description: String::new(),
+            method: "GET".to_string(),
+            url: String::new(),
+            headers: "{}".to_string(),
+            body: String::new(),
+        }
+    }
+}
+
+impl From<&CreateToolForm> for ToolDraft {
+    fn from(form: &CreateToolForm) -> Self {
+        ToolDraft {
+            name: form.name.clone(),
+            description: form.description.clone(),
+            method: form.method.to_uppercase(),
+            url: form.url.clone(),
+            headers: form.headers.clone(),
+            body: form.body.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&curl_import::CurlImport> for ToolDraft {
+    fn from(import: &curl_import::CurlImport) -> Self {
+        let headers: serde_json::Map<String, serde_json::Value> = import
+            .tool
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), serde_json::Value::String(value.clone())))
+            .collect();
+
+        ToolDraft {
+            name: import.tool.name.clone(),
+            description: String::new(),
+            method: import.tool.method.clone(),
+            url: import.tool.url.clone(),
+            headers: serde_json::to_string_pretty(&headers).unwrap_or_else(|_| "{}".to_string()),
+            body: import.tool.body.clone().unwrap_or_default(),
+        }
+    }
+}
+
 // Form structures
+#[derive(Deserialize)]
+pub struct CurlForm {
+    #[allow(dead_code)]
+    csrf_token: String,
+    curl: String,
+}
+
 #[derive(Deserialize)]
 pub struct TestToolForm {
     #[allow(dead_code)]
@@ -76,6 +164,9 @@ struct NewToolTemplate {
     toolkit_title: String,
     csrf_token: String,
     error: Option<String>,
+    draft: ToolDraft,
+    curl: String,
+    warnings: Vec<String>,
 }
 
 #[derive(Template, WebTemplate)]
@@ -164,6 +255,57 @@ pub async fn create_tool_page(
         toolkit_title: toolkit.title,
         csrf_token: generate_csrf_token(),
         error: None,
+        draft: ToolDraft::default(),
+        curl: String::new(),
+        warnings: Vec::new(),
+    };
+
+    Ok(Html(
+        template.render().map_err(|_| AppError::InternalError)?,
+    ))
+}
+
+/// POST /toolkits/:toolkit_id/tools/curl - Prefill the add tool form from a cURL command
+pub async fn parse_curl_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(toolkit_id): Path<i64>,
+    Form(form): Form<CurlForm>,
+) -> Result<impl IntoResponse, AppError> {
+    // Check authentication
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| AppError::InternalError)?
+        .ok_or(AppError::AuthenticationFailed)?;
+
+    let toolkit_service = state
+        .toolkit_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let toolkit = toolkit_service.get_toolkit(toolkit_id, user_id).await?;
+
+    let user_email = session
+        .get::<String>("email")
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+
+    let (draft, warnings, error) = match curl_import::parse(&form.curl) {
+        Ok(import) => (ToolDraft::from(&import), import.warnings, None),
+        Err(e) => (ToolDraft::default(), Vec::new(), Some(e.to_string())),
+    };
+
+    let template = NewToolTemplate {
+        user_email,
+        toolkit_id,
+        toolkit_title: toolkit.title,
+        csrf_token: generate_csrf_token(),
+        error,
+        draft,
+        curl: form.curl,
+        warnings,
     };
 
     Ok(Html(
@@ -189,6 +331,9 @@ pub async fn create_tool_handler(
 
     let tool_service = state.tool_service.as_ref().ok_or(AppError::InternalError)?;
 
+    // Keep the entered values for the form in case validation fails
+    let draft = ToolDraft::from(&form);
+
     // Convert form to request
     let request = form.into_request();
 
@@ -215,6 +360,9 @@ pub async fn create_tool_handler(
                 toolkit_title: toolkit.title,
                 csrf_token: generate_csrf_token(),
                 error: Some(msg),
+                draft,
+                curl: String::new(),
+                warnings: Vec::new(),
             };
             Ok(Html(template.render().map_err(|_| AppError::InternalError)?).into_response())
         }
@@ -305,7 +453,10 @@ pub async fn update_tool_handler(
     let request = form.into_request();
 
     match tool_service.update_tool(tool_id, user_id, request).await {
-        Ok(()) => Ok(Redirect::to(&format!("/toolkits/{}", toolkit_id)).into_response()),
+        Ok(()) => {
+            reload_tool_servers(&state, tool_id).await;
+            Ok(Redirect::to(&format!("/toolkits/{}", toolkit_id)).into_response())
+        }
         Err(AppError::Validation(msg)) => {
             // Reload data for form
             let toolkit_service = state
@@ -340,6 +491,18 @@ pub async fn update_tool_handler(
     }
 }
 
+/// Reload the MCP tools of every server with an instance of the tool
+async fn reload_tool_servers(state: &AppState, tool_id: i64) {
+    match ToolInstance::server_ids_for_tool(&state.pool, tool_id).await {
+        Ok(server_ids) => {
+            for server_id in server_ids {
+                reload_server_tools(&state.pool, server_id, &state.mcp_registry).await;
+            }
+        }
+        Err(e) => tracing::error!("Failed to find servers using tool {}: {}", tool_id, e),
+    }
+}
+
 /// POST /toolkits/:toolkit_id/tools/:tool_id/delete - Delete tool
 pub async fn delete_tool_handler(
     State(state): State<AppState>,
@@ -355,9 +518,18 @@ pub async fn delete_tool_handler(
 
     let tool_service = state.tool_service.as_ref().ok_or(AppError::InternalError)?;
 
+    // Instances of the tool are deleted with it, so find their servers first
+    let server_ids = ToolInstance::server_ids_for_tool(&state.pool, tool_id)
+        .await
+        .map_err(|_| AppError::InternalError)?;
+
     // Delete tool (parameters will cascade)
     tool_service.delete_tool(tool_id, user_id).await?;
 
+    for server_id in server_ids {
+        reload_server_tools(&state.pool, server_id, &state.mcp_registry).await;
+    }
+
     Ok(Redirect::to(&format!("/toolkits/{}", toolkit_id)))
 }
 
@@ -1,10 +1,15 @@
 use crate::error::AppError;
-use crate::models::{CreateToolkitForm, Toolkit, UpdateToolkitForm};
+use crate::handlers::server_handlers::openapi_response;
+use crate::models::{
+    CreateToolkitForm, EndpointConfig, RateLimitConfig, RateLimitFields, Toolkit, UpdateToolkitForm,
+};
+use crate::services::{tool_import::snake_case, OpenApiExport, ToolkitBundle};
 use crate::AppState;
 use askama::Template;
 use askama_web::WebTemplate;
 use axum::{
     extract::{Path, Query, State},
+    http::header,
     response::{Html, IntoResponse, Redirect, Response},
     Form,
 };
@@ -16,15 +21,35 @@ struct ToolkitDisplay {
     pub title: String,
     pub description: String, // Convert Option to String for templates
     pub visibility: String,
+    pub base_urls: String,
+    pub endpoint_strategy: String,
+    pub failover_status_codes: String,
+    pub rate_limit: RateLimitFields,
 }
 
 impl From<Toolkit> for ToolkitDisplay {
     fn from(t: Toolkit) -> Self {
+        let endpoints = EndpointConfig::parse(t.endpoints.as_deref());
+        let rate_limit = RateLimitFields::from(RateLimitConfig::parse(t.rate_limit.as_deref()));
         ToolkitDisplay {
             id: t.id,
             title: t.title,
             description: t.description.unwrap_or_default(),
             visibility: t.visibility,
+            base_urls: endpoints
+                .as_ref()
+                .map(|e| e.base_urls_text())
+                .unwrap_or_default(),
+            endpoint_strategy: endpoints
+                .as_ref()
+                .map(|e| e.strategy.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            failover_status_codes: endpoints
+                .as_ref()
+                .map(|e| e.failover_status_codes_text())
+                .unwrap_or_else(|| "502, 503, 504".to_string()),
+            rate_limit,
         }
     }
 }
@@ -206,6 +231,82 @@ pub async fn view_toolkit_handler(
     ))
 }
 
+/// GET /toolkits/:id/export - Download the toolkit as a versioned bundle
+///
+/// `?format=json` exports JSON, anything else YAML. Bundles hold no server
+/// globals or instance configuration, so they carry no secrets.
+pub async fn export_toolkit_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<i64>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> Result<Response, AppError> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| AppError::InternalError)?
+        .ok_or(AppError::AuthenticationFailed)?;
+
+    let toolkit_service = state
+        .toolkit_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let tool_service = state.tool_service.as_ref().ok_or(AppError::InternalError)?;
+
+    let toolkit = toolkit_service.get_toolkit(id, user_id).await?;
+    let tools = tool_service.list_tools(id, user_id).await?;
+    let bundle = ToolkitBundle::from_toolkit(&toolkit, &tools);
+
+    let (document, content_type, extension) = match params.get("format").map(String::as_str) {
+        Some("json") => (bundle.to_json(), "application/json", "json"),
+        _ => (bundle.to_yaml(), "application/yaml", "yaml"),
+    };
+    let document = document.map_err(|_| AppError::InternalError)?;
+
+    let stem = match snake_case(&toolkit.title).as_str() {
+        "" => "toolkit".to_string(),
+        stem => stem.to_string(),
+    };
+    let disposition = format!("attachment; filename=\"{}.saramcp.{}\"", stem, extension);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        document,
+    )
+        .into_response())
+}
+
+/// GET /toolkits/:id/openapi - OpenAPI document of the toolkit's tools
+///
+/// Every placeholder is a parameter, since no server binds any of them.
+pub async fn toolkit_openapi_handler(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<i64>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> Result<Response, AppError> {
+    let user_id = session
+        .get::<i64>("user_id")
+        .await
+        .map_err(|_| AppError::InternalError)?
+        .ok_or(AppError::AuthenticationFailed)?;
+
+    let toolkit_service = state
+        .toolkit_service
+        .as_ref()
+        .ok_or(AppError::InternalError)?;
+    let tool_service = state.tool_service.as_ref().ok_or(AppError::InternalError)?;
+
+    let toolkit = toolkit_service.get_toolkit(id, user_id).await?;
+    let tools = tool_service.list_tools(id, user_id).await?;
+    let document = OpenApiExport::for_toolkit(&toolkit, &tools);
+
+    openapi_response(&document, &params, &toolkit.title).map_err(|_| AppError::InternalError)
+}
+
 /// GET /toolkits/:id/edit - Display edit toolkit form
 pub async fn edit_toolkit_page(
     State(state): State<AppState>,
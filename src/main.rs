@@ -4,9 +4,11 @@ use saramcp::{
     db, handlers, mcp, repositories, services, AppState,
 };
 
+use clap::{Parser, Subcommand};
+
 use axum::{
     body::Body,
-    extract::{Query, State},
+    extract::{DefaultBodyLimit, Query, State},
     http::{HeaderValue, Request, StatusCode},
     middleware::{self, Next},
     response::{Html, IntoResponse, Redirect, Response},
@@ -32,11 +34,47 @@ use tower_sessions::Session;
 use tower_sessions_sqlx_store::SqliteStore;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Largest API description accepted by the toolkit import forms
+const IMPORT_BODY_LIMIT: usize = 10 * 1024 * 1024;
+
+/// How often the registry checks for configurations applied from the CLI
+const CONFIG_RELOAD_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Parser)]
+#[command(name = "saramcp")]
+#[command(about = "SaraMCP web application and MCP server host", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Serve one server's tools over stdin/stdout for local MCP clients
+    ///
+    /// Secrets are decrypted with SARAMCP_MASTER_KEY, which must be the key
+    /// the database was written with.
+    ServeStdio {
+        /// Server UUID
+        #[arg(short, long)]
+        server: String,
+
+        /// SQLite database file (defaults to DATABASE_URL)
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load environment variables
     dotenvy::dotenv().ok();
 
+    let cli = Cli::parse();
+    if let Some(Commands::ServeStdio { server, db }) = cli.command {
+        return serve_stdio(&server, db.as_deref()).await;
+    }
+
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
@@ -107,6 +145,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    // Reload servers a configuration was applied to from the command line
+    {
+        let mcp_registry = mcp_registry.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CONFIG_RELOAD_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = mcp::McpServerRegistry::reload_changed_configs(&mcp_registry).await
+                {
+                    tracing::warn!("Failed to check MCP server configurations: {}", e);
+                }
+            }
+        });
+    }
+
     // Create app state
     let app_state = AppState {
         user_service,
@@ -146,8 +199,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Toolkit routes
         .route("/toolkits/new", get(handlers::create_toolkit_page))
         .route("/toolkits/explore", get(handlers::explore_toolkits_handler))
+        .route("/toolkits/import", get(handlers::import_toolkit_page))
+        .route("/toolkits/import/bundle", get(handlers::import_bundle_page))
+        .route(
+            "/toolkits/import/bundle/preview",
+            post(handlers::preview_bundle_handler).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/toolkits/import/bundle",
+            post(handlers::import_bundle_handler).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/toolkits/import/preview",
+            post(handlers::preview_import_handler).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/toolkits/import",
+            post(handlers::import_toolkit_handler).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
         .route("/toolkits", post(handlers::create_toolkit_handler))
         .route("/toolkits/{id}", get(handlers::view_toolkit_handler))
+        .route(
+            "/toolkits/{id}/export",
+            get(handlers::export_toolkit_handler),
+        )
+        .route(
+            "/toolkits/{id}/openapi",
+            get(handlers::toolkit_openapi_handler),
+        )
         .route(
             "/toolkits/{id}/public",
             get(handlers::view_public_toolkit_handler),
@@ -167,6 +246,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/toolkits/{toolkit_id}/tools/new",
             get(handlers::create_tool_page),
         )
+        .route(
+            "/toolkits/{toolkit_id}/tools/curl",
+            post(handlers::parse_curl_handler),
+        )
         .route(
             "/toolkits/{toolkit_id}/tools",
             post(handlers::create_tool_handler),
@@ -214,10 +297,52 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/servers/{id}/access",
             post(handlers::update_server_access_handler),
         )
+        .route(
+            "/servers/{id}/network",
+            post(handlers::update_server_network_handler),
+        )
+        .route(
+            "/servers/{id}/config",
+            get(handlers::server_config_page).post(handlers::apply_server_config_handler),
+        )
+        .route(
+            "/servers/{id}/config.yaml",
+            get(handlers::download_server_config_handler),
+        )
+        .route(
+            "/servers/{id}/openapi",
+            get(handlers::server_openapi_handler),
+        )
+        .route(
+            "/servers/{id}/resources",
+            post(handlers::save_resource_document_handler),
+        )
+        .route(
+            "/servers/{id}/resources/publish",
+            post(handlers::publish_instance_resource_handler),
+        )
+        .route(
+            "/servers/{id}/resources/{resource_id}/delete",
+            post(handlers::delete_resource_handler),
+        )
+        .route("/servers/{id}/prompts", post(handlers::save_prompt_handler))
+        .route(
+            "/servers/{id}/prompts/{prompt_id}/delete",
+            post(handlers::delete_prompt_handler),
+        )
         .route(
             "/servers/{id}/install-toolkit",
             post(handlers::install_toolkit_handler),
         )
+        .route("/servers/{id}/mcp-import", get(handlers::mcp_import_page))
+        .route(
+            "/servers/{id}/mcp-import/preview",
+            post(handlers::preview_mcp_import_handler),
+        )
+        .route(
+            "/servers/{id}/mcp-import",
+            post(handlers::mcp_import_handler),
+        )
         // Instance routes
         .route(
             "/servers/{id}/instances/new",
@@ -235,6 +360,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/servers/{id}/instances/{instance_id}",
             post(handlers::update_instance_handler),
         )
+        .route(
+            "/servers/{id}/instances/{instance_id}/limits",
+            post(handlers::update_instance_limits_handler),
+        )
         .route(
             "/servers/{id}/instances/{instance_id}/delete",
             post(handlers::delete_instance_handler),
@@ -344,15 +473,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             axum::http::header::CACHE_CONTROL,
             axum::http::header::USER_AGENT,
             HeaderName::from_static("mcp-protocol-version"),
+            HeaderName::from_static("mcp-session-id"),
+            HeaderName::from_static("last-event-id"),
         ])
+        .expose_headers([HeaderName::from_static("mcp-session-id")])
         .max_age(std::time::Duration::from_secs(3600));
 
     // Add Streamable HTTP routes (before merging SSE routers)
-    // These handle POST requests to /s/{uuid} for simple request/response
+    // POST carries client messages, GET opens a session's stream and DELETE ends it
     let http_routes = Router::new()
         .route(
             "/s/{uuid}",
             post(mcp::http_transport::handle_streamable_http)
+                .get(mcp::http_transport::handle_streamable_http_get)
+                .delete(mcp::http_transport::handle_streamable_http_delete)
                 .options(mcp::http_transport::handle_streamable_http_options),
         )
         .with_state(mcp_registry.clone())
@@ -364,6 +498,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     app = app.merge(http_routes);
 
+    // OpenAPI document of a server at its MCP address, behind the same access control
+    let openapi_routes = Router::new()
+        .route("/s/{uuid}/openapi.json", get(handlers::mcp_openapi_handler))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            saramcp::middleware::mcp_auth_middleware,
+        ))
+        .layer(cors_layer.clone())
+        .with_state(app_state.clone());
+
+    app = app.merge(openapi_routes);
+
     tracing::info!("Registered HTTP transport routes at /s/{{uuid}}");
 
     // Dynamic subdomain SSE routing (doxyde pattern)
@@ -809,3 +955,23 @@ pub mod templates {
         pub tools_count: i32,
     }
 }
+
+/// Runs the stdio transport, logging to stderr since stdout carries the protocol
+async fn serve_stdio(server: &str, db: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "saramcp=info".into()),
+        )
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    let pool = match db {
+        Some(path) => db::create_pool_at(path).await?,
+        None => db::create_pool().await?,
+    };
+    sqlx::migrate!("./migrations").run(&pool).await?;
+
+    mcp::stdio::serve_stdio(pool, server).await?;
+    Ok(())
+}
@@ -0,0 +1,313 @@
+//! Argument completion for prompts and resource templates
+//!
+//! Prompt arguments complete from the `enum` or `lookup` of their argument
+//! documentation, resource template variables from the parameter metadata
+//! of the instance's tool. Enum values are matched by prefix. A lookup names
+//! a tool instance of the server: when it exposes a parameter it is called
+//! with the partial value and its answer is used as is, otherwise it returns
+//! every value and they are matched by prefix like an enum. The instance
+//! answers with a JSON array or one value per line, and answers are cached
+//! for [`LOOKUP_TTL`].
+
+use crate::models::{ServerPrompt, ServerResource, Tool, ToolInstance, RESOURCE_URI_PREFIX};
+use crate::services::execution_context::ExecutionContext;
+use crate::services::instance_executor::{result_text, InstanceExecutor};
+use crate::services::parameter_resolver::ParameterResolver;
+use crate::services::secrets_manager::SecretsManager;
+use rmcp::model::{CompleteRequestParam, CompleteResult, CompletionInfo, ErrorData, Reference};
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long lookup answers are reused
+pub const LOOKUP_TTL: Duration = Duration::from_secs(30);
+
+/// Where the values of an argument come from
+enum Source {
+    Values(Vec<String>),
+    Lookup(String),
+    None,
+}
+
+/// Lookup answers keyed by instance ID and the value sent to it
+type LookupAnswers = HashMap<(i64, String), (Instant, Vec<String>)>;
+
+/// Recent lookup answers, shared by the connections of a server
+#[derive(Clone, Default)]
+pub struct CompletionCache {
+    entries: Arc<Mutex<LookupAnswers>>,
+}
+
+impl CompletionCache {
+    fn get(&self, instance_id: i64, value: &str) -> Option<Vec<String>> {
+        let entries = self.entries.lock().ok()?;
+        entries
+            .get(&(instance_id, value.to_string()))
+            .filter(|(at, _)| at.elapsed() < LOOKUP_TTL)
+            .map(|(_, values)| values.clone())
+    }
+
+    fn insert(&self, instance_id: i64, value: &str, values: Vec<String>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|_, (at, _)| at.elapsed() < LOOKUP_TTL);
+            entries.insert((instance_id, value.to_string()), (Instant::now(), values));
+        }
+    }
+}
+
+/// Complete the value of a prompt argument or resource template variable
+pub async fn complete(
+    pool: &SqlitePool,
+    server_id: i64,
+    secrets: &SecretsManager,
+    cache: &CompletionCache,
+    request: &CompleteRequestParam,
+    context: &ExecutionContext,
+) -> Result<CompleteResult, ErrorData> {
+    let internal = |e: anyhow::Error| ErrorData::internal_error(e.to_string(), None);
+    let argument = &request.argument;
+
+    let source = match &request.r#ref {
+        Reference::Prompt(prompt) => {
+            let prompt = ServerPrompt::get_by_name(pool, server_id, &prompt.name)
+                .await
+                .map_err(internal)?
+                .ok_or_else(|| {
+                    ErrorData::invalid_params(format!("Prompt '{}' not found", prompt.name), None)
+                })?;
+            match prompt.argument_specs().remove(&argument.name) {
+                Some(spec) if !spec.enum_values.is_empty() => Source::Values(spec.enum_values),
+                Some(spec) => spec.lookup.map_or(Source::None, Source::Lookup),
+                None => Source::None,
+            }
+        }
+        Reference::Resource(resource) => {
+            let not_found = || {
+                ErrorData::resource_not_found(
+                    format!("Resource '{}' not found", resource.uri),
+                    None,
+                )
+            };
+            let path = resource
+                .uri
+                .strip_prefix(RESOURCE_URI_PREFIX)
+                .ok_or_else(not_found)?;
+            let name = path.split(['{', '?']).next().unwrap_or(path);
+            let resource = ServerResource::get_by_name(pool, server_id, name)
+                .await
+                .map_err(internal)?
+                .ok_or_else(not_found)?;
+            match resource.instance_id {
+                Some(instance_id) => parameter_source(pool, instance_id, &argument.name).await?,
+                None => Source::None,
+            }
+        }
+    };
+
+    let values = match source {
+        Source::Values(values) => matching(values, &argument.value),
+        Source::Lookup(instance_name) => {
+            lookup(
+                pool,
+                server_id,
+                secrets,
+                cache,
+                &instance_name,
+                &argument.value,
+                context,
+            )
+            .await?
+        }
+        Source::None => Vec::new(),
+    };
+    Ok(CompleteResult {
+        completion: completion_info(values),
+    })
+}
+
+/// Completion source of a tool instance parameter, from its tool's metadata
+async fn parameter_source(
+    pool: &SqlitePool,
+    instance_id: i64,
+    name: &str,
+) -> Result<Source, ErrorData> {
+    let internal = |e: anyhow::Error| ErrorData::internal_error(e.to_string(), None);
+
+    let Some(instance) = ToolInstance::get_by_id(pool, instance_id)
+        .await
+        .map_err(internal)?
+    else {
+        return Ok(Source::None);
+    };
+    let Some(tool) = Tool::get_by_id(pool, instance.tool_id)
+        .await
+        .map_err(|e| internal(e.into()))?
+    else {
+        return Ok(Source::None);
+    };
+
+    Ok(match tool.parameter_metadata_map().remove(name) {
+        Some(meta) if !meta.enum_values.is_empty() => {
+            Source::Values(meta.enum_values.iter().map(value_text).collect())
+        }
+        Some(meta) => meta.lookup.map_or(Source::None, Source::Lookup),
+        None => Source::None,
+    })
+}
+
+/// Values suggested by a lookup instance for a partial value
+async fn lookup(
+    pool: &SqlitePool,
+    server_id: i64,
+    secrets: &SecretsManager,
+    cache: &CompletionCache,
+    instance_name: &str,
+    partial: &str,
+    context: &ExecutionContext,
+) -> Result<Vec<String>, ErrorData> {
+    let internal = |e: anyhow::Error| ErrorData::internal_error(e.to_string(), None);
+
+    let instance = ToolInstance::find_by_server_and_name(pool, server_id, instance_name)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| {
+            ErrorData::internal_error(
+                format!("Lookup tool instance '{}' not found", instance_name),
+                None,
+            )
+        })?;
+    let instance_id = instance
+        .id
+        .ok_or_else(|| ErrorData::internal_error("Instance missing ID", None))?;
+
+    // An instance without exposed parameters lists every value
+    let exposed = ParameterResolver::get_exposed_params(pool, instance_id)
+        .await
+        .map_err(internal)?;
+    let mut arguments = Map::new();
+    if let Some(param) = exposed.first() {
+        arguments.insert(param.clone(), Value::String(partial.to_string()));
+    }
+    let sent = if exposed.is_empty() { "" } else { partial };
+
+    let values = match cache.get(instance_id, sent) {
+        Some(values) => values,
+        None => {
+            let tool = Tool::get_by_id(pool, instance.tool_id)
+                .await
+                .map_err(|e| internal(e.into()))?
+                .ok_or_else(|| ErrorData::internal_error("Tool not found", None))?;
+            let executor =
+                InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets.clone());
+            let result = executor
+                .execute_with_context(Some(arguments), context)
+                .await?;
+
+            let text = result_text(&result);
+            if result.is_error == Some(true) {
+                return Err(ErrorData::internal_error(text, None));
+            }
+
+            let values = parse_values(&text);
+            cache.insert(instance_id, sent, values.clone());
+            values
+        }
+    };
+
+    Ok(if exposed.is_empty() {
+        matching(values, partial)
+    } else {
+        values
+    })
+}
+
+/// Text of an enum or lookup value
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Values of a lookup answer: a JSON array, or one value per line
+fn parse_values(text: &str) -> Vec<String> {
+    match serde_json::from_str::<Value>(text) {
+        Ok(Value::Array(items)) => items
+            .iter()
+            .filter(|item| !item.is_object() && !item.is_array() && !item.is_null())
+            .map(value_text)
+            .collect(),
+        _ => text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// Values starting with the partial value, ignoring case
+fn matching(values: Vec<String>, partial: &str) -> Vec<String> {
+    let partial = partial.to_lowercase();
+    values
+        .into_iter()
+        .filter(|value| value.to_lowercase().starts_with(&partial))
+        .collect()
+}
+
+/// First values that fit in a response, with the total count
+fn completion_info(mut values: Vec<String>) -> CompletionInfo {
+    let total = values.len();
+    values.truncate(CompletionInfo::MAX_VALUES);
+    CompletionInfo {
+        has_more: Some(total > values.len()),
+        total: Some(total as u32),
+        values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_enum_values_match_by_prefix_ignoring_case() {
+        let values = vec![
+            "Open".to_string(),
+            "closed".to_string(),
+            "on-hold".to_string(),
+        ];
+        assert_eq!(matching(values.clone(), "o"), vec!["Open", "on-hold"]);
+        assert_eq!(matching(values, ""), vec!["Open", "closed", "on-hold"]);
+        assert_eq!(value_text(&json!(3)), "3");
+    }
+
+    #[test]
+    fn test_lookup_answers_are_json_arrays_or_lines() {
+        assert_eq!(
+            parse_values(r#"["acme", 42, {"id": 1}]"#),
+            vec!["acme", "42"]
+        );
+        assert_eq!(parse_values("acme\n\n  globex \n"), vec!["acme", "globex"]);
+    }
+
+    #[test]
+    fn test_completions_are_capped() {
+        let info = completion_info((0..150).map(|i| i.to_string()).collect());
+        assert_eq!(info.values.len(), CompletionInfo::MAX_VALUES);
+        assert_eq!(info.total, Some(150));
+        assert_eq!(info.has_more, Some(true));
+    }
+
+    #[test]
+    fn test_cache_reuses_answers() {
+        let cache = CompletionCache::default();
+        assert_eq!(cache.get(1, "ac"), None);
+        cache.insert(1, "ac", vec!["acme".to_string()]);
+        assert_eq!(cache.get(1, "ac"), Some(vec!["acme".to_string()]));
+        assert_eq!(cache.get(2, "ac"), None);
+    }
+}
@@ -1,19 +1,34 @@
 //! Streamable HTTP transport handler for MCP
 //!
-//! Provides a simple HTTP POST endpoint for MCP protocol messages.
-//! This is the primary transport method, with SSE as a fallback for
-//! clients that require long-lived connections.
+//! Implements the MCP Streamable HTTP transport at a single endpoint per
+//! server. This is the primary transport method, with the legacy SSE
+//! transport as a fallback for older clients.
 //!
 //! # URL Structure
 //!
-//! - `POST /s/{uuid}` - Send JSON-RPC request, receive JSON response
+//! - `POST /s/{uuid}` - Send a JSON-RPC message or batch; responses come back
+//!   as JSON, or as an SSE stream when the client accepts `text/event-stream`
+//! - `GET /s/{uuid}` - Open the session's SSE stream of server-initiated messages
+//! - `DELETE /s/{uuid}` - End the session
 //! - `OPTIONS /s/{uuid}` - CORS preflight
 //!
+//! # Sessions
+//!
+//! A successful `initialize` opens a session whose ID is returned in the
+//! `Mcp-Session-Id` header; clients send it with every later request. An
+//! unknown or ended session gets `404 Not Found`, after which the client
+//! initializes again. Requests without a session header are still served
+//! statelessly, but only a session can open a GET stream.
+//!
+//! Every SSE event carries an ID. A client that lost a stream reconnects
+//! with `GET` and `Last-Event-ID` to receive the events it missed.
+//!
 //! # Usage
 //!
 //! ```http
 //! POST /s/550e8400-e29b-41d4-a716-446655440000
 //! Content-Type: application/json
+//! Accept: application/json, text/event-stream
 //!
 //! {"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}
 //! ```
@@ -21,41 +36,66 @@
 //! Response:
 //! ```http
 //! HTTP/1.1 200 OK
-//! Content-Type: application/json
+//! Content-Type: text/event-stream
+//! Mcp-Session-Id: 1868a90c6f5e4e4c8b1a2f5a0f1d3c77
 //!
-//! {"jsonrpc":"2.0","id":1,"result":{...}}
+//! id: 1
+//! event: message
+//! data: {"jsonrpc":"2.0","id":1,"result":{...}}
 //! ```
 
 use axum::{
-    body::Body,
+    body::{Body, Bytes},
     extract::{Path, State},
-    http::{header, HeaderMap, StatusCode},
-    response::{IntoResponse, Response},
-    Json,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Extension,
 };
+use rmcp::model::{ErrorCode, ErrorData};
 use serde_json::Value;
+use std::convert::Infallible;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio::task::JoinSet;
 
+use crate::error::McpServiceError;
 use crate::mcp::registry::McpServerRegistry;
+use crate::mcp::service::{RequestOrigin, SaraMcpService, SUPPORTED_PROTOCOL_VERSIONS};
+use crate::mcp::session::{McpSession, McpSessionStore, StreamId, SESSION_HEADER};
+use crate::services::execution_context::ClientRequests;
+use crate::services::oauth_service::ValidatedToken;
+
+/// Header carrying the negotiated protocol version on requests after `initialize`
+pub const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// Header a client resumes a stream from
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
 
 /// POST /s/{uuid} - Streamable HTTP transport
 ///
-/// Handles a single MCP JSON-RPC request and returns the response.
-/// This is a simple request/response pattern, unlike SSE which maintains
-/// a persistent connection.
+/// Handles a JSON-RPC message, or a batch of them, and returns the responses.
 ///
 /// # Arguments
 ///
 /// * `Path(uuid)` - Server UUID from URL path
 /// * `State(registry)` - MCP server registry
-/// * `Json(request)` - JSON-RPC request body
+/// * `token` - OAuth token validated by `mcp_auth_middleware`, if the server requires one
+/// * `headers` - Request headers: `Accept`, `Mcp-Session-Id`, `MCP-Protocol-Version`
+/// * `body` - JSON-RPC request, notification or batch array
 ///
 /// # Returns
 ///
-/// * `200 OK` with JSON-RPC response on success
-/// * `404 Not Found` if server UUID doesn't exist
-/// * `500 Internal Server Error` if request processing fails
+/// * `200 OK` with the JSON-RPC response, or an array of them for a batch,
+///   or an SSE stream of the responses when `text/event-stream` is accepted
+/// * `202 Accepted` with no body when only notifications or responses were
+///   sent (e.g. `notifications/initialized`, `notifications/cancelled`, or
+///   the answer to an `elicitation/create` request)
+/// * `400 Bad Request` with a JSON-RPC error for unparseable JSON, an empty
+///   batch or an unsupported `MCP-Protocol-Version` header
+/// * `404 Not Found` if the server UUID or the session doesn't exist
 ///
 /// # Example
 ///
@@ -67,51 +107,464 @@ use crate::mcp::registry::McpServerRegistry;
 ///   "jsonrpc": "2.0",
 ///   "id": 1,
 ///   "method": "initialize",
-///   "params": {}
+///   "params": {"protocolVersion": "2025-06-18"}
 /// }
 /// ```
 pub async fn handle_streamable_http(
     Path(uuid): Path<String>,
     State(registry): State<Arc<RwLock<McpServerRegistry>>>,
-    Json(request): Json<Value>,
+    token: Option<Extension<ValidatedToken>>,
+    headers: HeaderMap,
+    body: Bytes,
 ) -> Result<Response, StatusCode> {
     tracing::debug!(uuid = %uuid, "Received HTTP transport request");
 
     // 1. Get MCP service from registry
-    let service = {
-        let registry = registry.read().await;
-        match registry.get_instance(&uuid) {
-            Some(instance) => instance.get_service(),
-            None => {
-                tracing::warn!(uuid = %uuid, "Server instance not found");
-                return Err(StatusCode::NOT_FOUND);
+    let (service, sessions) = service_and_sessions(&registry, &uuid).await?;
+    let user_id = token.map(|Extension(token)| token.user_id);
+
+    // 2. Requests after initialization name the negotiated protocol version
+    if let Some(version) = headers.get(PROTOCOL_VERSION_HEADER) {
+        let supported = version.to_str().is_ok_and(|version| {
+            SUPPORTED_PROTOCOL_VERSIONS
+                .iter()
+                .any(|supported| supported.to_string() == version)
+        });
+        if !supported {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorData::invalid_request("Unsupported MCP-Protocol-Version", None),
+            );
+        }
+    }
+
+    // 3. Requests naming a session must name an open one
+    let session = match headers.get(SESSION_HEADER) {
+        Some(session_id) => match session_id
+            .to_str()
+            .ok()
+            .and_then(|id| sessions.get(&uuid, id, user_id))
+        {
+            Some(session) => Some(session),
+            None => return session_not_found(),
+        },
+        None => None,
+    };
+
+    // 4. Parse the message
+    let message = match serde_json::from_slice::<Value>(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorData::new(ErrorCode::PARSE_ERROR, format!("Parse error: {}", e), None),
+            )
+        }
+    };
+    let requests = match message {
+        Value::Array(batch) if batch.is_empty() => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorData::invalid_request("Empty batch", None),
+            )
+        }
+        Value::Array(batch) => batch,
+        request => vec![request],
+    };
+
+    // Responses answer requests the server sent to the client
+    let (answers, requests): (Vec<Value>, Vec<Value>) =
+        requests.into_iter().partition(is_client_response);
+    for answer in &answers {
+        if !session.as_ref().is_some_and(|s| s.respond(answer)) {
+            tracing::debug!(id = ?answer.get("id"), "Ignoring response to no pending request");
+        }
+    }
+    if requests.is_empty() {
+        let mut headers = HeaderMap::new();
+        add_cors_headers(&mut headers);
+        return Ok((StatusCode::ACCEPTED, headers).into_response());
+    }
+
+    // 5. A lone initialize without a session opens one
+    let opens_session = session.is_none()
+        && requests.len() == 1
+        && requests[0].get("method").and_then(|m| m.as_str()) == Some("initialize");
+    let expects_response = requests.iter().any(|r| r.get("id").is_some());
+    let client_capabilities = requests[0].pointer("/params/capabilities").cloned();
+
+    // 6. Stream the responses to clients accepting SSE
+    if expects_response && accepts_event_stream(&headers) {
+        let mut new_session = opens_session.then(|| {
+            let session = sessions.open(&uuid, user_id);
+            session.set_client_capabilities(client_capabilities.as_ref());
+            session
+        });
+        let stream_session = session
+            .or_else(|| new_session.clone())
+            .unwrap_or_else(|| McpSession::ephemeral(&uuid));
+
+        let stream = stream_session.open_request_stream();
+        // Messages sent while handling the requests precede their responses
+        let origin = session_origin(&stream_session, stream);
+        let mut requests = requests;
+
+        // The initialize opening a session runs before the response is
+        // sent, so the session is only handed out once it succeeded
+        if let Some(opened) = new_session.take() {
+            let response = service
+                .handle_request_from(requests.remove(0), &origin)
+                .await
+                .map_err(|e| e.to_string());
+            if response
+                .as_ref()
+                .is_ok_and(|response| response.get("result").is_some())
+            {
+                new_session = Some(Arc::clone(&opened));
+            } else {
+                sessions.remove(&uuid, &opened.id, user_id);
             }
+            send_response(&stream_session, stream, response);
         }
+
+        let sender = Arc::clone(&stream_session);
+        tokio::spawn(async move {
+            // Requests of a batch run concurrently, so one may cancel another
+            let mut tasks = JoinSet::new();
+            for request in requests {
+                let service = Arc::clone(&service);
+                let origin = origin.clone();
+                tasks.spawn(async move { service.handle_request_from(request, &origin).await });
+            }
+            while let Some(joined) = tasks.join_next().await {
+                let response = joined
+                    .map_err(|e| e.to_string())
+                    .and_then(|response| response.map_err(|e| e.to_string()));
+                send_response(&sender, stream, response);
+            }
+            sender.send(stream, None);
+        });
+
+        let mut response = event_stream(stream_session, stream, 0).into_response();
+        if let Some(session) = new_session {
+            add_session_header(response.headers_mut(), &session)?;
+        }
+        add_cors_headers(response.headers_mut());
+        return Ok(response);
+    }
+
+    // 7. Otherwise answer with JSON, running batch requests concurrently.
+    //    Messages sent meanwhile go to the session's GET stream.
+    let origin = session
+        .map(|session| session_origin(&session, StreamId::Standalone))
+        .unwrap_or_else(RequestOrigin::stateless);
+    let batch = requests.len() > 1;
+    let mut tasks = JoinSet::new();
+    for (index, request) in requests.into_iter().enumerate() {
+        let service = Arc::clone(&service);
+        let origin = origin.clone();
+        tasks.spawn(async move { (index, service.handle_request_from(request, &origin).await) });
+    }
+
+    let mut responses = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (index, response) = joined.map_err(|e| {
+            tracing::error!(error = %e, "Request task failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        responses.push((index, handled(response)?));
+    }
+    responses.sort_by_key(|(index, _)| *index);
+    let mut responses: Vec<Value> = responses
+        .into_iter()
+        .map(|(_, response)| response)
+        .filter(|response| !response.is_null())
+        .collect();
+
+    // 8. Notifications are acknowledged without a body
+    if responses.is_empty() {
+        let mut headers = HeaderMap::new();
+        add_cors_headers(&mut headers);
+        return Ok((StatusCode::ACCEPTED, headers).into_response());
+    }
+
+    let response = match batch {
+        true => Value::Array(responses),
+        false => responses.remove(0),
+    };
+    let mut http_response = json_response(StatusCode::OK, &response)?;
+    if opens_session && response.get("result").is_some() {
+        let session = sessions.open(&uuid, user_id);
+        session.set_client_capabilities(client_capabilities.as_ref());
+        add_session_header(http_response.headers_mut(), &session)?;
+    }
+    Ok(http_response)
+}
+
+/// GET /s/{uuid} - Stream of server-initiated messages
+///
+/// Opens the SSE stream a session receives notifications on, such as list
+/// changes and log messages. With `Last-Event-ID`, the stream that event
+/// belongs to is resumed instead, replaying the events sent after it.
+///
+/// # Returns
+///
+/// * `200 OK` with an SSE stream
+/// * `400 Bad Request` without an `Mcp-Session-Id` header
+/// * `404 Not Found` if the server UUID or the session doesn't exist
+/// * `405 Method Not Allowed` if the client doesn't accept `text/event-stream`
+pub async fn handle_streamable_http_get(
+    Path(uuid): Path<String>,
+    State(registry): State<Arc<RwLock<McpServerRegistry>>>,
+    token: Option<Extension<ValidatedToken>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let (_, sessions) = service_and_sessions(&registry, &uuid).await?;
+    let user_id = token.map(|Extension(token)| token.user_id);
+
+    if !accepts_event_stream(&headers) {
+        return Err(StatusCode::METHOD_NOT_ALLOWED);
+    }
+    let Some(session_id) = headers.get(SESSION_HEADER) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            ErrorData::invalid_request("Missing Mcp-Session-Id header", None),
+        );
+    };
+    let Some(session) = session_id
+        .to_str()
+        .ok()
+        .and_then(|id| sessions.get(&uuid, id, user_id))
+    else {
+        return session_not_found();
+    };
+
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+        .and_then(|id| id.trim().parse::<u64>().ok());
+    let (stream, after) = match last_event_id {
+        Some(id) => (session.stream_of(id).unwrap_or(StreamId::Standalone), id),
+        None => (StreamId::Standalone, session.last_event_id()),
     };
 
-    // 2. Process JSON-RPC request
-    let response = service.handle_request(request).await.map_err(|e| {
+    let mut response = event_stream(session, stream, after).into_response();
+    add_cors_headers(response.headers_mut());
+    Ok(response)
+}
+
+/// DELETE /s/{uuid} - End a session
+///
+/// # Returns
+///
+/// * `204 No Content` once the session and its streams are closed
+/// * `400 Bad Request` without an `Mcp-Session-Id` header
+/// * `404 Not Found` if the server UUID or the session doesn't exist
+pub async fn handle_streamable_http_delete(
+    Path(uuid): Path<String>,
+    State(registry): State<Arc<RwLock<McpServerRegistry>>>,
+    token: Option<Extension<ValidatedToken>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let (_, sessions) = service_and_sessions(&registry, &uuid).await?;
+    let user_id = token.map(|Extension(token)| token.user_id);
+
+    let Some(session_id) = headers.get(SESSION_HEADER) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            ErrorData::invalid_request("Missing Mcp-Session-Id header", None),
+        );
+    };
+    let removed = session_id
+        .to_str()
+        .is_ok_and(|id| sessions.remove(&uuid, id, user_id));
+    if !removed {
+        return session_not_found();
+    }
+
+    let mut headers = HeaderMap::new();
+    add_cors_headers(&mut headers);
+    Ok((StatusCode::NO_CONTENT, headers).into_response())
+}
+
+async fn service_and_sessions(
+    registry: &RwLock<McpServerRegistry>,
+    uuid: &str,
+) -> Result<(Arc<SaraMcpService>, McpSessionStore), StatusCode> {
+    let registry = registry.read().await;
+    match registry.get_instance(uuid) {
+        Some(instance) => Ok((instance.get_service(), registry.sessions())),
+        None => {
+            tracing::warn!(uuid = %uuid, "Server instance not found");
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+/// Sends the response to a request on `stream`, logging requests that failed
+fn send_response(session: &McpSession, stream: StreamId, response: Result<Value, String>) {
+    match response {
+        Ok(response) if response.is_null() => {}
+        Ok(response) => {
+            session.send(stream, Some(response));
+        }
+        Err(e) => tracing::error!(error = %e, "Failed to handle request"),
+    }
+}
+
+/// Origin of requests of a session, sending their messages on `stream`
+fn session_origin(session: &Arc<McpSession>, stream: StreamId) -> RequestOrigin {
+    let requests = session
+        .supports_elicitation()
+        .then(|| client_requests(Arc::clone(session), stream));
+    let session = Arc::clone(session);
+    RequestOrigin {
+        client: session.id.clone(),
+        log_level: session.log_level.clone(),
+        notifications: Some(Arc::new(move |message| {
+            session.send(stream, Some(message));
+        })),
+        requests,
+    }
+}
+
+/// Requests to a session's client, sent on `stream`
+fn client_requests(session: Arc<McpSession>, stream: StreamId) -> ClientRequests {
+    Arc::new(move |method, params| {
+        let session = Arc::clone(&session);
+        let method = method.to_string();
+        Box::pin(async move { session.request(stream, &method, params).await })
+    })
+}
+
+/// True for a JSON-RPC response, as opposed to a request or notification
+fn is_client_response(message: &Value) -> bool {
+    message.get("method").is_none()
+        && message.get("id").is_some()
+        && (message.get("result").is_some() || message.get("error").is_some())
+}
+
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .any(|value| value.contains("text/event-stream"))
+}
+
+/// SSE stream of a session's events on one stream after the given event ID
+///
+/// A request stream ends after its last response; the standalone stream
+/// stays open until the session ends.
+fn event_stream(
+    session: Arc<McpSession>,
+    stream: StreamId,
+    after: u64,
+) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::channel::<Event>(32);
+
+    tokio::spawn(async move {
+        // Subscribe before reading the history so no event falls in between
+        let mut live = session.subscribe();
+        let mut last = after;
+
+        for event in session.events_after(stream, after) {
+            last = event.id;
+            let Some(message) = event.message else {
+                return;
+            };
+            if tx.send(sse_event(event.id, &message)).await.is_err() {
+                return;
+            }
+        }
+
+        loop {
+            let event = tokio::select! {
+                _ = session.closed().cancelled() => return,
+                _ = tx.closed() => return,
+                event = live.recv() => event,
+            };
+            match event {
+                Ok(event) if event.stream != stream || event.id <= last => {}
+                Ok(event) => {
+                    last = event.id;
+                    let Some(message) = event.message else {
+                        return;
+                    };
+                    if tx.send(sse_event(event.id, &message)).await.is_err() {
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "MCP session stream lagged behind");
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    });
+
+    let events = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok(event), rx))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+fn sse_event(id: u64, message: &Value) -> Event {
+    Event::default()
+        .id(id.to_string())
+        .event("message")
+        .data(message.to_string())
+}
+
+fn handled(response: Result<Value, McpServiceError>) -> Result<Value, StatusCode> {
+    response.map_err(|e| {
         tracing::error!(error = %e, "Failed to handle request");
         StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    })
+}
 
-    // 3. Return streaming response with CORS headers
-    let body = Body::from(
-        serde_json::to_string(&response).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
-    );
+fn add_session_header(headers: &mut HeaderMap, session: &McpSession) -> Result<(), StatusCode> {
+    let value =
+        HeaderValue::from_str(&session.id).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    headers.insert(SESSION_HEADER, value);
+    Ok(())
+}
 
-    let mut headers = HeaderMap::new();
+fn add_cors_headers(headers: &mut HeaderMap) {
     // Use static header values - these are known to be valid
     headers.insert(
-        header::CONTENT_TYPE,
-        header::HeaderValue::from_static("application/json"),
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        HeaderValue::from_static("*"),
     );
     headers.insert(
-        header::ACCESS_CONTROL_ALLOW_ORIGIN,
-        header::HeaderValue::from_static("*"),
+        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static("Mcp-Session-Id"),
     );
+}
+
+fn session_not_found() -> Result<Response, StatusCode> {
+    error_response(
+        StatusCode::NOT_FOUND,
+        ErrorData::invalid_request("Session not found, initialize a new one", None),
+    )
+}
 
-    Ok((StatusCode::OK, headers, body).into_response())
+fn error_response(status: StatusCode, error: ErrorData) -> Result<Response, StatusCode> {
+    json_response(status, &SaraMcpService::error_response(Value::Null, error))
+}
+
+fn json_response(status: StatusCode, response: &Value) -> Result<Response, StatusCode> {
+    let body =
+        Body::from(serde_json::to_string(response).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    add_cors_headers(&mut headers);
+
+    Ok((status, headers, body).into_response())
 }
 
 /// OPTIONS /s/{uuid} - CORS preflight handler
@@ -126,19 +579,25 @@ pub async fn handle_streamable_http_options() -> Response {
     // Use static header values - these are known to be valid
     headers.insert(
         header::ACCESS_CONTROL_ALLOW_ORIGIN,
-        header::HeaderValue::from_static("*"),
+        HeaderValue::from_static("*"),
     );
     headers.insert(
         header::ACCESS_CONTROL_ALLOW_METHODS,
-        header::HeaderValue::from_static("POST, OPTIONS"),
+        HeaderValue::from_static("GET, POST, DELETE, OPTIONS"),
     );
     headers.insert(
         header::ACCESS_CONTROL_ALLOW_HEADERS,
-        header::HeaderValue::from_static("Content-Type, Authorization"),
+        HeaderValue::from_static(
+            "Content-Type, Authorization, Accept, MCP-Protocol-Version, Mcp-Session-Id, Last-Event-ID",
+        ),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static("Mcp-Session-Id"),
     );
     headers.insert(
         header::ACCESS_CONTROL_MAX_AGE,
-        header::HeaderValue::from_static("3600"),
+        HeaderValue::from_static("3600"),
     );
 
     (StatusCode::NO_CONTENT, headers).into_response()
@@ -6,7 +6,6 @@
 use crate::mcp::registry::RegistryError;
 use crate::mcp::service::SaraMcpService;
 use axum::Router as AxumRouter;
-use rmcp::handler::server::router::Router as McpRouter;
 use rmcp::transport::sse_server::{SseServer, SseServerConfig};
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -56,11 +55,6 @@ impl McpServerInstance {
             |e| RegistryError::InstanceCreation(format!("Failed to create MCP service: {}", e)),
         )?);
 
-        // Get tool routes from the service
-        let tool_routes = service.get_tool_routes().await.map_err(|e| {
-            RegistryError::InstanceCreation(format!("Failed to get tool routes: {}", e))
-        })?;
-
         let bind_addr = "127.0.0.1:0"
             .parse()
             .map_err(|e| RegistryError::InstanceCreation(format!("Invalid bind addr: {}", e)))?;
@@ -76,11 +70,9 @@ impl McpServerInstance {
 
         let (subdomain_sse_server, subdomain_sse_router) = SseServer::new(subdomain_config);
         let service_clone = service.clone();
-        subdomain_sse_server.with_service(move || {
-            // Create a Router with the service and tool routes
-            let router = McpRouter::new((*service_clone).clone());
-            router.with_tools(tool_routes.clone())
-        });
+        // Connections share the tool router, so they see reloaded tools, and
+        // each keeps its own log level
+        subdomain_sse_server.with_service(move || service_clone.connection());
 
         Ok(Self {
             server_id,
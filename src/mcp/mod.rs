@@ -9,6 +9,11 @@
 //! - [`McpServerRegistry`] - Thread-safe registry of active MCP server instances
 //! - [`McpServerInstance`] - Individual server with SSE transport and service
 //! - [`SaraMcpService`] - MCP protocol handler (stub for Task 002, full impl in Task 003)
+//! - [`resources`] - Server documents and tool instances served as MCP resources
+//! - [`prompts`] - Server prompt templates served as MCP prompts
+//! - [`completions`] - Completion of prompt arguments and resource template variables
+//! - [`session`] - Streamable HTTP sessions and their resumable event streams
+//! - [`stdio`] - One server served over stdin/stdout for local clients
 //! - [`mcp_sse_handler`] and [`mcp_message_handler`] - HTTP request dispatchers
 //!
 //! # Example
@@ -33,13 +38,19 @@
 //! # }
 //! ```
 
+pub mod completions;
 pub mod handlers;
 pub mod http_transport;
 pub mod instance;
+pub mod prompts;
 pub mod registry;
+pub mod resources;
 pub mod service;
+pub mod session;
+pub mod stdio;
 
 pub use handlers::{mcp_message_handler, mcp_sse_handler};
 pub use instance::McpServerInstance;
 pub use registry::{McpServerRegistry, RegistryError, SharedRegistry};
-pub use service::SaraMcpService;
+pub use service::{ListChanged, SaraMcpService};
+pub use session::{McpSession, McpSessionStore};
@@ -0,0 +1,156 @@
+//! MCP prompts of a server
+//!
+//! A prompt renders its template as a single user message. Placeholders
+//! naming a plain server global are filled from it; the others are prompt
+//! arguments, cast by their placeholder type like tool parameters. Secret
+//! globals are never written into a prompt.
+
+use crate::error::McpServiceError;
+use crate::models::{ServerGlobal, ServerPrompt};
+use crate::services::variable_engine::TypedVariableEngine;
+use rmcp::model::{
+    ErrorData, GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage,
+    PromptMessageRole,
+};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+/// Values of the server's plain globals
+async fn plain_globals(
+    pool: &SqlitePool,
+    server_id: i64,
+) -> Result<HashMap<String, String>, McpServiceError> {
+    Ok(ServerGlobal::list_by_server(pool, server_id)
+        .await?
+        .into_iter()
+        .filter(|g| !g.is_secret.unwrap_or(false))
+        .map(|g| (g.key, g.value))
+        .collect())
+}
+
+/// Arguments of a prompt: its placeholders not filled by a global
+fn arguments(prompt: &ServerPrompt, globals: &HashMap<String, String>) -> Vec<PromptArgument> {
+    let specs = prompt.argument_specs();
+    prompt
+        .placeholders()
+        .into_iter()
+        .filter(|(_, name)| !globals.contains_key(name))
+        .map(|(var_type, name)| {
+            let spec = specs.get(&name).cloned().unwrap_or_default();
+            let description = match (spec.description, var_type.as_str()) {
+                (description, "string") => description,
+                (Some(description), _) => Some(format!("{} ({})", description, var_type)),
+                (None, _) => Some(var_type),
+            };
+            PromptArgument {
+                name,
+                title: None,
+                description,
+                required: Some(!spec.optional),
+            }
+        })
+        .collect()
+}
+
+/// Prompts of a server with their arguments
+pub async fn list_prompts(
+    pool: &SqlitePool,
+    server_id: i64,
+) -> Result<Vec<Prompt>, McpServiceError> {
+    let globals = plain_globals(pool, server_id).await?;
+    Ok(ServerPrompt::list_by_server(pool, server_id)
+        .await?
+        .into_iter()
+        .map(|prompt| {
+            let arguments = arguments(&prompt, &globals);
+            Prompt {
+                name: prompt.name,
+                title: None,
+                description: prompt.description,
+                arguments: Some(arguments).filter(|a| !a.is_empty()),
+                icons: None,
+            }
+        })
+        .collect())
+}
+
+/// Render a prompt with the given arguments
+pub async fn get_prompt(
+    pool: &SqlitePool,
+    server_id: i64,
+    name: &str,
+    provided: Option<JsonObject>,
+) -> Result<GetPromptResult, ErrorData> {
+    let prompt = ServerPrompt::get_by_name(pool, server_id, name)
+        .await
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?
+        .ok_or_else(|| ErrorData::invalid_params(format!("Prompt '{}' not found", name), None))?;
+
+    let mut context = plain_globals(pool, server_id).await?;
+    let provided = provided.unwrap_or_default();
+    let mut missing = Vec::new();
+    for argument in arguments(&prompt, &context) {
+        match provided.get(&argument.name) {
+            Some(Value::String(value)) => {
+                context.insert(argument.name, value.clone());
+            }
+            Some(Value::Null) | None if argument.required == Some(false) => {
+                context.insert(argument.name, String::new());
+            }
+            Some(Value::Null) | None => missing.push(argument.name),
+            Some(value) => {
+                context.insert(argument.name, value.to_string());
+            }
+        }
+    }
+    if !missing.is_empty() {
+        return Err(ErrorData::invalid_params(
+            format!("Missing prompt argument(s): {}", missing.join(", ")),
+            None,
+        ));
+    }
+
+    let text = TypedVariableEngine::new()
+        .substitute(&prompt.template, &context)
+        .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
+
+    Ok(GetPromptResult {
+        description: prompt.description,
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(template: &str, arguments: Option<&str>) -> ServerPrompt {
+        ServerPrompt {
+            id: Some(1),
+            server_id: 1,
+            name: "triage".to_string(),
+            description: None,
+            template: template.to_string(),
+            arguments: arguments.map(str::to_string),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_globals_are_not_arguments() {
+        let prompt = prompt(
+            "Triage {{ticket}} in {{project}} at priority {{integer:priority}}",
+            Some(r#"{"ticket": {"description": "Ticket key"}, "priority": {}}"#),
+        );
+        let globals = HashMap::from([("project".to_string(), "OPS".to_string())]);
+
+        let arguments = arguments(&prompt, &globals);
+        let names: Vec<&str> = arguments.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["ticket", "priority"]);
+        assert_eq!(arguments[0].description.as_deref(), Some("Ticket key"));
+        assert_eq!(arguments[0].required, Some(true));
+        assert_eq!(arguments[1].description.as_deref(), Some("integer"));
+    }
+}
@@ -4,6 +4,8 @@
 //! Uses `Arc<RwLock<HashMap<>>>` for thread-safe concurrent access.
 
 use crate::mcp::instance::McpServerInstance;
+use crate::mcp::service::ListChanged;
+use crate::mcp::session::McpSessionStore;
 use crate::models::server::Server;
 use sqlx::SqlitePool;
 use std::collections::HashMap;
@@ -53,6 +55,9 @@ pub enum RegistryError {
 /// ```
 pub struct McpServerRegistry {
     instances: HashMap<String, Arc<McpServerInstance>>,
+    sessions: McpSessionStore,
+    /// Configuration version of each server when it was last (re)loaded
+    config_versions: HashMap<String, i64>,
     pool: SqlitePool,
 }
 
@@ -63,6 +68,8 @@ impl McpServerRegistry {
     pub fn new(pool: SqlitePool) -> Self {
         Self {
             instances: HashMap::new(),
+            sessions: McpSessionStore::new(),
+            config_versions: HashMap::new(),
             pool,
         }
     }
@@ -115,6 +122,7 @@ impl McpServerRegistry {
             .instances
             .remove(uuid)
             .ok_or_else(|| RegistryError::ServerNotFound(uuid.to_string()))?;
+        self.sessions.close_server(uuid);
 
         instance
             .shutdown()
@@ -126,6 +134,9 @@ impl McpServerRegistry {
 
     /// Reloads tool definitions for a server
     ///
+    /// Connected clients are told that the tool and resource lists changed,
+    /// since GET instances are also published as resources.
+    ///
     /// # Arguments
     ///
     /// * `uuid` - Server UUID to reload
@@ -134,10 +145,6 @@ impl McpServerRegistry {
     ///
     /// * `Ok(())` - Tools reloaded successfully
     /// * `Err(RegistryError::ServerNotFound)` - Server doesn't exist
-    ///
-    /// # Note
-    ///
-    /// This is currently a stub. Full implementation will be added in Task 003.
     pub async fn reload_tools(&mut self, uuid: &str) -> Result<(), RegistryError> {
         let instance = self
             .instances
@@ -149,6 +156,34 @@ impl McpServerRegistry {
             .await
             .map_err(|e| RegistryError::InstanceCreation(e.to_string()))?;
 
+        self.notify_list_changed(uuid, ListChanged::Tools).await?;
+        self.notify_list_changed(uuid, ListChanged::Resources).await
+    }
+
+    /// Sends a `list_changed` notification to every client of a server
+    ///
+    /// # Arguments
+    ///
+    /// * `uuid` - Server UUID whose list changed
+    /// * `list` - The list that changed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Connected clients were notified
+    /// * `Err(RegistryError::ServerNotFound)` - Server doesn't exist
+    pub async fn notify_list_changed(
+        &self,
+        uuid: &str,
+        list: ListChanged,
+    ) -> Result<(), RegistryError> {
+        let instance = self
+            .instances
+            .get(uuid)
+            .ok_or_else(|| RegistryError::ServerNotFound(uuid.to_string()))?;
+
+        self.sessions.broadcast(uuid, &list.notification());
+        instance.get_service().notify_list_changed(list).await;
+
         Ok(())
     }
 
@@ -166,6 +201,11 @@ impl McpServerRegistry {
         self.instances.get(uuid).map(Arc::clone)
     }
 
+    /// Streamable HTTP sessions of all registered servers
+    pub fn sessions(&self) -> McpSessionStore {
+        self.sessions.clone()
+    }
+
     /// Loads all servers from the database on startup
     ///
     /// # Returns
@@ -178,12 +218,12 @@ impl McpServerRegistry {
     /// Continues loading even if some servers fail to register.
     /// Failures are logged but don't stop the startup process.
     pub async fn load_all_servers(&mut self) -> Result<(), RegistryError> {
-        let servers = sqlx::query!("SELECT id, uuid FROM servers")
+        let servers = sqlx::query!("SELECT id, uuid, config_version FROM servers")
             .fetch_all(&self.pool)
             .await?;
 
         for server in servers {
-            let uuid = match &server.uuid {
+            let uuid = match server.uuid {
                 Some(u) => u,
                 None => {
                     tracing::warn!("Server {:?} has no UUID, skipping", server.id);
@@ -191,7 +231,9 @@ impl McpServerRegistry {
                 }
             };
 
-            if let Err(e) = self.register_server(uuid).await {
+            self.config_versions
+                .insert(uuid.clone(), server.config_version);
+            if let Err(e) = self.register_server(&uuid).await {
                 tracing::warn!(
                     "Failed to register server {} ({:?}): {}",
                     uuid,
@@ -204,6 +246,68 @@ impl McpServerRegistry {
         Ok(())
     }
 
+    /// Reloads the servers a configuration document was applied to
+    ///
+    /// Applying a document bumps the server's configuration version, also
+    /// when the command line applies it outside this process. The versions
+    /// are compared under the read lock; the write lock is only taken when
+    /// some moved, see [`Self::reload_configs`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Changed servers were reloaded; failures are logged
+    /// * `Err(RegistryError::Database)` - Database error
+    pub async fn reload_changed_configs(registry: &RwLock<Self>) -> Result<(), RegistryError> {
+        let changed = registry.read().await.changed_configs().await?;
+        if !changed.is_empty() {
+            registry.write().await.reload_configs(changed).await;
+        }
+
+        Ok(())
+    }
+
+    /// Servers whose configuration version moved since they were loaded,
+    /// with their current version
+    pub async fn changed_configs(&self) -> Result<Vec<(String, i64)>, RegistryError> {
+        let servers = sqlx::query!("SELECT uuid, config_version FROM servers")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(servers
+            .into_iter()
+            .filter_map(|server| Some((server.uuid?, server.config_version)))
+            .filter(|(uuid, version)| {
+                self.config_versions.get(uuid).copied().unwrap_or_default() != *version
+            })
+            .collect())
+    }
+
+    /// Reloads the tools and prompts of the `changed` servers, or registers
+    /// them when a document created them
+    ///
+    /// Servers already reloaded at that version since `changed` was read are
+    /// skipped. Failures are logged.
+    pub async fn reload_configs(&mut self, changed: Vec<(String, i64)>) {
+        for (uuid, config_version) in changed {
+            if self.config_versions.get(&uuid) == Some(&config_version) {
+                continue;
+            }
+            self.config_versions.insert(uuid.clone(), config_version);
+
+            let result = if self.instances.contains_key(&uuid) {
+                match self.reload_tools(&uuid).await {
+                    Ok(()) => self.notify_list_changed(&uuid, ListChanged::Prompts).await,
+                    Err(e) => Err(e),
+                }
+            } else {
+                self.register_server(&uuid).await
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to reload server {}: {}", uuid, e);
+            }
+        }
+    }
+
     /// Shuts down all registered server instances
     ///
     /// # Returns
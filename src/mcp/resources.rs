@@ -0,0 +1,256 @@
+//! MCP resources of a server
+//!
+//! Documents attached to a server are served as stored. Published GET tool
+//! instances are executed when read: instances without exposed parameters
+//! are plain resources, the others resource templates carrying their
+//! parameters in the URI query (`saramcp://resources/forecast{?city,days}`).
+
+use crate::error::McpServiceError;
+use crate::models::{ServerResource, Tool, ToolInstance, RESOURCE_URI_PREFIX};
+use crate::services::execution_context::ExecutionContext;
+use crate::services::instance_executor::{result_text, InstanceExecutor};
+use crate::services::parameter_resolver::ParameterResolver;
+use crate::services::secrets_manager::SecretsManager;
+use rmcp::model::{
+    AnnotateAble, ErrorData, RawResource, RawResourceTemplate, ReadResourceResult, Resource,
+    ResourceContents, ResourceTemplate,
+};
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+/// Resources of a server with the exposed parameters of tool-backed ones
+async fn load(
+    pool: &SqlitePool,
+    server_id: i64,
+) -> Result<Vec<(ServerResource, Vec<String>)>, McpServiceError> {
+    let mut result = Vec::new();
+    for resource in ServerResource::list_by_server(pool, server_id).await? {
+        let exposed = match resource.instance_id {
+            Some(instance_id) => ParameterResolver::get_exposed_params(pool, instance_id).await?,
+            None => Vec::new(),
+        };
+        result.push((resource, exposed));
+    }
+    Ok(result)
+}
+
+/// Documents and tool instances readable without parameters
+pub async fn list_resources(
+    pool: &SqlitePool,
+    server_id: i64,
+) -> Result<Vec<Resource>, McpServiceError> {
+    Ok(load(pool, server_id)
+        .await?
+        .into_iter()
+        .filter(|(_, exposed)| exposed.is_empty())
+        .map(|(resource, _)| {
+            RawResource {
+                uri: resource.uri(),
+                size: resource.content.as_ref().map(|c| c.len() as u32),
+                name: resource.name,
+                title: None,
+                description: resource.description,
+                mime_type: Some(resource.mime_type),
+                icons: None,
+            }
+            .no_annotation()
+        })
+        .collect())
+}
+
+/// Tool instances whose exposed parameters go in the URI
+pub async fn list_resource_templates(
+    pool: &SqlitePool,
+    server_id: i64,
+) -> Result<Vec<ResourceTemplate>, McpServiceError> {
+    Ok(load(pool, server_id)
+        .await?
+        .into_iter()
+        .filter(|(_, exposed)| !exposed.is_empty())
+        .map(|(resource, exposed)| {
+            RawResourceTemplate {
+                uri_template: format!("{}{{?{}}}", resource.uri(), exposed.join(",")),
+                name: resource.name,
+                title: None,
+                description: resource.description,
+                mime_type: Some(resource.mime_type),
+            }
+            .no_annotation()
+        })
+        .collect())
+}
+
+/// Read a resource, executing its tool instance if it has one
+pub async fn read_resource(
+    pool: &SqlitePool,
+    server_id: i64,
+    secrets: &SecretsManager,
+    uri: &str,
+    context: &ExecutionContext,
+) -> Result<ReadResourceResult, ErrorData> {
+    let not_found = || ErrorData::resource_not_found(format!("Resource '{}' not found", uri), None);
+
+    let path = uri
+        .strip_prefix(RESOURCE_URI_PREFIX)
+        .ok_or_else(not_found)?;
+    let (name, query) = path.split_once('?').unwrap_or((path, ""));
+
+    let resource = ServerResource::get_by_name(pool, server_id, name)
+        .await
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?
+        .ok_or_else(not_found)?;
+
+    let text = match (&resource.content, resource.instance_id) {
+        (Some(content), _) => content.clone(),
+        (None, Some(instance_id)) => {
+            read_instance(pool, server_id, secrets, instance_id, query, context).await?
+        }
+        (None, None) => return Err(not_found()),
+    };
+
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(resource.mime_type),
+            text,
+            meta: None,
+        }],
+    })
+}
+
+async fn read_instance(
+    pool: &SqlitePool,
+    server_id: i64,
+    secrets: &SecretsManager,
+    instance_id: i64,
+    query: &str,
+    context: &ExecutionContext,
+) -> Result<String, ErrorData> {
+    let internal = |e: anyhow::Error| ErrorData::internal_error(e.to_string(), None);
+
+    let instance = ToolInstance::get_by_id(pool, instance_id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| ErrorData::resource_not_found("Tool instance not found", None))?;
+    let tool = Tool::get_by_id(pool, instance.tool_id)
+        .await
+        .map_err(|e| internal(e.into()))?
+        .ok_or_else(|| ErrorData::resource_not_found("Tool not found", None))?;
+
+    let exposed = ParameterResolver::get_exposed_params(pool, instance_id)
+        .await
+        .map_err(internal)?;
+    let arguments = uri_arguments(&tool, &exposed, query)?;
+
+    let executor =
+        InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets.clone());
+    let result = executor
+        .execute_with_context(Some(arguments), context)
+        .await?;
+
+    let text = result_text(&result);
+
+    if result.is_error == Some(true) {
+        return Err(ErrorData::internal_error(text, None));
+    }
+    Ok(text)
+}
+
+/// Exposed parameters from the URI query, cast to their placeholder types
+fn uri_arguments(
+    tool: &Tool,
+    exposed: &[String],
+    query: &str,
+) -> Result<Map<String, Value>, ErrorData> {
+    let provided: HashMap<String, String> = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    let types: HashMap<String, String> = tool
+        .extract_parameters()
+        .into_iter()
+        .map(|p| (p.name, p.param_type))
+        .collect();
+
+    let missing: Vec<&str> = exposed
+        .iter()
+        .filter(|name| !provided.contains_key(*name))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(ErrorData::invalid_params(
+            format!("Missing URI parameter(s): {}", missing.join(", ")),
+            None,
+        ));
+    }
+
+    let mut arguments = Map::new();
+    for name in exposed {
+        let raw = &provided[name];
+        let var_type = types.get(name).map(String::as_str).unwrap_or("string");
+        let value = match var_type.to_lowercase().as_str() {
+            "integer" => raw.parse::<i64>().ok().map(Value::from),
+            "number" => raw.parse::<f64>().ok().map(Value::from),
+            "boolean" | "bool" => raw.parse::<bool>().ok().map(Value::from),
+            "json" | "object" | "array" => serde_json::from_str(raw).ok(),
+            _ => Some(Value::String(raw.clone())),
+        }
+        .ok_or_else(|| {
+            ErrorData::invalid_params(
+                format!("Parameter '{}' is not a valid {}", name, var_type),
+                None,
+            )
+        })?;
+        arguments.insert(name.clone(), value);
+    }
+    Ok(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(url: &str) -> Tool {
+        Tool {
+            id: 1,
+            toolkit_id: 1,
+            name: "forecast".to_string(),
+            description: None,
+            method: "GET".to_string(),
+            url: Some(url.to_string()),
+            headers: None,
+            body: None,
+            timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
+            created_at: chrono::NaiveDateTime::default(),
+            updated_at: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_casts_uri_arguments_to_placeholder_types() {
+        let tool = tool("https://api.example.com/forecast?q={{string:city}}&days={{integer:days}}");
+        let exposed = vec!["city".to_string(), "days".to_string()];
+
+        let arguments = uri_arguments(&tool, &exposed, "city=New%20York&days=3&extra=1").unwrap();
+        assert_eq!(arguments["city"], "New York");
+        assert_eq!(arguments["days"], 3);
+        assert!(!arguments.contains_key("extra"));
+    }
+
+    #[test]
+    fn test_rejects_missing_and_mistyped_arguments() {
+        let tool = tool("https://api.example.com/forecast?q={{string:city}}&days={{integer:days}}");
+        let exposed = vec!["city".to_string(), "days".to_string()];
+
+        let err = uri_arguments(&tool, &exposed, "city=Oslo").unwrap_err();
+        assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+        assert!(err.message.contains("days"));
+
+        let err = uri_arguments(&tool, &exposed, "city=Oslo&days=soon").unwrap_err();
+        assert!(err.message.contains("not a valid integer"));
+    }
+}
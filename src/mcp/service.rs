@@ -36,6 +36,11 @@
 //!    - `initialize`: Returns server info and capabilities
 //!    - `tools/list`: Returns all registered tools
 //!    - `tools/call`: Routes to appropriate InstanceExecutor
+//!    - `resources/list`, `resources/templates/list`, `resources/read`:
+//!      Server documents and published GET tool instances
+//!    - `prompts/list`, `prompts/get`: Server prompt templates
+//!    - `completion/complete`: Values for prompt arguments and resource
+//!      template variables
 //!
 //! 3. **Reload**: `reload_tools()` (when instances change)
 //!    - Rebuilds ToolRouter with updated instances
@@ -63,17 +68,106 @@
 //! ```
 
 use crate::error::McpServiceError;
+use crate::mcp::completions::CompletionCache;
 use crate::models::instance::ToolInstance;
 use crate::models::tool::Tool;
+use crate::services::execution_context::{
+    ClientRequests, ExecutionContext, LogLevel, NotificationSink,
+};
 use crate::services::instance_executor::InstanceExecutor;
 use crate::services::schema_generator::SchemaGenerator;
 use crate::services::secrets_manager::SecretsManager;
 use rmcp::handler::server::tool::{ToolCallContext, ToolRoute, ToolRouter};
 use rmcp::handler::server::ServerHandler;
-use rmcp::model::{Implementation, ProtocolVersion, ServerCapabilities, ServerInfo};
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, CompleteRequestParam, CompleteResult, ErrorCode,
+    ErrorData, GetPromptRequestParam, GetPromptResult, Implementation, InitializeRequestParam,
+    InitializeResult, ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+    ListToolsResult, LoggingLevel, PaginatedRequestParam, ProtocolVersion,
+    ReadResourceRequestParam, ReadResourceResult, ServerCapabilities, ServerInfo,
+    SetLevelRequestParam,
+};
+use rmcp::service::{Peer, RequestContext, RoleServer};
 use sqlx::SqlitePool;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+/// Protocol versions the server speaks, newest first
+pub const SUPPORTED_PROTOCOL_VERSIONS: [ProtocolVersion; 3] = [
+    ProtocolVersion::V_2025_06_18,
+    ProtocolVersion::V_2025_03_26,
+    ProtocolVersion::V_2024_11_05,
+];
+
+/// Version to answer an `initialize` request with
+///
+/// The client's requested version when the server supports it, otherwise
+/// the server's default and the client decides whether to continue.
+pub fn negotiate_protocol_version(requested: Option<&ProtocolVersion>) -> ProtocolVersion {
+    requested
+        .filter(|version| SUPPORTED_PROTOCOL_VERSIONS.contains(version))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Serialize a method result into the `result` of a JSON-RPC response
+fn result_value<T: serde::Serialize>(result: &T) -> Result<serde_json::Value, ErrorData> {
+    serde_json::to_value(result)
+        .map_err(|e| ErrorData::internal_error(format!("Failed to serialize result: {}", e), None))
+}
+
+/// A list of a server whose contents changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListChanged {
+    Tools,
+    Resources,
+    Prompts,
+}
+
+impl ListChanged {
+    /// Method of the notification announcing the change
+    pub fn method(self) -> &'static str {
+        match self {
+            ListChanged::Tools => "notifications/tools/list_changed",
+            ListChanged::Resources => "notifications/resources/list_changed",
+            ListChanged::Prompts => "notifications/prompts/list_changed",
+        }
+    }
+
+    /// JSON-RPC notification announcing the change
+    pub fn notification(self) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": self.method(),
+        })
+    }
+}
+
+/// Streamable HTTP client a request came from
+#[derive(Clone, Default)]
+pub struct RequestOrigin {
+    /// Session ID of the client, or an ID of its own for requests outside a
+    /// session. A client can only cancel its own calls.
+    pub client: String,
+    /// Log level of the client's session, a throwaway one for stateless requests
+    pub log_level: LogLevel,
+    /// Where messages sent while handling the request go, if anywhere
+    pub notifications: Option<NotificationSink>,
+    /// Channel for requests to the client, when it supports elicitation
+    pub requests: Option<ClientRequests>,
+}
+
+impl RequestOrigin {
+    /// Origin of a request outside any session
+    pub fn stateless() -> Self {
+        Self {
+            client: uuid::Uuid::new_v4().simple().to_string(),
+            ..Self::default()
+        }
+    }
+}
 
 /// Instance data loaded from database
 struct InstanceData {
@@ -84,12 +178,44 @@ struct InstanceData {
     tool: Tool,
 }
 
+/// Cancellation tokens of in-flight Streamable HTTP tool calls, keyed by
+/// the calling client and the serialized JSON-RPC request ID
+type InFlightCalls = Arc<Mutex<HashMap<(String, String), Arc<CancellationToken>>>>;
+
+/// Removes a call from the in-flight registry when it completes or is dropped
+struct InFlightGuard {
+    calls: InFlightCalls,
+    key: (String, String),
+    token: Arc<CancellationToken>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if let Ok(mut calls) = self.calls.lock() {
+            // A later call reusing the request ID may have taken the entry
+            if calls
+                .get(&self.key)
+                .is_some_and(|token| Arc::ptr_eq(token, &self.token))
+            {
+                calls.remove(&self.key);
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct SaraMcpService {
     server_id: i64,
     pool: SqlitePool,
     tool_router: Arc<RwLock<ToolRouter<Self>>>,
     secrets: SecretsManager,
+    in_flight: InFlightCalls,
+    /// Clients connected over the SSE or stdio transports
+    peers: Arc<Mutex<Vec<Peer<RoleServer>>>>,
+    /// Log level chosen by the client of this connection
+    log_level: LogLevel,
+    /// Recent answers of completion lookup instances
+    completions: CompletionCache,
 }
 
 impl SaraMcpService {
@@ -198,7 +324,12 @@ impl SaraMcpService {
 
         let route = ToolRoute::new_dyn(tool_def, move |context: ToolCallContext<'_, Self>| {
             let exec = executor.clone();
-            Box::pin(async move { exec.execute(context.arguments).await })
+            let exec_context = ExecutionContext::from_request_context(&context.request_context)
+                .with_logging(context.service.log_level.clone(), None);
+            Box::pin(async move {
+                exec.execute_with_context(context.arguments, &exec_context)
+                    .await
+            })
         });
 
         Ok(route)
@@ -278,6 +409,10 @@ impl SaraMcpService {
             pool: pool.clone(),
             tool_router: Arc::new(RwLock::new(tool_router)),
             secrets,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            peers: Arc::new(Mutex::new(Vec::new())),
+            log_level: LogLevel::default(),
+            completions: CompletionCache::default(),
         })
     }
 
@@ -311,20 +446,98 @@ impl SaraMcpService {
         Ok(())
     }
 
-    /// Get tool routes for Router creation
+    /// Service for one SSE or stdio connection
     ///
-    /// Rebuilds all tool routes from the database for use with rmcp::handler::server::Router.
-    /// This is used by the SSE transport to create a Router that implements Service.
-    pub async fn get_tool_routes(
+    /// Shares tools and peers with this service, with its own log level.
+    pub fn connection(&self) -> Self {
+        Self {
+            log_level: LogLevel::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Tell the connected SSE and stdio clients that a list changed
+    ///
+    /// Streamable HTTP sessions are notified through the registry's
+    /// session store instead.
+    pub async fn notify_list_changed(&self, list: ListChanged) {
+        let peers: Vec<Peer<RoleServer>> = match self.peers.lock() {
+            Ok(mut peers) => {
+                peers.retain(|peer| !peer.is_transport_closed());
+                peers.clone()
+            }
+            Err(_) => return,
+        };
+
+        for peer in peers {
+            let sent = match list {
+                ListChanged::Tools => peer.notify_tool_list_changed().await,
+                ListChanged::Resources => peer.notify_resource_list_changed().await,
+                ListChanged::Prompts => peer.notify_prompt_list_changed().await,
+            };
+            if let Err(e) = sent {
+                tracing::debug!(server_id = self.server_id, "Failed to notify peer: {}", e);
+            }
+        }
+    }
+
+    /// Track a Streamable HTTP tool call so it can be cancelled
+    ///
+    /// Returns the execution context carrying the call's cancellation token
+    /// and a guard that unregisters the call when dropped. Calls without a
+    /// request ID cannot be addressed by the client and are not registered.
+    fn register_call(
         &self,
-    ) -> Result<Vec<rmcp::handler::server::tool::ToolRoute<Self>>, McpServiceError> {
-        let instances = Self::load_instances(&self.pool, self.server_id).await?;
-        let mut routes = Vec::new();
-        for instance in instances {
-            let route = Self::build_tool_route(&self.pool, instance, self.secrets.clone()).await?;
-            routes.push(route);
+        request_id: Option<&serde_json::Value>,
+        origin: &RequestOrigin,
+    ) -> (ExecutionContext, Option<InFlightGuard>) {
+        let token = CancellationToken::new();
+        let context = ExecutionContext::with_cancellation(token.clone())
+            .with_logging(origin.log_level.clone(), origin.notifications.clone())
+            .with_requests(origin.requests.clone());
+
+        let Some(id) = request_id else {
+            return (context, None);
+        };
+
+        let key = (origin.client.clone(), id.to_string());
+        let token = Arc::new(token);
+        if let Ok(mut calls) = self.in_flight.lock() {
+            calls.insert(key.clone(), Arc::clone(&token));
+        }
+
+        let guard = InFlightGuard {
+            calls: self.in_flight.clone(),
+            key,
+            token,
+        };
+        (context, Some(guard))
+    }
+
+    /// Cancel an in-flight call named by a `notifications/cancelled` message
+    ///
+    /// Only calls of the client sending the notification are cancelled.
+    fn cancel_call(&self, params: Option<&serde_json::Value>, origin: &RequestOrigin) {
+        let Some(request_id) = params.and_then(|p| p.get("requestId")) else {
+            return;
+        };
+
+        let key = (origin.client.clone(), request_id.to_string());
+        let token = self
+            .in_flight
+            .lock()
+            .ok()
+            .and_then(|mut calls| calls.remove(&key));
+
+        if let Some(token) = token {
+            tracing::info!(
+                server_id = self.server_id,
+                request_id = %request_id,
+                reason = ?params.and_then(|p| p.get("reason")),
+                "Cancelling tool call"
+            );
+            token.cancel();
         }
-        Ok(routes)
     }
 
     /// Handle a single JSON-RPC request (for Streamable HTTP transport)
@@ -339,14 +552,29 @@ impl SaraMcpService {
     ///
     /// # Returns
     ///
-    /// * `Ok(Value)` - JSON-RPC response with result or error
-    /// * `Err(McpServiceError)` - If request parsing or execution fails
+    /// * `Ok(Value)` - JSON-RPC response with result or error, or `Null` for
+    ///   notifications, which get no response
+    ///
+    /// Failures are reported as JSON-RPC errors: `-32600` for a message that
+    /// is not a JSON-RPC 2.0 request, `-32601` for unknown methods, `-32602`
+    /// for missing or invalid params and `-32603` for server-side failures.
     ///
     /// # Supported Methods
     ///
-    /// * `initialize` - Returns server info and capabilities
+    /// * `initialize` - Returns server info and capabilities, with the
+    ///   client's protocol version if supported, else the latest one
+    /// * `ping` - Returns an empty result
+    /// * `logging/setLevel` - Sets the minimum level of the session's log messages
     /// * `tools/list` - Returns list of available tools
     /// * `tools/call` - Executes a tool with given arguments
+    /// * `resources/list` - Returns documents and parameterless tool resources
+    /// * `resources/templates/list` - Returns tool resources taking URI parameters
+    /// * `resources/read` - Returns a document or the response of a tool resource
+    /// * `prompts/list` - Returns the server's prompts and their arguments
+    /// * `prompts/get` - Renders a prompt with the given arguments
+    /// * `completion/complete` - Suggests values for a prompt argument or
+    ///   resource template variable
+    /// * `notifications/cancelled` - Aborts an in-flight `tools/call`
     ///
     /// # Examples
     ///
@@ -366,134 +594,247 @@ impl SaraMcpService {
     pub async fn handle_request(
         &self,
         request: serde_json::Value,
+    ) -> Result<serde_json::Value, McpServiceError> {
+        self.handle_request_from(request, &RequestOrigin::default())
+            .await
+    }
+
+    /// Handle a single JSON-RPC request of a Streamable HTTP client
+    ///
+    /// Same as [`handle_request`](Self::handle_request), with the client's
+    /// log level and a channel for the log messages sent while handling it.
+    /// `notifications/cancelled` only reaches calls of the same client.
+    pub async fn handle_request_from(
+        &self,
+        request: serde_json::Value,
+        origin: &RequestOrigin,
     ) -> Result<serde_json::Value, McpServiceError> {
         use serde_json::json;
 
         // Parse request ID
         let request_id = request.get("id").cloned();
 
-        // Get method - handle missing method as an error, not early return
-        let method_result = request
-            .get("method")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| McpServiceError::Internal("Missing method field".to_string()));
-
-        // Route based on method
-        let result = match method_result {
-            Ok(method) => match method {
-                "initialize" => {
-                    // Return server info
-                    let info = self.get_info();
-                    Ok(json!({
-                        "protocolVersion": info.protocol_version,
-                        "capabilities": info.capabilities,
-                        "serverInfo": info.server_info,
-                        "instructions": info.instructions,
-                    }))
-                }
-
-                "tools/list" => {
-                    // List all available tools from the router
-                    let router = self.tool_router.read().await;
-                    let tools = router.list_all();
-                    Ok(json!({
-                        "tools": tools,
-                    }))
-                }
-
-                "tools/call" => {
-                    // Extract tool call parameters
-                    let params = request.get("params").ok_or_else(|| {
-                        McpServiceError::Internal("Missing params field".to_string())
-                    })?;
+        // Anything other than a JSON-RPC 2.0 object with a method is invalid
+        let method = match (
+            request.get("jsonrpc").and_then(|v| v.as_str()),
+            request.get("method").and_then(|v| v.as_str()),
+        ) {
+            (Some("2.0"), Some(method)) => method,
+            _ => {
+                return Ok(Self::error_response(
+                    request_id.unwrap_or_default(),
+                    ErrorData::invalid_request("Invalid JSON-RPC 2.0 request", None),
+                ))
+            }
+        };
 
-                    let tool_name =
-                        params.get("name").and_then(|v| v.as_str()).ok_or_else(|| {
-                            McpServiceError::Internal("Missing tool name".to_string())
-                        })?;
+        // Notifications carry no ID and get no JSON-RPC response
+        let Some(request_id) = request_id else {
+            if method == "notifications/cancelled" {
+                self.cancel_call(request.get("params"), origin);
+            }
+            return Ok(serde_json::Value::Null);
+        };
 
-                    let arguments = params.get("arguments").and_then(|v| v.as_object()).cloned();
+        match self.dispatch(method, &request, &request_id, origin).await {
+            Ok(result) => Ok(json!({
+                "jsonrpc": "2.0",
+                "id": request_id,
+                "result": result,
+            })),
+            Err(e) => Ok(Self::error_response(request_id, e)),
+        }
+    }
 
-                    // For HTTP transport, we need to call the tool directly without
-                    // creating a full MCP session context. We'll find the instance
-                    // and execute it directly using InstanceExecutor.
+    /// JSON-RPC error response to the request with the given ID
+    pub fn error_response(request_id: serde_json::Value, error: ErrorData) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "error": error,
+        })
+    }
 
-                    // Parse the tool name to get instance name
-                    // (In our system, instance_name = tool name in MCP)
-                    let instance = crate::models::instance::ToolInstance::find_by_server_and_name(
-                        &self.pool,
-                        self.server_id,
-                        tool_name,
-                    )
-                    .await
-                    .map_err(|e| McpServiceError::Internal(e.to_string()))?
-                    .ok_or_else(|| {
-                        McpServiceError::Internal(format!(
-                            "Tool instance '{}' not found",
-                            tool_name
-                        ))
-                    })?;
+    /// Run a JSON-RPC request, returning its result or error object
+    async fn dispatch(
+        &self,
+        method: &str,
+        request: &serde_json::Value,
+        request_id: &serde_json::Value,
+        origin: &RequestOrigin,
+    ) -> Result<serde_json::Value, ErrorData> {
+        use serde_json::json;
 
-                    let instance_id = instance.id.ok_or_else(|| {
-                        McpServiceError::Internal("Instance missing ID".to_string())
-                    })?;
+        let params = request.get("params");
+        let param_str = |name: &str| {
+            params
+                .and_then(|p| p.get(name))
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| ErrorData::invalid_params(format!("Missing '{}'", name), None))
+        };
 
-                    // Get the tool definition
-                    let tool = crate::models::tool::Tool::get_by_id(&self.pool, instance.tool_id)
+        match method {
+            "initialize" => {
+                let requested = params
+                    .and_then(|p| p.get("protocolVersion"))
+                    .cloned()
+                    .and_then(|v| serde_json::from_value::<ProtocolVersion>(v).ok());
+                let info = self.get_info();
+                Ok(json!({
+                    "protocolVersion": negotiate_protocol_version(requested.as_ref()),
+                    "capabilities": info.capabilities,
+                    "serverInfo": info.server_info,
+                    "instructions": info.instructions,
+                }))
+            }
+
+            "ping" => Ok(json!({})),
+
+            "logging/setLevel" => {
+                let level = params
+                    .and_then(|p| p.get("level"))
+                    .cloned()
+                    .and_then(|v| serde_json::from_value::<LoggingLevel>(v).ok())
+                    .ok_or_else(|| ErrorData::invalid_params("Missing or invalid 'level'", None))?;
+                origin.log_level.set(level);
+                Ok(json!({}))
+            }
+
+            "tools/list" => {
+                // List all available tools from the router
+                let router = self.tool_router.read().await;
+                let tools = router.list_all();
+                Ok(json!({
+                    "tools": tools,
+                }))
+            }
+
+            "tools/call" => {
+                let tool_name = param_str("name")?;
+                let arguments = params
+                    .and_then(|p| p.get("arguments"))
+                    .and_then(|v| v.as_object())
+                    .cloned();
+
+                // For HTTP transport, we need to call the tool directly without
+                // creating a full MCP session context. We'll find the instance
+                // and execute it directly using InstanceExecutor.
+
+                // Parse the tool name to get instance name
+                // (In our system, instance_name = tool name in MCP)
+                let instance =
+                    ToolInstance::find_by_server_and_name(&self.pool, self.server_id, tool_name)
                         .await
-                        .map_err(|e| McpServiceError::Internal(e.to_string()))?
+                        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?
                         .ok_or_else(|| {
-                            McpServiceError::ToolNotFound(format!(
-                                "Tool {} not found",
-                                instance.tool_id
-                            ))
+                            ErrorData::invalid_params(format!("Unknown tool '{}'", tool_name), None)
                         })?;
 
-                    // Execute using InstanceExecutor directly
-                    let executor = crate::services::instance_executor::InstanceExecutor::new(
-                        self.pool.clone(),
-                        self.server_id,
-                        instance_id,
-                        tool,
-                        self.secrets.clone(),
-                    );
-
-                    let call_result = executor
-                        .execute(arguments)
-                        .await
-                        .map_err(|e| McpServiceError::Internal(e.to_string()))?;
-
-                    Ok(serde_json::to_value(&call_result).map_err(|e| {
-                        McpServiceError::Internal(format!("Failed to serialize result: {}", e))
-                    })?)
-                }
-
-                _ => {
-                    // Method not supported
-                    Err(McpServiceError::Internal(format!(
-                        "Method '{}' not supported",
-                        method
-                    )))
-                }
-            },
-            Err(e) => Err(e),
-        };
+                let instance_id = instance
+                    .id
+                    .ok_or_else(|| ErrorData::internal_error("Instance missing ID", None))?;
 
-        // Build JSON-RPC response
-        match result {
-            Ok(res) => Ok(json!({
-                "jsonrpc": "2.0",
-                "id": request_id,
-                "result": res,
-            })),
-            Err(e) => Ok(json!({
-                "jsonrpc": "2.0",
-                "id": request_id,
-                "error": {
-                    "code": -32603,
-                    "message": e.to_string(),
-                },
-            })),
+                // Get the tool definition
+                let tool = Tool::get_by_id(&self.pool, instance.tool_id)
+                    .await
+                    .map_err(McpServiceError::from)?
+                    .ok_or_else(|| {
+                        McpServiceError::ToolNotFound(format!(
+                            "Tool {} not found",
+                            instance.tool_id
+                        ))
+                    })?;
+
+                // Execute using InstanceExecutor directly
+                let executor = InstanceExecutor::new(
+                    self.pool.clone(),
+                    self.server_id,
+                    instance_id,
+                    tool,
+                    self.secrets.clone(),
+                );
+
+                // Register the call so a later notifications/cancelled
+                // request can abort it
+                let (context, _guard) = self.register_call(Some(request_id), origin);
+
+                let call_result = executor.execute_with_context(arguments, &context).await?;
+                result_value(&call_result)
+            }
+
+            "resources/list" => {
+                let resources =
+                    super::resources::list_resources(&self.pool, self.server_id).await?;
+                Ok(json!({
+                    "resources": resources,
+                }))
+            }
+
+            "resources/templates/list" => {
+                let templates =
+                    super::resources::list_resource_templates(&self.pool, self.server_id).await?;
+                Ok(json!({
+                    "resourceTemplates": templates,
+                }))
+            }
+
+            "resources/read" => {
+                let uri = param_str("uri")?;
+                let (context, _guard) = self.register_call(Some(request_id), origin);
+                let read = super::resources::read_resource(
+                    &self.pool,
+                    self.server_id,
+                    &self.secrets,
+                    uri,
+                    &context,
+                )
+                .await?;
+                result_value(&read)
+            }
+
+            "prompts/list" => {
+                let prompts = super::prompts::list_prompts(&self.pool, self.server_id).await?;
+                Ok(json!({
+                    "prompts": prompts,
+                }))
+            }
+
+            "prompts/get" => {
+                let name = param_str("name")?;
+                let arguments = params
+                    .and_then(|p| p.get("arguments"))
+                    .and_then(|v| v.as_object())
+                    .cloned();
+                let prompt =
+                    super::prompts::get_prompt(&self.pool, self.server_id, name, arguments).await?;
+                result_value(&prompt)
+            }
+
+            "completion/complete" => {
+                let request = params
+                    .cloned()
+                    .and_then(|p| serde_json::from_value::<CompleteRequestParam>(p).ok())
+                    .ok_or_else(|| {
+                        ErrorData::invalid_params("Missing or invalid completion request", None)
+                    })?;
+                let (context, _guard) = self.register_call(Some(request_id), origin);
+                let completion = super::completions::complete(
+                    &self.pool,
+                    self.server_id,
+                    &self.secrets,
+                    &self.completions,
+                    &request,
+                    &context,
+                )
+                .await?;
+                result_value(&completion)
+            }
+
+            _ => Err(ErrorData::new(
+                ErrorCode::METHOD_NOT_FOUND,
+                format!("Method '{}' not found", method),
+                None,
+            )),
         }
     }
 }
@@ -502,7 +843,16 @@ impl ServerHandler for SaraMcpService {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::default(),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_logging()
+                .enable_completions()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .enable_resources()
+                .enable_resources_list_changed()
+                .enable_prompts()
+                .enable_prompts_list_changed()
+                .build(),
             server_info: Implementation {
                 name: format!("saramcp-server-{}", self.server_id),
                 version: env!("CARGO_PKG_VERSION").to_string(),
@@ -513,4 +863,125 @@ impl ServerHandler for SaraMcpService {
             instructions: Some(format!("SaraMCP Server {}", self.server_id)),
         }
     }
+
+    async fn initialize(
+        &self,
+        request: InitializeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, ErrorData> {
+        let mut info = self.get_info();
+        info.protocol_version = negotiate_protocol_version(Some(&request.protocol_version));
+        if context.peer.peer_info().is_none() {
+            context.peer.set_peer_info(request);
+        }
+        if let Ok(mut peers) = self.peers.lock() {
+            // Drop clients gone since, or servers whose lists never change
+            // would keep every peer they ever had
+            peers.retain(|peer| !peer.is_transport_closed());
+            peers.push(context.peer.clone());
+        }
+        Ok(info)
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        self.log_level.set(request.level);
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let tools = self.tool_router.read().await.list_all();
+        Ok(ListToolsResult::with_all_items(tools))
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        // Clone the router so a reload isn't held up by a running call
+        let router = self.tool_router.read().await.clone();
+        router
+            .call(ToolCallContext::new(self, request, context))
+            .await
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let resources = super::resources::list_resources(&self.pool, self.server_id).await?;
+        Ok(ListResourcesResult::with_all_items(resources))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        let templates =
+            super::resources::list_resource_templates(&self.pool, self.server_id).await?;
+        Ok(ListResourceTemplatesResult::with_all_items(templates))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        let exec_context = ExecutionContext::from_request_context(&context)
+            .with_logging(self.log_level.clone(), None);
+        super::resources::read_resource(
+            &self.pool,
+            self.server_id,
+            &self.secrets,
+            &request.uri,
+            &exec_context,
+        )
+        .await
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, ErrorData> {
+        let prompts = super::prompts::list_prompts(&self.pool, self.server_id).await?;
+        Ok(ListPromptsResult::with_all_items(prompts))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        super::prompts::get_prompt(&self.pool, self.server_id, &request.name, request.arguments)
+            .await
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, ErrorData> {
+        let exec_context = ExecutionContext::from_request_context(&context)
+            .with_logging(self.log_level.clone(), None);
+        super::completions::complete(
+            &self.pool,
+            self.server_id,
+            &self.secrets,
+            &self.completions,
+            &request,
+            &exec_context,
+        )
+        .await
+    }
 }
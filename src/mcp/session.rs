@@ -0,0 +1,435 @@
+//! Sessions of the Streamable HTTP transport
+//!
+//! A session is opened by an `initialize` request and named by the
+//! `Mcp-Session-Id` header afterwards. It numbers the SSE events sent to its
+//! client and keeps the latest ones, so a client reconnecting with
+//! `Last-Event-ID` can resume the stream it lost. Server-initiated messages
+//! go out on the session's standalone GET stream.
+//!
+//! Requests to the client, such as `elicitation/create`, are sent on a
+//! stream like any other message. The client answers with a POST carrying
+//! the JSON-RPC response, which [`McpSession::respond`] hands to the waiting
+//! request.
+
+use crate::services::execution_context::LogLevel;
+use rmcp::model::ErrorData;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, oneshot};
+use tokio_util::sync::CancellationToken;
+
+/// Header naming the session of a request
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Events kept per session for clients resuming a stream
+const EVENT_HISTORY: usize = 256;
+
+/// Sessions without any request for this long are dropped
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Requests to the client unanswered for this long fail
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Stream an SSE event is sent on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamId {
+    /// The GET stream carrying server-initiated messages
+    Standalone,
+    /// The response stream of a POST, numbered within the session
+    Request(u64),
+}
+
+/// A message sent on one of a session's streams
+#[derive(Debug, Clone)]
+pub struct SessionEvent {
+    /// SSE event ID, increasing across all streams of the session
+    pub id: u64,
+    pub stream: StreamId,
+    /// JSON-RPC message, `None` marking the end of a request stream
+    pub message: Option<Value>,
+}
+
+struct SessionState {
+    events: VecDeque<SessionEvent>,
+    next_event_id: u64,
+    next_stream_id: u64,
+    last_seen: Instant,
+    /// Requests sent to the client awaiting its answer, keyed by JSON-RPC ID
+    pending: HashMap<String, oneshot::Sender<Result<Value, ErrorData>>>,
+    next_request_id: u64,
+    elicitation: bool,
+}
+
+/// A client session of one server
+pub struct McpSession {
+    pub id: String,
+    pub server_uuid: String,
+    /// User of the OAuth token that opened the session, `None` on public servers
+    pub user_id: Option<i64>,
+    /// Minimum level of the log messages sent to the client
+    pub log_level: LogLevel,
+    state: Mutex<SessionState>,
+    sender: broadcast::Sender<SessionEvent>,
+    closed: CancellationToken,
+}
+
+impl McpSession {
+    fn new(server_uuid: &str, user_id: Option<i64>) -> Self {
+        let (sender, _) = broadcast::channel(EVENT_HISTORY);
+        Self {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            server_uuid: server_uuid.to_string(),
+            user_id,
+            log_level: LogLevel::default(),
+            state: Mutex::new(SessionState {
+                events: VecDeque::new(),
+                next_event_id: 1,
+                next_stream_id: 1,
+                last_seen: Instant::now(),
+                pending: HashMap::new(),
+                next_request_id: 1,
+                elicitation: false,
+            }),
+            sender,
+            closed: CancellationToken::new(),
+        }
+    }
+
+    /// A session that is not stored, numbering the events of a client that
+    /// did not open one
+    pub fn ephemeral(server_uuid: &str) -> Arc<Self> {
+        Arc::new(Self::new(server_uuid, None))
+    }
+
+    /// Allocates the stream a POST's responses are sent on
+    pub fn open_request_stream(&self) -> StreamId {
+        let mut state = self.lock();
+        let stream = StreamId::Request(state.next_stream_id);
+        state.next_stream_id += 1;
+        stream
+    }
+
+    /// Records a message on a stream and delivers it to connected clients
+    pub fn send(&self, stream: StreamId, message: Option<Value>) -> u64 {
+        let event = {
+            let mut state = self.lock();
+            let event = SessionEvent {
+                id: state.next_event_id,
+                stream,
+                message,
+            };
+            state.next_event_id += 1;
+            state.events.push_back(event.clone());
+            if state.events.len() > EVENT_HISTORY {
+                state.events.pop_front();
+            }
+            event
+        };
+
+        let id = event.id;
+        // No receiver just means no client is connected to the stream
+        let _ = self.sender.send(event);
+        id
+    }
+
+    /// Records the capabilities the client declared in `initialize`
+    pub fn set_client_capabilities(&self, capabilities: Option<&Value>) {
+        self.lock().elicitation = capabilities.is_some_and(|c| c.get("elicitation").is_some());
+    }
+
+    /// True when the client declared it can ask its user for input
+    pub fn supports_elicitation(&self) -> bool {
+        self.lock().elicitation
+    }
+
+    /// Sends a request to the client on a stream and waits for its result
+    ///
+    /// Fails when the client answers with an error, the session ends or no
+    /// answer arrives within [`REQUEST_TIMEOUT`].
+    pub async fn request(
+        &self,
+        stream: StreamId,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, ErrorData> {
+        let (sender, receiver) = oneshot::channel();
+        let id = {
+            let mut state = self.lock();
+            let id = json!(format!("saramcp-{}", state.next_request_id));
+            state.next_request_id += 1;
+            state.pending.insert(id.to_string(), sender);
+            id
+        };
+        self.send(
+            stream,
+            Some(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params,
+            })),
+        );
+
+        let answer = tokio::select! {
+            _ = self.closed.cancelled() => None,
+            answer = tokio::time::timeout(REQUEST_TIMEOUT, receiver) => answer.ok(),
+        };
+        self.lock().pending.remove(&id.to_string());
+        match answer {
+            Some(Ok(result)) => result,
+            _ => Err(ErrorData::internal_error(
+                format!("No answer from the client to {}", method),
+                None,
+            )),
+        }
+    }
+
+    /// Hands a JSON-RPC response from the client to the request awaiting it
+    ///
+    /// Returns false when no request of this session waits for it.
+    pub fn respond(&self, response: &Value) -> bool {
+        let Some(id) = response.get("id") else {
+            return false;
+        };
+        let Some(sender) = self.lock().pending.remove(&id.to_string()) else {
+            return false;
+        };
+
+        let result = match response.get("error") {
+            Some(error) => Err(serde_json::from_value(error.clone()).unwrap_or_else(|_| {
+                ErrorData::internal_error(format!("Client error: {}", error), None)
+            })),
+            None => Ok(response.get("result").cloned().unwrap_or(Value::Null)),
+        };
+        // The request may have given up waiting
+        let _ = sender.send(result);
+        true
+    }
+
+    /// Live events of all streams
+    pub fn subscribe(&self) -> broadcast::Receiver<SessionEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Recorded events of a stream after the given event ID
+    pub fn events_after(&self, stream: StreamId, after: u64) -> Vec<SessionEvent> {
+        self.lock()
+            .events
+            .iter()
+            .filter(|event| event.stream == stream && event.id > after)
+            .cloned()
+            .collect()
+    }
+
+    /// Stream a recorded event was sent on
+    pub fn stream_of(&self, event_id: u64) -> Option<StreamId> {
+        self.lock()
+            .events
+            .iter()
+            .find(|event| event.id == event_id)
+            .map(|event| event.stream)
+    }
+
+    /// ID of the latest event sent on any stream
+    pub fn last_event_id(&self) -> u64 {
+        self.lock().next_event_id - 1
+    }
+
+    /// Cancelled when the session ends, closing its open streams
+    pub fn closed(&self) -> &CancellationToken {
+        &self.closed
+    }
+
+    fn touch(&self) {
+        self.lock().last_seen = Instant::now();
+    }
+
+    fn is_idle(&self) -> bool {
+        self.lock().last_seen.elapsed() > IDLE_TIMEOUT
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SessionState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Open sessions of all servers, keyed by session ID
+#[derive(Clone, Default)]
+pub struct McpSessionStore {
+    sessions: Arc<Mutex<HashMap<String, Arc<McpSession>>>>,
+}
+
+impl McpSessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a session for a client of a server, dropping idle ones
+    pub fn open(&self, server_uuid: &str, user_id: Option<i64>) -> Arc<McpSession> {
+        let session = Arc::new(McpSession::new(server_uuid, user_id));
+        let mut sessions = self.lock();
+        sessions.retain(|_, session| {
+            let idle = session.is_idle();
+            if idle {
+                session.closed.cancel();
+            }
+            !idle
+        });
+        sessions.insert(session.id.clone(), Arc::clone(&session));
+        session
+    }
+
+    /// Session of a server opened by the same user, if still open
+    pub fn get(
+        &self,
+        server_uuid: &str,
+        session_id: &str,
+        user_id: Option<i64>,
+    ) -> Option<Arc<McpSession>> {
+        let session = self
+            .lock()
+            .get(session_id)
+            .filter(|s| s.server_uuid == server_uuid && s.user_id == user_id)
+            .cloned()?;
+        session.touch();
+        Some(session)
+    }
+
+    /// Ends a session, returning whether it was open
+    pub fn remove(&self, server_uuid: &str, session_id: &str, user_id: Option<i64>) -> bool {
+        let mut sessions = self.lock();
+        let owned = sessions
+            .get(session_id)
+            .is_some_and(|s| s.server_uuid == server_uuid && s.user_id == user_id);
+        if !owned {
+            return false;
+        }
+        if let Some(session) = sessions.remove(session_id) {
+            session.closed.cancel();
+        }
+        true
+    }
+
+    /// Ends every session of a server
+    pub fn close_server(&self, server_uuid: &str) {
+        self.lock().retain(|_, session| {
+            let matches = session.server_uuid == server_uuid;
+            if matches {
+                session.closed.cancel();
+            }
+            !matches
+        });
+    }
+
+    /// Sends a server-initiated message to every session of a server
+    pub fn broadcast(&self, server_uuid: &str, message: &Value) {
+        let sessions: Vec<Arc<McpSession>> = self
+            .lock()
+            .values()
+            .filter(|s| s.server_uuid == server_uuid)
+            .cloned()
+            .collect();
+        for session in sessions {
+            session.send(StreamId::Standalone, Some(message.clone()));
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<McpSession>>> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sessions_are_bound_to_server_and_user() {
+        let store = McpSessionStore::new();
+        let session = store.open("server-a", Some(7));
+
+        assert!(store.get("server-a", &session.id, Some(7)).is_some());
+        assert!(store.get("server-b", &session.id, Some(7)).is_none());
+        assert!(store.get("server-a", &session.id, Some(8)).is_none());
+        assert!(!store.remove("server-a", &session.id, None));
+
+        assert!(store.remove("server-a", &session.id, Some(7)));
+        assert!(session.closed().is_cancelled());
+        assert!(store.get("server-a", &session.id, Some(7)).is_none());
+    }
+
+    #[test]
+    fn test_events_are_replayed_per_stream() {
+        let session = McpSession::ephemeral("server-a");
+        let request = session.open_request_stream();
+
+        let first = session.send(request, Some(json!({"id": 1})));
+        session.send(StreamId::Standalone, Some(json!({"method": "ping"})));
+        session.send(request, Some(json!({"id": 2})));
+        session.send(request, None);
+
+        assert_eq!(session.stream_of(first), Some(request));
+        let replayed: Vec<Option<Value>> = session
+            .events_after(request, first)
+            .into_iter()
+            .map(|event| event.message)
+            .collect();
+        assert_eq!(replayed, vec![Some(json!({"id": 2})), None]);
+        assert_eq!(session.last_event_id(), 4);
+    }
+
+    #[test]
+    fn test_broadcast_reaches_the_sessions_of_a_server() {
+        let store = McpSessionStore::new();
+        let a = store.open("server-a", None);
+        let b = store.open("server-b", None);
+
+        store.broadcast(
+            "server-a",
+            &json!({"method": "notifications/tools/list_changed"}),
+        );
+
+        assert_eq!(a.events_after(StreamId::Standalone, 0).len(), 1);
+        assert!(b.events_after(StreamId::Standalone, 0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_requests_wait_for_the_client_answer() {
+        let session = McpSession::ephemeral("server-a");
+        session.set_client_capabilities(Some(&json!({"elicitation": {}})));
+        assert!(session.supports_elicitation());
+
+        let waiting = {
+            let session = Arc::clone(&session);
+            tokio::spawn(async move {
+                session
+                    .request(StreamId::Standalone, "elicitation/create", json!({}))
+                    .await
+            })
+        };
+        let request = loop {
+            if let Some(event) = session.events_after(StreamId::Standalone, 0).pop() {
+                break event.message.unwrap();
+            }
+            tokio::task::yield_now().await;
+        };
+        assert_eq!(request["method"], "elicitation/create");
+
+        assert!(!session.respond(&json!({"jsonrpc": "2.0", "id": "other", "result": {}})));
+        assert!(session.respond(&json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": {"action": "decline"}
+        })));
+        assert_eq!(
+            waiting.await.unwrap().unwrap(),
+            json!({"action": "decline"})
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! stdio transport for running one server locally
+//!
+//! Desktop clients that only speak stdio launch `saramcp serve-stdio`, which
+//! serves a single server from a local database over stdin/stdout. Tools run
+//! in-process, and secrets are decrypted with the local `SARAMCP_MASTER_KEY`.
+//!
+//! stdout carries the protocol, so logs must go to stderr.
+
+use anyhow::{anyhow, Context, Result};
+use rmcp::transport::IntoTransport;
+use rmcp::{RoleServer, ServiceExt};
+use sqlx::SqlitePool;
+
+use crate::mcp::service::SaraMcpService;
+use crate::models::Server;
+
+/// Serves a server over stdin/stdout until the client disconnects
+pub async fn serve_stdio(pool: SqlitePool, server_uuid: &str) -> Result<()> {
+    serve(pool, server_uuid, rmcp::transport::stdio()).await
+}
+
+/// Serves a server over any transport until the client disconnects
+pub async fn serve<T, E, A>(pool: SqlitePool, server_uuid: &str, transport: T) -> Result<()>
+where
+    T: IntoTransport<RoleServer, E, A>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let server = Server::get_by_uuid(&pool, server_uuid)
+        .await?
+        .ok_or_else(|| anyhow!("Server {} not found", server_uuid))?;
+    let server_id = server
+        .id
+        .ok_or_else(|| anyhow!("Server {} has no ID", server_uuid))?;
+
+    let service = SaraMcpService::new(server_id, pool)
+        .await
+        .context("Failed to create MCP service")?;
+    tracing::info!("Serving '{}' over stdio", server.name);
+
+    let running = service
+        .serve(transport)
+        .await
+        .context("MCP initialization failed")?;
+    let reason = running.waiting().await?;
+    tracing::info!("stdio session ended: {:?}", reason);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    async fn seed_server(pool: &SqlitePool) {
+        let user_id: i64 = sqlx::query_scalar(
+            "INSERT INTO users (email, password_hash) VALUES ('stdio@example.com', 'x') RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO servers (user_id, name, uuid) VALUES (?, 'Local', 'stdio-uuid')")
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn write_message(writer: &mut (impl AsyncWriteExt + Unpin), message: Value) {
+        writer
+            .write_all(format!("{}\n", message).as_bytes())
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_serves_a_server_over_a_byte_stream(pool: SqlitePool) {
+        seed_server(&pool).await;
+
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server);
+        let serving =
+            tokio::spawn(
+                async move { serve(pool, "stdio-uuid", (server_read, server_write)).await },
+            );
+
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let mut lines = BufReader::new(client_read).lines();
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": {"name": "test", "version": "1.0"}
+            }
+        });
+        write_message(&mut client_write, initialize).await;
+        let response: Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(response["id"], 1);
+        assert!(response["result"]["capabilities"]["tools"].is_object());
+
+        for message in [
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+        ] {
+            write_message(&mut client_write, message).await;
+        }
+        let response: Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(response["id"], 2);
+        assert!(response["result"]["tools"].is_array());
+
+        // Closing stdin ends the session
+        drop(client_write);
+        drop(lines);
+        serving.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+/// Calls allowed to wait for a slot unless configured otherwise
+pub const DEFAULT_MAX_QUEUE: u32 = 10;
+
+/// Longest a call waits for a slot unless configured otherwise
+pub const DEFAULT_QUEUE_TIMEOUT_MS: u64 = 10_000;
+
+/// Bound on the executions of a server or tool instance running at once
+///
+/// Stored as JSON in the `concurrency_limit` column of
+/// `server_network_settings` and `tool_instances`. Up to `max_concurrent`
+/// calls run in parallel; up to `max_queue` more wait for a free slot for at
+/// most `queue_timeout_ms`, and any other call fails immediately.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcurrencyLimit {
+    pub max_concurrent: u32,
+    #[serde(default = "default_max_queue")]
+    pub max_queue: u32,
+    #[serde(default = "default_queue_timeout_ms")]
+    pub queue_timeout_ms: u64,
+}
+
+fn default_max_queue() -> u32 {
+    DEFAULT_MAX_QUEUE
+}
+
+fn default_queue_timeout_ms() -> u64 {
+    DEFAULT_QUEUE_TIMEOUT_MS
+}
+
+impl ConcurrencyLimit {
+    /// Parses and validates a stored configuration
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let limit: ConcurrencyLimit =
+            serde_json::from_str(json).map_err(|e| format!("Invalid concurrency limit: {}", e))?;
+        limit.validate()?;
+        Ok(limit)
+    }
+
+    /// Parses a stored configuration, ignoring missing or invalid values
+    pub fn parse(json: Option<&str>) -> Option<Self> {
+        let json = json?;
+        if json.trim().is_empty() {
+            return None;
+        }
+
+        match Self::from_json(json) {
+            Ok(limit) => Some(limit),
+            Err(e) => {
+                tracing::warn!("Ignoring concurrency limit: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Builds the JSON to store from the raw form fields
+    ///
+    /// Returns `None` when no maximum was entered. The result is not
+    /// validated here; callers run [`ConcurrencyLimit::from_json`] before
+    /// saving so the user gets a validation error.
+    pub fn json_from_form(
+        max_concurrent: Option<&str>,
+        max_queue: Option<&str>,
+        queue_timeout_ms: Option<&str>,
+    ) -> Option<String> {
+        let max_concurrent = max_concurrent.map(str::trim).filter(|s| !s.is_empty())?;
+
+        // Unparseable numbers are kept as strings so validation reports them
+        let number = |value: &str| {
+            value
+                .parse::<u64>()
+                .map(JsonValue::from)
+                .unwrap_or(json!(value))
+        };
+
+        let mut limit = json!({ "max_concurrent": number(max_concurrent) });
+        let optional = [
+            ("max_queue", max_queue),
+            ("queue_timeout_ms", queue_timeout_ms),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value.map(str::trim).filter(|s| !s.is_empty()) {
+                limit[key] = number(value);
+            }
+        }
+
+        Some(limit.to_string())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_concurrent == 0 {
+            return Err("Concurrency limit must allow at least one call".to_string());
+        }
+        Ok(())
+    }
+
+    /// Short description for the UI, e.g. "4 at once, 10 queued up to 10000 ms"
+    pub fn summary(&self) -> String {
+        if self.max_queue == 0 {
+            format!("{} at once, no queue", self.max_concurrent)
+        } else {
+            format!(
+                "{} at once, {} queued up to {} ms",
+                self.max_concurrent, self.max_queue, self.queue_timeout_ms
+            )
+        }
+    }
+}
+
+/// Concurrency limit fields of the edit forms, as strings
+#[derive(Debug, Clone, Default)]
+pub struct ConcurrencyLimitFields {
+    pub max_concurrent: String,
+    pub max_queue: String,
+    pub queue_timeout_ms: String,
+}
+
+impl From<Option<ConcurrencyLimit>> for ConcurrencyLimitFields {
+    fn from(limit: Option<ConcurrencyLimit>) -> Self {
+        match limit {
+            Some(limit) => Self {
+                max_concurrent: limit.max_concurrent.to_string(),
+                max_queue: limit.max_queue.to_string(),
+                queue_timeout_ms: limit.queue_timeout_ms.to_string(),
+            },
+            None => Self {
+                max_concurrent: String::new(),
+                max_queue: DEFAULT_MAX_QUEUE.to_string(),
+                queue_timeout_ms: DEFAULT_QUEUE_TIMEOUT_MS.to_string(),
+            },
+        }
+    }
+}
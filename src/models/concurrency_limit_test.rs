@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use super::super::concurrency_limit::{
+        ConcurrencyLimit, ConcurrencyLimitFields, DEFAULT_MAX_QUEUE, DEFAULT_QUEUE_TIMEOUT_MS,
+    };
+
+    #[test]
+    fn test_json_from_form_empty_maximum() {
+        assert_eq!(
+            ConcurrencyLimit::json_from_form(None, Some("5"), Some("1000")),
+            None
+        );
+        assert_eq!(
+            ConcurrencyLimit::json_from_form(Some(" "), None, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_json_from_form_round_trip() {
+        let json = ConcurrencyLimit::json_from_form(Some(" 4 "), Some("0"), Some("2500")).unwrap();
+
+        let limit = ConcurrencyLimit::from_json(&json).unwrap();
+        assert_eq!(limit.max_concurrent, 4);
+        assert_eq!(limit.max_queue, 0);
+        assert_eq!(limit.queue_timeout_ms, 2500);
+        assert_eq!(limit.summary(), "4 at once, no queue");
+    }
+
+    #[test]
+    fn test_defaults_when_omitted() {
+        let limit = ConcurrencyLimit::from_json(r#"{"max_concurrent": 2}"#).unwrap();
+
+        assert_eq!(limit.max_queue, DEFAULT_MAX_QUEUE);
+        assert_eq!(limit.queue_timeout_ms, DEFAULT_QUEUE_TIMEOUT_MS);
+        assert_eq!(limit.summary(), "2 at once, 10 queued up to 10000 ms");
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        assert!(ConcurrencyLimit::from_json(r#"{"max_concurrent": 0}"#).is_err());
+        assert!(ConcurrencyLimit::from_json(r#"{"max_queue": 3}"#).is_err());
+
+        let json = ConcurrencyLimit::json_from_form(Some("two"), None, None).unwrap();
+        assert!(ConcurrencyLimit::from_json(&json).is_err());
+    }
+
+    #[test]
+    fn test_parse_ignores_invalid_configuration() {
+        assert_eq!(ConcurrencyLimit::parse(None), None);
+        assert_eq!(ConcurrencyLimit::parse(Some("not json")), None);
+        assert!(ConcurrencyLimit::parse(Some(r#"{"max_concurrent": 1}"#)).is_some());
+    }
+
+    #[test]
+    fn test_form_fields() {
+        let empty = ConcurrencyLimitFields::from(None);
+        assert_eq!(empty.max_concurrent, "");
+        assert_eq!(empty.max_queue, "10");
+
+        let limit = ConcurrencyLimit::from_json(r#"{"max_concurrent": 3}"#).unwrap();
+        let fields = ConcurrencyLimitFields::from(Some(limit));
+        assert_eq!(fields.max_concurrent, "3");
+        assert_eq!(fields.queue_timeout_ms, "10000");
+    }
+}
@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+/// Status codes that trigger failover when a tool does not configure its own
+pub const DEFAULT_FAILOVER_STATUS_CODES: [u16; 3] = [502, 503, 504];
+
+/// How requests are spread over the configured base URLs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EndpointStrategy {
+    /// Always start with the first base URL, fall back to the next ones
+    #[default]
+    PrimaryFailover,
+    /// Rotate the starting base URL on every request
+    RoundRobin,
+    /// Start with the base URL that answered fastest recently
+    LatencyBased,
+}
+
+impl EndpointStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndpointStrategy::PrimaryFailover => "primary-failover",
+            EndpointStrategy::RoundRobin => "round-robin",
+            EndpointStrategy::LatencyBased => "latency-based",
+        }
+    }
+}
+
+/// Multiple upstream base URLs for a tool or toolkit
+///
+/// Stored as JSON in the `endpoints` column of `tools` and `toolkits`. A tool
+/// without its own configuration inherits the toolkit's. Each base URL
+/// replaces the scheme, host and port of the tool's URL template; the path
+/// and query are kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointConfig {
+    pub base_urls: Vec<String>,
+    #[serde(default)]
+    pub strategy: EndpointStrategy,
+    #[serde(default = "default_failover_status_codes")]
+    pub failover_status_codes: Vec<u16>,
+}
+
+fn default_failover_status_codes() -> Vec<u16> {
+    DEFAULT_FAILOVER_STATUS_CODES.to_vec()
+}
+
+impl EndpointConfig {
+    /// Parses and validates a stored configuration
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let config: EndpointConfig = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid endpoint configuration: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses a stored configuration, ignoring missing or invalid values
+    pub fn parse(json: Option<&str>) -> Option<Self> {
+        let json = json?;
+        if json.trim().is_empty() {
+            return None;
+        }
+
+        match Self::from_json(json) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                tracing::warn!("Ignoring endpoint configuration: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Builds the JSON to store from the raw form fields
+    ///
+    /// Returns `None` when no base URL was entered. The result is not
+    /// validated here; services call [`EndpointConfig::from_json`] before
+    /// saving so the user gets a validation error.
+    pub fn json_from_form(
+        base_urls: Option<&str>,
+        strategy: Option<&str>,
+        failover_status_codes: Option<&str>,
+    ) -> Option<String> {
+        let base_urls: Vec<&str> = base_urls
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        if base_urls.is_empty() {
+            return None;
+        }
+
+        let strategy = strategy
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(EndpointStrategy::default().as_str());
+
+        let mut config = json!({
+            "base_urls": base_urls,
+            "strategy": strategy,
+        });
+
+        if let Some(codes) = failover_status_codes.filter(|c| !c.trim().is_empty()) {
+            // Unparseable codes are kept as strings so validation reports them
+            let codes: Vec<JsonValue> = codes
+                .split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(|c| c.parse::<u16>().map(JsonValue::from).unwrap_or(json!(c)))
+                .collect();
+            config["failover_status_codes"] = JsonValue::Array(codes);
+        }
+
+        Some(config.to_string())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.base_urls.is_empty() {
+            return Err("At least one base URL is required".to_string());
+        }
+
+        for base_url in &self.base_urls {
+            let url = reqwest::Url::parse(base_url)
+                .map_err(|e| format!("Invalid base URL '{}': {}", base_url, e))?;
+            if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+                return Err(format!(
+                    "Base URL '{}' must be an http(s) URL with a host",
+                    base_url
+                ));
+            }
+        }
+
+        if let Some(code) = self
+            .failover_status_codes
+            .iter()
+            .find(|code| !(100..=599).contains(*code))
+        {
+            return Err(format!("Invalid failover status code: {}", code));
+        }
+
+        Ok(())
+    }
+
+    /// True when a response with this status should be retried elsewhere
+    pub fn should_fail_over(&self, status: u16) -> bool {
+        self.failover_status_codes.contains(&status)
+    }
+
+    /// Base URLs, one per line, for the edit forms
+    pub fn base_urls_text(&self) -> String {
+        self.base_urls.join("\n")
+    }
+
+    /// Failover status codes, comma-separated, for the edit forms
+    pub fn failover_status_codes_text(&self) -> String {
+        self.failover_status_codes
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Rewrites `url` to target `base_url`
+    ///
+    /// The scheme, host and port of `url` are replaced by those of
+    /// `base_url`, and the base URL's path (if any) is prefixed to the path
+    /// of `url`. Relative URLs (starting with `/`) are appended as-is.
+    pub fn rebase_url(url: &str, base_url: &str) -> String {
+        let base = base_url.trim_end_matches('/');
+
+        let path_and_query = if url.starts_with('/') {
+            url.to_string()
+        } else {
+            match reqwest::Url::parse(url) {
+                Ok(parsed) => {
+                    let mut rest = parsed.path().to_string();
+                    if let Some(query) = parsed.query() {
+                        rest.push('?');
+                        rest.push_str(query);
+                    }
+                    if let Some(fragment) = parsed.fragment() {
+                        rest.push('#');
+                        rest.push_str(fragment);
+                    }
+                    rest
+                }
+                // Not a URL we can take apart: leave it untouched
+                Err(_) => return url.to_string(),
+            }
+        };
+
+        if path_and_query == "/" {
+            format!("{}/", base)
+        } else {
+            format!("{}{}", base, path_and_query)
+        }
+    }
+}
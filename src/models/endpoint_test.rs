@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use super::super::endpoint::{EndpointConfig, EndpointStrategy};
+
+    #[test]
+    fn test_json_from_form_empty_base_urls() {
+        assert_eq!(EndpointConfig::json_from_form(None, None, None), None);
+        assert_eq!(
+            EndpointConfig::json_from_form(Some("  \n \n"), Some("round-robin"), Some("503")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_json_from_form_round_trip() {
+        let json = EndpointConfig::json_from_form(
+            Some("https://eu.example.com\n\n  https://us.example.com/v1  \n"),
+            Some("round-robin"),
+            Some("500, 503"),
+        )
+        .unwrap();
+
+        let config = EndpointConfig::from_json(&json).unwrap();
+        assert_eq!(
+            config.base_urls,
+            vec!["https://eu.example.com", "https://us.example.com/v1"]
+        );
+        assert_eq!(config.strategy, EndpointStrategy::RoundRobin);
+        assert_eq!(config.failover_status_codes, vec![500, 503]);
+        assert!(config.should_fail_over(503));
+        assert!(!config.should_fail_over(404));
+    }
+
+    #[test]
+    fn test_defaults_when_omitted() {
+        let config =
+            EndpointConfig::from_json(r#"{"base_urls": ["https://a.example.com"]}"#).unwrap();
+
+        assert_eq!(config.strategy, EndpointStrategy::PrimaryFailover);
+        assert_eq!(config.failover_status_codes, vec![502, 503, 504]);
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let invalid_url = EndpointConfig::json_from_form(Some("not a url"), None, None).unwrap();
+        assert!(EndpointConfig::from_json(&invalid_url).is_err());
+
+        let invalid_scheme =
+            EndpointConfig::json_from_form(Some("ftp://files.example.com"), None, None).unwrap();
+        assert!(EndpointConfig::from_json(&invalid_scheme).is_err());
+
+        let invalid_strategy =
+            EndpointConfig::json_from_form(Some("https://a.example.com"), Some("random"), None)
+                .unwrap();
+        assert!(EndpointConfig::from_json(&invalid_strategy).is_err());
+
+        let invalid_code =
+            EndpointConfig::json_from_form(Some("https://a.example.com"), None, Some("5xx"))
+                .unwrap();
+        assert!(EndpointConfig::from_json(&invalid_code).is_err());
+
+        assert!(EndpointConfig::parse(Some(&invalid_code)).is_none());
+    }
+
+    #[test]
+    fn test_rebase_url() {
+        assert_eq!(
+            EndpointConfig::rebase_url(
+                "https://api.example.com/users/42?expand=true",
+                "https://eu.example.com"
+            ),
+            "https://eu.example.com/users/42?expand=true"
+        );
+        assert_eq!(
+            EndpointConfig::rebase_url("http://localhost:8080/v2/items", "https://us.example.com/"),
+            "https://us.example.com/v2/items"
+        );
+        assert_eq!(
+            EndpointConfig::rebase_url("/status", "https://eu.example.com/api"),
+            "https://eu.example.com/api/status"
+        );
+        assert_eq!(
+            EndpointConfig::rebase_url("https://api.example.com", "https://eu.example.com"),
+            "https://eu.example.com/"
+        );
+    }
+}
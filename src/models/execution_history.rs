@@ -11,7 +11,7 @@ pub struct ExecutionHistory {
     pub started_at: String,
     pub completed_at: Option<String>,
     pub duration_ms: Option<i64>,
-    pub status: String, // success, error, timeout
+    pub status: String, // success, error, timeout, cancelled, blocked, throttled
     pub http_status_code: Option<i64>,
     pub error_message: Option<String>,
     pub input_params: Option<String>,     // JSON
@@ -22,6 +22,9 @@ pub struct ExecutionHistory {
     pub response_size_bytes: Option<i64>,
     pub transport: Option<String>, // http, sse
     pub created_at: Option<String>,
+    pub endpoint: Option<String>, // Base URL used when the tool has several
+    pub throttled_ms: Option<i64>, // Time spent waiting for rate limits
+    pub queued_ms: Option<i64>,   // Time spent waiting for a concurrency slot
 }
 
 impl ExecutionHistory {
@@ -44,15 +47,19 @@ impl ExecutionHistory {
         request_method: Option<&str>,
         response_size_bytes: Option<i64>,
         transport: Option<&str>,
+        endpoint: Option<&str>,
+        throttled_ms: Option<i64>,
+        queued_ms: Option<i64>,
     ) -> Result<i64> {
         let result = sqlx::query!(
             r#"
             INSERT INTO execution_history (
                 server_id, instance_id, tool_id, started_at, completed_at, duration_ms,
                 status, http_status_code, error_message, input_params, response_body,
-                response_headers, request_url, request_method, response_size_bytes, transport
+                response_headers, request_url, request_method, response_size_bytes, transport,
+                endpoint, throttled_ms, queued_ms
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
             server_id,
             instance_id,
@@ -69,7 +76,10 @@ impl ExecutionHistory {
             request_url,
             request_method,
             response_size_bytes,
-            transport
+            transport,
+            endpoint,
+            throttled_ms,
+            queued_ms
         )
         .execute(pool)
         .await?;
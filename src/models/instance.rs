@@ -1,6 +1,6 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Connection, FromRow, SqliteConnection, SqliteExecutor, SqlitePool};
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct ToolInstance {
@@ -11,6 +11,8 @@ pub struct ToolInstance {
     pub description: Option<String>,
     pub created_at: Option<time::OffsetDateTime>,
     pub updated_at: Option<time::OffsetDateTime>,
+    /// JSON `ConcurrencyLimit` of the instance's calls
+    pub concurrency_limit: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -32,6 +34,14 @@ pub struct ConfigureInstanceForm {
     pub csrf_token: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct InstanceLimitsForm {
+    pub concurrency_max: Option<String>,
+    pub concurrency_queue: Option<String>,
+    pub concurrency_timeout: Option<String>, // Milliseconds
+    pub csrf_token: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ParamConfig {
     pub name: String,
@@ -50,6 +60,7 @@ pub struct InstanceDetail {
     pub tool_description: Option<String>,
     pub toolkit_name: String,
     pub params: Vec<InstanceParam>,
+    pub concurrency_limit: Option<String>,
     pub created_at: chrono::NaiveDateTime,
 }
 
@@ -78,11 +89,11 @@ impl ToolInstance {
     }
 
     pub async fn create_with_config(
-        pool: &SqlitePool,
+        conn: &mut SqliteConnection,
         server_id: i64,
         form: ConfigureInstanceForm,
     ) -> Result<i64> {
-        let mut tx = pool.begin().await?;
+        let mut tx = conn.begin().await?;
 
         // Inherit tool description if form description is empty or None
         let description = if form
@@ -151,7 +162,10 @@ impl ToolInstance {
         Ok(instance)
     }
 
-    pub async fn get_detail(pool: &SqlitePool, id: i64) -> Result<Option<InstanceDetail>> {
+    pub async fn get_detail(
+        conn: &mut SqliteConnection,
+        id: i64,
+    ) -> Result<Option<InstanceDetail>> {
         // Get instance with tool and toolkit info
         let instance_data = sqlx::query!(
             r#"
@@ -162,6 +176,7 @@ impl ToolInstance {
                 ti.instance_name,
                 ti.description,
                 ti.created_at,
+                ti.concurrency_limit,
                 t.name as tool_name,
                 t.description as tool_description,
                 tk.title as toolkit_name
@@ -172,12 +187,12 @@ impl ToolInstance {
             "#,
             id
         )
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         if let Some(data) = instance_data {
             // Get parameters
-            let params = InstanceParam::list_by_instance(pool, id).await?;
+            let params = InstanceParam::list_by_instance(&mut *conn, id).await?;
 
             Ok(Some(InstanceDetail {
                 id: data.id,
@@ -189,6 +204,7 @@ impl ToolInstance {
                 tool_description: data.tool_description.clone(),
                 toolkit_name: data.toolkit_name.clone(),
                 params,
+                concurrency_limit: data.concurrency_limit.clone(),
                 created_at: chrono::NaiveDateTime::default(),
             }))
         } else {
@@ -208,6 +224,18 @@ impl ToolInstance {
         Ok(instances)
     }
 
+    /// Servers with an instance of the tool
+    pub async fn server_ids_for_tool(pool: &SqlitePool, tool_id: i64) -> Result<Vec<i64>> {
+        let server_ids = sqlx::query_scalar!(
+            "SELECT DISTINCT server_id FROM tool_instances WHERE tool_id = ?",
+            tool_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(server_ids)
+    }
+
     pub async fn find_by_server_and_name(
         pool: &SqlitePool,
         server_id: i64,
@@ -238,6 +266,7 @@ impl ToolInstance {
                 ti.instance_name,
                 ti.description,
                 ti.created_at,
+                ti.concurrency_limit,
                 t.name as tool_name,
                 t.description as tool_description,
                 tk.title as toolkit_name
@@ -265,6 +294,7 @@ impl ToolInstance {
                 tool_description: data.tool_description.clone(),
                 toolkit_name: data.toolkit_name.clone(),
                 params,
+                concurrency_limit: data.concurrency_limit.clone(),
                 created_at: chrono::NaiveDateTime::default(),
             });
         }
@@ -273,7 +303,7 @@ impl ToolInstance {
     }
 
     pub async fn update(
-        pool: &SqlitePool,
+        db: impl SqliteExecutor<'_>,
         id: i64,
         instance_name: &str,
         description: Option<&str>,
@@ -288,15 +318,35 @@ impl ToolInstance {
             description,
             id
         )
-        .execute(pool)
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_concurrency_limit(
+        db: impl SqliteExecutor<'_>,
+        id: i64,
+        concurrency_limit: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE tool_instances
+            SET concurrency_limit = ?, updated_at = unixepoch()
+            WHERE id = ?
+            "#,
+            concurrency_limit,
+            id
+        )
+        .execute(db)
         .await?;
 
         Ok(())
     }
 
-    pub async fn delete(pool: &SqlitePool, id: i64) -> Result<()> {
+    pub async fn delete(db: impl SqliteExecutor<'_>, id: i64) -> Result<()> {
         sqlx::query!("DELETE FROM tool_instances WHERE id = ?", id)
-            .execute(pool)
+            .execute(db)
             .await?;
 
         Ok(())
@@ -359,13 +409,52 @@ impl InstanceParam {
         Ok(result.last_insert_rowid())
     }
 
-    pub async fn list_by_instance(pool: &SqlitePool, instance_id: i64) -> Result<Vec<Self>> {
+    /// Replaces every parameter of an instance
+    pub async fn replace_all(
+        conn: &mut SqliteConnection,
+        instance_id: i64,
+        params: Vec<InstanceParam>,
+    ) -> Result<()> {
+        let mut tx = conn.begin().await?;
+
+        // Clear existing params
+        sqlx::query!(
+            "DELETE FROM instance_params WHERE instance_id = ?",
+            instance_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Insert new params
+        for param in params {
+            sqlx::query!(
+                r#"
+                INSERT INTO instance_params (instance_id, param_name, source, value)
+                VALUES (?, ?, ?, ?)
+                "#,
+                instance_id,
+                param.param_name,
+                param.source,
+                param.value
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn list_by_instance(
+        db: impl SqliteExecutor<'_>,
+        instance_id: i64,
+    ) -> Result<Vec<Self>> {
         let params = sqlx::query_as!(
             InstanceParam,
             "SELECT * FROM instance_params WHERE instance_id = ? ORDER BY param_name",
             instance_id
         )
-        .fetch_all(pool)
+        .fetch_all(db)
         .await?;
 
         Ok(params)
@@ -1,27 +1,59 @@
 pub mod auth_token;
+pub mod concurrency_limit;
+pub mod endpoint;
 pub mod execution_history;
 pub mod instance;
 pub mod oauth;
+pub mod parameter_metadata;
+pub mod rate_limit;
 pub mod server;
 pub mod server_global;
+pub mod server_network;
+pub mod server_prompt;
+pub mod server_resource;
 pub mod tool;
 pub mod toolkit;
 pub mod user;
 
+#[cfg(test)]
+mod concurrency_limit_test;
+
+#[cfg(test)]
+mod endpoint_test;
+
 #[cfg(test)]
 mod instance_test;
 
+#[cfg(test)]
+mod parameter_metadata_test;
+
+#[cfg(test)]
+mod rate_limit_test;
+
+#[cfg(test)]
+mod server_prompt_test;
+
 #[cfg(test)]
 mod tool_test;
 
 pub use auth_token::{MagicLoginToken, PendingRegistration};
+pub use concurrency_limit::{ConcurrencyLimit, ConcurrencyLimitFields};
+pub use endpoint::{EndpointConfig, EndpointStrategy};
 pub use execution_history::{DailyExecutionStats, ExecutionHistory, ToolUsageStats};
 pub use instance::{
-    ConfigureInstanceForm, InstanceDetail, InstanceParam, ParamConfig, ToolInstance,
+    ConfigureInstanceForm, InstanceDetail, InstanceLimitsForm, InstanceParam, ParamConfig,
+    ToolInstance,
 };
 pub use oauth::{OAuthAccessToken, OAuthAuthorizationCode, OAuthClient, OAuthRefreshToken};
+pub use parameter_metadata::{ParameterMetadata, ParameterMetadataMap};
+pub use rate_limit::{RateLimitConfig, RateLimitFields, RateLimitMode};
 pub use server::{CreateServerForm, Server, ServerSummary, ServerToolkit, UpdateServerForm};
 pub use server_global::{GlobalsForm, ServerGlobal};
+pub use server_network::{NetworkSettingsForm, ServerNetworkSettings};
+pub use server_prompt::{PromptArgumentSpec, PromptArgumentSpecs, PromptForm, ServerPrompt};
+pub use server_resource::{
+    PublishInstanceForm, ResourceDocumentForm, ServerResource, RESOURCE_URI_PREFIX,
+};
 pub use tool::{
     CreateToolForm, CreateToolRequest, ExtractedParameter, Tool, UpdateToolForm, UpdateToolRequest,
 };
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+/// Documentation of a single tool parameter
+///
+/// Stored per parameter name as JSON in the `parameter_metadata` column of
+/// `tools`, typically filled in by importers from the source API description.
+/// It only affects the input schema and argument completions shown to MCP
+/// clients; values are still cast by the placeholder type.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParameterMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Allowed values, empty when any value of the type is accepted
+    #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    pub enum_values: Vec<JsonValue>,
+    /// Tool instance that suggests values to clients, looked up by name on
+    /// the server using the tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lookup: Option<String>,
+}
+
+/// Metadata of a tool's parameters, keyed by parameter name
+pub type ParameterMetadataMap = BTreeMap<String, ParameterMetadata>;
+
+impl ParameterMetadata {
+    /// Parses and validates stored metadata
+    pub fn map_from_json(json: &str) -> Result<ParameterMetadataMap, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid parameter metadata: {}", e))
+    }
+
+    /// Parses stored metadata, ignoring missing or invalid values
+    pub fn parse_map(json: Option<&str>) -> ParameterMetadataMap {
+        let Some(json) = json.filter(|s| !s.trim().is_empty()) else {
+            return ParameterMetadataMap::new();
+        };
+
+        Self::map_from_json(json).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring parameter metadata: {}", e);
+            ParameterMetadataMap::new()
+        })
+    }
+
+    /// Serializes metadata for storage, `None` when there is nothing to keep
+    pub fn map_to_json(map: &ParameterMetadataMap) -> Option<String> {
+        let map: ParameterMetadataMap = map
+            .iter()
+            .filter(|(_, meta)| !meta.is_empty())
+            .map(|(name, meta)| (name.clone(), meta.clone()))
+            .collect();
+        if map.is_empty() {
+            return None;
+        }
+        serde_json::to_string(&map).ok()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none() && self.enum_values.is_empty() && self.lookup.is_none()
+    }
+}
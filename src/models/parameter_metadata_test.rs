@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use super::super::parameter_metadata::{ParameterMetadata, ParameterMetadataMap};
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip() {
+        let mut map = ParameterMetadataMap::new();
+        map.insert(
+            "status".to_string(),
+            ParameterMetadata {
+                description: Some("Order status".to_string()),
+                enum_values: vec![json!("open"), json!("closed")],
+                lookup: None,
+            },
+        );
+
+        let json = ParameterMetadata::map_to_json(&map).unwrap();
+        assert!(json.contains(r#""enum":["open","closed"]"#));
+        assert_eq!(ParameterMetadata::map_from_json(&json).unwrap(), map);
+    }
+
+    #[test]
+    fn test_empty_entries_are_dropped() {
+        let mut map = ParameterMetadataMap::new();
+        map.insert("id".to_string(), ParameterMetadata::default());
+
+        assert_eq!(ParameterMetadata::map_to_json(&map), None);
+    }
+
+    #[test]
+    fn test_parse_map_ignores_invalid_metadata() {
+        assert!(ParameterMetadata::parse_map(None).is_empty());
+        assert!(ParameterMetadata::parse_map(Some("[1, 2]")).is_empty());
+
+        let map = ParameterMetadata::parse_map(Some(r#"{"q": {"description": "Search"}}"#));
+        assert_eq!(map["q"].description.as_deref(), Some("Search"));
+        assert!(map["q"].enum_values.is_empty());
+    }
+
+    #[test]
+    fn test_lookup_alone_is_kept() {
+        let map =
+            ParameterMetadata::parse_map(Some(r#"{"customer": {"lookup": "find_customers"}}"#));
+        assert_eq!(map["customer"].lookup.as_deref(), Some("find_customers"));
+        assert!(ParameterMetadata::map_to_json(&map)
+            .unwrap()
+            .contains("find_customers"));
+    }
+}
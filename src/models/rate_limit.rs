@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+/// Longest a call waits for its rate limits unless configured otherwise
+pub const DEFAULT_MAX_WAIT_MS: u64 = 30_000;
+
+/// What happens to a call that exceeds a rate limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RateLimitMode {
+    /// Delay the call until a token is available (up to `max_wait_ms`)
+    #[default]
+    Wait,
+    /// Fail the call immediately
+    Reject,
+}
+
+impl RateLimitMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitMode::Wait => "wait",
+            RateLimitMode::Reject => "reject",
+        }
+    }
+}
+
+/// Token-bucket rate limit of a tool, toolkit or server
+///
+/// Stored as JSON in the `rate_limit` column of `tools`, `toolkits` and
+/// `server_network_settings`. The bucket holds up to `burst` tokens (defaults
+/// to `requests`) and refills at `requests` tokens per `period_seconds`; each
+/// upstream call takes one token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    pub requests: u32,
+    #[serde(default = "default_period_seconds")]
+    pub period_seconds: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
+    #[serde(default)]
+    pub mode: RateLimitMode,
+    #[serde(default = "default_max_wait_ms")]
+    pub max_wait_ms: u64,
+}
+
+fn default_period_seconds() -> u32 {
+    1
+}
+
+fn default_max_wait_ms() -> u64 {
+    DEFAULT_MAX_WAIT_MS
+}
+
+impl RateLimitConfig {
+    /// Parses and validates a stored configuration
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let config: RateLimitConfig = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid rate limit configuration: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses a stored configuration, ignoring missing or invalid values
+    pub fn parse(json: Option<&str>) -> Option<Self> {
+        let json = json?;
+        if json.trim().is_empty() {
+            return None;
+        }
+
+        match Self::from_json(json) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                tracing::warn!("Ignoring rate limit configuration: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Builds the JSON to store from the raw form fields
+    ///
+    /// Returns `None` when no request count was entered. The result is not
+    /// validated here; services call [`RateLimitConfig::from_json`] before
+    /// saving so the user gets a validation error.
+    pub fn json_from_form(
+        requests: Option<&str>,
+        period_seconds: Option<&str>,
+        burst: Option<&str>,
+        mode: Option<&str>,
+        max_wait_ms: Option<&str>,
+    ) -> Option<String> {
+        let requests = requests.map(str::trim).filter(|s| !s.is_empty())?;
+
+        // Unparseable numbers are kept as strings so validation reports them
+        let number = |value: &str| {
+            value
+                .parse::<u64>()
+                .map(JsonValue::from)
+                .unwrap_or(json!(value))
+        };
+
+        let mode = mode
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(RateLimitMode::default().as_str());
+
+        let mut config = json!({
+            "requests": number(requests),
+            "mode": mode,
+        });
+
+        let optional = [
+            ("period_seconds", period_seconds),
+            ("burst", burst),
+            ("max_wait_ms", max_wait_ms),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value.map(str::trim).filter(|s| !s.is_empty()) {
+                config[key] = number(value);
+            }
+        }
+
+        Some(config.to_string())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.requests == 0 {
+            return Err("Rate limit must allow at least one request".to_string());
+        }
+        if self.period_seconds == 0 {
+            return Err("Rate limit period must be at least one second".to_string());
+        }
+        if self.burst == Some(0) {
+            return Err("Rate limit burst must be at least one request".to_string());
+        }
+        Ok(())
+    }
+
+    /// Number of tokens the bucket holds when full
+    pub fn capacity(&self) -> f64 {
+        self.burst.unwrap_or(self.requests) as f64
+    }
+
+    /// Tokens added to the bucket per second
+    pub fn refill_per_second(&self) -> f64 {
+        self.requests as f64 / self.period_seconds as f64
+    }
+
+    /// Short description for the UI, e.g. "10 requests / 60 s"
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} requests / {} s", self.requests, self.period_seconds);
+        if let Some(burst) = self.burst {
+            summary.push_str(&format!(", burst {}", burst));
+        }
+        summary.push_str(match self.mode {
+            RateLimitMode::Wait => ", waits",
+            RateLimitMode::Reject => ", fails fast",
+        });
+        summary
+    }
+}
+
+/// Rate limit fields of the edit forms, as strings
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitFields {
+    pub requests: String,
+    pub period_seconds: String,
+    pub burst: String,
+    pub mode: String,
+    pub max_wait_ms: String,
+}
+
+impl From<Option<RateLimitConfig>> for RateLimitFields {
+    fn from(config: Option<RateLimitConfig>) -> Self {
+        match config {
+            Some(config) => Self {
+                requests: config.requests.to_string(),
+                period_seconds: config.period_seconds.to_string(),
+                burst: config.burst.map(|b| b.to_string()).unwrap_or_default(),
+                mode: config.mode.as_str().to_string(),
+                max_wait_ms: config.max_wait_ms.to_string(),
+            },
+            None => Self {
+                period_seconds: default_period_seconds().to_string(),
+                mode: RateLimitMode::default().as_str().to_string(),
+                max_wait_ms: DEFAULT_MAX_WAIT_MS.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use super::super::rate_limit::{
+        RateLimitConfig, RateLimitFields, RateLimitMode, DEFAULT_MAX_WAIT_MS,
+    };
+
+    #[test]
+    fn test_json_from_form_empty_requests() {
+        assert_eq!(
+            RateLimitConfig::json_from_form(None, Some("60"), None, Some("reject"), None),
+            None
+        );
+        assert_eq!(
+            RateLimitConfig::json_from_form(Some("  "), None, None, None, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_json_from_form_round_trip() {
+        let json = RateLimitConfig::json_from_form(
+            Some(" 10 "),
+            Some("60"),
+            Some("20"),
+            Some("reject"),
+            Some("5000"),
+        )
+        .unwrap();
+
+        let config = RateLimitConfig::from_json(&json).unwrap();
+        assert_eq!(config.requests, 10);
+        assert_eq!(config.period_seconds, 60);
+        assert_eq!(config.burst, Some(20));
+        assert_eq!(config.mode, RateLimitMode::Reject);
+        assert_eq!(config.max_wait_ms, 5000);
+        assert_eq!(config.capacity(), 20.0);
+        assert!((config.refill_per_second() - 10.0 / 60.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_defaults_when_omitted() {
+        let config = RateLimitConfig::from_json(r#"{"requests": 5}"#).unwrap();
+
+        assert_eq!(config.period_seconds, 1);
+        assert_eq!(config.burst, None);
+        assert_eq!(config.capacity(), 5.0);
+        assert_eq!(config.mode, RateLimitMode::Wait);
+        assert_eq!(config.max_wait_ms, DEFAULT_MAX_WAIT_MS);
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        assert!(RateLimitConfig::from_json(r#"{"requests": 0}"#).is_err());
+        assert!(RateLimitConfig::from_json(r#"{"requests": 1, "period_seconds": 0}"#).is_err());
+        assert!(RateLimitConfig::from_json(r#"{"requests": 1, "burst": 0}"#).is_err());
+        assert!(RateLimitConfig::from_json(r#"{"requests": 1, "mode": "queue"}"#).is_err());
+
+        let json = RateLimitConfig::json_from_form(Some("ten"), None, None, None, None).unwrap();
+        assert!(RateLimitConfig::from_json(&json).is_err());
+    }
+
+    #[test]
+    fn test_parse_ignores_invalid_configuration() {
+        assert_eq!(RateLimitConfig::parse(None), None);
+        assert_eq!(RateLimitConfig::parse(Some("")), None);
+        assert_eq!(RateLimitConfig::parse(Some("not json")), None);
+        assert!(RateLimitConfig::parse(Some(r#"{"requests": 3}"#)).is_some());
+    }
+
+    #[test]
+    fn test_form_fields() {
+        let empty = RateLimitFields::from(None);
+        assert_eq!(empty.requests, "");
+        assert_eq!(empty.period_seconds, "1");
+        assert_eq!(empty.mode, "wait");
+
+        let config = RateLimitConfig::from_json(r#"{"requests": 3, "burst": 6}"#).unwrap();
+        assert_eq!(config.summary(), "3 requests / 1 s, burst 6, waits");
+        let fields = RateLimitFields::from(Some(config));
+        assert_eq!(fields.requests, "3");
+        assert_eq!(fields.burst, "6");
+    }
+}
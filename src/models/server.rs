@@ -1,6 +1,6 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqliteExecutor, SqlitePool};
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -54,7 +54,7 @@ impl Server {
     }
 
     pub async fn create_with_toolkits(
-        pool: &SqlitePool,
+        db: impl SqliteExecutor<'_>,
         user_id: i64,
         form: CreateServerForm,
     ) -> Result<i64> {
@@ -70,7 +70,7 @@ impl Server {
             form.name,
             form.description
         )
-        .execute(pool)
+        .execute(db)
         .await?;
 
         Ok(result.last_insert_rowid())
@@ -130,7 +130,7 @@ impl Server {
     }
 
     pub async fn update(
-        pool: &SqlitePool,
+        db: impl SqliteExecutor<'_>,
         id: i64,
         user_id: i64,
         form: UpdateServerForm,
@@ -146,7 +146,42 @@ impl Server {
             id,
             user_id
         )
-        .execute(pool)
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_access_level(
+        db: impl SqliteExecutor<'_>,
+        id: i64,
+        user_id: i64,
+        access_level: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE servers
+            SET access_level = ?, updated_at = unixepoch()
+            WHERE id = ? AND user_id = ?
+            "#,
+            access_level,
+            id,
+            user_id
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks a configuration document as applied, for the web process to
+    /// reload the server
+    pub async fn bump_config_version(db: impl SqliteExecutor<'_>, id: i64) -> Result<()> {
+        sqlx::query!(
+            "UPDATE servers SET config_version = config_version + 1 WHERE id = ?",
+            id
+        )
+        .execute(db)
         .await?;
 
         Ok(())
@@ -239,4 +274,31 @@ impl ServerToolkit {
 
         Ok(toolkits)
     }
+    pub async fn add(db: impl SqliteExecutor<'_>, server_id: i64, toolkit_id: i64) -> Result<()> {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO server_toolkits (server_id, toolkit_id) VALUES (?, ?)",
+            server_id,
+            toolkit_id
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove(
+        db: impl SqliteExecutor<'_>,
+        server_id: i64,
+        toolkit_id: i64,
+    ) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM server_toolkits WHERE server_id = ? AND toolkit_id = ?",
+            server_id,
+            toolkit_id
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
 }
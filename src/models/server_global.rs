@@ -1,6 +1,6 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqliteExecutor, SqlitePool};
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct ServerGlobal {
@@ -122,13 +122,41 @@ impl ServerGlobal {
         Ok(())
     }
 
-    pub async fn delete(pool: &SqlitePool, server_id: i64, key: &str) -> Result<()> {
+    /// Stores a global, overwriting the value of an existing one
+    ///
+    /// Secret values are stored as given, callers encrypt them first.
+    pub async fn upsert(
+        db: impl SqliteExecutor<'_>,
+        server_id: i64,
+        key: &str,
+        value: &str,
+        is_secret: bool,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO server_globals (server_id, key, value, is_secret)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(server_id, key)
+            DO UPDATE SET value = excluded.value, is_secret = excluded.is_secret, updated_at = CURRENT_TIMESTAMP
+            "#,
+            server_id,
+            key,
+            value,
+            is_secret
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(db: impl SqliteExecutor<'_>, server_id: i64, key: &str) -> Result<()> {
         sqlx::query!(
             "DELETE FROM server_globals WHERE server_id = ? AND key = ?",
             server_id,
             key
         )
-        .execute(pool)
+        .execute(db)
         .await?;
 
         Ok(())
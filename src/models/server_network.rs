@@ -0,0 +1,183 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Outbound network settings of a server
+///
+/// `proxy_url`, `client_cert` and `client_key` are stored encrypted with the
+/// master key; `ca_bundle` is stored as plain PEM. `allowed_hosts` and
+/// `denied_hosts` hold egress rules, one per line, managed by operators.
+/// `rate_limit` is a JSON `RateLimitConfig` shared by all calls of the server,
+/// `concurrency_limit` a JSON `ConcurrencyLimit` bounding them.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ServerNetworkSettings {
+    pub server_id: i64,
+    pub proxy_url: Option<String>,
+    pub ca_bundle: Option<String>,
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    pub allowed_hosts: Option<String>,
+    pub denied_hosts: Option<String>,
+    pub rate_limit: Option<String>,
+    pub concurrency_limit: Option<String>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NetworkSettingsForm {
+    /// Left empty to keep the stored proxy
+    #[serde(default)]
+    pub proxy_url: String,
+    /// Checkbox removing the stored proxy
+    pub clear_proxy: Option<String>,
+    #[serde(default)]
+    pub ca_bundle: String,
+    /// Left empty to keep the stored certificate
+    #[serde(default)]
+    pub client_cert: String,
+    /// Left empty to keep the stored key
+    #[serde(default)]
+    pub client_key: String,
+    /// Checkbox removing the stored client certificate and key
+    pub clear_client_identity: Option<String>,
+    pub rate_limit_requests: Option<String>,
+    pub rate_limit_period: Option<String>, // Seconds
+    pub rate_limit_burst: Option<String>,
+    pub rate_limit_mode: Option<String>,     // wait or reject
+    pub rate_limit_max_wait: Option<String>, // Milliseconds
+    pub concurrency_max: Option<String>,
+    pub concurrency_queue: Option<String>,
+    pub concurrency_timeout: Option<String>, // Milliseconds
+    pub csrf_token: String,
+}
+
+impl ServerNetworkSettings {
+    pub async fn get(pool: &SqlitePool, server_id: i64) -> Result<Option<Self>> {
+        let settings = sqlx::query_as!(
+            ServerNetworkSettings,
+            r#"
+            SELECT server_id as "server_id!", proxy_url, ca_bundle, client_cert, client_key,
+                   allowed_hosts, denied_hosts, rate_limit, concurrency_limit,
+                   created_at, updated_at
+            FROM server_network_settings
+            WHERE server_id = ?
+            "#,
+            server_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(settings)
+    }
+
+    /// Stores the proxy, CA bundle, client identity, rate limit and
+    /// concurrency limit of a server
+    ///
+    /// Egress host rules are left untouched.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert(
+        pool: &SqlitePool,
+        server_id: i64,
+        proxy_url: Option<&str>,
+        ca_bundle: Option<&str>,
+        client_cert: Option<&str>,
+        client_key: Option<&str>,
+        rate_limit: Option<&str>,
+        concurrency_limit: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO server_network_settings
+                (server_id, proxy_url, ca_bundle, client_cert, client_key, rate_limit,
+                 concurrency_limit)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(server_id) DO UPDATE SET
+                proxy_url = excluded.proxy_url,
+                ca_bundle = excluded.ca_bundle,
+                client_cert = excluded.client_cert,
+                client_key = excluded.client_key,
+                rate_limit = excluded.rate_limit,
+                concurrency_limit = excluded.concurrency_limit,
+                updated_at = unixepoch()
+            "#,
+            server_id,
+            proxy_url,
+            ca_bundle,
+            client_cert,
+            client_key,
+            rate_limit,
+            concurrency_limit
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stores the egress host rules of a server
+    pub async fn set_host_rules(
+        pool: &SqlitePool,
+        server_id: i64,
+        allowed_hosts: Option<&str>,
+        denied_hosts: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO server_network_settings (server_id, allowed_hosts, denied_hosts)
+            VALUES (?, ?, ?)
+            ON CONFLICT(server_id) DO UPDATE SET
+                allowed_hosts = excluded.allowed_hosts,
+                denied_hosts = excluded.denied_hosts,
+                updated_at = unixepoch()
+            "#,
+            server_id,
+            allowed_hosts,
+            denied_hosts
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, server_id: i64) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM server_network_settings WHERE server_id = ?",
+            server_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// True when no setting is configured, i.e. the default client applies
+    pub fn is_empty(&self) -> bool {
+        self.proxy_url.is_none()
+            && self.ca_bundle.is_none()
+            && self.client_cert.is_none()
+            && self.client_key.is_none()
+            && self.allowed_hosts.is_none()
+            && self.denied_hosts.is_none()
+            && self.rate_limit.is_none()
+            && self.concurrency_limit.is_none()
+    }
+
+    /// True when a setting the client is built from is configured, the
+    /// fields [`EgressClientCache`] fingerprints; limits apply to any client
+    ///
+    /// [`EgressClientCache`]: crate::services::EgressClientCache
+    pub fn needs_client(&self) -> bool {
+        self.proxy_url.is_some()
+            || self.ca_bundle.is_some()
+            || self.client_cert.is_some()
+            || self.client_key.is_some()
+            || self.allowed_hosts.is_some()
+            || self.denied_hosts.is_some()
+    }
+
+    pub fn has_client_identity(&self) -> bool {
+        self.client_cert.is_some() && self.client_key.is_some()
+    }
+}
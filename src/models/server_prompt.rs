@@ -0,0 +1,259 @@
+use crate::services::variable_engine::TypedVariableEngine;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use std::collections::{BTreeMap, HashSet};
+
+/// An MCP prompt of a server
+///
+/// The template is sent as a single user message. Its `{{type:name}}`
+/// placeholders are filled from plain server globals of the same name or
+/// from prompt arguments, cast by their type like tool parameters.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ServerPrompt {
+    pub id: Option<i64>,
+    pub server_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub template: String,
+    pub arguments: Option<String>,
+    pub created_at: Option<time::OffsetDateTime>,
+    pub updated_at: Option<time::OffsetDateTime>,
+}
+
+/// Documentation of a prompt argument
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PromptArgumentSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    /// Values offered for completion
+    #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    pub enum_values: Vec<String>,
+    /// Tool instance of the server that suggests values for completion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lookup: Option<String>,
+}
+
+/// Documentation of a prompt's arguments, keyed by argument name
+pub type PromptArgumentSpecs = BTreeMap<String, PromptArgumentSpec>;
+
+#[derive(Debug, Deserialize)]
+pub struct PromptForm {
+    pub name: String,
+    pub description: Option<String>,
+    pub template: String,
+    pub arguments: Option<String>, // One `name: description` per line, `name?:` when optional
+    pub csrf_token: String,
+}
+
+impl ServerPrompt {
+    /// Names follow the same alphabet as tool instance names
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    }
+
+    /// Placeholders of the template as `(type, name)`, first occurrence of each name
+    pub fn placeholders(&self) -> Vec<(String, String)> {
+        let mut seen = HashSet::new();
+        TypedVariableEngine::new()
+            .find_variables(&self.template)
+            .into_iter()
+            .filter(|(_, name)| seen.insert(name.clone()))
+            .map(|(var_type, name)| (var_type.unwrap_or_else(|| "string".to_string()), name))
+            .collect()
+    }
+
+    /// Parsed argument documentation, ignoring an invalid stored value
+    pub fn argument_specs(&self) -> PromptArgumentSpecs {
+        let Some(json) = self.arguments.as_deref().filter(|s| !s.trim().is_empty()) else {
+            return PromptArgumentSpecs::new();
+        };
+
+        serde_json::from_str(json).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring arguments of prompt '{}': {}", self.name, e);
+            PromptArgumentSpecs::new()
+        })
+    }
+
+    /// Argument documentation in the line format of the edit form
+    pub fn arguments_text(&self) -> String {
+        self.argument_specs()
+            .iter()
+            .map(|(name, spec)| {
+                let marker = if spec.optional { "?" } else { "" };
+                let mut line = match &spec.description {
+                    Some(description) => format!("{}{}: {}", name, marker, description),
+                    None => format!("{}{}:", name, marker),
+                };
+                if !spec.enum_values.is_empty() {
+                    line.push_str(&format!("; enum: {}", spec.enum_values.join(", ")));
+                }
+                if let Some(lookup) = &spec.lookup {
+                    line.push_str(&format!("; lookup: {}", lookup));
+                }
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Parses argument documentation written one `name: description` per line
+    ///
+    /// A `?` after the name marks the argument optional. Completion sources
+    /// follow the description: `; enum: open, closed` lists the values, and
+    /// `; lookup: find_customers` names a tool instance suggesting them.
+    pub fn parse_arguments_text(text: &str) -> Result<PromptArgumentSpecs, String> {
+        let mut specs = PromptArgumentSpecs::new();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (name, rest) = line.split_once(':').unwrap_or((line, ""));
+            let mut parts = rest.split(';');
+            let mut description = parts.next().unwrap_or_default().to_string();
+            let mut enum_values = Vec::new();
+            let mut lookup = None;
+            for part in parts {
+                match part.split_once(':').map(|(k, v)| (k.trim(), v.trim())) {
+                    Some(("enum", values)) => {
+                        enum_values = values
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|v| !v.is_empty())
+                            .map(str::to_string)
+                            .collect();
+                    }
+                    Some(("lookup", instance)) if !instance.is_empty() => {
+                        lookup = Some(instance.to_string());
+                    }
+                    // Not a completion source, so part of the description
+                    _ => {
+                        description.push(';');
+                        description.push_str(part);
+                    }
+                }
+            }
+            let name = name.trim();
+            let (name, optional) = match name.strip_suffix('?') {
+                Some(name) => (name.trim_end(), true),
+                None => (name, false),
+            };
+            if name.is_empty() {
+                return Err(format!("Argument line '{}' has no name", line));
+            }
+            let description = Some(description.trim())
+                .filter(|d| !d.is_empty())
+                .map(str::to_string);
+            specs.insert(
+                name.to_string(),
+                PromptArgumentSpec {
+                    description,
+                    optional,
+                    enum_values,
+                    lookup,
+                },
+            );
+        }
+        Ok(specs)
+    }
+
+    /// Serializes argument documentation for storage, `None` when empty
+    pub fn specs_to_json(specs: &PromptArgumentSpecs) -> Option<String> {
+        if specs.is_empty() {
+            return None;
+        }
+        serde_json::to_string(specs).ok()
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        server_id: i64,
+        name: &str,
+        description: Option<&str>,
+        template: &str,
+        arguments: Option<&str>,
+    ) -> Result<i64> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO server_prompts (server_id, name, description, template, arguments)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            server_id,
+            name,
+            description,
+            template,
+            arguments
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: i64,
+        description: Option<&str>,
+        template: &str,
+        arguments: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE server_prompts
+            SET description = ?, template = ?, arguments = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+            description,
+            template,
+            arguments,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_by_server(pool: &SqlitePool, server_id: i64) -> Result<Vec<Self>> {
+        let prompts = sqlx::query_as!(
+            ServerPrompt,
+            "SELECT * FROM server_prompts WHERE server_id = ? ORDER BY name",
+            server_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(prompts)
+    }
+
+    pub async fn get_by_name(
+        pool: &SqlitePool,
+        server_id: i64,
+        name: &str,
+    ) -> Result<Option<Self>> {
+        let prompt = sqlx::query_as!(
+            ServerPrompt,
+            "SELECT * FROM server_prompts WHERE server_id = ? AND name = ?",
+            server_id,
+            name
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(prompt)
+    }
+
+    pub async fn delete(pool: &SqlitePool, server_id: i64, id: i64) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM server_prompts WHERE id = ? AND server_id = ?",
+            id,
+            server_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use super::super::server_prompt::{PromptArgumentSpec, ServerPrompt};
+
+    fn prompt(template: &str, arguments: Option<&str>) -> ServerPrompt {
+        ServerPrompt {
+            id: Some(1),
+            server_id: 1,
+            name: "triage".to_string(),
+            description: None,
+            template: template.to_string(),
+            arguments: arguments.map(str::to_string),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_placeholders_default_to_string_and_are_deduplicated() {
+        let prompt = prompt(
+            "Triage {{ticket}} in {{project}}, priority {{integer:priority}}. Then close {{ticket}}.",
+            None,
+        );
+
+        assert_eq!(
+            prompt.placeholders(),
+            vec![
+                ("string".to_string(), "ticket".to_string()),
+                ("string".to_string(), "project".to_string()),
+                ("integer".to_string(), "priority".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arguments_text_round_trip() {
+        let specs = ServerPrompt::parse_arguments_text(
+            "ticket: The Jira ticket key\n\n  notes ?: Extra context  \nteam:",
+        )
+        .unwrap();
+
+        assert_eq!(
+            specs["ticket"],
+            PromptArgumentSpec {
+                description: Some("The Jira ticket key".to_string()),
+                optional: false,
+                ..Default::default()
+            }
+        );
+        assert!(specs["notes"].optional);
+        assert_eq!(specs["team"], PromptArgumentSpec::default());
+
+        let json = ServerPrompt::specs_to_json(&specs);
+        let stored = prompt("", json.as_deref());
+        assert_eq!(stored.argument_specs(), specs);
+        assert_eq!(
+            stored.arguments_text(),
+            "notes?: Extra context\nteam:\nticket: The Jira ticket key"
+        );
+    }
+
+    #[test]
+    fn test_arguments_text_completion_sources() {
+        let specs = ServerPrompt::parse_arguments_text(
+            "status: Ticket status; enum: open, closed\n\
+             customer?: Customer; lookup: find_customers\n\
+             notes: Context; keep it short",
+        )
+        .unwrap();
+
+        assert_eq!(
+            specs["status"].description.as_deref(),
+            Some("Ticket status")
+        );
+        assert_eq!(specs["status"].enum_values, vec!["open", "closed"]);
+        assert_eq!(specs["customer"].lookup.as_deref(), Some("find_customers"));
+        assert!(specs["customer"].optional);
+        assert_eq!(
+            specs["notes"].description.as_deref(),
+            Some("Context; keep it short")
+        );
+
+        let json = ServerPrompt::specs_to_json(&specs);
+        let stored = prompt("", json.as_deref());
+        assert_eq!(
+            stored.arguments_text(),
+            "customer?: Customer; lookup: find_customers\n\
+             notes: Context; keep it short\n\
+             status: Ticket status; enum: open, closed"
+        );
+    }
+
+    #[test]
+    fn test_argument_line_without_name_is_rejected() {
+        assert!(ServerPrompt::parse_arguments_text(": no name").is_err());
+        assert_eq!(ServerPrompt::specs_to_json(&Default::default()), None);
+    }
+}
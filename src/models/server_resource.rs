@@ -0,0 +1,174 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Scheme and authority of the URIs resources are read at
+pub const RESOURCE_URI_PREFIX: &str = "saramcp://resources/";
+
+/// An MCP resource of a server
+///
+/// Either a static document (`content` set) or a GET tool instance read on
+/// demand (`instance_id` set).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ServerResource {
+    pub id: Option<i64>,
+    pub server_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: String,
+    pub content: Option<String>,
+    pub instance_id: Option<i64>,
+    pub created_at: Option<time::OffsetDateTime>,
+    pub updated_at: Option<time::OffsetDateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResourceDocumentForm {
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+    pub content: String,
+    pub csrf_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PublishInstanceForm {
+    pub instance_id: i64,
+    pub name: Option<String>, // Defaults to the instance name
+    pub mime_type: Option<String>,
+    pub csrf_token: String,
+}
+
+impl ServerResource {
+    /// URI of the resource, or the base of its template
+    pub fn uri(&self) -> String {
+        format!("{}{}", RESOURCE_URI_PREFIX, self.name)
+    }
+
+    pub fn is_document(&self) -> bool {
+        self.content.is_some()
+    }
+
+    /// Names become URI path segments, so they are kept to a safe alphabet
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    }
+
+    pub async fn create_document(
+        pool: &SqlitePool,
+        server_id: i64,
+        name: &str,
+        description: Option<&str>,
+        mime_type: &str,
+        content: &str,
+    ) -> Result<i64> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO server_resources (server_id, name, description, mime_type, content)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            server_id,
+            name,
+            description,
+            mime_type,
+            content
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn create_for_instance(
+        pool: &SqlitePool,
+        server_id: i64,
+        name: &str,
+        description: Option<&str>,
+        mime_type: &str,
+        instance_id: i64,
+    ) -> Result<i64> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO server_resources (server_id, name, description, mime_type, instance_id)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            server_id,
+            name,
+            description,
+            mime_type,
+            instance_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn update_document(
+        pool: &SqlitePool,
+        id: i64,
+        description: Option<&str>,
+        mime_type: &str,
+        content: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE server_resources
+            SET description = ?, mime_type = ?, content = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND content IS NOT NULL
+            "#,
+            description,
+            mime_type,
+            content,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_by_server(pool: &SqlitePool, server_id: i64) -> Result<Vec<Self>> {
+        let resources = sqlx::query_as!(
+            ServerResource,
+            "SELECT * FROM server_resources WHERE server_id = ? ORDER BY name",
+            server_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(resources)
+    }
+
+    pub async fn get_by_name(
+        pool: &SqlitePool,
+        server_id: i64,
+        name: &str,
+    ) -> Result<Option<Self>> {
+        let resource = sqlx::query_as!(
+            ServerResource,
+            "SELECT * FROM server_resources WHERE server_id = ? AND name = ?",
+            server_id,
+            name
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(resource)
+    }
+
+    pub async fn delete(pool: &SqlitePool, server_id: i64, id: i64) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM server_resources WHERE id = ? AND server_id = ?",
+            id,
+            server_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
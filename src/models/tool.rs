@@ -1,3 +1,6 @@
+use crate::models::endpoint::EndpointConfig;
+use crate::models::parameter_metadata::{ParameterMetadata, ParameterMetadataMap};
+use crate::models::rate_limit::RateLimitConfig;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -15,11 +18,15 @@ pub struct Tool {
     pub toolkit_id: i64,
     pub name: String,
     pub description: Option<String>,
-    pub method: String,          // GET, POST, PUT, DELETE, PATCH
+    pub method: String,          // GET, POST, PUT, DELETE, PATCH, MCP, MCP-SSE
     pub url: Option<String>,     // URL with {{type:param}} templates
     pub headers: Option<String>, // JSON string
     pub body: Option<String>,    // JSON string
     pub timeout_ms: i32,
+    pub streaming: bool, // Consume the response incrementally and report progress
+    pub endpoints: Option<String>, // JSON EndpointConfig; falls back to the toolkit's
+    pub rate_limit: Option<String>, // JSON RateLimitConfig
+    pub parameter_metadata: Option<String>, // JSON ParameterMetadataMap
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
@@ -33,6 +40,15 @@ pub struct CreateToolForm {
     pub headers: String,      // JSON string
     pub body: Option<String>, // JSON string, optional for GET/DELETE
     pub timeout_ms: Option<i32>,
+    pub streaming: Option<String>, // Checkbox: present when checked
+    pub base_urls: Option<String>, // One base URL per line
+    pub endpoint_strategy: Option<String>,
+    pub failover_status_codes: Option<String>, // Comma-separated
+    pub rate_limit_requests: Option<String>,
+    pub rate_limit_period: Option<String>, // Seconds
+    pub rate_limit_burst: Option<String>,
+    pub rate_limit_mode: Option<String>,     // wait or reject
+    pub rate_limit_max_wait: Option<String>, // Milliseconds
     pub csrf_token: String,
 }
 
@@ -45,6 +61,15 @@ pub struct UpdateToolForm {
     pub headers: String,      // JSON string
     pub body: Option<String>, // JSON string, optional for GET/DELETE
     pub timeout_ms: Option<i32>,
+    pub streaming: Option<String>, // Checkbox: present when checked
+    pub base_urls: Option<String>, // One base URL per line
+    pub endpoint_strategy: Option<String>,
+    pub failover_status_codes: Option<String>, // Comma-separated
+    pub rate_limit_requests: Option<String>,
+    pub rate_limit_period: Option<String>, // Seconds
+    pub rate_limit_burst: Option<String>,
+    pub rate_limit_mode: Option<String>,     // wait or reject
+    pub rate_limit_max_wait: Option<String>, // Milliseconds
     pub csrf_token: String,
 }
 
@@ -58,6 +83,10 @@ pub struct CreateToolRequest {
     pub headers: Option<String>,
     pub body: Option<String>,
     pub timeout_ms: i32,
+    pub streaming: bool,
+    pub endpoints: Option<String>,
+    pub rate_limit: Option<String>,
+    pub parameter_metadata: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -69,6 +98,9 @@ pub struct UpdateToolRequest {
     pub headers: Option<String>,
     pub body: Option<String>,
     pub timeout_ms: i32,
+    pub streaming: bool,
+    pub endpoints: Option<String>,
+    pub rate_limit: Option<String>,
 }
 
 // Structure for extracted parameters
@@ -100,6 +132,10 @@ impl Tool {
             headers: r.get("headers"),
             body: r.get("body"),
             timeout_ms: r.get("timeout_ms"),
+            streaming: r.get("streaming"),
+            endpoints: r.get("endpoints"),
+            rate_limit: r.get("rate_limit"),
+            parameter_metadata: r.get("parameter_metadata"),
             created_at: chrono::DateTime::from_timestamp(r.get::<i64, _>("created_at"), 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -109,6 +145,90 @@ impl Tool {
         }))
     }
 
+    /// Endpoint configuration inherited from the tool's toolkit
+    pub async fn toolkit_endpoints(
+        pool: &sqlx::SqlitePool,
+        toolkit_id: i64,
+    ) -> sqlx::Result<Option<String>> {
+        let endpoints: Option<Option<String>> =
+            sqlx::query_scalar("SELECT endpoints FROM toolkits WHERE id = ?")
+                .bind(toolkit_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(endpoints.flatten())
+    }
+
+    /// Parsed multi-endpoint configuration of this tool, if any
+    pub fn endpoint_config(&self) -> Option<EndpointConfig> {
+        EndpointConfig::parse(self.endpoints.as_deref())
+    }
+
+    /// Rate limit of the tool's toolkit, applied per upstream host
+    pub async fn toolkit_rate_limit(
+        pool: &sqlx::SqlitePool,
+        toolkit_id: i64,
+    ) -> sqlx::Result<Option<String>> {
+        let rate_limit: Option<Option<String>> =
+            sqlx::query_scalar("SELECT rate_limit FROM toolkits WHERE id = ?")
+                .bind(toolkit_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(rate_limit.flatten())
+    }
+
+    /// Parsed rate limit of this tool, if any
+    pub fn rate_limit_config(&self) -> Option<RateLimitConfig> {
+        RateLimitConfig::parse(self.rate_limit.as_deref())
+    }
+
+    /// Parsed parameter documentation of this tool, keyed by parameter name
+    pub fn parameter_metadata_map(&self) -> ParameterMetadataMap {
+        ParameterMetadata::parse_map(self.parameter_metadata.as_deref())
+    }
+
+    /// Checks that a body template is well-formed for its content type
+    ///
+    /// Bodies are JSON unless the headers declare another `Content-Type`.
+    /// Placeholders may stand for whole JSON values (`{"age": {{integer:age}}}`),
+    /// so each one is replaced by a number before parsing.
+    pub fn validate_body_template(body: &str, headers: Option<&str>) -> Result<(), String> {
+        if body.trim().is_empty() || !Self::content_type_is_json(headers) {
+            return Ok(());
+        }
+
+        let filled = PARAMETER_PATTERN.replace_all(body, "0");
+        serde_json::from_str::<JsonValue>(&filled)
+            .map(|_| ())
+            .map_err(|_| "Body must be valid JSON".to_string())
+    }
+
+    /// Whether a headers template sends JSON, the default without `Content-Type`
+    fn content_type_is_json(headers: Option<&str>) -> bool {
+        let Some(headers) = headers.and_then(|h| serde_json::from_str::<JsonValue>(h).ok()) else {
+            return true;
+        };
+        let content_type = headers.as_object().and_then(|map| {
+            map.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                .and_then(|(_, value)| value.as_str())
+        });
+
+        match content_type {
+            Some(content_type) => {
+                let mime = content_type
+                    .split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_ascii_lowercase();
+                mime == "application/json" || mime.ends_with("+json")
+            }
+            None => true,
+        }
+    }
+
     /// Extract parameters from URL, headers, and body templates
     pub fn extract_parameters(&self) -> Vec<ExtractedParameter> {
         let mut params = Vec::new();
@@ -222,6 +342,20 @@ impl CreateToolForm {
                 }
             }),
             timeout_ms: self.timeout_ms.unwrap_or(30000),
+            streaming: self.streaming.is_some(),
+            endpoints: EndpointConfig::json_from_form(
+                self.base_urls.as_deref(),
+                self.endpoint_strategy.as_deref(),
+                self.failover_status_codes.as_deref(),
+            ),
+            rate_limit: RateLimitConfig::json_from_form(
+                self.rate_limit_requests.as_deref(),
+                self.rate_limit_period.as_deref(),
+                self.rate_limit_burst.as_deref(),
+                self.rate_limit_mode.as_deref(),
+                self.rate_limit_max_wait.as_deref(),
+            ),
+            parameter_metadata: None,
         }
     }
 }
@@ -236,6 +370,15 @@ impl UpdateToolForm {
             headers: self.headers,
             body: self.body,
             timeout_ms: self.timeout_ms,
+            streaming: self.streaming,
+            base_urls: self.base_urls,
+            endpoint_strategy: self.endpoint_strategy,
+            failover_status_codes: self.failover_status_codes,
+            rate_limit_requests: self.rate_limit_requests,
+            rate_limit_period: self.rate_limit_period,
+            rate_limit_burst: self.rate_limit_burst,
+            rate_limit_mode: self.rate_limit_mode,
+            rate_limit_max_wait: self.rate_limit_max_wait,
             csrf_token: self.csrf_token,
         };
         let create_request = create_form.into_request();
@@ -248,6 +391,9 @@ impl UpdateToolForm {
             headers: create_request.headers,
             body: create_request.body,
             timeout_ms: create_request.timeout_ms,
+            streaming: create_request.streaming,
+            endpoints: create_request.endpoints,
+            rate_limit: create_request.rate_limit,
         }
     }
 }
@@ -17,6 +17,10 @@ mod tests {
             headers: None,
             body: None,
             timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
             created_at: chrono::DateTime::from_timestamp(0, 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -51,6 +55,10 @@ mod tests {
             headers: Some(r#"{"Authorization": "Bearer {{string:token}}", "X-API-Version": "{{string:version}}"}"#.to_string()),
             body: None,
             timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
             created_at: chrono::DateTime::from_timestamp(0, 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -83,6 +91,10 @@ mod tests {
             headers: Some("{}".to_string()),
             body: Some(r#"{"name": "{{string:username}}", "age": {{integer:age}}, "active": {{boolean:is_active}}}"#.to_string()),
             timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
             created_at: chrono::DateTime::from_timestamp(0, 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -120,6 +132,10 @@ mod tests {
             headers: Some(r#"{"X-API-Key": "{{string:api_key}}"}"#.to_string()),
             body: Some(r#"{"key": "{{string:api_key}}"}"#.to_string()),
             timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
             created_at: chrono::DateTime::from_timestamp(0, 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -157,6 +173,10 @@ mod tests {
                 .to_string(),
             ),
             timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
             created_at: chrono::DateTime::from_timestamp(0, 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -194,6 +214,10 @@ mod tests {
             headers: Some(r#"{"Content-Type": "application/json"}"#.to_string()),
             body: None,
             timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
             created_at: chrono::DateTime::from_timestamp(0, 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -219,6 +243,10 @@ mod tests {
             headers: Some(r#"{"Authorization": "Bearer {{token}}"}"#.to_string()),
             body: None,
             timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
             created_at: chrono::DateTime::from_timestamp(0, 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -259,6 +287,10 @@ mod tests {
             headers: Some(r#"{"X-User-Id": "{{integer:user_id}}"}"#.to_string()),
             body: Some(r#"{"title": "{{title}}", "content": "{{string:content}}", "published": {{boolean:is_published}}}"#.to_string()),
             timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
             created_at: chrono::DateTime::from_timestamp(0, 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -287,4 +319,25 @@ mod tests {
         assert_eq!(published_param.param_type, "boolean"); // Explicitly specified as boolean
         assert_eq!(published_param.source, "body");
     }
+
+    #[test]
+    fn test_validate_body_template_allows_placeholder_values() {
+        let body = r#"{"name": "{{name}}", "age": {{integer:age}}, "tags": {{json:tags}}}"#;
+        assert!(Tool::validate_body_template(body, None).is_ok());
+        assert!(Tool::validate_body_template("", None).is_ok());
+
+        let error = Tool::validate_body_template(r#"{"name": }"#, Some("{}")).unwrap_err();
+        assert_eq!(error, "Body must be valid JSON");
+    }
+
+    #[test]
+    fn test_validate_body_template_follows_content_type() {
+        let form = r#"{"Content-Type": "application/x-www-form-urlencoded"}"#;
+        assert!(
+            Tool::validate_body_template("q={{q}}&limit={{integer:limit}}", Some(form)).is_ok()
+        );
+
+        let json = r#"{"content-type": "application/vnd.api+json; charset=utf-8"}"#;
+        assert!(Tool::validate_body_template("q={{q}}", Some(json)).is_err());
+    }
 }
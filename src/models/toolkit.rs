@@ -1,3 +1,5 @@
+use crate::models::endpoint::EndpointConfig;
+use crate::models::rate_limit::RateLimitConfig;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -10,6 +12,8 @@ pub struct Toolkit {
     pub visibility: String,
     pub parent_toolkit_id: Option<i64>,
     pub clone_count: i32,
+    pub endpoints: Option<String>, // JSON EndpointConfig inherited by the toolkit's tools
+    pub rate_limit: Option<String>, // JSON RateLimitConfig applied per upstream host
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
@@ -27,6 +31,14 @@ pub struct UpdateToolkitForm {
     pub title: String,
     pub description: String,
     pub visibility: String,
+    pub base_urls: Option<String>, // One base URL per line
+    pub endpoint_strategy: Option<String>,
+    pub failover_status_codes: Option<String>, // Comma-separated
+    pub rate_limit_requests: Option<String>,
+    pub rate_limit_period: Option<String>, // Seconds
+    pub rate_limit_burst: Option<String>,
+    pub rate_limit_mode: Option<String>,     // wait or reject
+    pub rate_limit_max_wait: Option<String>, // Milliseconds
     pub csrf_token: String,
 }
 
@@ -63,6 +75,18 @@ impl From<UpdateToolkitForm> for UpdateToolkitRequest {
                 Some(form.description.trim().to_string())
             },
             visibility: form.visibility,
+            endpoints: EndpointConfig::json_from_form(
+                form.base_urls.as_deref(),
+                form.endpoint_strategy.as_deref(),
+                form.failover_status_codes.as_deref(),
+            ),
+            rate_limit: RateLimitConfig::json_from_form(
+                form.rate_limit_requests.as_deref(),
+                form.rate_limit_period.as_deref(),
+                form.rate_limit_burst.as_deref(),
+                form.rate_limit_mode.as_deref(),
+                form.rate_limit_max_wait.as_deref(),
+            ),
         }
     }
 }
@@ -80,6 +104,8 @@ pub struct UpdateToolkitRequest {
     pub title: String,
     pub description: Option<String>,
     pub visibility: String,
+    pub endpoints: Option<String>,
+    pub rate_limit: Option<String>,
 }
 
 // Model for displaying public toolkits with statistics
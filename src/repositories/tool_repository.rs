@@ -9,6 +9,11 @@ pub trait ToolRepository: Send + Sync {
     async fn get_by_id(&self, id: i64) -> Result<Option<Tool>>;
     async fn list_by_toolkit(&self, toolkit_id: i64) -> Result<Vec<Tool>>;
     async fn update(&self, id: i64, request: UpdateToolRequest) -> Result<bool>;
+    async fn set_parameter_metadata(
+        &self,
+        id: i64,
+        parameter_metadata: Option<String>,
+    ) -> Result<bool>;
     async fn delete(&self, id: i64) -> Result<bool>;
 }
 
@@ -28,8 +33,8 @@ impl ToolRepository for SqliteToolRepository {
         // Insert tool (no transaction needed since parameters are auto-extracted)
         let tool_id = sqlx::query!(
             r#"
-            INSERT INTO tools (toolkit_id, name, description, method, url, headers, body, timeout_ms)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO tools (toolkit_id, name, description, method, url, headers, body, timeout_ms, streaming, endpoints, rate_limit, parameter_metadata)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
             toolkit_id,
             request.name,
@@ -38,7 +43,11 @@ impl ToolRepository for SqliteToolRepository {
             request.url,
             request.headers,
             request.body,
-            request.timeout_ms
+            request.timeout_ms,
+            request.streaming,
+            request.endpoints,
+            request.rate_limit,
+            request.parameter_metadata
         )
         .execute(&self.pool)
         .await?
@@ -50,7 +59,7 @@ impl ToolRepository for SqliteToolRepository {
     async fn get_by_id(&self, id: i64) -> Result<Option<Tool>> {
         let row = sqlx::query!(
             r#"
-            SELECT id, toolkit_id, name, description, method, url, headers, body, timeout_ms, created_at, updated_at
+            SELECT id, toolkit_id, name, description, method, url, headers, body, timeout_ms, streaming, endpoints, rate_limit, parameter_metadata, created_at, updated_at
             FROM tools
             WHERE id = ?
             "#,
@@ -69,6 +78,10 @@ impl ToolRepository for SqliteToolRepository {
             headers: r.headers,
             body: r.body,
             timeout_ms: r.timeout_ms.unwrap_or(30000) as i32,
+            streaming: r.streaming,
+            endpoints: r.endpoints,
+            rate_limit: r.rate_limit,
+            parameter_metadata: r.parameter_metadata,
             created_at: r
                 .created_at
                 .map(|dt| {
@@ -91,7 +104,7 @@ impl ToolRepository for SqliteToolRepository {
     async fn list_by_toolkit(&self, toolkit_id: i64) -> Result<Vec<Tool>> {
         let rows = sqlx::query!(
             r#"
-            SELECT id, toolkit_id, name, description, method, url, headers, body, timeout_ms, created_at, updated_at
+            SELECT id, toolkit_id, name, description, method, url, headers, body, timeout_ms, streaming, endpoints, rate_limit, parameter_metadata, created_at, updated_at
             FROM tools
             WHERE toolkit_id = ?
             ORDER BY created_at DESC
@@ -113,6 +126,10 @@ impl ToolRepository for SqliteToolRepository {
                 headers: r.headers,
                 body: r.body,
                 timeout_ms: r.timeout_ms.unwrap_or(30000) as i32,
+                streaming: r.streaming,
+                endpoints: r.endpoints,
+                rate_limit: r.rate_limit,
+                parameter_metadata: r.parameter_metadata,
                 created_at: r
                     .created_at
                     .map(|dt| {
@@ -138,7 +155,7 @@ impl ToolRepository for SqliteToolRepository {
         let result = sqlx::query!(
             r#"
             UPDATE tools
-            SET name = ?, description = ?, method = ?, url = ?, headers = ?, body = ?, timeout_ms = ?, updated_at = unixepoch()
+            SET name = ?, description = ?, method = ?, url = ?, headers = ?, body = ?, timeout_ms = ?, streaming = ?, endpoints = ?, rate_limit = ?, updated_at = unixepoch()
             WHERE id = ?
             "#,
             request.name,
@@ -148,6 +165,25 @@ impl ToolRepository for SqliteToolRepository {
             request.headers,
             request.body,
             request.timeout_ms,
+            request.streaming,
+            request.endpoints,
+            request.rate_limit,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn set_parameter_metadata(
+        &self,
+        id: i64,
+        parameter_metadata: Option<String>,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE tools SET parameter_metadata = ?, updated_at = unixepoch() WHERE id = ?",
+            parameter_metadata,
             id
         )
         .execute(&self.pool)
@@ -193,6 +229,10 @@ mod tests {
             headers: Some("{}".to_string()),
             body: None,
             timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: None,
         };
 
         let tool_id = repo.create(toolkit_id, request).await.unwrap();
@@ -215,6 +255,9 @@ mod tests {
             headers: Some(r#"{"Authorization": "Bearer {{token}}"}"#.to_string()),
             body: Some(r#"{"data": "{{json:payload}}"}"#.to_string()),
             timeout_ms: 60000,
+            streaming: true,
+            endpoints: None,
+            rate_limit: None,
         };
 
         let updated = repo.update(tool_id, update_request).await.unwrap();
@@ -223,6 +266,7 @@ mod tests {
         // Verify tool was updated
         let updated_tool = repo.get_by_id(tool_id).await.unwrap().unwrap();
         assert_eq!(updated_tool.name, "Updated Tool");
+        assert!(updated_tool.streaming);
         assert!(updated_tool
             .url
             .as_ref()
@@ -59,7 +59,7 @@ impl ToolkitRepository for SqliteToolkitRepository {
     async fn get_by_id(&self, id: i64, user_id: i64) -> Result<Option<Toolkit>> {
         let row = sqlx::query!(
             r#"
-            SELECT id, user_id, title, description, visibility, parent_toolkit_id, clone_count, created_at, updated_at
+            SELECT id, user_id, title, description, visibility, parent_toolkit_id, clone_count, endpoints, rate_limit, created_at, updated_at
             FROM toolkits
             WHERE id = ? AND user_id = ?
             "#,
@@ -77,6 +77,8 @@ impl ToolkitRepository for SqliteToolkitRepository {
             visibility: r.visibility.unwrap_or_else(|| "private".to_string()),
             parent_toolkit_id: r.parent_toolkit_id,
             clone_count: r.clone_count as i32,
+            endpoints: r.endpoints,
+            rate_limit: r.rate_limit,
             created_at: r
                 .created_at
                 .map(|dt| {
@@ -99,7 +101,7 @@ impl ToolkitRepository for SqliteToolkitRepository {
     async fn list_by_user(&self, user_id: i64) -> Result<Vec<Toolkit>> {
         let rows = sqlx::query!(
             r#"
-            SELECT id, user_id, title, description, visibility, parent_toolkit_id, clone_count, created_at, updated_at
+            SELECT id, user_id, title, description, visibility, parent_toolkit_id, clone_count, endpoints, rate_limit, created_at, updated_at
             FROM toolkits
             WHERE user_id = ?
             ORDER BY created_at DESC
@@ -119,6 +121,8 @@ impl ToolkitRepository for SqliteToolkitRepository {
                 visibility: r.visibility.unwrap_or_else(|| "private".to_string()),
                 parent_toolkit_id: r.parent_toolkit_id,
                 clone_count: r.clone_count as i32,
+                endpoints: r.endpoints,
+                rate_limit: r.rate_limit,
                 created_at: r
                     .created_at
                     .map(|dt| {
@@ -173,12 +177,14 @@ impl ToolkitRepository for SqliteToolkitRepository {
         let result = sqlx::query!(
             r#"
             UPDATE toolkits
-            SET title = ?, description = ?, visibility = ?, updated_at = unixepoch()
+            SET title = ?, description = ?, visibility = ?, endpoints = ?, rate_limit = ?, updated_at = unixepoch()
             WHERE id = ? AND user_id = ?
             "#,
             request.title,
             request.description,
             request.visibility,
+            request.endpoints,
+            request.rate_limit,
             id,
             user_id
         )
@@ -259,7 +265,7 @@ impl ToolkitRepository for SqliteToolkitRepository {
     async fn get_public_toolkit(&self, id: i64) -> Result<Option<Toolkit>> {
         let row = sqlx::query!(
             r#"
-            SELECT id, user_id, title, description, visibility, parent_toolkit_id, clone_count, created_at, updated_at
+            SELECT id, user_id, title, description, visibility, parent_toolkit_id, clone_count, endpoints, rate_limit, created_at, updated_at
             FROM toolkits
             WHERE id = ? AND visibility = 'public'
             "#,
@@ -276,6 +282,8 @@ impl ToolkitRepository for SqliteToolkitRepository {
             visibility: r.visibility.unwrap_or_else(|| "private".to_string()),
             parent_toolkit_id: r.parent_toolkit_id,
             clone_count: r.clone_count as i32,
+            endpoints: r.endpoints,
+            rate_limit: r.rate_limit,
             created_at: r
                 .created_at
                 .map(|dt| {
@@ -307,8 +315,8 @@ impl ToolkitRepository for SqliteToolkitRepository {
         // Create the new toolkit
         let new_toolkit_id = sqlx::query!(
             r#"
-            INSERT INTO toolkits (user_id, title, description, visibility, parent_toolkit_id, clone_count)
-            SELECT ?, ?, description, visibility, ?, 0
+            INSERT INTO toolkits (user_id, title, description, visibility, parent_toolkit_id, clone_count, endpoints, rate_limit)
+            SELECT ?, ?, description, visibility, ?, 0, endpoints, rate_limit
             FROM toolkits
             WHERE id = ?
             "#,
@@ -324,8 +332,8 @@ impl ToolkitRepository for SqliteToolkitRepository {
         // Copy all tools from the original toolkit
         sqlx::query!(
             r#"
-            INSERT INTO tools (toolkit_id, name, description, method, url, headers, body, timeout_ms)
-            SELECT ?, name, description, method, url, headers, body, timeout_ms
+            INSERT INTO tools (toolkit_id, name, description, method, url, headers, body, timeout_ms, streaming, endpoints, rate_limit, parameter_metadata)
+            SELECT ?, name, description, method, url, headers, body, timeout_ms, streaming, endpoints, rate_limit, parameter_metadata
             FROM tools
             WHERE toolkit_id = ?
             "#,
@@ -388,6 +396,8 @@ mod tests {
             title: "Updated Toolkit".to_string(),
             description: Some("Updated description".to_string()),
             visibility: "public".to_string(),
+            endpoints: None,
+            rate_limit: None,
         };
         let updated = repo
             .update(toolkit_id, user_id, update_request)
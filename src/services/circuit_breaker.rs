@@ -0,0 +1,658 @@
+//! Circuit breakers for persistently failing upstreams
+//!
+//! When an upstream is down every tool call waits for the full timeout, and
+//! agents tend to retry in loops. A circuit breaker tracks the outcome of
+//! the calls made to an upstream and short-circuits them once it looks dead:
+//!
+//! ```text
+//!            failures over threshold
+//!   Closed ───────────────────────────▶ Open
+//!     ▲                                  │ open duration elapsed
+//!     │ trial call succeeds              ▼
+//!     └────────────────────────────── Half-open
+//!                trial call fails: back to Open
+//! ```
+//!
+//! - **Closed** - calls go through; the circuit opens after
+//!   `failure_threshold` consecutive failures, or when the failure rate of the
+//!   last `window` calls reaches `error_rate` (once `minimum_calls` were made)
+//! - **Open** - calls fail immediately until `open_duration` has elapsed
+//! - **Half-open** - one trial call is let through; its outcome closes or
+//!   re-opens the circuit, other calls keep failing meanwhile
+//!
+//! Connection errors, timeouts and 5xx responses count as failures; other
+//! responses (including 4xx) show that the upstream is alive.
+//!
+//! Circuits are keyed per upstream host (`host:{host}`) or per tool
+//! (`tool:{id}`) depending on [`CircuitScope`], and kept in memory. The
+//! process-wide registry ([`CircuitBreakerRegistry::shared`]) is configured
+//! from the environment:
+//!
+//! - `SARAMCP_CIRCUIT_BREAKER` - `host` (default), `tool` or `off`
+//! - `SARAMCP_CIRCUIT_FAILURE_THRESHOLD` - consecutive failures (default 5)
+//! - `SARAMCP_CIRCUIT_ERROR_RATE` - failure ratio between 0 and 1 (default 0.5)
+//! - `SARAMCP_CIRCUIT_MINIMUM_CALLS` - calls before the rate applies (default 10)
+//! - `SARAMCP_CIRCUIT_OPEN_SECONDS` - time before a trial call (default 30)
+
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+static SHARED_REGISTRY: Lazy<CircuitBreakerRegistry> =
+    Lazy::new(|| CircuitBreakerRegistry::new(CircuitBreakerConfig::from_env()));
+
+/// What a circuit covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitScope {
+    /// One circuit per upstream host, shared by every tool calling it
+    Host,
+    /// One circuit per tool
+    Tool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// `None` disables circuit breaking
+    pub scope: Option<CircuitScope>,
+    pub failure_threshold: u32,
+    pub error_rate: f64,
+    pub minimum_calls: usize,
+    /// Number of recent calls the error rate is computed over
+    pub window: usize,
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            scope: Some(CircuitScope::Host),
+            failure_threshold: 5,
+            error_rate: 0.5,
+            minimum_calls: 10,
+            window: 20,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// A configuration that guards no call
+    pub fn disabled() -> Self {
+        Self {
+            scope: None,
+            ..Self::default()
+        }
+    }
+
+    /// Reads the configuration from `SARAMCP_CIRCUIT_*` variables
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            let value = std::env::var(name).ok()?;
+            match value.trim().parse() {
+                Ok(parsed) => Some(parsed),
+                Err(_) => {
+                    tracing::warn!("Ignoring invalid {}: {}", name, value);
+                    None
+                }
+            }
+        }
+
+        let defaults = Self::default();
+        let scope = match std::env::var("SARAMCP_CIRCUIT_BREAKER")
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "" | "host" => Some(CircuitScope::Host),
+            "tool" => Some(CircuitScope::Tool),
+            "off" | "false" | "0" => None,
+            other => {
+                tracing::warn!("Ignoring invalid SARAMCP_CIRCUIT_BREAKER: {}", other);
+                defaults.scope
+            }
+        };
+
+        Self {
+            scope,
+            failure_threshold: var::<u32>("SARAMCP_CIRCUIT_FAILURE_THRESHOLD")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.failure_threshold),
+            error_rate: var::<f64>("SARAMCP_CIRCUIT_ERROR_RATE")
+                .filter(|r| *r > 0.0 && *r <= 1.0)
+                .unwrap_or(defaults.error_rate),
+            minimum_calls: var::<usize>("SARAMCP_CIRCUIT_MINIMUM_CALLS")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.minimum_calls),
+            window: defaults.window,
+            open_duration: var::<u64>("SARAMCP_CIRCUIT_OPEN_SECONDS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.open_duration),
+        }
+        .with_window_covering_minimum()
+    }
+
+    /// Makes sure the error rate can be computed at all
+    fn with_window_covering_minimum(mut self) -> Self {
+        self.window = self.window.max(self.minimum_calls);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half-open",
+        }
+    }
+}
+
+/// A call refused because its circuit is open
+#[derive(Debug, Clone)]
+pub struct CircuitOpen {
+    pub key: String,
+    pub state: CircuitState,
+    /// When the next trial call will be allowed
+    pub retry_after: Duration,
+    /// The failure that opened the circuit
+    pub last_error: Option<String>,
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.state {
+            CircuitState::HalfOpen => {
+                write!(f, "{} is failing, a trial request is in progress", self.key)?
+            }
+            _ => write!(
+                f,
+                "{} is failing, calls are suspended for {} s",
+                self.key,
+                self.retry_after.as_secs().max(1)
+            )?,
+        }
+        if let Some(error) = &self.last_error {
+            write!(f, " (last error: {})", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+/// State of a circuit, for display
+#[derive(Debug, Clone)]
+pub struct CircuitSnapshot {
+    pub key: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Failure percentage over the recent calls
+    pub failure_rate: u32,
+    pub recent_calls: usize,
+    /// Seconds until the next trial call while open
+    pub retry_in_secs: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug)]
+struct Circuit {
+    state: CircuitState,
+    consecutive_failures: u32,
+    /// Recent outcomes, `true` for failures
+    outcomes: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+    last_error: Option<String>,
+    /// Servers whose calls went through this circuit
+    servers: HashSet<i64>,
+}
+
+impl Circuit {
+    fn new() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            outcomes: VecDeque::new(),
+            opened_at: None,
+            probe_in_flight: false,
+            last_error: None,
+            servers: HashSet::new(),
+        }
+    }
+
+    fn failure_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        self.outcomes.iter().filter(|failed| **failed).count() as f64 / self.outcomes.len() as f64
+    }
+
+    fn open(&mut self, now: Instant) {
+        self.state = CircuitState::Open;
+        self.opened_at = Some(now);
+        self.probe_in_flight = false;
+    }
+
+    fn close(&mut self) {
+        self.state = CircuitState::Closed;
+        self.opened_at = None;
+        self.probe_in_flight = false;
+        self.consecutive_failures = 0;
+        self.outcomes.clear();
+        self.last_error = None;
+    }
+
+    fn remaining_open(&self, config: &CircuitBreakerConfig, now: Instant) -> Duration {
+        self.opened_at
+            .map(|at| (at + config.open_duration).saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+}
+
+/// Permission to make one call through a circuit
+///
+/// Report the outcome with [`record_success`](Self::record_success) or
+/// [`record_failure`](Self::record_failure). Dropping the permit without a
+/// report (e.g. a cancelled call) leaves the circuit unchanged, and lets
+/// another trial call through if this one was the trial.
+pub struct CircuitPermit {
+    registry: Option<CircuitBreakerRegistry>,
+    key: String,
+    probe: bool,
+}
+
+impl CircuitPermit {
+    /// A permit for calls without a circuit
+    fn unguarded() -> Self {
+        Self {
+            registry: None,
+            key: String::new(),
+            probe: false,
+        }
+    }
+
+    pub fn record_success(mut self) {
+        if let Some(registry) = self.registry.take() {
+            registry.record(&self.key, None, self.probe);
+        }
+    }
+
+    pub fn record_failure(mut self, error: &str) {
+        if let Some(registry) = self.registry.take() {
+            registry.record(&self.key, Some(error), self.probe);
+        }
+    }
+}
+
+impl Drop for CircuitPermit {
+    fn drop(&mut self) {
+        if let Some(registry) = self.registry.take() {
+            if self.probe {
+                registry.release_probe(&self.key);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerRegistry {
+    config: Arc<RwLock<CircuitBreakerConfig>>,
+    circuits: Arc<Mutex<HashMap<String, Circuit>>>,
+}
+
+impl CircuitBreakerRegistry {
+    /// Creates an empty registry, independent from the shared one
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config.with_window_covering_minimum())),
+            circuits: Arc::default(),
+        }
+    }
+
+    /// Replaces the configuration, keeping the state of existing circuits
+    pub fn set_config(&self, config: CircuitBreakerConfig) {
+        if let Ok(mut current) = self.config.write() {
+            *current = config.with_window_covering_minimum();
+        }
+    }
+
+    fn config(&self) -> CircuitBreakerConfig {
+        self.config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_else(|_| CircuitBreakerConfig::disabled())
+    }
+
+    /// Returns the process-wide registry
+    pub fn shared() -> Self {
+        SHARED_REGISTRY.clone()
+    }
+
+    /// Circuit key of a call to `host` made by a tool, if circuits are enabled
+    pub fn key_for(&self, tool_id: i64, host: Option<&str>) -> Option<String> {
+        match self.config().scope? {
+            CircuitScope::Host => host.map(|host| format!("host:{}", host)),
+            CircuitScope::Tool => Some(format!("tool:{}", tool_id)),
+        }
+    }
+
+    /// Asks to make a call through circuit `key` on behalf of a server
+    ///
+    /// `key` is `None` for calls that are not guarded (see
+    /// [`key_for`](Self::key_for)).
+    pub fn acquire(&self, key: Option<&str>, server_id: i64) -> Result<CircuitPermit, CircuitOpen> {
+        let Some(key) = key else {
+            return Ok(CircuitPermit::unguarded());
+        };
+        let Ok(mut circuits) = self.circuits.lock() else {
+            return Ok(CircuitPermit::unguarded());
+        };
+
+        let config = self.config();
+        let now = Instant::now();
+        let circuit = circuits.entry(key.to_string()).or_insert_with(Circuit::new);
+        circuit.servers.insert(server_id);
+
+        let probe = match circuit.state {
+            CircuitState::Closed => false,
+            CircuitState::Open => {
+                let remaining = circuit.remaining_open(&config, now);
+                if !remaining.is_zero() {
+                    return Err(CircuitOpen {
+                        key: key.to_string(),
+                        state: CircuitState::Open,
+                        retry_after: remaining,
+                        last_error: circuit.last_error.clone(),
+                    });
+                }
+                tracing::info!(circuit = key, "Circuit half-open, sending a trial request");
+                circuit.state = CircuitState::HalfOpen;
+                circuit.probe_in_flight = true;
+                true
+            }
+            CircuitState::HalfOpen => {
+                if circuit.probe_in_flight {
+                    return Err(CircuitOpen {
+                        key: key.to_string(),
+                        state: CircuitState::HalfOpen,
+                        retry_after: Duration::ZERO,
+                        last_error: circuit.last_error.clone(),
+                    });
+                }
+                circuit.probe_in_flight = true;
+                true
+            }
+        };
+
+        Ok(CircuitPermit {
+            registry: Some(self.clone()),
+            key: key.to_string(),
+            probe,
+        })
+    }
+
+    fn record(&self, key: &str, error: Option<&str>, probe: bool) {
+        let Ok(mut circuits) = self.circuits.lock() else {
+            return;
+        };
+        let Some(circuit) = circuits.get_mut(key) else {
+            return;
+        };
+        let config = self.config();
+        let now = Instant::now();
+
+        let Some(error) = error else {
+            if probe {
+                tracing::info!(circuit = key, "Trial request succeeded, circuit closed");
+                circuit.close();
+            } else {
+                circuit.consecutive_failures = 0;
+                Self::push_outcome(circuit, false, config.window);
+            }
+            return;
+        };
+
+        circuit.last_error = Some(error.to_string());
+
+        if probe {
+            tracing::warn!(
+                circuit = key,
+                error,
+                "Trial request failed, circuit re-opened"
+            );
+            circuit.open(now);
+            return;
+        }
+
+        circuit.consecutive_failures += 1;
+        Self::push_outcome(circuit, true, config.window);
+
+        let tripped = circuit.consecutive_failures >= config.failure_threshold
+            || (circuit.outcomes.len() >= config.minimum_calls
+                && circuit.failure_rate() >= config.error_rate);
+
+        // Calls started before the circuit opened do not extend the pause
+        if tripped && circuit.state == CircuitState::Closed {
+            tracing::warn!(
+                circuit = key,
+                consecutive_failures = circuit.consecutive_failures,
+                failure_rate = circuit.failure_rate(),
+                "Upstream failing, circuit opened"
+            );
+            circuit.open(now);
+        }
+    }
+
+    fn push_outcome(circuit: &mut Circuit, failed: bool, window: usize) {
+        circuit.outcomes.push_back(failed);
+        while circuit.outcomes.len() > window {
+            circuit.outcomes.pop_front();
+        }
+    }
+
+    fn release_probe(&self, key: &str) {
+        if let Ok(mut circuits) = self.circuits.lock() {
+            if let Some(circuit) = circuits.get_mut(key) {
+                circuit.probe_in_flight = false;
+            }
+        }
+    }
+
+    /// Circuits used by any of `server_ids`, sorted by key
+    pub fn snapshots_for_servers(&self, server_ids: &[i64]) -> Vec<CircuitSnapshot> {
+        let Ok(circuits) = self.circuits.lock() else {
+            return Vec::new();
+        };
+        let config = self.config();
+        let now = Instant::now();
+
+        let mut snapshots: Vec<CircuitSnapshot> = circuits
+            .iter()
+            .filter(|(_, circuit)| server_ids.iter().any(|id| circuit.servers.contains(id)))
+            .map(|(key, circuit)| CircuitSnapshot {
+                key: key.clone(),
+                state: circuit.state,
+                consecutive_failures: circuit.consecutive_failures,
+                failure_rate: (circuit.failure_rate() * 100.0).round() as u32,
+                recent_calls: circuit.outcomes.len(),
+                retry_in_secs: (circuit.state == CircuitState::Open)
+                    .then(|| circuit.remaining_open(&config, now).as_secs()),
+                last_error: circuit.last_error.clone(),
+            })
+            .collect();
+
+        snapshots.sort_by(|a, b| a.key.cmp(&b.key));
+        snapshots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(open_duration: Duration) -> CircuitBreakerRegistry {
+        CircuitBreakerRegistry::new(CircuitBreakerConfig {
+            scope: Some(CircuitScope::Host),
+            failure_threshold: 3,
+            error_rate: 0.5,
+            minimum_calls: 4,
+            window: 4,
+            open_duration,
+        })
+    }
+
+    fn fail(registry: &CircuitBreakerRegistry, key: &str) {
+        registry
+            .acquire(Some(key), 1)
+            .unwrap()
+            .record_failure("connection refused");
+    }
+
+    fn succeed(registry: &CircuitBreakerRegistry, key: &str) {
+        registry.acquire(Some(key), 1).unwrap().record_success();
+    }
+
+    fn state(registry: &CircuitBreakerRegistry, key: &str) -> CircuitState {
+        registry
+            .snapshots_for_servers(&[1])
+            .into_iter()
+            .find(|s| s.key == key)
+            .unwrap()
+            .state
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let registry = registry(Duration::from_secs(60));
+
+        fail(&registry, "host:a");
+        fail(&registry, "host:a");
+        assert_eq!(state(&registry, "host:a"), CircuitState::Closed);
+        fail(&registry, "host:a");
+        assert_eq!(state(&registry, "host:a"), CircuitState::Open);
+
+        let open = registry.acquire(Some("host:a"), 1).err().unwrap();
+        assert_eq!(open.state, CircuitState::Open);
+        assert!(open.retry_after > Duration::from_secs(50));
+        assert!(open.to_string().contains("connection refused"));
+
+        // Other circuits are unaffected
+        assert!(registry.acquire(Some("host:b"), 1).is_ok());
+    }
+
+    #[test]
+    fn test_opens_on_error_rate() {
+        let registry = registry(Duration::from_secs(60));
+
+        fail(&registry, "host:a");
+        succeed(&registry, "host:a");
+        fail(&registry, "host:a");
+        assert_eq!(state(&registry, "host:a"), CircuitState::Closed);
+        fail(&registry, "host:a");
+        // 3 failures out of 4 calls, but only 2 in a row
+        assert_eq!(state(&registry, "host:a"), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_successes_keep_circuit_closed() {
+        let registry = registry(Duration::from_secs(60));
+
+        for _ in 0..10 {
+            fail(&registry, "host:a");
+            succeed(&registry, "host:a");
+            succeed(&registry, "host:a");
+            succeed(&registry, "host:a");
+        }
+        assert_eq!(state(&registry, "host:a"), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_trial_closes_or_reopens() {
+        let registry = registry(Duration::ZERO);
+
+        for _ in 0..3 {
+            fail(&registry, "host:a");
+        }
+        assert_eq!(state(&registry, "host:a"), CircuitState::Open);
+
+        // Open duration elapsed: one trial call, others refused meanwhile
+        let trial = registry.acquire(Some("host:a"), 1).unwrap();
+        assert_eq!(state(&registry, "host:a"), CircuitState::HalfOpen);
+        let refused = registry.acquire(Some("host:a"), 1).err().unwrap();
+        assert_eq!(refused.state, CircuitState::HalfOpen);
+
+        trial.record_failure("timeout");
+        assert_eq!(state(&registry, "host:a"), CircuitState::Open);
+
+        let trial = registry.acquire(Some("host:a"), 1).unwrap();
+        trial.record_success();
+        assert_eq!(state(&registry, "host:a"), CircuitState::Closed);
+        assert!(registry.acquire(Some("host:a"), 1).is_ok());
+    }
+
+    #[test]
+    fn test_dropped_trial_lets_another_through() {
+        let registry = registry(Duration::ZERO);
+        for _ in 0..3 {
+            fail(&registry, "host:a");
+        }
+
+        drop(registry.acquire(Some("host:a"), 1).unwrap());
+        assert!(registry.acquire(Some("host:a"), 1).is_ok());
+    }
+
+    #[test]
+    fn test_keys_and_disabled_scope() {
+        let by_host = registry(Duration::ZERO);
+        assert_eq!(
+            by_host.key_for(7, Some("api.example.com")).as_deref(),
+            Some("host:api.example.com")
+        );
+        assert_eq!(by_host.key_for(7, None), None);
+
+        let by_tool = CircuitBreakerRegistry::new(CircuitBreakerConfig {
+            scope: Some(CircuitScope::Tool),
+            ..Default::default()
+        });
+        assert_eq!(
+            by_tool.key_for(7, Some("api.example.com")).as_deref(),
+            Some("tool:7")
+        );
+
+        let disabled = CircuitBreakerRegistry::new(CircuitBreakerConfig::disabled());
+        assert_eq!(disabled.key_for(7, Some("api.example.com")), None);
+        assert!(disabled.acquire(None, 1).is_ok());
+    }
+
+    #[test]
+    fn test_snapshots_are_filtered_by_server() {
+        let registry = registry(Duration::from_secs(60));
+        registry
+            .acquire(Some("host:a"), 1)
+            .unwrap()
+            .record_success();
+        registry
+            .acquire(Some("host:b"), 2)
+            .unwrap()
+            .record_success();
+
+        let keys: Vec<String> = registry
+            .snapshots_for_servers(&[2])
+            .into_iter()
+            .map(|s| s.key)
+            .collect();
+        assert_eq!(keys, vec!["host:b"]);
+        assert_eq!(registry.snapshots_for_servers(&[1, 2]).len(), 2);
+    }
+}
@@ -0,0 +1,310 @@
+//! Concurrency limits of servers and tool instances
+//!
+//! Each limited server or instance gets a semaphore with `max_concurrent`
+//! slots. A call takes a slot from every rule that applies to it and holds
+//! them until its request completes (or is cancelled). When a slot is not
+//! free, the call joins a bounded queue: at most `max_queue` calls wait per
+//! rule, each for at most `queue_timeout_ms`, and calls beyond that fail
+//! immediately.
+//!
+//! Slots are kept in memory by the process-wide [`ConcurrencyLimiter`] and
+//! keyed `server:{id}` and `instance:{id}`. Changing a limit gives the rule a
+//! fresh semaphore; calls running on the previous one finish unaffected.
+
+use crate::models::concurrency_limit::ConcurrencyLimit;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+static SHARED_LIMITER: Lazy<ConcurrencyLimiter> = Lazy::new(ConcurrencyLimiter::new);
+
+/// A concurrency limit applying to a call
+#[derive(Debug, Clone)]
+pub struct ConcurrencyRule {
+    key: String,
+    scope: &'static str,
+    limit: ConcurrencyLimit,
+}
+
+impl ConcurrencyRule {
+    pub fn server(server_id: i64, limit: ConcurrencyLimit) -> Self {
+        Self {
+            key: format!("server:{}", server_id),
+            scope: "server",
+            limit,
+        }
+    }
+
+    pub fn instance(instance_id: i64, limit: ConcurrencyLimit) -> Self {
+        Self {
+            key: format!("instance:{}", instance_id),
+            scope: "tool instance",
+            limit,
+        }
+    }
+}
+
+/// A call refused because a concurrency limit was reached
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimited {
+    pub scope: &'static str,
+    pub max_concurrent: u32,
+    /// Time spent in the queue before giving up, zero when it was full
+    pub queued: Duration,
+}
+
+impl fmt::Display for ConcurrencyLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.queued.is_zero() {
+            write!(
+                f,
+                "{} allows {} concurrent calls and its queue is full",
+                self.scope, self.max_concurrent
+            )
+        } else {
+            write!(
+                f,
+                "no {} slot freed up within {} ms",
+                self.scope,
+                self.queued.as_millis()
+            )
+        }
+    }
+}
+
+impl std::error::Error for ConcurrencyLimited {}
+
+/// Slots held by a call; dropping them frees the slots
+#[derive(Debug)]
+pub struct ConcurrencyPermits {
+    _permits: Vec<OwnedSemaphorePermit>,
+}
+
+#[derive(Debug)]
+struct Slots {
+    limit: ConcurrencyLimit,
+    semaphore: Arc<Semaphore>,
+    waiting: Arc<AtomicUsize>,
+}
+
+impl Slots {
+    fn new(limit: ConcurrencyLimit) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit.max_concurrent as usize)),
+            waiting: Arc::default(),
+            limit,
+        }
+    }
+}
+
+/// Counts a call as queued until dropped
+struct QueuePlace(Arc<AtomicUsize>);
+
+impl Drop for QueuePlace {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConcurrencyLimiter {
+    slots: Arc<Mutex<HashMap<String, Slots>>>,
+}
+
+impl ConcurrencyLimiter {
+    /// Creates an empty limiter, independent from the shared one
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the process-wide limiter
+    pub fn shared() -> Self {
+        SHARED_LIMITER.clone()
+    }
+
+    /// Takes a slot from each rule, in order, queueing when needed
+    ///
+    /// Returns the slots with the time spent queueing. The queue timeout is
+    /// the shortest one of the rules and covers the whole wait.
+    pub async fn acquire(
+        &self,
+        rules: &[ConcurrencyRule],
+    ) -> Result<(ConcurrencyPermits, Duration), ConcurrencyLimited> {
+        let started = Instant::now();
+        let Some(timeout) = rules.iter().map(|r| r.limit.queue_timeout_ms).min() else {
+            return Ok((
+                ConcurrencyPermits {
+                    _permits: Vec::new(),
+                },
+                Duration::ZERO,
+            ));
+        };
+        let deadline = started + Duration::from_millis(timeout);
+
+        let mut permits = Vec::with_capacity(rules.len());
+        let mut queued = false;
+
+        for rule in rules {
+            let (semaphore, waiting) = self.slots_for(rule);
+
+            if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+                permits.push(permit);
+                continue;
+            }
+
+            let refused = |queued: Duration| ConcurrencyLimited {
+                scope: rule.scope,
+                max_concurrent: rule.limit.max_concurrent,
+                queued,
+            };
+
+            if waiting.fetch_add(1, Ordering::SeqCst) >= rule.limit.max_queue as usize {
+                waiting.fetch_sub(1, Ordering::SeqCst);
+                return Err(refused(Duration::ZERO));
+            }
+            let _place = QueuePlace(waiting);
+            queued = true;
+
+            match tokio::time::timeout_at(deadline, semaphore.acquire_owned()).await {
+                Ok(Ok(permit)) => permits.push(permit),
+                // Semaphores are never closed, a timeout is the only failure
+                _ => return Err(refused(started.elapsed().max(Duration::from_millis(1)))),
+            }
+        }
+
+        let waited = if queued {
+            started.elapsed()
+        } else {
+            Duration::ZERO
+        };
+        Ok((ConcurrencyPermits { _permits: permits }, waited))
+    }
+
+    fn slots_for(&self, rule: &ConcurrencyRule) -> (Arc<Semaphore>, Arc<AtomicUsize>) {
+        let mut slots = self
+            .slots
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let entry = slots
+            .entry(rule.key.clone())
+            .or_insert_with(|| Slots::new(rule.limit.clone()));
+        if entry.limit != rule.limit {
+            *entry = Slots::new(rule.limit.clone());
+        }
+        (entry.semaphore.clone(), entry.waiting.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(max_concurrent: u32, max_queue: u32, queue_timeout_ms: u64) -> ConcurrencyLimit {
+        ConcurrencyLimit {
+            max_concurrent,
+            max_queue,
+            queue_timeout_ms,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_rules_never_waits() {
+        let limiter = ConcurrencyLimiter::new();
+        let (_, queued) = limiter.acquire(&[]).await.unwrap();
+        assert_eq!(queued, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_fails_immediately() {
+        let limiter = ConcurrencyLimiter::new();
+        let rules = [ConcurrencyRule::server(1, limit(1, 0, 1000))];
+
+        let (held, queued) = limiter.acquire(&rules).await.unwrap();
+        assert_eq!(queued, Duration::ZERO);
+
+        let refused = limiter.acquire(&rules).await.unwrap_err();
+        assert_eq!(refused.queued, Duration::ZERO);
+        assert!(refused.to_string().contains("queue is full"));
+
+        drop(held);
+        assert!(limiter.acquire(&rules).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_queued_call_runs_when_slot_frees() {
+        let limiter = ConcurrencyLimiter::new();
+        let rules = [ConcurrencyRule::instance(3, limit(1, 1, 5000))];
+
+        let (held, _) = limiter.acquire(&rules).await.unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            drop(held);
+        });
+
+        let (_, queued) = limiter.acquire(&rules).await.unwrap();
+        assert!(queued >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_queue_timeout() {
+        let limiter = ConcurrencyLimiter::new();
+        let rules = [ConcurrencyRule::instance(3, limit(1, 1, 300))];
+
+        let _held = limiter.acquire(&rules).await.unwrap();
+        let refused = limiter.acquire(&rules).await.unwrap_err();
+        assert_eq!(refused.scope, "tool instance");
+        assert!(refused.queued >= Duration::from_millis(300));
+        assert!(refused.to_string().contains("within"));
+
+        // The timed out call left the queue, so the next one queues again
+        let again = limiter.acquire(&rules).await.unwrap_err();
+        assert!(!again.queued.is_zero());
+    }
+
+    #[tokio::test]
+    async fn test_rules_are_independent_and_all_apply() {
+        let limiter = ConcurrencyLimiter::new();
+        let server = ConcurrencyRule::server(1, limit(2, 0, 1000));
+
+        let _a = limiter
+            .acquire(&[
+                ConcurrencyRule::instance(1, limit(1, 0, 1000)),
+                server.clone(),
+            ])
+            .await
+            .unwrap();
+        let _b = limiter
+            .acquire(&[
+                ConcurrencyRule::instance(2, limit(1, 0, 1000)),
+                server.clone(),
+            ])
+            .await
+            .unwrap();
+
+        // Instance 3 is free, but the server is at its limit
+        let refused = limiter
+            .acquire(&[ConcurrencyRule::instance(3, limit(1, 0, 1000)), server])
+            .await
+            .unwrap_err();
+        assert_eq!(refused.scope, "server");
+    }
+
+    #[tokio::test]
+    async fn test_changed_limit_takes_effect() {
+        let limiter = ConcurrencyLimiter::new();
+        let _held = limiter
+            .acquire(&[ConcurrencyRule::server(1, limit(1, 0, 1000))])
+            .await
+            .unwrap();
+
+        assert!(limiter
+            .acquire(&[ConcurrencyRule::server(1, limit(2, 0, 1000))])
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,701 @@
+//! cURL command import
+//!
+//! Reads a pasted `curl` command into an [`ImportedTool`], the reverse of the
+//! command shown after a tool test. The command is split like a POSIX shell
+//! would (quotes, `$'...'` strings, backslash line continuations) and the
+//! common options are mapped:
+//!
+//! - `-X`/`--request` and `--url` set the method and URL; `-G`/`--get` moves
+//!   the data to the query string
+//! - `-H`/`--header`, `-A`/`--user-agent`, `-e`/`--referer` and
+//!   `-b`/`--cookie` become headers; `-u`/`--user` a basic `Authorization`
+//! - `-d`/`--data`/`--data-raw`/`--data-binary` and `--data-urlencode` become
+//!   a form-encoded body, `--json` a JSON body and `-F`/`--form` a multipart
+//!   body, each with the `Content-Type` curl would send
+//!
+//! Values are kept literally; the user turns them into placeholders
+//! afterwards. Options that have no equivalent, such as reading a body from
+//! a file, are reported as warnings.
+
+use crate::services::tool_import::{snake_case, ImportedTool};
+use base64::Engine;
+
+/// Boundary of multipart bodies converted from `-F` fields
+const MULTIPART_BOUNDARY: &str = "saramcp-form-boundary";
+
+const METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// Options without an argument that do not change the request
+const IGNORED_FLAGS: [&str; 22] = [
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-L",
+    "--location",
+    "-k",
+    "--insecure",
+    "-v",
+    "--verbose",
+    "-i",
+    "--include",
+    "-f",
+    "--fail",
+    "--compressed",
+    "-#",
+    "--progress-bar",
+    "-N",
+    "--no-buffer",
+    "--http1.1",
+    "--http2",
+    "-g",
+];
+
+/// Options with an argument that do not change the request
+const IGNORED_OPTIONS: [&str; 12] = [
+    "-o",
+    "--output",
+    "-m",
+    "--max-time",
+    "--connect-timeout",
+    "--retry",
+    "-w",
+    "--write-out",
+    "-x",
+    "--proxy",
+    "--cacert",
+    "--cert",
+];
+
+/// Short options that take an argument, which may be attached (`-XPOST`)
+const SHORT_WITH_ARGUMENT: &str = "XHduFAebmowx";
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum CurlImportError {
+    #[error("Paste a command starting with curl")]
+    NotCurl,
+
+    #[error("Unterminated quote in the command")]
+    UnterminatedQuote,
+
+    #[error("Option {0} needs a value")]
+    MissingArgument(String),
+
+    #[error("The command has no URL")]
+    MissingUrl,
+
+    #[error("{0} requests are not supported, use GET, POST, PUT, PATCH or DELETE")]
+    UnsupportedMethod(String),
+}
+
+/// A tool read from a cURL command
+#[derive(Debug, Clone, Default)]
+pub struct CurlImport {
+    pub tool: ImportedTool,
+    /// Options that were ignored or only partly converted
+    pub warnings: Vec<String>,
+}
+
+/// The body options of a command, in the order given
+enum Data {
+    /// `-d` and friends, sent as given
+    Raw(String),
+    /// `--data-urlencode`, with the value part encoded
+    UrlEncoded(String),
+    /// `--json`
+    Json(String),
+}
+
+/// Parses a cURL command into a proposed tool
+pub fn parse(command: &str) -> Result<CurlImport, CurlImportError> {
+    let words = split_words(command)?;
+    let mut words = words.into_iter();
+    match words.next() {
+        Some(first) if first == "curl" || first == "curl.exe" => {}
+        _ => return Err(CurlImportError::NotCurl),
+    }
+
+    let mut import = CurlImport::default();
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut data: Vec<Data> = Vec::new();
+    let mut form: Vec<(String, String)> = Vec::new();
+    let mut get = false;
+
+    let mut options = Options::new(words);
+    while let Some(word) = options.next_word() {
+        let (option, attached) = match split_option(&word) {
+            Some(split) => split,
+            None => {
+                if url.is_none() {
+                    url = Some(word);
+                } else {
+                    import
+                        .warnings
+                        .push(format!("Extra URL {} was ignored", word));
+                }
+                continue;
+            }
+        };
+
+        match option.as_str() {
+            "-X" | "--request" => method = Some(options.value(&option, attached)?.to_uppercase()),
+            "--url" => url = Some(options.value(&option, attached)?),
+            "-H" | "--header" => {
+                let header = options.value(&option, attached)?;
+                match header.split_once(':') {
+                    Some((name, value)) if !name.trim().is_empty() => {
+                        headers.push((name.trim().to_string(), value.trim().to_string()));
+                    }
+                    _ => import
+                        .warnings
+                        .push(format!("Header {} was ignored", header)),
+                }
+            }
+            "-A" | "--user-agent" => {
+                headers.push(("User-Agent".to_string(), options.value(&option, attached)?));
+            }
+            "-e" | "--referer" => {
+                headers.push(("Referer".to_string(), options.value(&option, attached)?));
+            }
+            "-b" | "--cookie" => {
+                let cookie = options.value(&option, attached)?;
+                if cookie.contains('=') {
+                    headers.push(("Cookie".to_string(), cookie));
+                } else {
+                    import
+                        .warnings
+                        .push(format!("Cookie file {} was ignored", cookie));
+                }
+            }
+            "-u" | "--user" => {
+                let user = options.value(&option, attached)?;
+                let credentials = if user.contains(':') {
+                    user
+                } else {
+                    format!("{}:", user)
+                };
+                headers.push((
+                    "Authorization".to_string(),
+                    format!(
+                        "Basic {}",
+                        base64::engine::general_purpose::STANDARD.encode(credentials)
+                    ),
+                ));
+            }
+            "-d" | "--data" | "--data-ascii" | "--data-binary" => {
+                let value = options.value(&option, attached)?;
+                if let Some(file) = value.strip_prefix('@') {
+                    import.warnings.push(format!(
+                        "Body file {} cannot be read, add the body by hand",
+                        file
+                    ));
+                } else {
+                    data.push(Data::Raw(value));
+                }
+            }
+            "--data-raw" => data.push(Data::Raw(options.value(&option, attached)?)),
+            "--data-urlencode" => {
+                data.push(Data::UrlEncoded(options.value(&option, attached)?));
+            }
+            "--json" => data.push(Data::Json(options.value(&option, attached)?)),
+            "-F" | "--form" | "--form-string" => {
+                let field = options.value(&option, attached)?;
+                match field.split_once('=') {
+                    Some((name, value))
+                        if option != "--form-string"
+                            && (value.starts_with('@') || value.starts_with('<')) =>
+                    {
+                        import.warnings.push(format!(
+                            "File field {} cannot be imported, add it by hand",
+                            name
+                        ));
+                    }
+                    Some((name, value)) => form.push((name.to_string(), value.to_string())),
+                    None => import
+                        .warnings
+                        .push(format!("Form field {} was ignored", field)),
+                }
+            }
+            "-G" | "--get" => get = true,
+            "-I" | "--head" => method = Some("HEAD".to_string()),
+            flag if IGNORED_FLAGS.contains(&flag) => {}
+            ignored if IGNORED_OPTIONS.contains(&ignored) => {
+                options.value(&option, attached)?;
+                import
+                    .warnings
+                    .push(format!("Option {} was ignored", ignored));
+            }
+            unknown => import.warnings.push(format!(
+                "Option {} is not supported and was ignored",
+                unknown
+            )),
+        }
+    }
+
+    let mut url = url.ok_or(CurlImportError::MissingUrl)?;
+    if !url.contains("://") {
+        // curl defaults to http, but an API pasted without a scheme is
+        // nowadays far more likely to be served over https
+        url = format!("https://{}", url);
+    }
+
+    let has_body = !data.is_empty() || !form.is_empty();
+    let method = match method {
+        Some(method) => method,
+        None if get => "GET".to_string(),
+        None if has_body => "POST".to_string(),
+        None => "GET".to_string(),
+    };
+    if !METHODS.contains(&method.as_str()) {
+        return Err(CurlImportError::UnsupportedMethod(method));
+    }
+
+    let mut tool = ImportedTool {
+        key: format!("{} {}", method, url),
+        name: tool_name(&method, &url),
+        method,
+        headers,
+        ..Default::default()
+    };
+
+    if get && !data.is_empty() {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        url = format!("{}{}{}", url, separator, join_data(&data));
+        if !form.is_empty() {
+            import
+                .warnings
+                .push("Form fields cannot be sent with --get and were ignored".to_string());
+        }
+    } else if !form.is_empty() {
+        if !data.is_empty() {
+            import
+                .warnings
+                .push("Data cannot be mixed with form fields and was ignored".to_string());
+        }
+        tool.body = Some(multipart_body(&form));
+        // The boundary must match the body, so any Content-Type is replaced
+        tool.headers
+            .retain(|(name, _)| !name.eq_ignore_ascii_case("content-type"));
+        tool.headers.push((
+            "Content-Type".to_string(),
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        ));
+    } else if !data.is_empty() {
+        let json = data.iter().any(|d| matches!(d, Data::Json(_)));
+        if json {
+            set_default_header(&mut tool, "Content-Type", "application/json");
+            set_default_header(&mut tool, "Accept", "application/json");
+        } else {
+            set_default_header(
+                &mut tool,
+                "Content-Type",
+                "application/x-www-form-urlencoded",
+            );
+        }
+        tool.body = Some(join_data(&data));
+    }
+    tool.url = url;
+
+    import.tool = tool;
+    Ok(import)
+}
+
+/// Splits a command into words like a POSIX shell
+///
+/// Handles single and double quotes, `$'...'` strings and backslash escapes;
+/// a backslash before a line break continues the command on the next line.
+fn split_words(command: &str) -> Result<Vec<String>, CurlImportError> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(escaped) => {
+                    word.push(escaped);
+                    in_word = true;
+                }
+                None => {}
+            },
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(CurlImportError::UnterminatedQuote),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => {}
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => word.push(escaped),
+                            Some(other) => {
+                                word.push('\\');
+                                word.push(other);
+                            }
+                            None => return Err(CurlImportError::UnterminatedQuote),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(CurlImportError::UnterminatedQuote),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => word.push('\n'),
+                            Some('r') => word.push('\r'),
+                            Some('t') => word.push('\t'),
+                            Some(escaped) => word.push(escaped),
+                            None => return Err(CurlImportError::UnterminatedQuote),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(CurlImportError::UnterminatedQuote),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Splits an option word into the option and a value attached to it
+///
+/// Returns `None` for words that are not options. Short options may be
+/// grouped (`-sSL`) or carry their value (`-XPOST`); the last option of a
+/// group is returned and the flags before it are dropped, as none of the
+/// flags that can be grouped change the request.
+fn split_option(word: &str) -> Option<(String, Option<String>)> {
+    if word.starts_with("--") {
+        return Some((word.to_string(), None));
+    }
+    let letters = word.strip_prefix('-').filter(|l| !l.is_empty())?;
+
+    let mut last = None;
+    for (i, letter) in letters.char_indices() {
+        if SHORT_WITH_ARGUMENT.contains(letter) {
+            let rest = &letters[i + letter.len_utf8()..];
+            let value = (!rest.is_empty()).then(|| rest.to_string());
+            return Some((format!("-{}", letter), value));
+        }
+        last = Some(letter);
+    }
+    last.map(|letter| (format!("-{}", letter), None))
+}
+
+/// Words of a command after `curl`, handing out option values
+struct Options<I: Iterator<Item = String>> {
+    words: I,
+}
+
+impl<I: Iterator<Item = String>> Options<I> {
+    fn new(words: I) -> Self {
+        Self { words }
+    }
+
+    fn next_word(&mut self) -> Option<String> {
+        self.words.next()
+    }
+
+    /// Value of an option, attached to it or in the next word
+    fn value(&mut self, option: &str, attached: Option<String>) -> Result<String, CurlImportError> {
+        attached
+            .or_else(|| self.words.next())
+            .ok_or_else(|| CurlImportError::MissingArgument(option.to_string()))
+    }
+}
+
+/// Joins data options with `&`, as curl does
+fn join_data(data: &[Data]) -> String {
+    data.iter()
+        .map(|d| match d {
+            Data::Raw(text) | Data::Json(text) => text.clone(),
+            Data::UrlEncoded(text) => match text.split_once('=') {
+                Some((name, value)) if !name.is_empty() => {
+                    format!("{}={}", name, form_encode(value))
+                }
+                Some((_, value)) => form_encode(value),
+                None => form_encode(text),
+            },
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn multipart_body(fields: &[(String, String)]) -> String {
+    let mut parts = String::new();
+    for (name, value) in fields {
+        parts.push_str(&format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            MULTIPART_BOUNDARY,
+            name.replace('"', "%22"),
+            value
+        ));
+    }
+    parts.push_str(&format!("--{}--\r\n", MULTIPART_BOUNDARY));
+    parts
+}
+
+fn set_default_header(tool: &mut ImportedTool, name: &str, value: &str) {
+    if !tool
+        .headers
+        .iter()
+        .any(|(n, _)| n.eq_ignore_ascii_case(name))
+    {
+        tool.headers.push((name.to_string(), value.to_string()));
+    }
+}
+
+fn form_encode(text: &str) -> String {
+    form_urlencoded::byte_serialize(text.as_bytes()).collect()
+}
+
+/// Suggests a tool name from the method and the last path segment,
+/// e.g. `get_users` for `GET https://api.example.com/v1/users?page=2`
+fn tool_name(method: &str, url: &str) -> String {
+    let path = url
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(url)
+        .split(['?', '#'])
+        .next()
+        .unwrap_or("");
+    let segment = path
+        .split('/')
+        .skip(1)
+        .filter(|s| !s.is_empty() && !s.chars().all(|c| c.is_ascii_digit()))
+        .last()
+        .unwrap_or("");
+    snake_case(&format!("{} {}", method.to_lowercase(), segment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header<'a>(tool: &'a ImportedTool, name: &str) -> Option<&'a str> {
+        tool.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn test_simple_get() {
+        let import = parse("curl https://api.example.com/v1/users?page=2").unwrap();
+
+        assert_eq!(import.tool.method, "GET");
+        assert_eq!(import.tool.url, "https://api.example.com/v1/users?page=2");
+        assert_eq!(import.tool.name, "get_users");
+        assert!(import.tool.body.is_none());
+        assert!(import.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_multiline_command_with_quotes() {
+        let command = r#"curl -X PUT \
+  -H 'Content-Type: application/json' \
+  -H "Authorization: Bearer abc\"123" \
+  --data-raw '{"name": "Ada", "note": "it'\''s"}' \
+  'https://api.example.com/users/42'"#;
+        let import = parse(command).unwrap();
+        let tool = &import.tool;
+
+        assert_eq!(tool.method, "PUT");
+        assert_eq!(tool.url, "https://api.example.com/users/42");
+        assert_eq!(tool.name, "put_users");
+        assert_eq!(header(tool, "content-type"), Some("application/json"));
+        assert_eq!(header(tool, "authorization"), Some("Bearer abc\"123"));
+        assert_eq!(
+            tool.body.as_deref(),
+            Some(r#"{"name": "Ada", "note": "it's"}"#)
+        );
+    }
+
+    #[test]
+    fn test_data_defaults_to_post_form() {
+        let import =
+            parse("curl --url https://example.com/login -d user=ada -d 'pass=s3cret'").unwrap();
+        let tool = &import.tool;
+
+        assert_eq!(tool.method, "POST");
+        assert_eq!(tool.url, "https://example.com/login");
+        assert_eq!(tool.body.as_deref(), Some("user=ada&pass=s3cret"));
+        assert_eq!(
+            header(tool, "Content-Type"),
+            Some("application/x-www-form-urlencoded")
+        );
+    }
+
+    #[test]
+    fn test_data_urlencode() {
+        let import = parse(
+            "curl https://example.com/search --data-urlencode 'q=rust & mcp' --data-urlencode =raw?",
+        )
+        .unwrap();
+
+        assert_eq!(import.tool.body.as_deref(), Some("q=rust+%26+mcp&raw%3F"));
+    }
+
+    #[test]
+    fn test_get_moves_data_to_query() {
+        let import = parse(
+            "curl -G https://example.com/search?lang=en -d q=rust --data-urlencode 'tag=a b'",
+        )
+        .unwrap();
+
+        assert_eq!(import.tool.method, "GET");
+        assert_eq!(
+            import.tool.url,
+            "https://example.com/search?lang=en&q=rust&tag=a+b"
+        );
+        assert!(import.tool.body.is_none());
+    }
+
+    #[test]
+    fn test_json_option() {
+        let import = parse(r#"curl --json '{"a": 1}' example.com/items"#).unwrap();
+        let tool = &import.tool;
+
+        assert_eq!(tool.method, "POST");
+        assert_eq!(tool.url, "https://example.com/items");
+        assert_eq!(tool.body.as_deref(), Some(r#"{"a": 1}"#));
+        assert_eq!(header(tool, "Content-Type"), Some("application/json"));
+        assert_eq!(header(tool, "Accept"), Some("application/json"));
+    }
+
+    #[test]
+    fn test_form_fields_become_multipart() {
+        let import = parse(
+            "curl -F title=Report -F 'file=@report.pdf' -H 'Content-Type: text/plain' https://example.com/upload",
+        )
+        .unwrap();
+        let tool = &import.tool;
+
+        assert_eq!(tool.method, "POST");
+        assert_eq!(
+            header(tool, "Content-Type"),
+            Some("multipart/form-data; boundary=saramcp-form-boundary")
+        );
+        assert_eq!(
+            tool.body.as_deref(),
+            Some("--saramcp-form-boundary\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nReport\r\n--saramcp-form-boundary--\r\n")
+        );
+        assert_eq!(
+            import.warnings,
+            ["File field file cannot be imported, add it by hand"]
+        );
+    }
+
+    #[test]
+    fn test_user_becomes_basic_auth() {
+        let import = parse("curl -u ada:s3cret https://example.com/me").unwrap();
+        assert_eq!(
+            header(&import.tool, "Authorization"),
+            Some("Basic YWRhOnMzY3JldA==")
+        );
+
+        let import = parse("curl --user ada https://example.com/me").unwrap();
+        assert_eq!(
+            header(&import.tool, "Authorization"),
+            Some("Basic YWRhOg==")
+        );
+    }
+
+    #[test]
+    fn test_grouped_and_attached_short_options() {
+        let import =
+            parse("curl -sSL -XPATCH -H'X-Trace: 1' -d'{}' https://example.com/items/7").unwrap();
+        let tool = &import.tool;
+
+        assert_eq!(tool.method, "PATCH");
+        assert_eq!(header(tool, "X-Trace"), Some("1"));
+        assert_eq!(tool.body.as_deref(), Some("{}"));
+        assert_eq!(tool.name, "patch_items");
+        assert!(import.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_ansi_c_quoted_body() {
+        let import =
+            parse(r#"curl 'https://example.com/notes' --data-raw $'{"text":"line\nnext"}'"#)
+                .unwrap();
+        assert_eq!(
+            import.tool.body.as_deref(),
+            Some("{\"text\":\"line\nnext\"}")
+        );
+    }
+
+    #[test]
+    fn test_unsupported_options_are_reported() {
+        let import = parse(
+            "curl -m 10 --data @body.json --aws-sigv4 https://example.com/a https://example.com/b",
+        )
+        .unwrap();
+
+        assert_eq!(import.tool.url, "https://example.com/a");
+        assert_eq!(import.tool.method, "GET");
+        assert_eq!(
+            import.warnings,
+            [
+                "Option -m was ignored",
+                "Body file body.json cannot be read, add the body by hand",
+                "Option --aws-sigv4 is not supported and was ignored",
+                "Extra URL https://example.com/b was ignored",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse("wget https://example.com").unwrap_err(),
+            CurlImportError::NotCurl
+        );
+        assert_eq!(parse("").unwrap_err(), CurlImportError::NotCurl);
+        assert_eq!(
+            parse("curl 'https://example.com").unwrap_err(),
+            CurlImportError::UnterminatedQuote
+        );
+        assert_eq!(
+            parse("curl https://example.com -H").unwrap_err(),
+            CurlImportError::MissingArgument("-H".to_string())
+        );
+        assert_eq!(parse("curl -s").unwrap_err(), CurlImportError::MissingUrl);
+        assert_eq!(
+            parse("curl -I https://example.com").unwrap_err(),
+            CurlImportError::UnsupportedMethod("HEAD".to_string())
+        );
+    }
+}
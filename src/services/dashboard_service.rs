@@ -38,6 +38,8 @@ pub struct RecentExecution {
     pub tool_name: String,
     pub started_at: String,
     pub duration_ms: Option<i64>,
+    /// Part of `duration_ms` spent waiting for a concurrency slot
+    pub queued_ms: Option<i64>,
     pub status: String,
     pub http_status_code: Option<i64>,
 }
@@ -251,6 +253,7 @@ impl DashboardService {
                 t.name as "tool_name!: String",
                 eh.started_at as "started_at!: String",
                 eh.duration_ms,
+                eh.queued_ms,
                 eh.status as "status!: String",
                 eh.http_status_code
             FROM execution_history eh
@@ -276,6 +279,7 @@ impl DashboardService {
                 tool_name: row.tool_name,
                 started_at: row.started_at,
                 duration_ms: row.duration_ms,
+                queued_ms: row.queued_ms,
                 status: row.status,
                 http_status_code: row.http_status_code,
             })
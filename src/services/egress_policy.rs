@@ -0,0 +1,489 @@
+//! Egress policy for outbound tool requests (SSRF protection)
+//!
+//! Tool URLs are user-controlled, so without a policy any user could make
+//! SaraMCP call cloud metadata endpoints (`169.254.169.254`), admin ports on
+//! `localhost` or other hosts of the internal network. [`EgressPolicy`]
+//! decides which destinations a request may reach:
+//!
+//! 1. a destination matching the **denylist** is always blocked
+//! 2. a destination matching the **allowlist** is always permitted
+//! 3. otherwise loopback, link-local, private, shared (CGNAT), multicast
+//!    and reserved addresses are blocked
+//!
+//! Rules are host names (`api.example.com`), domain wildcards
+//! (`*.corp.example.com`), IP addresses and CIDR ranges (`10.20.0.0/16`).
+//! Operators set global rules through the environment (see
+//! [`EgressPolicy::from_env`]) and per-server rules with
+//! `saramcp-cli server egress`.
+//!
+//! Addresses are checked after DNS resolution: [`EgressResolver`] is
+//! installed as the DNS resolver of every client built by
+//! [`HttpExecutor::client_builder`], so the addresses that were checked are
+//! the ones connected to and DNS rebinding cannot swap them. IP literals
+//! never reach the resolver and are checked before sending and on redirects.
+//!
+//! [`HttpExecutor::client_builder`]: crate::services::HttpExecutor::client_builder
+
+use ipnet::IpNet;
+use once_cell::sync::Lazy;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::RwLock;
+
+static GLOBAL_POLICY: Lazy<RwLock<EgressPolicy>> =
+    Lazy::new(|| RwLock::new(EgressPolicy::from_env()));
+
+/// A destination refused by the egress policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EgressBlocked {
+    pub host: String,
+    pub reason: String,
+}
+
+impl fmt::Display for EgressBlocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.host, self.reason)
+    }
+}
+
+impl std::error::Error for EgressBlocked {}
+
+/// One allowlist or denylist entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostRule {
+    /// Exact host name, case-insensitive
+    Host(String),
+    /// `*.example.com`: any subdomain of `example.com`
+    Domain(String),
+    /// IP address or CIDR range
+    Network(IpNet),
+}
+
+impl HostRule {
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let rule = rule.trim().to_ascii_lowercase();
+        if rule.is_empty() {
+            return Err("empty rule".to_string());
+        }
+
+        if let Ok(net) = rule.parse::<IpNet>() {
+            return Ok(HostRule::Network(net));
+        }
+        if let Ok(ip) = rule.parse::<IpAddr>() {
+            return Ok(HostRule::Network(IpNet::from(ip)));
+        }
+        if let Some(domain) = rule.strip_prefix("*.") {
+            if Self::is_host_name(domain) {
+                return Ok(HostRule::Domain(domain.to_string()));
+            }
+        } else if Self::is_host_name(&rule) {
+            return Ok(HostRule::Host(rule));
+        }
+
+        Err(format!("invalid host rule '{}'", rule))
+    }
+
+    fn is_host_name(value: &str) -> bool {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    }
+
+    fn matches_host(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        match self {
+            HostRule::Host(name) => host == *name,
+            HostRule::Domain(domain) => host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.')),
+            HostRule::Network(net) => parse_ip_literal(&host).is_some_and(|ip| net.contains(&ip)),
+        }
+    }
+
+    fn matches_ip(&self, ip: IpAddr) -> bool {
+        match self {
+            HostRule::Network(net) => net.contains(&ip),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for HostRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostRule::Host(name) => write!(f, "{}", name),
+            HostRule::Domain(domain) => write!(f, "*.{}", domain),
+            HostRule::Network(net) if net.prefix_len() == net.max_prefix_len() => {
+                write!(f, "{}", net.addr())
+            }
+            HostRule::Network(net) => write!(f, "{}", net),
+        }
+    }
+}
+
+/// Outcome of the name-only check, before addresses are known
+#[derive(Debug, PartialEq, Eq)]
+enum HostVerdict {
+    Denied,
+    Allowed,
+    CheckAddresses,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EgressPolicy {
+    /// Block loopback, link-local, private and reserved addresses
+    pub block_private_networks: bool,
+    pub allowed_hosts: Vec<HostRule>,
+    pub denied_hosts: Vec<HostRule>,
+}
+
+impl Default for EgressPolicy {
+    fn default() -> Self {
+        Self {
+            block_private_networks: true,
+            allowed_hosts: Vec::new(),
+            denied_hosts: Vec::new(),
+        }
+    }
+}
+
+impl EgressPolicy {
+    /// Policy from the environment
+    ///
+    /// - `SARAMCP_EGRESS_ALLOWED_HOSTS` / `SARAMCP_EGRESS_DENIED_HOSTS`:
+    ///   comma or whitespace separated rules; an invalid list is ignored
+    /// - `SARAMCP_EGRESS_ALLOW_PRIVATE_NETWORKS=true` disables the
+    ///   private-network block (denylist still applies)
+    pub fn from_env() -> Self {
+        let rules = |var: &str| {
+            std::env::var(var)
+                .map(|value| {
+                    Self::parse_rules(&value).unwrap_or_else(|e| {
+                        tracing::warn!("Ignoring {}: {}", var, e);
+                        Vec::new()
+                    })
+                })
+                .unwrap_or_default()
+        };
+
+        let allow_private = std::env::var("SARAMCP_EGRESS_ALLOW_PRIVATE_NETWORKS")
+            .map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+
+        Self {
+            block_private_networks: !allow_private,
+            allowed_hosts: rules("SARAMCP_EGRESS_ALLOWED_HOSTS"),
+            denied_hosts: rules("SARAMCP_EGRESS_DENIED_HOSTS"),
+        }
+    }
+
+    /// The process-wide policy applied to every request
+    pub fn global() -> Self {
+        GLOBAL_POLICY
+            .read()
+            .map(|policy| policy.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replaces the process-wide policy; executors created afterwards use it
+    pub fn set_global(policy: EgressPolicy) {
+        if let Ok(mut global) = GLOBAL_POLICY.write() {
+            *global = policy;
+        }
+    }
+
+    /// Parses rules separated by newlines, commas or spaces; `#` starts a
+    /// comment running to the end of the line
+    pub fn parse_rules(text: &str) -> Result<Vec<HostRule>, String> {
+        text.lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .flat_map(|line| line.split(|c: char| c == ',' || c.is_whitespace()))
+            .filter(|rule| !rule.is_empty())
+            .map(HostRule::parse)
+            .collect()
+    }
+
+    /// This policy extended with additional rules (e.g. a server's own)
+    pub fn with_rules(mut self, allowed: Vec<HostRule>, denied: Vec<HostRule>) -> Self {
+        self.allowed_hosts.extend(allowed);
+        self.denied_hosts.extend(denied);
+        self
+    }
+
+    fn check_host(&self, host: &str) -> HostVerdict {
+        if self.denied_hosts.iter().any(|r| r.matches_host(host)) {
+            HostVerdict::Denied
+        } else if self.allowed_hosts.iter().any(|r| r.matches_host(host)) {
+            HostVerdict::Allowed
+        } else {
+            HostVerdict::CheckAddresses
+        }
+    }
+
+    /// Checks one resolved address of `host`
+    ///
+    /// Denied hosts and addresses are blocked, even when the other one is
+    /// allowed.
+    pub fn check_address(&self, host: &str, ip: IpAddr) -> Result<(), EgressBlocked> {
+        let blocked = |reason: String| EgressBlocked {
+            host: host.to_string(),
+            reason,
+        };
+
+        let verdict = self.check_host(host);
+        if verdict == HostVerdict::Denied {
+            return Err(blocked("host is denied".to_string()));
+        }
+        if self.denied_hosts.iter().any(|r| r.matches_ip(ip)) {
+            return Err(blocked(format!("{} is denied", ip)));
+        }
+        if verdict == HostVerdict::Allowed || self.allowed_hosts.iter().any(|r| r.matches_ip(ip)) {
+            return Ok(());
+        }
+        if self.block_private_networks && is_private_address(ip) {
+            return Err(blocked(format!("{} is a private or reserved address", ip)));
+        }
+        Ok(())
+    }
+
+    /// Checks a host without resolving it: host rules and IP literals
+    ///
+    /// Host names that need resolution pass; their addresses are checked by
+    /// [`EgressResolver`] when connecting.
+    pub fn check_host_literal(&self, host: &str) -> Result<(), EgressBlocked> {
+        match parse_ip_literal(host) {
+            Some(ip) => self.check_address(host, ip),
+            None if self.check_host(host) == HostVerdict::Denied => Err(EgressBlocked {
+                host: host.to_string(),
+                reason: "host is denied".to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks the destination of `url` before a request is sent
+    ///
+    /// Host names are resolved so requests sent through a proxy (which
+    /// resolves the target itself) are covered too.
+    pub async fn check_url(&self, url: &reqwest::Url) -> Result<(), EgressBlocked> {
+        let Some(host) = url.host_str() else {
+            return Ok(());
+        };
+
+        self.check_host_literal(host)?;
+        if parse_ip_literal(host).is_some() {
+            return Ok(());
+        }
+
+        let port = url.port_or_known_default().unwrap_or(80);
+        // Resolution failures surface as connection errors later on
+        let Ok(addrs) = tokio::net::lookup_host((host, port)).await else {
+            return Ok(());
+        };
+        for addr in addrs {
+            self.check_address(host, addr.ip())?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_ip_literal(host: &str) -> Option<IpAddr> {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// True for addresses that must not be reachable from tool requests
+pub fn is_private_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_private_v4(v4),
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_private_v4(v4);
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00 // unique local fc00::/7
+                || (first & 0xffc0) == 0xfe80 // link-local fe80::/10
+                || is_nat64_private(v6)
+        }
+    }
+}
+
+fn is_private_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0 // "this network" 0.0.0.0/8
+        || (a == 100 && (64..128).contains(&b)) // shared address space 100.64.0.0/10
+        || (a == 192 && b == 0 && ip.octets()[2] == 0) // IETF protocol assignments
+        || (a == 198 && (b == 18 || b == 19)) // benchmarking 198.18.0.0/15
+        || a >= 240 // reserved 240.0.0.0/4
+}
+
+/// `64:ff9b::/96` embeds an IPv4 address reachable through NAT64
+fn is_nat64_private(ip: Ipv6Addr) -> bool {
+    let s = ip.segments();
+    if s[..6] != [0x64, 0xff9b, 0, 0, 0, 0] {
+        return false;
+    }
+    let v4 = Ipv4Addr::new((s[6] >> 8) as u8, s[6] as u8, (s[7] >> 8) as u8, s[7] as u8);
+    is_private_v4(v4)
+}
+
+/// DNS resolver enforcing an [`EgressPolicy`] on resolved addresses
+///
+/// Blocked addresses are dropped; when none is left the lookup fails with
+/// [`EgressBlocked`], which the executor reports as a blocked request.
+#[derive(Debug, Clone)]
+pub struct EgressResolver {
+    policy: EgressPolicy,
+}
+
+impl EgressResolver {
+    pub fn new(policy: EgressPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+impl Resolve for EgressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.policy.clone();
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+
+            policy
+                .check_host_literal(&host)
+                .map_err(|blocked| Box::new(blocked) as Box<dyn std::error::Error + Send + Sync>)?;
+
+            let mut permitted = Vec::new();
+            let mut last_blocked = None;
+            for addr in tokio::net::lookup_host((host.as_str(), 0)).await? {
+                match policy.check_address(&host, addr.ip()) {
+                    Ok(()) => permitted.push(addr),
+                    Err(blocked) => last_blocked = Some(blocked),
+                }
+            }
+
+            match (permitted.is_empty(), last_blocked) {
+                (true, Some(blocked)) => Err(Box::new(blocked) as Box<_>),
+                _ => Ok(Box::new(permitted.into_iter()) as Addrs),
+            }
+        })
+    }
+}
+
+/// Finds an [`EgressBlocked`] error in the source chain of `error`
+pub fn find_blocked(error: &(dyn std::error::Error + 'static)) -> Option<EgressBlocked> {
+    let mut current = Some(error);
+    while let Some(e) = current {
+        if let Some(blocked) = e.downcast_ref::<EgressBlocked>() {
+            return Some(blocked.clone());
+        }
+        current = e.source();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_private_addresses() {
+        for blocked in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(
+                is_private_address(ip(blocked)),
+                "{} should be blocked",
+                blocked
+            );
+        }
+
+        for public in ["93.184.216.34", "8.8.8.8", "2606:4700::1111"] {
+            assert!(!is_private_address(ip(public)), "{} should pass", public);
+        }
+    }
+
+    #[test]
+    fn test_rule_parsing_and_matching() {
+        let rules = EgressPolicy::parse_rules(
+            "api.example.com\n*.corp.example.com  # internal\n10.20.0.0/16\n::1",
+        )
+        .unwrap();
+
+        assert!(rules[0].matches_host("API.example.com"));
+        assert!(!rules[0].matches_host("evil-api.example.com"));
+        assert!(rules[1].matches_host("billing.corp.example.com"));
+        assert!(!rules[1].matches_host("corp.example.com"));
+        assert!(!rules[1].matches_host("evilcorp.example.com"));
+        assert!(rules[2].matches_ip(ip("10.20.3.4")));
+        assert!(rules[3].matches_host("[::1]"));
+
+        assert!(EgressPolicy::parse_rules("http://example.com").is_err());
+        assert_eq!(rules[1].to_string(), "*.corp.example.com");
+        assert_eq!(rules[3].to_string(), "::1");
+    }
+
+    #[test]
+    fn test_default_policy_blocks_private_literals() {
+        let policy = EgressPolicy::default();
+
+        assert!(policy.check_host_literal("169.254.169.254").is_err());
+        assert!(policy.check_host_literal("[::1]").is_err());
+        assert!(policy.check_host_literal("93.184.216.34").is_ok());
+        // Names are checked once resolved
+        assert!(policy.check_host_literal("localhost").is_ok());
+    }
+
+    #[test]
+    fn test_denylist_wins_over_allowlist() {
+        let policy = EgressPolicy::default().with_rules(
+            EgressPolicy::parse_rules("10.0.0.0/8\n*.example.com").unwrap(),
+            EgressPolicy::parse_rules("10.0.0.5\nadmin.example.com").unwrap(),
+        );
+
+        assert!(policy.check_address("internal", ip("10.1.1.1")).is_ok());
+        assert!(policy.check_address("internal", ip("10.0.0.5")).is_err());
+        assert!(policy.check_host_literal("api.example.com").is_ok());
+        assert!(policy.check_host_literal("admin.example.com").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_url_resolves_host_names() {
+        let policy = EgressPolicy::default();
+        let url = reqwest::Url::parse("http://localhost:8080/admin").unwrap();
+
+        let blocked = policy.check_url(&url).await.unwrap_err();
+        assert_eq!(blocked.host, "localhost");
+
+        let allowed = policy.with_rules(vec![HostRule::parse("localhost").unwrap()], vec![]);
+        assert!(allowed.check_url(&url).await.is_ok());
+    }
+}
@@ -0,0 +1,235 @@
+//! Health tracking for upstream base URLs
+//!
+//! Tools and toolkits can declare several base URLs (see
+//! [`EndpointConfig`]). [`EndpointHealthRegistry`] records the outcome and
+//! latency of every request per base URL and decides in which order the
+//! base URLs are tried:
+//!
+//! - the strategy gives the preferred order (declared order, rotation, or
+//!   fastest first)
+//! - endpoints that failed recently are moved to the back of the list until
+//!   their cooldown expires, so a dead region is only retried as a last resort
+//!
+//! Health is kept in memory and shared by every [`HttpExecutor`] of the
+//! process through [`EndpointHealthRegistry::shared`].
+//!
+//! [`HttpExecutor`]: crate::services::HttpExecutor
+
+use crate::models::endpoint::{EndpointConfig, EndpointStrategy};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a failed endpoint is deprioritized
+const FAILURE_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Weight of the newest sample in the latency moving average
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+static SHARED_REGISTRY: Lazy<EndpointHealthRegistry> = Lazy::new(EndpointHealthRegistry::new);
+
+/// Observed health of one base URL
+#[derive(Debug, Clone, Default)]
+pub struct EndpointHealth {
+    pub consecutive_failures: u32,
+    pub total_successes: u64,
+    pub total_failures: u64,
+    /// Exponential moving average of request latency
+    pub latency_ms: Option<f64>,
+    pub last_failure: Option<Instant>,
+}
+
+impl EndpointHealth {
+    /// False while the endpoint is cooling down after a failure
+    pub fn is_healthy(&self) -> bool {
+        match self.last_failure {
+            Some(at) if self.consecutive_failures > 0 => at.elapsed() >= FAILURE_COOLDOWN,
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RegistryState {
+    endpoints: HashMap<String, EndpointHealth>,
+    /// Round-robin position per base URL list
+    cursors: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EndpointHealthRegistry {
+    state: Arc<Mutex<RegistryState>>,
+}
+
+impl EndpointHealthRegistry {
+    /// Creates an empty registry, independent from the shared one
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the process-wide registry
+    pub fn shared() -> Self {
+        SHARED_REGISTRY.clone()
+    }
+
+    /// Orders the base URLs of `config` for the next request
+    pub fn order(&self, config: &EndpointConfig) -> Vec<String> {
+        let Ok(mut state) = self.state.lock() else {
+            return config.base_urls.clone();
+        };
+
+        let mut ordered = config.base_urls.clone();
+        match config.strategy {
+            EndpointStrategy::PrimaryFailover => {}
+            EndpointStrategy::RoundRobin => {
+                let cursor = state
+                    .cursors
+                    .entry(config.base_urls.join("\n"))
+                    .or_insert(0);
+                let start = *cursor % ordered.len().max(1);
+                *cursor = cursor.wrapping_add(1);
+                ordered.rotate_left(start);
+            }
+            EndpointStrategy::LatencyBased => {
+                // Unmeasured endpoints sort first so they get a sample
+                let latency = |url: &String| {
+                    state
+                        .endpoints
+                        .get(url)
+                        .and_then(|h| h.latency_ms)
+                        .unwrap_or(0.0)
+                };
+                ordered.sort_by(|a, b| latency(a).total_cmp(&latency(b)));
+            }
+        }
+
+        // Stable partition: healthy endpoints keep their order and go first
+        let (healthy, cooling_down): (Vec<String>, Vec<String>) =
+            ordered.into_iter().partition(|url| {
+                state
+                    .endpoints
+                    .get(url)
+                    .map(EndpointHealth::is_healthy)
+                    .unwrap_or(true)
+            });
+
+        healthy.into_iter().chain(cooling_down).collect()
+    }
+
+    pub fn record_success(&self, base_url: &str, latency: Duration) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+
+        let health = state.endpoints.entry(base_url.to_string()).or_default();
+        let sample = latency.as_secs_f64() * 1000.0;
+        health.latency_ms = Some(match health.latency_ms {
+            Some(avg) => avg + LATENCY_SMOOTHING * (sample - avg),
+            None => sample,
+        });
+        health.consecutive_failures = 0;
+        health.total_successes += 1;
+    }
+
+    pub fn record_failure(&self, base_url: &str) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+
+        let health = state.endpoints.entry(base_url.to_string()).or_default();
+        health.consecutive_failures += 1;
+        health.total_failures += 1;
+        health.last_failure = Some(Instant::now());
+    }
+
+    /// Current health of a base URL, if it has been used
+    pub fn get(&self, base_url: &str) -> Option<EndpointHealth> {
+        self.state
+            .lock()
+            .ok()
+            .and_then(|state| state.endpoints.get(base_url).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(strategy: EndpointStrategy) -> EndpointConfig {
+        EndpointConfig {
+            base_urls: vec![
+                "https://eu.example.com".to_string(),
+                "https://us.example.com".to_string(),
+                "https://ap.example.com".to_string(),
+            ],
+            strategy,
+            failover_status_codes: vec![503],
+        }
+    }
+
+    #[test]
+    fn test_primary_failover_keeps_declared_order() {
+        let registry = EndpointHealthRegistry::new();
+        let config = config(EndpointStrategy::PrimaryFailover);
+
+        assert_eq!(registry.order(&config), config.base_urls);
+        assert_eq!(registry.order(&config), config.base_urls);
+    }
+
+    #[test]
+    fn test_failed_endpoint_moves_to_the_back() {
+        let registry = EndpointHealthRegistry::new();
+        let config = config(EndpointStrategy::PrimaryFailover);
+
+        registry.record_failure("https://eu.example.com");
+
+        assert_eq!(
+            registry.order(&config),
+            vec![
+                "https://us.example.com",
+                "https://ap.example.com",
+                "https://eu.example.com"
+            ]
+        );
+
+        registry.record_success("https://eu.example.com", Duration::from_millis(5));
+        assert_eq!(registry.order(&config), config.base_urls);
+    }
+
+    #[test]
+    fn test_round_robin_rotates_start() {
+        let registry = EndpointHealthRegistry::new();
+        let config = config(EndpointStrategy::RoundRobin);
+
+        let firsts: Vec<String> = (0..4).map(|_| registry.order(&config)[0].clone()).collect();
+        assert_eq!(
+            firsts,
+            vec![
+                "https://eu.example.com",
+                "https://us.example.com",
+                "https://ap.example.com",
+                "https://eu.example.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_latency_based_prefers_fastest() {
+        let registry = EndpointHealthRegistry::new();
+        let config = config(EndpointStrategy::LatencyBased);
+
+        registry.record_success("https://eu.example.com", Duration::from_millis(300));
+        registry.record_success("https://us.example.com", Duration::from_millis(40));
+        registry.record_success("https://ap.example.com", Duration::from_millis(120));
+
+        assert_eq!(
+            registry.order(&config),
+            vec![
+                "https://us.example.com",
+                "https://ap.example.com",
+                "https://eu.example.com"
+            ]
+        );
+    }
+}
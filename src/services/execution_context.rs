@@ -0,0 +1,257 @@
+//! Per-call context threaded from the MCP transport into tool execution
+//!
+//! Transports that keep a connection to the client (SSE) can push messages
+//! back while a tool is running. [`ExecutionContext`] carries the handle to
+//! the calling peer together with the request metadata needed to address
+//! those messages, so the execution layer stays transport-agnostic.
+//!
+//! The context also carries the request's cancellation token. rmcp cancels
+//! it when the client sends `notifications/cancelled` over SSE; the
+//! Streamable HTTP transport cancels it from its own in-flight registry.
+//!
+//! Log messages go to clients that chose a level with `logging/setLevel`,
+//! through the peer or, for Streamable HTTP sessions, through a
+//! [`NotificationSink`] writing to the session's event stream.
+//!
+//! Clients that declared the `elicitation` capability can be asked for
+//! input while a call runs, through the peer or, for Streamable HTTP
+//! sessions, through a [`ClientRequests`] channel that waits for the answer
+//! the client posts back.
+//!
+//! Stateless callers (the web UI test page) use
+//! [`ExecutionContext::default`], for which every notification is a no-op
+//! and which is never cancelled.
+
+use futures::future::BoxFuture;
+use rmcp::model::{
+    CreateElicitationRequestParam, CreateElicitationResult, ErrorData, LoggingLevel,
+    LoggingMessageNotificationParam, ProgressNotificationParam, ProgressToken,
+};
+use rmcp::service::{Peer, RequestContext};
+use rmcp::RoleServer;
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+/// Name log messages are sent under
+const LOGGER: &str = "saramcp";
+
+/// Delivers JSON-RPC notifications to a client without an rmcp peer
+pub type NotificationSink = Arc<dyn Fn(serde_json::Value) + Send + Sync>;
+
+/// Sends a JSON-RPC request to a client without an rmcp peer, resolving to
+/// the result the client answers with
+pub type ClientRequests = Arc<
+    dyn Fn(&str, serde_json::Value) -> BoxFuture<'static, Result<serde_json::Value, ErrorData>>
+        + Send
+        + Sync,
+>;
+
+/// Minimum level of the log messages a client receives
+///
+/// Shared by all requests of one client connection or session. Nothing is
+/// sent until the client sets a level.
+#[derive(Debug, Clone, Default)]
+pub struct LogLevel(Arc<Mutex<Option<LoggingLevel>>>);
+
+impl LogLevel {
+    pub fn set(&self, level: LoggingLevel) {
+        if let Ok(mut current) = self.0.lock() {
+            *current = Some(level);
+        }
+    }
+
+    pub fn get(&self) -> Option<LoggingLevel> {
+        self.0.lock().ok().and_then(|current| *current)
+    }
+
+    /// True when messages of `level` are at or above the chosen level
+    pub fn allows(&self, level: LoggingLevel) -> bool {
+        self.get()
+            .is_some_and(|minimum| severity(level) >= severity(minimum))
+    }
+}
+
+/// Rank of a level, from `debug` to `emergency`
+fn severity(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ExecutionContext {
+    peer: Option<Peer<RoleServer>>,
+    progress_token: Option<ProgressToken>,
+    cancellation: CancellationToken,
+    log_level: LogLevel,
+    sink: Option<NotificationSink>,
+    requests: Option<ClientRequests>,
+}
+
+impl std::fmt::Debug for ExecutionContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecutionContext")
+            .field("has_peer", &self.peer.is_some())
+            .field("progress_token", &self.progress_token)
+            .field("cancelled", &self.cancellation.is_cancelled())
+            .field("log_level", &self.log_level.get())
+            .field("can_elicit", &self.can_elicit())
+            .finish()
+    }
+}
+
+impl ExecutionContext {
+    /// Builds a context from an rmcp request context
+    ///
+    /// Captures the peer, the request's cancellation token and the
+    /// `_meta.progressToken` sent by the client, if any.
+    pub fn from_request_context(context: &RequestContext<RoleServer>) -> Self {
+        Self {
+            peer: Some(context.peer.clone()),
+            progress_token: context.meta.get_progress_token(),
+            cancellation: context.ct.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Builds a peer-less context that is cancelled through `cancellation`
+    pub fn with_cancellation(cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation,
+            ..Self::default()
+        }
+    }
+
+    /// Sends log messages at the client's chosen level, through `sink` when
+    /// the context has no peer
+    pub fn with_logging(mut self, log_level: LogLevel, sink: Option<NotificationSink>) -> Self {
+        self.log_level = log_level;
+        self.sink = sink;
+        self
+    }
+
+    /// Sends requests to the client through `requests` when the context has
+    /// no peer, `None` when the client cannot answer them
+    pub fn with_requests(mut self, requests: Option<ClientRequests>) -> Self {
+        self.requests = requests;
+        self
+    }
+
+    /// Token cancelled when the client abandons the request
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// True when the client asked for progress notifications and can receive them
+    pub fn can_report_progress(&self) -> bool {
+        self.peer.is_some() && self.progress_token.is_some()
+    }
+
+    /// Sends a `notifications/progress` message to the calling client
+    ///
+    /// Does nothing when the client did not supply a progress token. Delivery
+    /// failures are logged and otherwise ignored: progress is best-effort and
+    /// must never fail the tool call.
+    pub async fn report_progress(
+        &self,
+        progress: f64,
+        total: Option<f64>,
+        message: Option<String>,
+    ) {
+        let (Some(peer), Some(token)) = (&self.peer, &self.progress_token) else {
+            return;
+        };
+
+        let param = ProgressNotificationParam {
+            progress_token: token.clone(),
+            progress,
+            total,
+            message,
+        };
+
+        if let Err(e) = peer.notify_progress(param).await {
+            tracing::debug!("Failed to send progress notification: {}", e);
+        }
+    }
+
+    /// True when the client wants log messages of `level`
+    pub fn logs(&self, level: LoggingLevel) -> bool {
+        (self.peer.is_some() || self.sink.is_some()) && self.log_level.allows(level)
+    }
+
+    /// Sends a `notifications/message` log message to the calling client
+    ///
+    /// Does nothing below the client's chosen level. Like progress, logging
+    /// is best-effort and never fails the tool call.
+    pub async fn log(&self, level: LoggingLevel, data: serde_json::Value) {
+        if !self.logs(level) {
+            return;
+        }
+
+        let param = LoggingMessageNotificationParam {
+            level,
+            logger: Some(LOGGER.to_string()),
+            data,
+        };
+
+        if let Some(peer) = &self.peer {
+            if let Err(e) = peer.notify_logging_message(param).await {
+                tracing::debug!("Failed to send log message: {}", e);
+            }
+        } else if let Some(sink) = &self.sink {
+            sink(serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/message",
+                "params": param,
+            }));
+        }
+    }
+
+    /// True when the client declared it can ask its user for input
+    pub fn can_elicit(&self) -> bool {
+        match &self.peer {
+            Some(peer) => peer.supports_elicitation(),
+            None => self.requests.is_some(),
+        }
+    }
+
+    /// Sends an `elicitation/create` request and waits for the user's answer
+    pub async fn elicit(
+        &self,
+        param: CreateElicitationRequestParam,
+    ) -> Result<CreateElicitationResult, ErrorData> {
+        let failed =
+            |e: String| ErrorData::internal_error(format!("Elicitation failed: {}", e), None);
+
+        if let Some(peer) = self
+            .peer
+            .as_ref()
+            .filter(|peer| peer.supports_elicitation())
+        {
+            return peer
+                .create_elicitation(param)
+                .await
+                .map_err(|e| failed(e.to_string()));
+        }
+        let Some(requests) = &self.requests else {
+            return Err(failed(
+                "the client does not support elicitation".to_string(),
+            ));
+        };
+
+        let params = serde_json::to_value(param).map_err(|e| failed(e.to_string()))?;
+        let result = requests("elicitation/create", params).await?;
+        serde_json::from_value(result).map_err(|e| failed(e.to_string()))
+    }
+}
@@ -33,6 +33,9 @@ impl ExecutionTracker {
         request_method: Option<String>,
         response_size_bytes: Option<usize>,
         transport: Option<String>,
+        endpoint: Option<String>,
+        throttled_ms: Option<u64>,
+        queued_ms: Option<u64>,
     ) -> Result<i64> {
         let duration_ms = (completed_at - started_at).whole_milliseconds() as i64;
 
@@ -58,6 +61,9 @@ impl ExecutionTracker {
             request_method.as_deref(),
             response_size_bytes.map(|s| s as i64),
             transport.as_deref(),
+            endpoint.as_deref(),
+            throttled_ms.map(|ms| ms as i64),
+            queued_ms.map(|ms| ms as i64),
         )
         .await?;
 
@@ -102,6 +108,9 @@ pub enum ExecutionStatus {
     Success,
     Error,
     Timeout,
+    Cancelled,
+    Blocked,
+    Throttled,
 }
 
 impl ExecutionStatus {
@@ -110,6 +119,9 @@ impl ExecutionStatus {
             ExecutionStatus::Success => "success",
             ExecutionStatus::Error => "error",
             ExecutionStatus::Timeout => "timeout",
+            ExecutionStatus::Cancelled => "cancelled",
+            ExecutionStatus::Blocked => "blocked",
+            ExecutionStatus::Throttled => "throttled",
         }
     }
 
@@ -0,0 +1,765 @@
+//! HAR import
+//!
+//! Reads browser traffic recorded as a HAR file (HTTP Archive 1.2) into a
+//! [`ToolImport`] with one tool per endpoint:
+//!
+//! - requests are grouped by method and path, with ID-like path segments
+//!   (numbers, UUIDs, long hex strings) turned into placeholders, so
+//!   `/users/1` and `/users/2` become one `/users/{{integer:user_id}}` tool
+//! - query values that differ between the requests of an endpoint become
+//!   placeholders; values that never change stay literal
+//! - cookies, `Authorization` and API key headers become secret server
+//!   globals with the recorded value as default, never hard-coded values
+//! - a single origin becomes the `base_url` global
+//!
+//! Static assets, browser-managed headers and methods other than GET, POST,
+//! PUT, PATCH and DELETE are left out. The body of the first request of an
+//! endpoint is kept as recorded.
+
+use crate::models::ParameterMetadata;
+use crate::services::tool_import::{
+    snake_case, unique_name, ImportOptions, ImportedParameter, ImportedTool, SuggestedGlobal,
+    ToolImport,
+};
+use serde_json::Value;
+use std::collections::HashSet;
+
+const METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// Resource types recorded by browsers that are API calls
+const API_RESOURCE_TYPES: [&str; 2] = ["xhr", "fetch"];
+
+/// Extensions of static assets, skipped when the resource type is unknown
+const STATIC_EXTENSIONS: [&str; 16] = [
+    ".js", ".mjs", ".css", ".map", ".png", ".jpg", ".jpeg", ".gif", ".svg", ".ico", ".webp",
+    ".woff", ".woff2", ".ttf", ".html", ".htm",
+];
+
+/// Headers set by the browser or the HTTP client, not by the application
+const BROWSER_HEADERS: [&str; 17] = [
+    "host",
+    "connection",
+    "content-length",
+    "accept-encoding",
+    "accept-language",
+    "user-agent",
+    "origin",
+    "referer",
+    "cache-control",
+    "pragma",
+    "priority",
+    "dnt",
+    "te",
+    "upgrade-insecure-requests",
+    "if-none-match",
+    "if-modified-since",
+    "keep-alive",
+];
+
+/// Header names, lowercased, that carry credentials besides `Authorization`
+const CREDENTIAL_HEADERS: [&str; 7] = [
+    "x-api-key",
+    "api-key",
+    "apikey",
+    "x-auth-token",
+    "x-access-token",
+    "x-csrf-token",
+    "x-xsrf-token",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum HarImportError {
+    #[error("HAR file is not valid JSON: {0}")]
+    InvalidJson(String),
+
+    #[error("Not a HAR file, save the network log with \"Save all as HAR\"")]
+    UnsupportedFormat,
+
+    #[error("HAR file has no API requests")]
+    NoRequests,
+}
+
+/// Parses a HAR file into a proposed toolkit
+pub fn parse(text: &str, _options: ImportOptions) -> Result<ToolImport, HarImportError> {
+    let root: Value =
+        serde_json::from_str(text).map_err(|e| HarImportError::InvalidJson(e.to_string()))?;
+    let Some(entries) = root.pointer("/log/entries").and_then(Value::as_array) else {
+        return Err(HarImportError::UnsupportedFormat);
+    };
+
+    let mut import = ToolImport {
+        title: root
+            .pointer("/log/pages/0/title")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|t| !t.is_empty() && !t.contains("://"))
+            .unwrap_or("Recorded API")
+            .to_string(),
+        ..Default::default()
+    };
+
+    let mut endpoints: Vec<Endpoint> = Vec::new();
+    let mut skipped_assets = 0;
+    let mut skipped_methods = HashSet::new();
+    for entry in entries {
+        let Some(request) = Request::from_entry(entry) else {
+            skipped_assets += 1;
+            continue;
+        };
+        if !METHODS.contains(&request.method.as_str()) {
+            skipped_methods.insert(request.method.clone());
+            continue;
+        }
+
+        let template = request.path_template();
+        match endpoints.iter_mut().find(|e| {
+            e.method == request.method && e.origin == request.origin && e.path == template
+        }) {
+            Some(endpoint) => endpoint.requests.push(request),
+            None => endpoints.push(Endpoint {
+                method: request.method.clone(),
+                origin: request.origin.clone(),
+                path: template,
+                requests: vec![request],
+            }),
+        }
+    }
+
+    if skipped_assets > 0 {
+        import.warnings.push(format!(
+            "{} requests for pages and static assets were left out",
+            skipped_assets
+        ));
+    }
+    let mut skipped_methods: Vec<String> = skipped_methods.into_iter().collect();
+    skipped_methods.sort();
+    for method in skipped_methods {
+        import
+            .warnings
+            .push(format!("{} requests are not supported", method));
+    }
+
+    let origins: HashSet<&str> = endpoints.iter().map(|e| e.origin.as_str()).collect();
+    let base_url = match origins.into_iter().collect::<Vec<_>>().as_slice() {
+        [origin] => {
+            import.suggest_global(SuggestedGlobal {
+                name: "base_url".to_string(),
+                description: "Base URL of the API, e.g. https://app.example.com".to_string(),
+                secret: false,
+                value: origin.to_string(),
+            });
+            true
+        }
+        _ => false,
+    };
+
+    for endpoint in &endpoints {
+        let tool = endpoint.build_tool(base_url, &mut import);
+        import.tools.push(tool);
+    }
+    import.dedupe_tool_names();
+
+    if import.tools.is_empty() {
+        return Err(HarImportError::NoRequests);
+    }
+    Ok(import)
+}
+
+/// A recorded request
+struct Request {
+    method: String,
+    /// Scheme and host, e.g. `https://app.example.com`
+    origin: String,
+    segments: Vec<String>,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    cookies: Option<String>,
+    mime_type: Option<String>,
+    body: Option<String>,
+}
+
+impl Request {
+    /// Reads the request of an entry, `None` for pages and static assets
+    fn from_entry(entry: &Value) -> Option<Self> {
+        let request = entry.get("request")?;
+        let url = request.get("url").and_then(Value::as_str)?;
+        let parsed = reqwest::Url::parse(url).ok()?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return None;
+        }
+
+        let path = parsed.path().to_lowercase();
+        match entry.get("_resourceType").and_then(Value::as_str) {
+            Some(kind) if !API_RESOURCE_TYPES.contains(&kind) => return None,
+            Some(_) => {}
+            None if STATIC_EXTENSIONS.iter().any(|ext| path.ends_with(ext)) => return None,
+            None => {}
+        }
+
+        let mut headers = Vec::new();
+        let mut cookies = None;
+        for header in request
+            .get("headers")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let Some(name) = header.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            let value = header.get("value").and_then(Value::as_str).unwrap_or("");
+            let lowered = name.to_lowercase();
+            if lowered == "cookie" {
+                cookies = Some(value.to_string());
+            } else if !name.starts_with(':')
+                && !lowered.starts_with("sec-")
+                && !BROWSER_HEADERS.contains(&lowered.as_str())
+            {
+                headers.push((name.to_string(), value.to_string()));
+            }
+        }
+
+        let post_data = request.get("postData");
+        let mime_type = post_data
+            .and_then(|p| p.get("mimeType"))
+            .and_then(Value::as_str)
+            .filter(|m| !m.is_empty())
+            .map(str::to_string);
+        let body = post_data.and_then(|p| match p.get("text").and_then(Value::as_str) {
+            Some(text) if !text.is_empty() => Some(text.to_string()),
+            _ => p.get("params").and_then(Value::as_array).map(|params| {
+                params
+                    .iter()
+                    .filter_map(|param| {
+                        let name = param.get("name").and_then(Value::as_str)?;
+                        let value = param.get("value").and_then(Value::as_str).unwrap_or("");
+                        Some(format!("{}={}", name, value))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("&")
+            }),
+        });
+
+        Some(Self {
+            method: request
+                .get("method")
+                .and_then(Value::as_str)
+                .unwrap_or("GET")
+                .to_uppercase(),
+            origin: parsed.origin().ascii_serialization(),
+            segments: parsed
+                .path_segments()
+                .into_iter()
+                .flatten()
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+            query: parsed
+                .query_pairs()
+                .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                .collect(),
+            headers,
+            cookies,
+            mime_type,
+            body,
+        })
+    }
+
+    /// The path with ID-like segments replaced by `{id}`, to group requests
+    fn path_template(&self) -> String {
+        let segments: Vec<&str> = self
+            .segments
+            .iter()
+            .map(|s| if is_id(s) { "{id}" } else { s.as_str() })
+            .collect();
+        format!("/{}", segments.join("/"))
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Requests sharing a method, origin and path template
+struct Endpoint {
+    method: String,
+    origin: String,
+    path: String,
+    requests: Vec<Request>,
+}
+
+impl Endpoint {
+    fn build_tool(&self, base_url: bool, import: &mut ToolImport) -> ImportedTool {
+        let first = &self.requests[0];
+        let mut tool = ImportedTool {
+            key: format!("{} {}{}", self.method, self.origin, self.path),
+            description: Some(match self.requests.len() {
+                1 => format!("{} {} (recorded once)", self.method, self.path),
+                n => format!("{} {} (recorded {} times)", self.method, self.path, n),
+            }),
+            method: self.method.clone(),
+            ..Default::default()
+        };
+        let mut used = HashSet::new();
+
+        // Path: ID segments become placeholders named after the segment before
+        let mut path = String::new();
+        let mut name_parts = vec![self.method.to_lowercase()];
+        for (i, segment) in first.segments.iter().enumerate() {
+            path.push('/');
+            if !is_id(segment) {
+                path.push_str(segment);
+                name_parts.push(segment.clone());
+                continue;
+            }
+            let name = match i.checked_sub(1).map(|p| &first.segments[p]) {
+                Some(previous) if !is_id(previous) => {
+                    format!("{}_id", singular(&snake_case(previous)))
+                }
+                _ => "id".to_string(),
+            };
+            let values: Vec<&str> = self
+                .requests
+                .iter()
+                .filter_map(|r| r.segments.get(i).map(String::as_str))
+                .collect();
+            let parameter = ImportedParameter {
+                name: unique_name(&name, &mut used),
+                param_type: value_type(&values).to_string(),
+                location: "path".to_string(),
+                metadata: examples(&values),
+            };
+            path.push_str(&parameter.placeholder());
+            name_parts.push(format!("by {}", parameter.name));
+            tool.parameters.push(parameter);
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        tool.name = snake_case(&name_parts.join(" "));
+
+        // Query: values that vary between requests become placeholders
+        let mut query = Vec::new();
+        for (key, value) in &first.query {
+            let values: Vec<&str> = self
+                .requests
+                .iter()
+                .filter_map(|r| r.query.iter().find(|(k, _)| k == key))
+                .map(|(_, v)| v.as_str())
+                .collect();
+            let varies = values.iter().any(|v| v != value);
+            let value = if varies {
+                let parameter = ImportedParameter {
+                    name: unique_name(&snake_case(key), &mut used),
+                    param_type: value_type(&values).to_string(),
+                    location: "query".to_string(),
+                    metadata: examples(&values),
+                };
+                let placeholder = parameter.placeholder();
+                tool.parameters.push(parameter);
+                placeholder
+            } else {
+                form_encode(value)
+            };
+            query.push(format!("{}={}", form_encode(key), value));
+        }
+
+        let origin = if base_url {
+            "{{url:base_url}}".to_string()
+        } else {
+            self.origin.clone()
+        };
+        tool.url = if query.is_empty() {
+            format!("{}{}", origin, path)
+        } else {
+            format!("{}{}?{}", origin, path, query.join("&"))
+        };
+
+        // Headers: credentials go to secret globals
+        for (name, value) in &first.headers {
+            let lowered = name.to_lowercase();
+            let value = if lowered == "authorization" {
+                match value.split_once(' ') {
+                    Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => format!(
+                        "{} {}",
+                        scheme,
+                        credential(&mut tool, import, "bearer_token", "Bearer token", token)
+                    ),
+                    Some((scheme, token)) if scheme.eq_ignore_ascii_case("basic") => format!(
+                        "{} {}",
+                        scheme,
+                        credential(
+                            &mut tool,
+                            import,
+                            "basic_auth",
+                            "Base64-encoded username:password",
+                            token
+                        )
+                    ),
+                    _ => credential(
+                        &mut tool,
+                        import,
+                        "authorization",
+                        "Authorization header value",
+                        value,
+                    ),
+                }
+            } else if CREDENTIAL_HEADERS.contains(&lowered.as_str()) {
+                credential(
+                    &mut tool,
+                    import,
+                    &snake_case(name),
+                    &format!("Credential sent as {}", name),
+                    value,
+                )
+            } else {
+                value.clone()
+            };
+            tool.headers.push((name.clone(), value));
+        }
+        if let Some(cookies) = &first.cookies {
+            let value = credential(
+                &mut tool,
+                import,
+                "cookie",
+                "Session cookies recorded with the browser",
+                cookies,
+            );
+            tool.headers.push(("Cookie".to_string(), value));
+        }
+
+        if let Some(body) = &first.body {
+            if first.header("content-type").is_none() {
+                if let Some(mime_type) = &first.mime_type {
+                    tool.headers
+                        .push(("Content-Type".to_string(), mime_type.clone()));
+                }
+            }
+            tool.body = Some(body.clone());
+            if self.requests.iter().any(|r| r.body.as_ref() != Some(body)) {
+                tool.skipped
+                    .push("differing bodies (first one kept)".to_string());
+            }
+        }
+
+        tool
+    }
+}
+
+/// Placeholder for a recorded credential, suggested as a secret global
+fn credential(
+    tool: &mut ImportedTool,
+    import: &mut ToolImport,
+    global: &str,
+    description: &str,
+    value: &str,
+) -> String {
+    import.suggest_global(SuggestedGlobal {
+        name: global.to_string(),
+        description: description.to_string(),
+        secret: true,
+        value: value.to_string(),
+    });
+    if !tool.parameters.iter().any(|p| p.name == global) {
+        tool.parameters.push(ImportedParameter {
+            name: global.to_string(),
+            param_type: "string".to_string(),
+            location: "header".to_string(),
+            metadata: ParameterMetadata {
+                description: Some(description.to_string()),
+                enum_values: Vec::new(),
+                lookup: None,
+            },
+        });
+    }
+    format!("{{{{string:{}}}}}", global)
+}
+
+/// Whether a path segment looks like an identifier rather than a resource name
+///
+/// Numbers, UUIDs and hex strings of 8+ characters with a digit count as IDs.
+fn is_id(segment: &str) -> bool {
+    let digits = segment.chars().filter(char::is_ascii_digit).count();
+    if digits == 0 {
+        return false;
+    }
+    if digits == segment.len() {
+        return true;
+    }
+    let is_uuid = segment.len() == 36
+        && segment.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        });
+    is_uuid || (segment.len() >= 8 && segment.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// `integer` when every recorded value is a whole number, `string` otherwise
+fn value_type(values: &[&str]) -> &'static str {
+    let integers = values
+        .iter()
+        .all(|v| !v.is_empty() && v.len() < 16 && v.chars().all(|c| c.is_ascii_digit()));
+    if integers {
+        "integer"
+    } else {
+        "string"
+    }
+}
+
+/// Describes a parameter with a few of the recorded values
+fn examples(values: &[&str]) -> ParameterMetadata {
+    let mut distinct: Vec<&str> = Vec::new();
+    for value in values {
+        if !distinct.contains(value) {
+            distinct.push(value);
+        }
+    }
+    distinct.truncate(3);
+    ParameterMetadata {
+        description: Some(format!("Recorded values: {}", distinct.join(", "))),
+        enum_values: Vec::new(),
+        lookup: None,
+    }
+}
+
+/// Naive singular of a resource name, `users` to `user`
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if name.ends_with("ss") {
+        name.to_string()
+    } else {
+        name.strip_suffix('s').unwrap_or(name).to_string()
+    }
+}
+
+fn form_encode(text: &str) -> String {
+    form_urlencoded::byte_serialize(text.as_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(method: &str, url: &str, headers: Value) -> Value {
+        json!({
+            "_resourceType": "fetch",
+            "request": {"method": method, "url": url, "headers": headers},
+            "response": {"status": 200}
+        })
+    }
+
+    fn har(entries: Value) -> String {
+        json!({"log": {"version": "1.2", "entries": entries}}).to_string()
+    }
+
+    #[test]
+    fn test_groups_requests_by_endpoint() {
+        let text = har(json!([
+            entry("GET", "https://app.example.com/api/users/17", json!([])),
+            entry("GET", "https://app.example.com/api/users/42", json!([])),
+            entry("GET", "https://app.example.com/api/users", json!([])),
+            entry(
+                "DELETE",
+                "https://app.example.com/api/users/42/sessions/3f2b9c1d-5e6a-4b7c-8d9e-0a1b2c3d4e5f",
+                json!([])
+            ),
+        ]));
+        let import = parse(&text, ImportOptions::default()).unwrap();
+
+        let urls: Vec<&str> = import.tools.iter().map(|t| t.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "{{url:base_url}}/api/users/{{integer:user_id}}",
+                "{{url:base_url}}/api/users",
+                "{{url:base_url}}/api/users/{{integer:user_id}}/sessions/{{string:session_id}}",
+            ]
+        );
+        let names: Vec<&str> = import.tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "get_api_users_by_user_id",
+                "get_api_users",
+                "delete_api_users_by_user_id_sessions_by_session_id",
+            ]
+        );
+        assert_eq!(
+            import.tools[0].description.as_deref(),
+            Some("GET /api/users/{id} (recorded 2 times)")
+        );
+        assert_eq!(
+            import.tools[0].parameters[0]
+                .metadata
+                .description
+                .as_deref(),
+            Some("Recorded values: 17, 42")
+        );
+
+        assert_eq!(import.globals.len(), 1);
+        assert_eq!(import.globals[0].name, "base_url");
+        assert_eq!(import.globals[0].value, "https://app.example.com");
+    }
+
+    #[test]
+    fn test_varying_query_values_become_parameters() {
+        let text = har(json!([
+            entry(
+                "GET",
+                "https://app.example.com/search?q=rust&page=1&lang=en",
+                json!([])
+            ),
+            entry(
+                "GET",
+                "https://app.example.com/search?q=mcp+server&page=2&lang=en",
+                json!([])
+            ),
+        ]));
+        let import = parse(&text, ImportOptions::default()).unwrap();
+        let tool = &import.tools[0];
+
+        assert_eq!(
+            tool.url,
+            "{{url:base_url}}/search?q={{string:q}}&page={{integer:page}}&lang=en"
+        );
+        assert_eq!(
+            tool.parameters_summary(),
+            "q (string, query), page (integer, query)"
+        );
+    }
+
+    #[test]
+    fn test_credentials_become_secret_globals() {
+        let text = har(json!([entry(
+            "POST",
+            "https://app.example.com/api/orders",
+            json!([
+                {"name": ":authority", "value": "app.example.com"},
+                {"name": "Authorization", "value": "Bearer eyJhbGciOi"},
+                {"name": "X-Api-Key", "value": "k-123"},
+                {"name": "Cookie", "value": "session=abc; theme=dark"},
+                {"name": "User-Agent", "value": "Mozilla/5.0"},
+                {"name": "sec-ch-ua", "value": "\"Chromium\""},
+                {"name": "Accept", "value": "application/json"}
+            ])
+        )]));
+        let import = parse(&text, ImportOptions::default()).unwrap();
+        let tool = &import.tools[0];
+
+        assert_eq!(
+            tool.headers,
+            [
+                (
+                    "Authorization".to_string(),
+                    "Bearer {{string:bearer_token}}".to_string()
+                ),
+                ("X-Api-Key".to_string(), "{{string:x_api_key}}".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+                ("Cookie".to_string(), "{{string:cookie}}".to_string()),
+            ]
+        );
+
+        let secrets: Vec<(&str, &str)> = import
+            .globals
+            .iter()
+            .filter(|g| g.secret)
+            .map(|g| (g.name.as_str(), g.value.as_str()))
+            .collect();
+        assert_eq!(
+            secrets,
+            [
+                ("bearer_token", "eyJhbGciOi"),
+                ("x_api_key", "k-123"),
+                ("cookie", "session=abc; theme=dark"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_body_and_content_type() {
+        let text = har(json!([{
+            "request": {
+                "method": "POST",
+                "url": "https://app.example.com/api/orders",
+                "headers": [],
+                "postData": {"mimeType": "application/json", "text": "{\"sku\":\"A1\"}"}
+            }
+        }, {
+            "request": {
+                "method": "POST",
+                "url": "https://app.example.com/api/orders",
+                "headers": [],
+                "postData": {"mimeType": "application/json", "text": "{\"sku\":\"B2\"}"}
+            }
+        }]));
+        let import = parse(&text, ImportOptions::default()).unwrap();
+        let tool = &import.tools[0];
+
+        assert_eq!(tool.body.as_deref(), Some("{\"sku\":\"A1\"}"));
+        assert_eq!(
+            tool.headers,
+            [("Content-Type".to_string(), "application/json".to_string())]
+        );
+        assert_eq!(tool.skipped, ["differing bodies (first one kept)"]);
+    }
+
+    #[test]
+    fn test_skips_assets_and_keeps_origins_apart() {
+        let text = har(json!([
+            {"_resourceType": "script", "request": {"method": "GET", "url": "https://app.example.com/app.js"}},
+            {"request": {"method": "GET", "url": "https://cdn.example.com/logo.png"}},
+            {"request": {"method": "GET", "url": "https://app.example.com/api/me"}},
+            {"request": {"method": "GET", "url": "https://auth.example.com/userinfo"}},
+            {"request": {"method": "OPTIONS", "url": "https://app.example.com/api/me"}},
+        ]));
+        let import = parse(&text, ImportOptions::default()).unwrap();
+
+        let urls: Vec<&str> = import.tools.iter().map(|t| t.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://app.example.com/api/me",
+                "https://auth.example.com/userinfo"
+            ]
+        );
+        assert!(import.globals.is_empty());
+        assert_eq!(
+            import.warnings,
+            [
+                "2 requests for pages and static assets were left out",
+                "OPTIONS requests are not supported",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_id() {
+        assert!(is_id("42"));
+        assert!(is_id("3f2b9c1d-5e6a-4b7c-8d9e-0a1b2c3d4e5f"));
+        assert!(is_id("507f1f77bcf86cd799439011"));
+        assert!(!is_id("users"));
+        assert!(!is_id("v1"));
+        assert!(!is_id("deadbeef"));
+    }
+
+    #[test]
+    fn test_rejects_other_documents() {
+        assert!(matches!(
+            parse("not json", ImportOptions::default()),
+            Err(HarImportError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            parse(r#"{"openapi": "3.0.0"}"#, ImportOptions::default()),
+            Err(HarImportError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            parse(&har(json!([])), ImportOptions::default()),
+            Err(HarImportError::NoRequests)
+        ));
+    }
+}
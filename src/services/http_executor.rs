@@ -10,6 +10,10 @@
 //! - Support for all standard HTTP methods (GET, POST, PUT, DELETE, PATCH)
 //! - Dynamic URL, header, and body rendering using the TypedVariableEngine
 //! - Configurable timeouts per tool
+//! - Incremental consumption of streamed responses (SSE, NDJSON, chunked text)
+//! - Failover and load balancing across several upstream base URLs
+//! - Calls forwarded to remote MCP servers for `MCP` and `MCP-SSE` tools
+//!   (see [`mcp_client`](crate::services::mcp_client))
 //! - Comprehensive error handling with typed errors
 //!
 //! # Example
@@ -39,12 +43,32 @@
 //! # }
 //! ```
 
+use crate::models::endpoint::EndpointConfig;
 use crate::models::tool::Tool;
+use crate::services::circuit_breaker::CircuitOpen;
+use crate::services::concurrency_limiter::ConcurrencyLimited;
+use crate::services::egress_client::EgressClient;
+use crate::services::egress_policy::{self, EgressBlocked, EgressPolicy, EgressResolver};
+use crate::services::endpoint_health::EndpointHealthRegistry;
+use crate::services::mcp_client::{self, McpTransport, RemoteServer};
+use crate::services::rate_limiter::RateLimited;
+use crate::services::stream_decoder::{StreamDecoder, StreamFormat};
 use crate::services::variable_engine::TypedVariableEngine;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Timeout applied when a tool does not define a positive `timeout_ms`
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+
+/// Timeout for establishing the TCP/TLS connection to the upstream
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Redirects followed before giving up, as reqwest's default policy
+const MAX_REDIRECTS: usize = 10;
 
 /// Errors that can occur during HTTP request execution
 #[derive(Debug, thiserror::Error)]
@@ -69,6 +93,21 @@ pub enum HttpExecutorError {
 
     #[error("Response body read failed: {0}")]
     ResponseBodyError(String),
+
+    #[error("Blocked by egress policy: {0}")]
+    EgressBlocked(EgressBlocked),
+
+    #[error("Rate limited: {0}")]
+    RateLimited(RateLimited),
+
+    #[error("Circuit open: {0}")]
+    CircuitOpen(CircuitOpen),
+
+    #[error("Concurrency limit reached: {0}")]
+    ConcurrencyLimited(ConcurrencyLimited),
+
+    #[error("MCP request failed: {0}")]
+    McpFailed(String),
 }
 
 /// Result of executing an HTTP request
@@ -86,6 +125,41 @@ pub struct ExecutionResult {
     pub is_success: bool,
     /// Equivalent cURL command for debugging
     pub curl_command: String,
+    /// Base URL that served the request, when the tool has several
+    pub endpoint: Option<String>,
+    /// Base URLs that failed before the one that served the request
+    pub failovers: Vec<Failover>,
+}
+
+/// A base URL given up on in favour of the next one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failover {
+    pub endpoint: String,
+    /// Failover status code, or the connection error
+    pub reason: String,
+}
+
+/// Response of the base URL that answered, with how the request was sent
+struct SentRequest {
+    response: reqwest::Response,
+    curl_command: String,
+    timeout: Duration,
+    endpoint: Option<String>,
+    failovers: Vec<Failover>,
+}
+
+/// One decoded message of a streamed upstream response
+///
+/// Emitted by [`HttpExecutor::execute_tool_streaming`] as soon as a complete
+/// SSE event, NDJSON line or text chunk has been received.
+#[derive(Debug, Clone)]
+pub struct StreamChunk {
+    /// 1-based position of this message in the stream
+    pub sequence: u64,
+    /// Total response bytes received so far
+    pub bytes_received: u64,
+    /// Decoded message payload
+    pub data: String,
 }
 
 /// HTTP request executor with template rendering
@@ -101,6 +175,8 @@ pub struct ExecutionResult {
 pub struct HttpExecutor {
     client: reqwest::Client,
     engine: TypedVariableEngine,
+    health: EndpointHealthRegistry,
+    policy: EgressPolicy,
 }
 
 impl Default for HttpExecutor {
@@ -112,8 +188,11 @@ impl Default for HttpExecutor {
 impl HttpExecutor {
     /// Creates a new HttpExecutor with default configuration
     ///
-    /// Initializes an HTTP client with a 30-second default timeout and
-    /// a TypedVariableEngine for parameter substitution.
+    /// Initializes an HTTP client with a connect timeout and a
+    /// TypedVariableEngine for parameter substitution. The overall request
+    /// timeout is taken from each tool's `timeout_ms` (30 seconds when unset),
+    /// so long-running and streaming tools are not capped by a client-wide limit.
+    /// Requests are subject to the global [`EgressPolicy`].
     ///
     /// # Examples
     ///
@@ -123,17 +202,105 @@ impl HttpExecutor {
     /// let executor = HttpExecutor::new();
     /// ```
     pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
+        Self::with_policy(EgressPolicy::global())
+    }
+
+    fn with_policy(policy: EgressPolicy) -> Self {
+        let client = Self::client_builder(&policy)
             .build()
             .unwrap_or_else(|_| reqwest::Client::new());
 
         Self {
             client,
             engine: TypedVariableEngine::new(),
+            health: EndpointHealthRegistry::shared(),
+            policy,
         }
     }
 
+    /// Client builder with the executor's defaults
+    ///
+    /// Sets the connect timeout and enforces `policy` on resolved addresses
+    /// and redirects. Clients customized per server (see
+    /// [`EgressClientCache`]) start from this builder.
+    ///
+    /// [`EgressClientCache`]: crate::services::EgressClientCache
+    pub fn client_builder(policy: &EgressPolicy) -> reqwest::ClientBuilder {
+        let redirect_policy = policy.clone();
+        reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .dns_resolver(Arc::new(EgressResolver::new(policy.clone())))
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    return attempt.error("too many redirects");
+                }
+                // Host names are checked by the resolver when connecting
+                match attempt
+                    .url()
+                    .host_str()
+                    .map(|host| redirect_policy.check_host_literal(host))
+                {
+                    Some(Err(blocked)) => attempt.error(blocked),
+                    _ => attempt.follow(),
+                }
+            }))
+    }
+
+    /// Uses `policy` instead of the global egress policy
+    pub fn with_egress_policy(self, policy: EgressPolicy) -> Self {
+        Self {
+            health: self.health,
+            ..Self::with_policy(policy)
+        }
+    }
+
+    /// Sends requests through `client`, built from
+    /// [`client_builder`](Self::client_builder) with the same `policy`
+    pub fn with_client(mut self, client: reqwest::Client, policy: EgressPolicy) -> Self {
+        self.client = client;
+        self.policy = policy;
+        self
+    }
+
+    /// Sends requests through a server's client, see [`EgressClientCache`]
+    ///
+    /// [`EgressClientCache`]: crate::services::EgressClientCache
+    pub fn with_egress_client(mut self, egress: EgressClient) -> Self {
+        self.client = egress.client;
+        self.policy = egress.policy;
+        self
+    }
+
+    /// Uses `health` instead of the process-wide endpoint health registry
+    pub fn with_health_registry(mut self, health: EndpointHealthRegistry) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Host the tool's request is sent to, before any base URL failover
+    pub fn target_host(&self, tool: &Tool, params: &HashMap<String, Value>) -> Option<String> {
+        let url = self.render_url(tool.url.as_deref(), params).ok()?;
+        let url = reqwest::Url::parse(&url).ok()?;
+        url.host_str().map(str::to_ascii_lowercase)
+    }
+
+    /// URL the tool's request is sent to, before any base URL failover
+    pub fn target_url(&self, tool: &Tool, params: &HashMap<String, Value>) -> Option<String> {
+        self.render_url(tool.url.as_deref(), params).ok()
+    }
+
+    /// Host and explicit port the tool's request is sent to, before any base
+    /// URL failover
+    pub fn target_authority(&self, tool: &Tool, params: &HashMap<String, Value>) -> Option<String> {
+        let url = self.render_url(tool.url.as_deref(), params).ok()?;
+        let url = reqwest::Url::parse(&url).ok()?;
+        let host = url.host_str()?.to_ascii_lowercase();
+        Some(match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        })
+    }
+
     fn render_url(
         &self,
         template_opt: Option<&str>,
@@ -265,24 +432,213 @@ impl HttpExecutor {
         curl_parts.join(" \\\n  ")
     }
 
+    fn tool_timeout(tool: &Tool) -> Duration {
+        if tool.timeout_ms > 0 {
+            Duration::from_millis(tool.timeout_ms as u64)
+        } else {
+            Duration::from_millis(DEFAULT_TIMEOUT_MS)
+        }
+    }
+
+    fn response_headers(response: &reqwest::Response) -> HashMap<String, String> {
+        response
+            .headers()
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+            .collect()
+    }
+
+    fn body_error(e: reqwest::Error, timeout: Duration) -> HttpExecutorError {
+        if e.is_timeout() {
+            HttpExecutorError::Timeout(timeout.as_millis() as u64)
+        } else {
+            HttpExecutorError::ResponseBodyError(e.to_string())
+        }
+    }
+
+    /// Renders the tool templates and builds the outgoing request
+    ///
+    /// Returns the request, its equivalent cURL command and the timeout
+    /// that applies to it.
+    fn build_request(
+        &self,
+        tool: &Tool,
+        params: &HashMap<String, Value>,
+        base_url: Option<&str>,
+    ) -> Result<(reqwest::Request, String, Duration), HttpExecutorError> {
+        let mut url = self.render_url(tool.url.as_deref(), params)?;
+        if let Some(base_url) = base_url {
+            url = EndpointConfig::rebase_url(&url, base_url);
+        }
+        let headers = self.render_headers(tool.headers.as_deref(), params)?;
+        let body = self.render_body(tool.body.as_deref(), params)?;
+
+        let method = reqwest::Method::from_bytes(tool.method.as_bytes())
+            .map_err(|_| HttpExecutorError::InvalidMethod(tool.method.clone()))?;
+
+        let timeout = Self::tool_timeout(tool);
+
+        // Generate cURL command for debugging
+        let curl_command = self.generate_curl_command(&tool.method, &url, &headers, &body);
+
+        let mut request_builder = self.client.request(method, &url).headers(headers);
+
+        if let Some(body_content) = &body {
+            request_builder = request_builder.body(body_content.clone());
+        }
+
+        let request = request_builder
+            .timeout(timeout)
+            .build()
+            .map_err(HttpExecutorError::RequestFailed)?;
+
+        Ok((request, curl_command, timeout))
+    }
+
+    /// True for errors where no response was received from the endpoint
+    fn is_connection_error(error: &HttpExecutorError) -> bool {
+        matches!(error, HttpExecutorError::RequestFailed(e) if e.is_connect())
+    }
+
+    /// Sends the tool request, failing over between its base URLs
+    ///
+    /// Tools without an endpoint configuration are sent once to their URL.
+    /// Otherwise the base URLs are tried in the order chosen by the health
+    /// registry; the next one is tried on connection errors and on the
+    /// configured failover status codes. The last response (or error) is
+    /// returned when every endpoint failed.
+    ///
+    /// Returns the response with its cURL command, its timeout, the base
+    /// URL that produced it and the ones that failed before.
+    async fn send_with_failover(
+        &self,
+        tool: &Tool,
+        params: &HashMap<String, Value>,
+    ) -> Result<SentRequest, HttpExecutorError> {
+        let Some(config) = tool.endpoint_config() else {
+            let (request, curl_command, timeout) = self.build_request(tool, params, None)?;
+            let response = self.send(request, timeout).await?;
+            return Ok(SentRequest {
+                response,
+                curl_command,
+                timeout,
+                endpoint: None,
+                failovers: Vec::new(),
+            });
+        };
+
+        let endpoints = self.health.order(&config);
+        let last = endpoints.len().saturating_sub(1);
+        let mut failovers = Vec::new();
+
+        for (attempt, base_url) in endpoints.iter().enumerate() {
+            let (request, curl_command, timeout) =
+                self.build_request(tool, params, Some(base_url))?;
+            let started = Instant::now();
+
+            match self.send(request, timeout).await {
+                Ok(response) if config.should_fail_over(response.status().as_u16()) => {
+                    self.health.record_failure(base_url);
+                    if attempt < last {
+                        tracing::warn!(
+                            endpoint = %base_url,
+                            status = response.status().as_u16(),
+                            "Upstream endpoint failed, trying next"
+                        );
+                        failovers.push(Failover {
+                            endpoint: base_url.clone(),
+                            reason: format!("HTTP {}", response.status().as_u16()),
+                        });
+                        continue;
+                    }
+                    return Ok(SentRequest {
+                        response,
+                        curl_command,
+                        timeout,
+                        endpoint: Some(base_url.clone()),
+                        failovers,
+                    });
+                }
+                Ok(response) => {
+                    self.health.record_success(base_url, started.elapsed());
+                    return Ok(SentRequest {
+                        response,
+                        curl_command,
+                        timeout,
+                        endpoint: Some(base_url.clone()),
+                        failovers,
+                    });
+                }
+                Err(e) if Self::is_connection_error(&e) => {
+                    self.health.record_failure(base_url);
+                    if attempt < last {
+                        tracing::warn!(
+                            endpoint = %base_url,
+                            error = %e,
+                            "Upstream endpoint unreachable, trying next"
+                        );
+                        failovers.push(Failover {
+                            endpoint: base_url.clone(),
+                            reason: e.to_string(),
+                        });
+                        continue;
+                    }
+                    return Err(e);
+                }
+                Err(e) => {
+                    if matches!(e, HttpExecutorError::Timeout(_)) {
+                        self.health.record_failure(base_url);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Err(HttpExecutorError::InvalidUrl(
+            "No base URL configured".to_string(),
+        ))
+    }
+
+    async fn send(
+        &self,
+        request: reqwest::Request,
+        timeout: Duration,
+    ) -> Result<reqwest::Response, HttpExecutorError> {
+        self.policy
+            .check_url(request.url())
+            .await
+            .map_err(HttpExecutorError::EgressBlocked)?;
+
+        self.client.execute(request).await.map_err(|e| {
+            if let Some(blocked) = egress_policy::find_blocked(&e) {
+                HttpExecutorError::EgressBlocked(blocked)
+            } else if e.is_timeout() && !e.is_connect() {
+                HttpExecutorError::Timeout(timeout.as_millis() as u64)
+            } else {
+                HttpExecutorError::RequestFailed(e)
+            }
+        })
+    }
+
     async fn format_response(
         &self,
-        response: reqwest::Response,
-        curl_command: String,
+        sent: SentRequest,
     ) -> Result<ExecutionResult, HttpExecutorError> {
+        let SentRequest {
+            response,
+            curl_command,
+            timeout,
+            endpoint,
+            failovers,
+        } = sent;
         let status = response.status().as_u16();
         let is_success = response.status().is_success();
-
-        let headers: HashMap<String, String> = response
-            .headers()
-            .iter()
-            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
-            .collect();
+        let headers = Self::response_headers(&response);
 
         let body = response
             .text()
             .await
-            .map_err(|e| HttpExecutorError::ResponseBodyError(e.to_string()))?;
+            .map_err(|e| Self::body_error(e, timeout))?;
 
         Ok(ExecutionResult {
             status,
@@ -290,6 +646,127 @@ impl HttpExecutor {
             headers,
             is_success,
             curl_command,
+            endpoint,
+            failovers,
+        })
+    }
+
+    /// Rendered URL and headers of a request to a remote MCP server
+    async fn mcp_target(
+        &self,
+        url: Option<&str>,
+        headers: Option<&str>,
+        params: &HashMap<String, Value>,
+    ) -> Result<(String, HeaderMap), HttpExecutorError> {
+        let url = self.render_url(url, params)?;
+        let parsed =
+            reqwest::Url::parse(&url).map_err(|e| HttpExecutorError::InvalidUrl(e.to_string()))?;
+        self.policy
+            .check_url(&parsed)
+            .await
+            .map_err(HttpExecutorError::EgressBlocked)?;
+
+        let headers = self.render_headers(headers, params)?;
+        Ok((url, headers))
+    }
+
+    /// Lists the tools of the remote MCP server at `url`
+    ///
+    /// `url` and `headers` are templates like a tool's, rendered with
+    /// `params`. The listing is bounded by the default tool timeout.
+    pub async fn list_mcp_tools(
+        &self,
+        transport: McpTransport,
+        url: &str,
+        headers: Option<&str>,
+        params: &HashMap<String, Value>,
+    ) -> Result<RemoteServer, HttpExecutorError> {
+        let (url, headers) = self.mcp_target(Some(url), headers, params).await?;
+
+        let timeout = Duration::from_millis(DEFAULT_TIMEOUT_MS);
+        let listing = async {
+            let session =
+                mcp_client::connect(self.client.clone(), headers, transport, &url).await?;
+            let server = mcp_client::list_tools(&session).await;
+            if let Err(e) = session.cancel().await {
+                tracing::debug!("Failed to close MCP session: {}", e);
+            }
+            server
+        };
+        tokio::time::timeout(timeout, listing)
+            .await
+            .map_err(|_| HttpExecutorError::Timeout(timeout.as_millis() as u64))?
+            .map_err(HttpExecutorError::McpFailed)
+    }
+
+    /// Calls the remote tool of an `MCP` or `MCP-SSE` tool
+    ///
+    /// The remote result is returned as JSON in the body, with status 200.
+    /// `is_success` is false when the remote tool reported an error or the
+    /// remote server rejected the call, whose error then becomes the text of
+    /// an error result. The tool's `timeout_ms` bounds the whole exchange,
+    /// from connecting until the remote tool answered.
+    async fn execute_mcp_tool(
+        &self,
+        tool: &Tool,
+        params: &HashMap<String, Value>,
+        transport: McpTransport,
+    ) -> Result<ExecutionResult, HttpExecutorError> {
+        let (url, headers) = self
+            .mcp_target(tool.url.as_deref(), tool.headers.as_deref(), params)
+            .await?;
+        let request = mcp_client::call_request(&self.engine, tool.body.as_deref(), params)
+            .map_err(HttpExecutorError::TemplateError)?;
+        let curl_command = self.generate_curl_command(
+            "POST",
+            &url,
+            &headers,
+            &Some(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tools/call",
+                    "params": request,
+                })
+                .to_string(),
+            ),
+        );
+
+        let timeout = Self::tool_timeout(tool);
+        let call = async {
+            let session = mcp_client::connect(self.client.clone(), headers, transport, &url)
+                .await
+                .map_err(HttpExecutorError::McpFailed)?;
+            let result = session.call_tool(request).await;
+            if let Err(e) = session.cancel().await {
+                tracing::debug!("Failed to close MCP session: {}", e);
+            }
+            Ok::<_, HttpExecutorError>(result)
+        };
+        let result = tokio::time::timeout(timeout, call)
+            .await
+            .map_err(|_| HttpExecutorError::Timeout(timeout.as_millis() as u64))??;
+
+        let result = match result {
+            Ok(result) => result,
+            Err(rmcp::ServiceError::McpError(error)) => {
+                rmcp::model::CallToolResult::error(vec![rmcp::model::Content::text(format!(
+                    "MCP error {}: {}",
+                    error.code.0, error.message
+                ))])
+            }
+            Err(e) => return Err(HttpExecutorError::McpFailed(e.to_string())),
+        };
+
+        Ok(ExecutionResult {
+            status: 200,
+            body: serde_json::to_string(&result)
+                .map_err(|e| HttpExecutorError::ResponseBodyError(e.to_string()))?,
+            headers: HashMap::new(),
+            is_success: result.is_error != Some(true),
+            curl_command,
+            endpoint: None,
+            failovers: Vec::new(),
         })
     }
 
@@ -302,6 +779,8 @@ impl HttpExecutor {
     /// 4. Builds and executes the HTTP request with the tool's timeout
     /// 5. Formats and returns the response
     ///
+    /// `MCP` and `MCP-SSE` tools call their remote MCP tool instead.
+    ///
     /// # Arguments
     ///
     /// * `tool` - The Tool containing HTTP method, URL, headers, body templates, and timeout
@@ -318,6 +797,8 @@ impl HttpExecutor {
     ///   - `InvalidHeaders` - Header parsing or rendering failed
     ///   - `RequestFailed` - Network or HTTP error
     ///   - `ResponseBodyError` - Failed to read response body
+    ///   - `EgressBlocked` - Destination refused by the egress policy
+    ///   - `McpFailed` - Remote MCP server unreachable or failed to answer
     ///
     /// # Examples
     ///
@@ -349,37 +830,109 @@ impl HttpExecutor {
         tool: &Tool,
         params: &HashMap<String, Value>,
     ) -> Result<ExecutionResult, HttpExecutorError> {
-        let url = self.render_url(tool.url.as_deref(), params)?;
-        let headers = self.render_headers(tool.headers.as_deref(), params)?;
-        let body = self.render_body(tool.body.as_deref(), params)?;
+        if let Some(transport) = McpTransport::from_method(&tool.method) {
+            return self.execute_mcp_tool(tool, params, transport).await;
+        }
+        let sent = self.send_with_failover(tool, params).await?;
+        self.format_response(sent).await
+    }
 
-        let method = reqwest::Method::from_bytes(tool.method.as_bytes())
-            .map_err(|_| HttpExecutorError::InvalidMethod(tool.method.clone()))?;
+    /// Executes a streaming tool, consuming the response body incrementally
+    ///
+    /// Behaves like [`execute_tool`](Self::execute_tool) but reads the body
+    /// chunk by chunk. Each complete message (SSE event, NDJSON line or text
+    /// chunk, depending on the response `Content-Type`) is sent to `progress`
+    /// as it arrives. The returned [`ExecutionResult`] carries the aggregated
+    /// messages as its body.
+    ///
+    /// The tool's `timeout_ms` bounds the whole exchange, from connecting
+    /// until the last byte of the stream has been read.
+    ///
+    /// Error responses (non-2xx) are not streamed: their body is read in one
+    /// piece and returned unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool` - The Tool to execute
+    /// * `params` - Parameter values to substitute into templates
+    /// * `progress` - Receives decoded messages in order; send failures
+    ///   (receiver dropped) are ignored
+    ///
+    /// # Errors
+    ///
+    /// Same as [`execute_tool`](Self::execute_tool).
+    pub async fn execute_tool_streaming(
+        &self,
+        tool: &Tool,
+        params: &HashMap<String, Value>,
+        progress: UnboundedSender<StreamChunk>,
+    ) -> Result<ExecutionResult, HttpExecutorError> {
+        if let Some(transport) = McpTransport::from_method(&tool.method) {
+            return self.execute_mcp_tool(tool, params, transport).await;
+        }
+        let timeout = Self::tool_timeout(tool);
+        let deadline = tokio::time::Instant::now() + timeout;
 
-        let timeout = Duration::from_millis(tool.timeout_ms as u64);
+        let sent = tokio::time::timeout_at(deadline, self.send_with_failover(tool, params))
+            .await
+            .map_err(|_| HttpExecutorError::Timeout(timeout.as_millis() as u64))??;
 
-        // Generate cURL command for debugging
-        let curl_command = self.generate_curl_command(&tool.method, &url, &headers, &body);
+        if !sent.response.status().is_success() {
+            return self.format_response(sent).await;
+        }
+        let SentRequest {
+            response,
+            curl_command,
+            timeout,
+            endpoint,
+            failovers,
+        } = sent;
 
-        let mut request_builder = self.client.request(method, &url).headers(headers);
+        let status = response.status().as_u16();
+        let headers = Self::response_headers(&response);
+        let format =
+            StreamFormat::from_content_type(headers.get("content-type").map(|s| s.as_str()));
+
+        let mut decoder = StreamDecoder::new(format);
+        let mut response = response;
+        let mut bytes_received: u64 = 0;
+        let mut sequence: u64 = 0;
+
+        let mut emit = |messages: Vec<String>, bytes_received: u64| {
+            for data in messages {
+                sequence += 1;
+                let _ = progress.send(StreamChunk {
+                    sequence,
+                    bytes_received,
+                    data,
+                });
+            }
+        };
 
-        if let Some(body_content) = &body {
-            request_builder = request_builder.body(body_content.clone());
-        }
+        loop {
+            let chunk = tokio::time::timeout_at(deadline, response.chunk())
+                .await
+                .map_err(|_| HttpExecutorError::Timeout(timeout.as_millis() as u64))?
+                .map_err(|e| Self::body_error(e, timeout))?;
 
-        let request = request_builder
-            .timeout(timeout)
-            .build()
-            .map_err(HttpExecutorError::RequestFailed)?;
+            let Some(bytes) = chunk else {
+                break;
+            };
 
-        let response = self.client.execute(request).await.map_err(|e| {
-            if e.is_timeout() {
-                HttpExecutorError::Timeout(timeout.as_millis() as u64)
-            } else {
-                HttpExecutorError::RequestFailed(e)
-            }
-        })?;
+            bytes_received += bytes.len() as u64;
+            emit(decoder.push(&bytes), bytes_received);
+        }
 
-        self.format_response(response, curl_command).await
+        emit(decoder.finish(), bytes_received);
+
+        Ok(ExecutionResult {
+            status,
+            body: decoder.aggregate(),
+            headers,
+            is_success: true,
+            curl_command,
+            endpoint,
+            failovers,
+        })
     }
 }
@@ -46,6 +46,77 @@
 //! 2. **server** - Server-wide defaults from server_globals table
 //! 3. **exposed** - Runtime values provided by the LLM
 //!
+//! # Elicitation
+//!
+//! Exposed parameters that are missing or don't fit their type or allowed
+//! values fail the call with `INVALID_PARAMS`. When the client declared the
+//! `elicitation` capability, the user is first asked for them with an
+//! `elicitation/create` request, and the call continues with the answer.
+//!
+//! # Remote MCP Tools
+//!
+//! Tools with the `MCP` or `MCP-SSE` method forward their calls to a tool of
+//! a remote MCP server (see [`mcp_client`](crate::services::mcp_client)),
+//! behind the same access control, limits, circuit breakers and history as
+//! HTTP tools. The remote tool result is returned unchanged.
+//!
+//! # Streaming Tools
+//!
+//! Tools marked as `streaming` are consumed incrementally. Each decoded
+//! message of the upstream stream is forwarded to the client as a
+//! `notifications/progress` message (when the client supplied a progress
+//! token), and the aggregated messages form the final tool result.
+//!
+//! # Multiple Endpoints
+//!
+//! Tools (or their toolkit) may declare several base URLs. The HttpExecutor
+//! fails over between them and the base URL that served the request is
+//! stored in the execution history.
+//!
+//! # Server Network Settings
+//!
+//! When the server has a proxy, CA bundle or client certificate configured,
+//! requests are sent with a client built from those settings and cached per
+//! server by [`EgressClientCache`]. A server whose settings cannot be loaded
+//! fails its calls rather than bypassing its proxy.
+//!
+//! # Egress Policy
+//!
+//! Requests refused by the egress policy (SSRF protection) fail with an
+//! `INVALID_REQUEST` error and are recorded with the `blocked` status.
+//!
+//! # Rate Limits
+//!
+//! Before a request is sent, a token is taken from the rate limits of the
+//! tool, of its toolkit (per upstream host) and of the server, as well as
+//! any pause requested by the upstream host through its rate-limit headers
+//! (see [`RateLimiter`]). Calls either wait for their turn, with the wait
+//! stored in the execution history, or fail with an `INVALID_REQUEST` error
+//! and are recorded with the `throttled` status.
+//!
+//! # Concurrency Limits
+//!
+//! Servers and tool instances may bound how many of their calls run at
+//! once (see [`ConcurrencyLimiter`]). Calls over the limit wait in a bounded
+//! queue; the time spent there is stored in the execution history apart
+//! from the upstream latency. Calls that find the queue full or time out in
+//! it fail with an `INVALID_REQUEST` error and are recorded with the
+//! `throttled` status.
+//!
+//! # Circuit Breakers
+//!
+//! Calls to an upstream (or a tool, see [`CircuitBreakerRegistry`]) that
+//! keeps failing are short-circuited: while its circuit is open, calls fail
+//! immediately with an `INTERNAL_ERROR` error instead of waiting for the
+//! upstream, and are recorded with the `error` status.
+//!
+//! # Cancellation
+//!
+//! Calls made through [`InstanceExecutor::execute_with_context`] are raced
+//! against the context's cancellation token. When the client cancels, the
+//! upstream request is dropped (aborting the connection) and the execution
+//! is recorded with the `cancelled` status.
+//!
 //! # Integration
 //!
 //! Used by SaraMcpService to create dynamic tool handlers. Each InstanceExecutor
@@ -74,16 +145,94 @@
 //! }
 //! ```
 
+use crate::models::concurrency_limit::ConcurrencyLimit;
+use crate::models::instance::ToolInstance;
+use crate::models::rate_limit::RateLimitConfig;
+use crate::models::server_network::ServerNetworkSettings;
 use crate::models::tool::Tool;
+use crate::services::circuit_breaker::CircuitBreakerRegistry;
+use crate::services::concurrency_limiter::{ConcurrencyLimiter, ConcurrencyRule};
+use crate::services::egress_client::EgressClientCache;
+use crate::services::execution_context::ExecutionContext;
 use crate::services::execution_tracker::{ExecutionStatus, ExecutionTracker};
-use crate::services::http_executor::HttpExecutor;
-use crate::services::parameter_resolver::ParameterResolver;
+use crate::services::http_executor::{
+    ExecutionResult, HttpExecutor, HttpExecutorError, StreamChunk,
+};
+use crate::services::mcp_client::McpTransport;
+use crate::services::parameter_resolver::{ParameterResolver, UnresolvedParameters};
+use crate::services::rate_limiter::{RateLimitRule, RateLimiter};
+use crate::services::schema_generator::SchemaGenerator;
 use crate::services::secrets_manager::SecretsManager;
-use rmcp::model::{CallToolResult, Content};
+use rmcp::model::{
+    CallToolResult, Content, CreateElicitationRequestParam, ElicitationAction, LoggingLevel,
+};
+use serde_json::json;
 use sqlx::SqlitePool;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use time::OffsetDateTime;
 
+/// Replaces secret values in `text`, also in their URL-encoded form
+fn redact(text: &str, secrets: &[String]) -> String {
+    let mut redacted = text.to_string();
+    for secret in secrets.iter().filter(|s| !s.is_empty()) {
+        redacted = redacted.replace(secret.as_str(), "[REDACTED]");
+        let encoded = urlencoding::encode(secret);
+        if encoded != secret.as_str() {
+            redacted = redacted.replace(encoded.as_ref(), "[REDACTED]");
+        }
+    }
+    redacted
+}
+
+/// Replaces secret values in every string of a JSON value
+fn redact_json(value: &mut serde_json::Value, secrets: &[String]) {
+    match value {
+        serde_json::Value::String(text) => *text = redact(text, secrets),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_json(item, secrets);
+            }
+        }
+        serde_json::Value::Object(fields) => {
+            for field in fields.values_mut() {
+                redact_json(field, secrets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Text contents of a tool result, one per line
+pub(crate) fn result_text(result: &CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|content| content.as_text().map(|t| t.text.as_str()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Time a call spent waiting before its request was sent
+#[derive(Debug, Clone, Copy, Default)]
+struct Waits {
+    /// For a concurrency slot
+    queued: Duration,
+    /// For its rate limits
+    throttled: Duration,
+}
+
+impl Waits {
+    fn queued_ms(&self) -> Option<u64> {
+        (!self.queued.is_zero()).then_some(self.queued.as_millis() as u64)
+    }
+
+    fn throttled_ms(&self) -> Option<u64> {
+        (!self.throttled.is_zero()).then_some(self.throttled.as_millis() as u64)
+    }
+}
+
 #[derive(Clone)]
 pub struct InstanceExecutor {
     pool: SqlitePool,
@@ -93,6 +242,11 @@ pub struct InstanceExecutor {
     http_executor: HttpExecutor,
     resolver: ParameterResolver,
     tracker: ExecutionTracker,
+    secrets: SecretsManager,
+    egress: EgressClientCache,
+    rate_limiter: RateLimiter,
+    circuit_breakers: CircuitBreakerRegistry,
+    concurrency: ConcurrencyLimiter,
 }
 
 impl InstanceExecutor {
@@ -123,11 +277,34 @@ impl InstanceExecutor {
             instance_id,
             tool,
             http_executor: HttpExecutor::new(),
-            resolver: ParameterResolver::new(secrets),
+            resolver: ParameterResolver::new(secrets.clone()),
             tracker,
+            secrets,
+            egress: EgressClientCache::shared(),
+            rate_limiter: RateLimiter::shared(),
+            circuit_breakers: CircuitBreakerRegistry::shared(),
+            concurrency: ConcurrencyLimiter::shared(),
         }
     }
 
+    /// Uses `rate_limiter` instead of the process-wide rate limiter
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Uses `circuit_breakers` instead of the process-wide circuit breakers
+    pub fn with_circuit_breakers(mut self, circuit_breakers: CircuitBreakerRegistry) -> Self {
+        self.circuit_breakers = circuit_breakers;
+        self
+    }
+
+    /// Uses `concurrency` instead of the process-wide concurrency limiter
+    pub fn with_concurrency_limiter(mut self, concurrency: ConcurrencyLimiter) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
     /// Execute the tool with the given parameters
     ///
     /// Performs complete tool execution: parameter resolution, HTTP request,
@@ -167,63 +344,215 @@ impl InstanceExecutor {
         &self,
         llm_params: Option<serde_json::Map<String, serde_json::Value>>,
     ) -> Result<CallToolResult, rmcp::ErrorData> {
-        let started_at = OffsetDateTime::now_utc();
+        self.execute_with_context(llm_params, &ExecutionContext::default())
+            .await
+    }
 
-        // Convert LLM params for tracking
-        let input_params_for_tracking = llm_params.as_ref().map(|m| {
-            m.iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect::<HashMap<String, serde_json::Value>>()
-        });
+    /// Execute the tool on behalf of a connected MCP client
+    ///
+    /// Same as [`execute`](Self::execute), with access to the calling client
+    /// through `context`. Streaming tools report each decoded message of the
+    /// upstream response as a progress notification, and the upstream request
+    /// is aborted if the context is cancelled before it completes.
+    pub async fn execute_with_context(
+        &self,
+        llm_params: Option<serde_json::Map<String, serde_json::Value>>,
+        context: &ExecutionContext,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let started_at = OffsetDateTime::now_utc();
 
         let llm_map = llm_params.map(|m| {
             m.into_iter()
                 .collect::<HashMap<String, serde_json::Value>>()
         });
 
-        // Resolve parameters
-        let resolved = self
-            .resolver
-            .resolve_parameters(&self.pool, self.instance_id, llm_map)
-            .await
-            .map_err(|e| rmcp::ErrorData {
-                code: rmcp::model::ErrorCode::INVALID_PARAMS,
-                message: format!("Parameter resolution failed: {}", e).into(),
-                data: None,
-            })?;
+        // Resolve parameters, with any values the user provided on request.
+        // Those are tracked along with the LLM's.
+        let (input_params_for_tracking, resolved) = self.resolve(llm_map, context).await?;
 
-        // Execute HTTP request
-        let response = self
-            .http_executor
-            .execute_tool(&self.tool, &resolved)
-            .await
-            .map_err(|e| rmcp::ErrorData {
+        let tool = self.tool_with_endpoints().await;
+        let http_executor = self.http_executor_for_server().await?;
+        self.log_request(context, &http_executor, &tool, &resolved)
+            .await;
+
+        // Execute HTTP request, aborting it if the client cancels the call.
+        // Dropping the request future drops the in-flight reqwest response,
+        // including any remaining failover attempts.
+        let sent_at = Instant::now();
+        let outcome = tokio::select! {
+            biased;
+            _ = context.cancellation().cancelled() => None,
+            result = self.guarded_send(&http_executor, &tool, &resolved, context) => Some(result),
+        };
+
+        let completed_at = OffsetDateTime::now_utc();
+        self.log_outcome(context, outcome.as_ref(), sent_at.elapsed())
+            .await;
+
+        let Some(result) = outcome else {
+            tracing::info!(
+                instance_id = self.instance_id,
+                "Tool call cancelled by client"
+            );
+            self.record_unsent(
+                ExecutionStatus::Cancelled,
+                "Cancelled by client".to_string(),
+                started_at,
+                completed_at,
+                input_params_for_tracking,
+                Duration::ZERO,
+            )
+            .await;
+            return Err(rmcp::ErrorData {
                 code: rmcp::model::ErrorCode::INTERNAL_ERROR,
-                message: format!("HTTP execution failed: {}", e).into(),
+                message: "Request cancelled".into(),
                 data: None,
-            })?;
+            });
+        };
 
-        let completed_at = OffsetDateTime::now_utc();
+        if let Err(HttpExecutorError::EgressBlocked(blocked)) = &result {
+            tracing::warn!(
+                instance_id = self.instance_id,
+                destination = %blocked,
+                "Tool call blocked by egress policy"
+            );
+            let message = format!("Blocked by egress policy: {}", blocked);
+            self.record_unsent(
+                ExecutionStatus::Blocked,
+                message.clone(),
+                started_at,
+                completed_at,
+                input_params_for_tracking,
+                Duration::ZERO,
+            )
+            .await;
+            return Err(rmcp::ErrorData {
+                code: rmcp::model::ErrorCode::INVALID_REQUEST,
+                message: message.into(),
+                data: None,
+            });
+        }
+
+        if let Err(HttpExecutorError::RateLimited(limited)) = &result {
+            tracing::warn!(
+                instance_id = self.instance_id,
+                limit = %limited,
+                "Tool call throttled by rate limit"
+            );
+            let message = format!("Rate limited: {}", limited);
+            self.record_unsent(
+                ExecutionStatus::Throttled,
+                message.clone(),
+                started_at,
+                completed_at,
+                input_params_for_tracking,
+                Duration::ZERO,
+            )
+            .await;
+            return Err(rmcp::ErrorData {
+                code: rmcp::model::ErrorCode::INVALID_REQUEST,
+                message: message.into(),
+                data: Some(serde_json::json!({
+                    "retry_after_ms": limited.retry_after.as_millis() as u64,
+                })),
+            });
+        }
+
+        if let Err(HttpExecutorError::ConcurrencyLimited(limited)) = &result {
+            tracing::warn!(
+                instance_id = self.instance_id,
+                limit = %limited,
+                "Tool call refused by concurrency limit"
+            );
+            let message = format!("Concurrency limit reached: {}", limited);
+            self.record_unsent(
+                ExecutionStatus::Throttled,
+                message.clone(),
+                started_at,
+                completed_at,
+                input_params_for_tracking,
+                limited.queued,
+            )
+            .await;
+            return Err(rmcp::ErrorData {
+                code: rmcp::model::ErrorCode::INVALID_REQUEST,
+                message: message.into(),
+                data: Some(serde_json::json!({
+                    "max_concurrent": limited.max_concurrent,
+                    "queued_ms": limited.queued.as_millis() as u64,
+                })),
+            });
+        }
+
+        if let Err(HttpExecutorError::CircuitOpen(open)) = &result {
+            tracing::warn!(
+                instance_id = self.instance_id,
+                circuit = %open.key,
+                "Tool call short-circuited"
+            );
+            let message = format!("Circuit open: {}", open);
+            self.record_unsent(
+                ExecutionStatus::Error,
+                message.clone(),
+                started_at,
+                completed_at,
+                input_params_for_tracking,
+                Duration::ZERO,
+            )
+            .await;
+            return Err(rmcp::ErrorData {
+                code: rmcp::model::ErrorCode::INTERNAL_ERROR,
+                message: message.into(),
+                data: Some(serde_json::json!({
+                    "circuit": open.key,
+                    "state": open.state.as_str(),
+                    "retry_after_ms": open.retry_after.as_millis() as u64,
+                })),
+            });
+        }
+
+        let (response, waits, sent_at) = result.map_err(|e| rmcp::ErrorData {
+            code: rmcp::model::ErrorCode::INTERNAL_ERROR,
+            message: format!("HTTP execution failed: {}", e).into(),
+            data: None,
+        })?;
+
+        // Remote MCP tools answer with their tool result as JSON
+        let remote = if self.forwards_to_mcp() {
+            Some(
+                serde_json::from_str::<CallToolResult>(&response.body).map_err(|e| {
+                    rmcp::ErrorData {
+                        code: rmcp::model::ErrorCode::INTERNAL_ERROR,
+                        message: format!("Invalid MCP tool result: {}", e).into(),
+                        data: None,
+                    }
+                })?,
+            )
+        } else {
+            None
+        };
 
         // Determine execution status
         let status = ExecutionStatus::from_result(response.is_success);
-        let error_message = if response.is_success {
-            None
-        } else {
-            Some(format!("HTTP {} - {}", response.status, response.body))
+        let error_message = match &remote {
+            _ if response.is_success => None,
+            Some(result) => Some(format!("MCP tool error - {}", result_text(result))),
+            None => Some(format!("HTTP {} - {}", response.status, response.body)),
         };
 
-        // Track execution (log errors but don't fail the execution)
+        // Track execution (log errors but don't fail the execution). The
+        // duration is the upstream latency, the waits before sending are
+        // recorded apart.
         if let Err(e) = self
             .tracker
             .record_execution(
                 self.server_id,
                 self.instance_id,
                 self.tool.id,
-                started_at,
+                sent_at,
                 completed_at,
                 status,
-                Some(response.status),
+                remote.is_none().then_some(response.status),
                 error_message.clone(),
                 input_params_for_tracking,
                 Some(response.body.clone()),
@@ -231,7 +560,10 @@ impl InstanceExecutor {
                 self.tool.url.clone(),
                 Some(self.tool.method.clone()),
                 Some(response.body.len()),
-                Some("http".to_string()),
+                Some(self.transport().to_string()),
+                response.endpoint.clone(),
+                waits.throttled_ms(),
+                waits.queued_ms(),
             )
             .await
         {
@@ -239,11 +571,578 @@ impl InstanceExecutor {
         }
 
         // Return result to MCP
-        if response.is_success {
+        if let Some(result) = remote {
+            Ok(result)
+        } else if response.is_success {
             Ok(CallToolResult::success(vec![Content::text(response.body)]))
         } else {
             let error_msg = format!("HTTP {} - {}", response.status, response.body);
             Ok(CallToolResult::error(vec![Content::text(error_msg)]))
         }
     }
+
+    /// Resolves the call's parameters
+    ///
+    /// When exposed parameters are missing or invalid and the client supports
+    /// elicitation, the user is asked for them with an `elicitation/create`
+    /// request and resolution is retried with the answer. Otherwise, or if
+    /// the user declines, the call fails with `INVALID_PARAMS`.
+    async fn resolve(
+        &self,
+        mut llm_map: Option<HashMap<String, serde_json::Value>>,
+        context: &ExecutionContext,
+    ) -> Result<
+        (
+            Option<HashMap<String, serde_json::Value>>,
+            HashMap<String, serde_json::Value>,
+        ),
+        rmcp::ErrorData,
+    > {
+        let failed = |e: &dyn std::fmt::Display| rmcp::ErrorData {
+            code: rmcp::model::ErrorCode::INVALID_PARAMS,
+            message: format!("Parameter resolution failed: {}", e).into(),
+            data: None,
+        };
+
+        let error = match self
+            .resolver
+            .resolve_parameters(&self.pool, self.instance_id, llm_map.clone())
+            .await
+        {
+            Ok(resolved) => return Ok((llm_map, resolved)),
+            Err(e) => e,
+        };
+        let Some(unresolved) = error
+            .downcast_ref::<UnresolvedParameters>()
+            .filter(|_| context.can_elicit())
+        else {
+            return Err(failed(&error));
+        };
+
+        tracing::info!(
+            instance_id = self.instance_id,
+            parameters = ?unresolved.names(),
+            "Asking the user for parameters"
+        );
+        let request = self.elicitation_request(unresolved);
+        let answer = tokio::select! {
+            biased;
+            _ = context.cancellation().cancelled() => {
+                return Err(rmcp::ErrorData {
+                    code: rmcp::model::ErrorCode::INTERNAL_ERROR,
+                    message: "Request cancelled".into(),
+                    data: None,
+                });
+            }
+            answer = context.elicit(request) => answer?,
+        };
+
+        let content = match (answer.action, answer.content) {
+            (ElicitationAction::Accept, Some(serde_json::Value::Object(content))) => content,
+            (ElicitationAction::Accept, _) => {
+                return Err(failed(&format!("{} (no values provided)", unresolved)))
+            }
+            (_, _) => return Err(failed(&format!("{} (declined by the user)", unresolved))),
+        };
+
+        let types = self.parameter_types();
+        let values = llm_map.get_or_insert_with(HashMap::new);
+        for (name, value) in content {
+            // Elicitation only carries primitives, so JSON values arrive as text
+            let value = match (&value, types.get(&name).map(String::as_str)) {
+                (serde_json::Value::String(text), Some("json" | "object" | "array")) => {
+                    serde_json::from_str(text).unwrap_or(value)
+                }
+                _ => value,
+            };
+            values.insert(name, value);
+        }
+
+        let resolved = self
+            .resolver
+            .resolve_parameters(&self.pool, self.instance_id, llm_map.clone())
+            .await
+            .map_err(|e| failed(&e))?;
+        Ok((llm_map, resolved))
+    }
+
+    /// Placeholder types of the tool's parameters, lowercased
+    fn parameter_types(&self) -> HashMap<String, String> {
+        self.tool
+            .extract_parameters()
+            .into_iter()
+            .map(|p| (p.name, p.param_type.to_lowercase()))
+            .collect()
+    }
+
+    /// `elicitation/create` request for unresolved parameters
+    ///
+    /// The requested schema has one property per parameter, built like the
+    /// tool's input schema. Elicitation schemas only allow primitive
+    /// properties, so JSON parameters are asked for as text.
+    fn elicitation_request(
+        &self,
+        unresolved: &UnresolvedParameters,
+    ) -> CreateElicitationRequestParam {
+        let types = self.parameter_types();
+        let metadata = self.tool.parameter_metadata_map();
+
+        let mut properties = serde_json::Map::new();
+        for name in unresolved.names() {
+            let param_type = match types.get(name).map(String::as_str) {
+                Some("json" | "object" | "array") | None => "string",
+                Some(param_type) => param_type,
+            };
+            properties.insert(
+                name.to_string(),
+                SchemaGenerator::build_property_schema(name, param_type, metadata.get(name)),
+            );
+        }
+
+        let mut requested_schema = serde_json::Map::new();
+        requested_schema.insert("type".to_string(), json!("object"));
+        requested_schema.insert("properties".to_string(), json!(properties));
+        requested_schema.insert("required".to_string(), json!(unresolved.names()));
+
+        CreateElicitationRequestParam {
+            message: format!("{} needs more information. {}.", self.tool.name, unresolved),
+            requested_schema,
+        }
+    }
+
+    /// Tell the client where the request goes, with secret values redacted
+    async fn log_request(
+        &self,
+        context: &ExecutionContext,
+        http_executor: &HttpExecutor,
+        tool: &Tool,
+        resolved: &HashMap<String, serde_json::Value>,
+    ) {
+        if !context.logs(LoggingLevel::Debug) {
+            return;
+        }
+
+        let secrets = match self
+            .resolver
+            .secret_values(&self.pool, self.server_id)
+            .await
+        {
+            Ok(secrets) => secrets,
+            Err(e) => {
+                // Without the secrets nothing can be shown safely
+                tracing::warn!("Failed to load secrets for redaction: {}", e);
+                return;
+            }
+        };
+        let url = http_executor
+            .target_url(tool, resolved)
+            .map(|url| redact(&url, &secrets))
+            .unwrap_or_default();
+
+        context
+            .log(
+                LoggingLevel::Debug,
+                json!({
+                    "message": format!("{} {}", tool.method, url),
+                    "tool": self.tool.name,
+                    "method": tool.method,
+                    "url": url,
+                }),
+            )
+            .await;
+    }
+
+    /// Tell the client how the upstream request went, with secret values
+    /// redacted
+    ///
+    /// Base URLs that failed over are reported before the final outcome.
+    /// Request errors quote the URL, so every string sent is redacted.
+    async fn log_outcome(
+        &self,
+        context: &ExecutionContext,
+        outcome: Option<&Result<(ExecutionResult, Waits, OffsetDateTime), HttpExecutorError>>,
+        elapsed: Duration,
+    ) {
+        // Errors are the most severe messages logged here
+        if !context.logs(LoggingLevel::Error) {
+            return;
+        }
+
+        let secrets = match self
+            .resolver
+            .secret_values(&self.pool, self.server_id)
+            .await
+        {
+            Ok(secrets) => secrets,
+            Err(e) => {
+                // Without the secrets nothing can be shown safely
+                tracing::warn!("Failed to load secrets for redaction: {}", e);
+                return;
+            }
+        };
+
+        let elapsed_ms = elapsed.as_millis() as u64;
+        let (level, mut data) = match outcome {
+            None => (
+                LoggingLevel::Info,
+                json!({
+                    "message": format!("Cancelled by client after {} ms", elapsed_ms),
+                    "tool": self.tool.name,
+                    "duration_ms": elapsed_ms,
+                }),
+            ),
+            Some(Err(
+                e @ (HttpExecutorError::EgressBlocked(_)
+                | HttpExecutorError::RateLimited(_)
+                | HttpExecutorError::ConcurrencyLimited(_)
+                | HttpExecutorError::CircuitOpen(_)),
+            )) => (
+                LoggingLevel::Warning,
+                json!({
+                    "message": format!("Not sent: {}", e),
+                    "tool": self.tool.name,
+                }),
+            ),
+            Some(Err(e)) => (
+                LoggingLevel::Error,
+                json!({
+                    "message": format!("Failed after {} ms: {}", elapsed_ms, e),
+                    "tool": self.tool.name,
+                    "duration_ms": elapsed_ms,
+                }),
+            ),
+            Some(Ok((response, waits, _))) => {
+                // Upstream latency, without the waits reported beside it
+                let elapsed_ms = elapsed
+                    .saturating_sub(waits.queued + waits.throttled)
+                    .as_millis() as u64;
+                for failover in &response.failovers {
+                    let mut data = json!({
+                        "message": format!(
+                            "{} failed ({}), retried on the next base URL",
+                            failover.endpoint, failover.reason
+                        ),
+                        "tool": self.tool.name,
+                        "endpoint": failover.endpoint,
+                        "reason": failover.reason,
+                    });
+                    redact_json(&mut data, &secrets);
+                    context.log(LoggingLevel::Warning, data).await;
+                }
+
+                let (level, message) = if self.forwards_to_mcp() {
+                    let level = if response.is_success {
+                        LoggingLevel::Info
+                    } else {
+                        LoggingLevel::Warning
+                    };
+                    let outcome = if response.is_success {
+                        "answered"
+                    } else {
+                        "failed"
+                    };
+                    (level, format!("MCP tool {} in {} ms", outcome, elapsed_ms))
+                } else {
+                    let level = match response.status {
+                        500.. => LoggingLevel::Error,
+                        400.. => LoggingLevel::Warning,
+                        _ => LoggingLevel::Info,
+                    };
+                    (
+                        level,
+                        format!("HTTP {} in {} ms", response.status, elapsed_ms),
+                    )
+                };
+                (
+                    level,
+                    json!({
+                        "message": message,
+                        "tool": self.tool.name,
+                        "status": response.status,
+                        "duration_ms": elapsed_ms,
+                        "queued_ms": waits.queued_ms(),
+                        "throttled_ms": waits.throttled_ms(),
+                        "endpoint": response.endpoint,
+                    }),
+                )
+            }
+        };
+
+        redact_json(&mut data, &secrets);
+        context.log(level, data).await;
+    }
+
+    /// Record an execution that produced no upstream response, because the
+    /// client cancelled it or the egress policy, a concurrency limit, a rate
+    /// limit or an open circuit refused it
+    async fn record_unsent(
+        &self,
+        status: ExecutionStatus,
+        error_message: String,
+        started_at: OffsetDateTime,
+        completed_at: OffsetDateTime,
+        input_params: Option<HashMap<String, serde_json::Value>>,
+        queued: Duration,
+    ) {
+        if let Err(e) = self
+            .tracker
+            .record_execution(
+                self.server_id,
+                self.instance_id,
+                self.tool.id,
+                started_at,
+                completed_at,
+                status,
+                None,
+                Some(error_message),
+                input_params,
+                None,
+                None,
+                self.tool.url.clone(),
+                Some(self.tool.method.clone()),
+                None,
+                Some(self.transport().to_string()),
+                None,
+                None,
+                (!queued.is_zero()).then_some(queued.as_millis() as u64),
+            )
+            .await
+        {
+            tracing::error!("Failed to track execution: {}", e);
+        }
+    }
+
+    /// Whether calls go to a remote MCP server instead of an HTTP API
+    fn forwards_to_mcp(&self) -> bool {
+        McpTransport::from_method(&self.tool.method).is_some()
+    }
+
+    /// Transport recorded in the execution history
+    ///
+    /// Remote MCP calls are told apart by their request method.
+    fn transport(&self) -> &'static str {
+        match McpTransport::from_method(&self.tool.method) {
+            Some(McpTransport::Sse) => "sse",
+            _ => "http",
+        }
+    }
+
+    /// The tool, with the toolkit's base URLs when it has none of its own
+    async fn tool_with_endpoints(&self) -> Cow<'_, Tool> {
+        if self.tool.endpoints.is_some() {
+            return Cow::Borrowed(&self.tool);
+        }
+
+        match Tool::toolkit_endpoints(&self.pool, self.tool.toolkit_id).await {
+            Ok(Some(endpoints)) => {
+                let mut tool = self.tool.clone();
+                tool.endpoints = Some(endpoints);
+                Cow::Owned(tool)
+            }
+            Ok(None) => Cow::Borrowed(&self.tool),
+            Err(e) => {
+                tracing::warn!("Failed to load toolkit endpoints: {}", e);
+                Cow::Borrowed(&self.tool)
+            }
+        }
+    }
+
+    /// The HTTP executor, using the server's egress client when it has one
+    async fn http_executor_for_server(&self) -> Result<Cow<'_, HttpExecutor>, rmcp::ErrorData> {
+        let client = self
+            .egress
+            .client_for_server(&self.pool, self.server_id, &self.secrets)
+            .await
+            .map_err(|e| rmcp::ErrorData {
+                code: rmcp::model::ErrorCode::INTERNAL_ERROR,
+                message: format!("Server network settings unusable: {}", e).into(),
+                data: None,
+            })?;
+
+        Ok(match client {
+            Some(egress) => Cow::Owned(self.http_executor.clone().with_egress_client(egress)),
+            None => Cow::Borrowed(&self.http_executor),
+        })
+    }
+
+    /// Concurrency limits applying to a call of this instance, narrowest
+    /// first so a call waiting for its instance holds no server slot
+    ///
+    /// Limits that cannot be loaded are skipped rather than failing the call.
+    async fn concurrency_rules(&self) -> Vec<ConcurrencyRule> {
+        let mut rules = Vec::new();
+
+        match ToolInstance::get_by_id(&self.pool, self.instance_id).await {
+            Ok(instance) => {
+                let limit = instance.and_then(|i| i.concurrency_limit);
+                if let Some(limit) = ConcurrencyLimit::parse(limit.as_deref()) {
+                    rules.push(ConcurrencyRule::instance(self.instance_id, limit));
+                }
+            }
+            Err(e) => tracing::warn!("Failed to load instance concurrency limit: {}", e),
+        }
+
+        match ServerNetworkSettings::get(&self.pool, self.server_id).await {
+            Ok(settings) => {
+                let limit = settings.and_then(|s| s.concurrency_limit);
+                if let Some(limit) = ConcurrencyLimit::parse(limit.as_deref()) {
+                    rules.push(ConcurrencyRule::server(self.server_id, limit));
+                }
+            }
+            Err(e) => tracing::warn!("Failed to load server concurrency limit: {}", e),
+        }
+
+        rules
+    }
+
+    /// Rate limits applying to a call of this tool to `host`
+    ///
+    /// Limits that cannot be loaded are skipped rather than failing the call.
+    async fn rate_limit_rules(&self, host: Option<&str>) -> Vec<RateLimitRule> {
+        let mut rules = Vec::new();
+
+        if let Some(config) = self.tool.rate_limit_config() {
+            rules.push(RateLimitRule::tool(self.tool.id, config));
+        }
+
+        if let Some(host) = host {
+            match Tool::toolkit_rate_limit(&self.pool, self.tool.toolkit_id).await {
+                Ok(rate_limit) => {
+                    if let Some(config) = RateLimitConfig::parse(rate_limit.as_deref()) {
+                        rules.push(RateLimitRule::toolkit(self.tool.toolkit_id, host, config));
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to load toolkit rate limit: {}", e),
+            }
+        }
+
+        match ServerNetworkSettings::get(&self.pool, self.server_id).await {
+            Ok(settings) => {
+                let rate_limit = settings.and_then(|s| s.rate_limit);
+                if let Some(config) = RateLimitConfig::parse(rate_limit.as_deref()) {
+                    rules.push(RateLimitRule::server(self.server_id, config));
+                }
+            }
+            Err(e) => tracing::warn!("Failed to load server rate limit: {}", e),
+        }
+
+        rules
+    }
+
+    /// Wait for a concurrency slot, check the call's circuit and wait for its
+    /// rate limits, then send the request
+    ///
+    /// Returns the response with the time spent waiting and the time the
+    /// request was sent. Concurrency slots
+    /// are held until the response is read. Rate-limit headers of the
+    /// response pause further calls to the host that sent it, and its outcome
+    /// is reported to the circuit.
+    async fn guarded_send(
+        &self,
+        http_executor: &HttpExecutor,
+        tool: &Tool,
+        resolved: &HashMap<String, serde_json::Value>,
+        context: &ExecutionContext,
+    ) -> Result<(ExecutionResult, Waits, OffsetDateTime), HttpExecutorError> {
+        let (_slots, queued) = self
+            .concurrency
+            .acquire(&self.concurrency_rules().await)
+            .await
+            .map_err(HttpExecutorError::ConcurrencyLimited)?;
+
+        let circuit = self.circuit_breakers.key_for(
+            tool.id,
+            http_executor.target_authority(tool, resolved).as_deref(),
+        );
+        let permit = self
+            .circuit_breakers
+            .acquire(circuit.as_deref(), self.server_id)
+            .map_err(HttpExecutorError::CircuitOpen)?;
+
+        let host = http_executor.target_host(tool, resolved);
+        let rules = self.rate_limit_rules(host.as_deref()).await;
+
+        let throttled = self
+            .rate_limiter
+            .acquire(&rules, host.as_deref())
+            .await
+            .map_err(HttpExecutorError::RateLimited)?;
+
+        let sent_at = OffsetDateTime::now_utc();
+        let response = match self
+            .send_request(http_executor, tool, resolved, context)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                // Only failures of the upstream itself count against it
+                if matches!(
+                    e,
+                    HttpExecutorError::RequestFailed(_)
+                        | HttpExecutorError::Timeout(_)
+                        | HttpExecutorError::ResponseBodyError(_)
+                        | HttpExecutorError::McpFailed(_)
+                ) {
+                    permit.record_failure(&e.to_string());
+                }
+                return Err(e);
+            }
+        };
+
+        if response.status >= 500 {
+            permit.record_failure(&format!("HTTP {}", response.status));
+        } else {
+            permit.record_success();
+        }
+
+        // With several base URLs, the response came from the one that served it
+        let responder = response
+            .endpoint
+            .as_deref()
+            .and_then(|endpoint| reqwest::Url::parse(endpoint).ok())
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .or(host);
+        if let Some(responder) = responder {
+            self.rate_limiter
+                .observe_response(&responder, response.status, &response.headers);
+        }
+
+        Ok((response, Waits { queued, throttled }, sent_at))
+    }
+
+    /// Run the HTTP request, streaming the response when the tool asks for it
+    async fn send_request(
+        &self,
+        http_executor: &HttpExecutor,
+        tool: &Tool,
+        resolved: &HashMap<String, serde_json::Value>,
+        context: &ExecutionContext,
+    ) -> Result<ExecutionResult, HttpExecutorError> {
+        if !tool.streaming || self.forwards_to_mcp() {
+            return http_executor.execute_tool(tool, resolved).await;
+        }
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<StreamChunk>();
+
+        // Forward chunks as progress notifications in arrival order
+        let forward_context = context.clone();
+        let forwarder = tokio::spawn(async move {
+            while let Some(chunk) = rx.recv().await {
+                forward_context
+                    .report_progress(chunk.sequence as f64, None, Some(chunk.data))
+                    .await;
+            }
+        });
+
+        let result = http_executor
+            .execute_tool_streaming(tool, resolved, tx)
+            .await;
+
+        // The sender is dropped once streaming ends, which stops the forwarder
+        if let Err(e) = forwarder.await {
+            tracing::warn!("Progress forwarder task failed: {}", e);
+        }
+
+        result
+    }
 }
@@ -1,5 +1,7 @@
+use crate::error::AppError;
 use crate::models::{
-    ConfigureInstanceForm, ExtractedParameter, InstanceDetail, InstanceParam, Tool, ToolInstance,
+    ConcurrencyLimit, ConfigureInstanceForm, ExtractedParameter, InstanceDetail,
+    InstanceLimitsForm, InstanceParam, Tool, ToolInstance,
 };
 use crate::services::{ParameterResolver, SecretsManager};
 use anyhow::Result;
@@ -36,7 +38,8 @@ impl InstanceService {
         server_id: i64,
         form: ConfigureInstanceForm,
     ) -> Result<i64> {
-        ToolInstance::create_with_config(&self.pool, server_id, form).await
+        let mut conn = self.pool.acquire().await?;
+        ToolInstance::create_with_config(&mut conn, server_id, form).await
     }
 
     pub async fn get_instance(&self, instance_id: i64) -> Result<Option<ToolInstance>> {
@@ -44,7 +47,8 @@ impl InstanceService {
     }
 
     pub async fn get_instance_detail(&self, instance_id: i64) -> Result<Option<InstanceDetail>> {
-        ToolInstance::get_detail(&self.pool, instance_id).await
+        let mut conn = self.pool.acquire().await?;
+        ToolInstance::get_detail(&mut conn, instance_id).await
     }
 
     pub async fn list_instances_by_server(&self, server_id: i64) -> Result<Vec<InstanceDetail>> {
@@ -60,6 +64,25 @@ impl InstanceService {
         ToolInstance::update(&self.pool, instance_id, name, description).await
     }
 
+    /// Stores the concurrency limit of an instance; an empty maximum removes it
+    ///
+    /// Invalid limits fail with [`AppError::Validation`].
+    pub async fn update_concurrency_limit(
+        &self,
+        instance_id: i64,
+        form: &InstanceLimitsForm,
+    ) -> Result<()> {
+        let limit = ConcurrencyLimit::json_from_form(
+            form.concurrency_max.as_deref(),
+            form.concurrency_queue.as_deref(),
+            form.concurrency_timeout.as_deref(),
+        );
+        if let Some(json) = &limit {
+            ConcurrencyLimit::from_json(json).map_err(AppError::Validation)?;
+        }
+        ToolInstance::set_concurrency_limit(&self.pool, instance_id, limit.as_deref()).await
+    }
+
     pub async fn delete_instance(&self, instance_id: i64) -> Result<()> {
         ToolInstance::delete(&self.pool, instance_id).await
     }
@@ -70,34 +93,8 @@ impl InstanceService {
         instance_id: i64,
         params: Vec<InstanceParam>,
     ) -> Result<()> {
-        let mut tx = self.pool.begin().await?;
-
-        // Clear existing params
-        sqlx::query!(
-            "DELETE FROM instance_params WHERE instance_id = ?",
-            instance_id
-        )
-        .execute(&mut *tx)
-        .await?;
-
-        // Insert new params
-        for param in params {
-            sqlx::query!(
-                r#"
-                INSERT INTO instance_params (instance_id, param_name, source, value)
-                VALUES (?, ?, ?, ?)
-                "#,
-                instance_id,
-                param.param_name,
-                param.source,
-                param.value
-            )
-            .execute(&mut *tx)
-            .await?;
-        }
-
-        tx.commit().await?;
-        Ok(())
+        let mut conn = self.pool.acquire().await?;
+        InstanceParam::replace_all(&mut conn, instance_id, params).await
     }
 
     // Get exposed parameters for an instance
@@ -0,0 +1,538 @@
+//! Client side of remote MCP servers used as tool sources
+//!
+//! Tools with the `MCP` method forward their calls to a tool of a remote MCP
+//! server over streamable HTTP, `MCP-SSE` tools over the older HTTP+SSE
+//! transport. The tool URL is the endpoint of the remote server and its
+//! headers are sent with every request, typically credentials bound to
+//! server secrets. The body is the `tools/call` request as a template:
+//!
+//! ```json
+//! {"name": "get_forecast", "arguments": {"city": "{{string:city}}", "days": "{{integer:days}}"}}
+//! ```
+//!
+//! A string made of a single placeholder is replaced by the parameter's
+//! value, cast to the placeholder type, and left out when the parameter has
+//! no value; other strings are rendered as text. Each call opens its own
+//! session with the remote server, closed once the tool answered.
+//!
+//! See [`mcp_import`](crate::services::mcp_import) for turning the
+//! `tools/list` of a remote server into such tools.
+
+use crate::services::variable_engine::{TypedVariableEngine, VariableType};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use http::Uri;
+use reqwest::header::{HeaderMap, ACCEPT, CONTENT_TYPE};
+use reqwest::StatusCode;
+use rmcp::model::{CallToolRequestParam, ClientInfo, ClientJsonRpcMessage, Implementation, Tool};
+use rmcp::service::{RoleClient, RunningService, ServiceExt};
+use rmcp::transport::common::client_side_sse::BoxedSseResponse;
+use rmcp::transport::common::http_header::{
+    EVENT_STREAM_MIME_TYPE, HEADER_LAST_EVENT_ID, HEADER_SESSION_ID, JSON_MIME_TYPE,
+};
+use rmcp::transport::sse_client::{SseClient, SseClientConfig, SseTransportError};
+use rmcp::transport::streamable_http_client::{
+    SseError, StreamableHttpClient, StreamableHttpClientTransportConfig, StreamableHttpError,
+    StreamableHttpPostResponse,
+};
+use rmcp::transport::{SseClientTransport, StreamableHttpClientTransport};
+use serde_json::{Map, Value};
+use sse_stream::{Sse, SseStream};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Method of tools calling a remote MCP server over streamable HTTP
+pub const MCP_METHOD: &str = "MCP";
+
+/// Method of tools calling a remote MCP server over HTTP+SSE
+pub const MCP_SSE_METHOD: &str = "MCP-SSE";
+
+/// Transport used to reach a remote MCP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum McpTransport {
+    StreamableHttp,
+    Sse,
+}
+
+impl McpTransport {
+    /// Transport of a tool method, `None` for HTTP methods
+    pub fn from_method(method: &str) -> Option<Self> {
+        match method {
+            MCP_METHOD => Some(Self::StreamableHttp),
+            MCP_SSE_METHOD => Some(Self::Sse),
+            _ => None,
+        }
+    }
+
+    /// Tool method using this transport
+    pub fn method(self) -> &'static str {
+        match self {
+            Self::StreamableHttp => MCP_METHOD,
+            Self::Sse => MCP_SSE_METHOD,
+        }
+    }
+}
+
+/// Session with a remote MCP server, closed when dropped
+pub type McpSession = RunningService<RoleClient, ClientInfo>;
+
+/// HTTP client of a remote MCP server, sending the tool headers with every
+/// request
+///
+/// The transports of rmcp only send an `Authorization` header of their own,
+/// so the requests are built here, otherwise like rmcp's reqwest clients.
+#[derive(Clone)]
+struct McpHttpClient {
+    client: reqwest::Client,
+    headers: HeaderMap,
+}
+
+impl McpHttpClient {
+    fn request(
+        &self,
+        method: reqwest::Method,
+        uri: &str,
+        auth_token: Option<String>,
+    ) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .request(method, uri)
+            .headers(self.headers.clone());
+        match auth_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+/// Checks a response is an event stream
+fn event_stream(response: &reqwest::Response) -> Result<(), Option<String>> {
+    match response.headers().get(CONTENT_TYPE) {
+        Some(ct) if ct.as_bytes().starts_with(EVENT_STREAM_MIME_TYPE.as_bytes()) => Ok(()),
+        Some(ct) => Err(Some(String::from_utf8_lossy(ct.as_bytes()).to_string())),
+        None => Err(None),
+    }
+}
+
+impl SseClient for McpHttpClient {
+    type Error = reqwest::Error;
+
+    async fn post_message(
+        &self,
+        uri: Uri,
+        message: ClientJsonRpcMessage,
+        auth_token: Option<String>,
+    ) -> Result<(), SseTransportError<Self::Error>> {
+        self.request(reqwest::Method::POST, &uri.to_string(), auth_token)
+            .json(&message)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(SseTransportError::Client)
+            .map(drop)
+    }
+
+    async fn get_stream(
+        &self,
+        uri: Uri,
+        last_event_id: Option<String>,
+        auth_token: Option<String>,
+    ) -> Result<BoxedSseResponse, SseTransportError<Self::Error>> {
+        let mut request = self
+            .request(reqwest::Method::GET, &uri.to_string(), auth_token)
+            .header(ACCEPT, EVENT_STREAM_MIME_TYPE);
+        if let Some(last_event_id) = last_event_id {
+            request = request.header(HEADER_LAST_EVENT_ID, last_event_id);
+        }
+        let response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(SseTransportError::Client)?;
+        event_stream(&response).map_err(SseTransportError::UnexpectedContentType)?;
+        Ok(SseStream::from_byte_stream(response.bytes_stream()).boxed())
+    }
+}
+
+impl StreamableHttpClient for McpHttpClient {
+    type Error = reqwest::Error;
+
+    async fn post_message(
+        &self,
+        uri: Arc<str>,
+        message: ClientJsonRpcMessage,
+        session_id: Option<Arc<str>>,
+        auth_token: Option<String>,
+    ) -> Result<StreamableHttpPostResponse, StreamableHttpError<Self::Error>> {
+        let mut request = self
+            .request(reqwest::Method::POST, &uri, auth_token)
+            .header(ACCEPT, [EVENT_STREAM_MIME_TYPE, JSON_MIME_TYPE].join(", "));
+        if let Some(session_id) = session_id {
+            request = request.header(HEADER_SESSION_ID, session_id.as_ref());
+        }
+        let response = request
+            .json(&message)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())?;
+        if response.status() == StatusCode::ACCEPTED {
+            return Ok(StreamableHttpPostResponse::Accepted);
+        }
+
+        let session_id = response
+            .headers()
+            .get(HEADER_SESSION_ID)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        match event_stream(&response) {
+            Ok(()) => {
+                let events = SseStream::from_byte_stream(response.bytes_stream()).boxed();
+                Ok(StreamableHttpPostResponse::Sse(events, session_id))
+            }
+            Err(Some(ct)) if ct.starts_with(JSON_MIME_TYPE) => Ok(
+                StreamableHttpPostResponse::Json(response.json().await?, session_id),
+            ),
+            Err(ct) => Err(StreamableHttpError::UnexpectedContentType(ct)),
+        }
+    }
+
+    async fn delete_session(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        auth_token: Option<String>,
+    ) -> Result<(), StreamableHttpError<Self::Error>> {
+        let response = self
+            .request(reqwest::Method::DELETE, &uri, auth_token)
+            .header(HEADER_SESSION_ID, session_id.as_ref())
+            .send()
+            .await?;
+        // Servers may not support ending sessions
+        if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+            response.error_for_status()?;
+        }
+        Ok(())
+    }
+
+    async fn get_stream(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        last_event_id: Option<String>,
+        auth_token: Option<String>,
+    ) -> Result<BoxStream<'static, Result<Sse, SseError>>, StreamableHttpError<Self::Error>> {
+        let mut request = self
+            .request(reqwest::Method::GET, &uri, auth_token)
+            .header(ACCEPT, EVENT_STREAM_MIME_TYPE)
+            .header(HEADER_SESSION_ID, session_id.as_ref());
+        if let Some(last_event_id) = last_event_id {
+            request = request.header(HEADER_LAST_EVENT_ID, last_event_id);
+        }
+        let response = request.send().await?;
+        if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+            return Err(StreamableHttpError::ServerDoesNotSupportSse);
+        }
+        let response = response.error_for_status()?;
+        event_stream(&response).map_err(StreamableHttpError::UnexpectedContentType)?;
+        Ok(SseStream::from_byte_stream(response.bytes_stream()).boxed())
+    }
+}
+
+/// Why an HTTP+SSE request to a remote MCP server failed
+#[derive(Debug, thiserror::Error)]
+pub enum SseRequestError {
+    #[error("Message endpoint {0} is not on the origin of the SSE endpoint")]
+    CrossOrigin(Uri),
+
+    #[error(transparent)]
+    Request(#[from] SseTransportError<reqwest::Error>),
+}
+
+/// SSE client posting messages only to the origin of the SSE endpoint
+///
+/// The remote server names the endpoint messages are posted to. Posting to
+/// an absolute URL elsewhere would hand the tool headers to another host
+/// and reach addresses the egress policy never checked.
+#[derive(Clone)]
+struct SameOriginClient {
+    client: McpHttpClient,
+    sse_endpoint: Uri,
+}
+
+impl SseClient for SameOriginClient {
+    type Error = SseRequestError;
+
+    async fn post_message(
+        &self,
+        uri: Uri,
+        message: ClientJsonRpcMessage,
+        auth_token: Option<String>,
+    ) -> Result<(), SseTransportError<Self::Error>> {
+        if uri.scheme() != self.sse_endpoint.scheme()
+            || uri.authority() != self.sse_endpoint.authority()
+        {
+            return Err(SseTransportError::Client(SseRequestError::CrossOrigin(uri)));
+        }
+        SseClient::post_message(&self.client, uri, message, auth_token)
+            .await
+            .map_err(|e| SseTransportError::Client(e.into()))
+    }
+
+    async fn get_stream(
+        &self,
+        uri: Uri,
+        last_event_id: Option<String>,
+        auth_token: Option<String>,
+    ) -> Result<BoxedSseResponse, SseTransportError<Self::Error>> {
+        SseClient::get_stream(&self.client, uri, last_event_id, auth_token)
+            .await
+            .map_err(|e| SseTransportError::Client(e.into()))
+    }
+}
+
+/// Opens a session with the MCP server at `url`
+///
+/// Requests go through `client` and carry `headers`. Over SSE, messages are
+/// only posted to the origin of `url`.
+pub async fn connect(
+    client: reqwest::Client,
+    headers: HeaderMap,
+    transport: McpTransport,
+    url: &str,
+) -> Result<McpSession, String> {
+    let client = McpHttpClient { client, headers };
+    let info = ClientInfo {
+        client_info: Implementation {
+            name: "saramcp".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            title: None,
+            website_url: None,
+            icons: None,
+        },
+        ..Default::default()
+    };
+
+    match transport {
+        McpTransport::StreamableHttp => {
+            let transport = StreamableHttpClientTransport::with_client(
+                client,
+                StreamableHttpClientTransportConfig::with_uri(url),
+            );
+            info.serve(transport).await.map_err(|e| e.to_string())
+        }
+        McpTransport::Sse => {
+            let client = SameOriginClient {
+                client,
+                sse_endpoint: url
+                    .parse()
+                    .map_err(|e: http::uri::InvalidUri| e.to_string())?,
+            };
+            let config = SseClientConfig {
+                sse_endpoint: url.into(),
+                ..Default::default()
+            };
+            let transport = SseClientTransport::start_with_client(client, config)
+                .await
+                .map_err(|e| e.to_string())?;
+            info.serve(transport).await.map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// What a remote MCP server offers as tools
+#[derive(Debug, Clone, Default)]
+pub struct RemoteServer {
+    /// Title, or name when it has none, the server introduced itself with
+    pub title: Option<String>,
+    pub instructions: Option<String>,
+    pub tools: Vec<Tool>,
+}
+
+/// Lists the tools of a connected server, following pagination
+pub async fn list_tools(session: &McpSession) -> Result<RemoteServer, String> {
+    let tools = session.list_all_tools().await.map_err(|e| e.to_string())?;
+    let info = session.peer_info();
+
+    Ok(RemoteServer {
+        title: info.map(|info| {
+            let server = &info.server_info;
+            server.title.clone().unwrap_or_else(|| server.name.clone())
+        }),
+        instructions: info.and_then(|info| info.instructions.clone()),
+        tools,
+    })
+}
+
+/// The `tools/call` request of a tool's body template
+pub fn call_request(
+    engine: &TypedVariableEngine,
+    template: Option<&str>,
+    params: &HashMap<String, Value>,
+) -> Result<CallToolRequestParam, String> {
+    let template: Value = serde_json::from_str(template.unwrap_or_default())
+        .map_err(|e| format!("body must be a JSON tools/call request: {}", e))?;
+
+    let name = template
+        .get("name")
+        .and_then(Value::as_str)
+        .filter(|name| !name.is_empty())
+        .ok_or("body has no tool name")?
+        .to_string();
+
+    let arguments = match template.get("arguments") {
+        None | Some(Value::Null) => None,
+        Some(Value::Object(arguments)) => {
+            let mut rendered = Map::new();
+            for (key, value) in arguments {
+                if let Some(value) = render(engine, value, params)? {
+                    rendered.insert(key.clone(), value);
+                }
+            }
+            Some(rendered)
+        }
+        Some(_) => return Err("body arguments must be a JSON object".to_string()),
+    };
+
+    Ok(CallToolRequestParam {
+        name: name.into(),
+        arguments,
+    })
+}
+
+/// Renders an argument value, `None` when its placeholder has no value
+fn render(
+    engine: &TypedVariableEngine,
+    value: &Value,
+    params: &HashMap<String, Value>,
+) -> Result<Option<Value>, String> {
+    match value {
+        Value::String(text) => {
+            if let Some((param_type, name)) = engine.sole_variable(text) {
+                return Ok(params
+                    .get(name)
+                    .map(|value| typed(value, param_type.unwrap_or("string"))));
+            }
+            let context = params
+                .iter()
+                .map(|(name, value)| (name.clone(), text_of(value)))
+                .collect();
+            engine
+                .substitute(text, &context)
+                .map(|text| Some(Value::String(text)))
+                .map_err(|e| e.to_string())
+        }
+        Value::Array(items) => {
+            let mut rendered = Vec::new();
+            for item in items {
+                rendered.extend(render(engine, item, params)?);
+            }
+            Ok(Some(Value::Array(rendered)))
+        }
+        Value::Object(members) => {
+            let mut rendered = Map::new();
+            for (key, member) in members {
+                if let Some(member) = render(engine, member, params)? {
+                    rendered.insert(key.clone(), member);
+                }
+            }
+            Ok(Some(Value::Object(rendered)))
+        }
+        other => Ok(Some(other.clone())),
+    }
+}
+
+/// A parameter value as the JSON type of its placeholder
+///
+/// Values given as text by clients are cast, so `"3"` becomes `3` for an
+/// integer placeholder. Values that don't cast are sent unchanged.
+fn typed(value: &Value, param_type: &str) -> Value {
+    if value.is_array() || value.is_object() {
+        return value.clone();
+    }
+    let var_type = match param_type.to_lowercase().as_str() {
+        "number" => VariableType::Number,
+        "integer" => VariableType::Integer,
+        "boolean" | "bool" => VariableType::Boolean,
+        "json" | "object" | "array" => VariableType::Json,
+        _ => VariableType::String,
+    };
+    var_type
+        .cast(&text_of(value))
+        .unwrap_or_else(|_| value.clone())
+}
+
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_transports_are_tool_methods() {
+        assert_eq!(
+            McpTransport::from_method("MCP"),
+            Some(McpTransport::StreamableHttp)
+        );
+        assert_eq!(
+            McpTransport::from_method("MCP-SSE"),
+            Some(McpTransport::Sse)
+        );
+        assert_eq!(McpTransport::from_method("GET"), None);
+        assert_eq!(McpTransport::Sse.method(), MCP_SSE_METHOD);
+    }
+
+    #[test]
+    fn test_call_request_types_and_drops_arguments() {
+        let engine = TypedVariableEngine::new();
+        let template = r#"{"name": "get_forecast", "arguments": {
+            "city": "{{string:city}}",
+            "days": "{{integer:days}}",
+            "units": "{{string:units}}",
+            "filter": {"tags": ["{{string:tag}}"], "raw": "{{json:raw}}"},
+            "label": "Forecast for {{city}}",
+            "verbose": true
+        }}"#;
+        let params = HashMap::from([
+            ("city".to_string(), json!("Paris")),
+            ("days".to_string(), json!("3")),
+            ("tag".to_string(), json!("rain")),
+            ("raw".to_string(), json!("{\"a\": 1}")),
+        ]);
+
+        let request = call_request(&engine, Some(template), &params).unwrap();
+        assert_eq!(request.name, "get_forecast");
+        assert_eq!(
+            Value::Object(request.arguments.unwrap()),
+            json!({
+                "city": "Paris",
+                "days": 3,
+                "filter": {"tags": ["rain"], "raw": {"a": 1}},
+                "label": "Forecast for Paris",
+                "verbose": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_call_request_needs_a_tool_name() {
+        let engine = TypedVariableEngine::new();
+        let params = HashMap::new();
+
+        assert!(call_request(&engine, Some(r#"{"arguments": {}}"#), &params).is_err());
+        assert!(call_request(&engine, None, &params).is_err());
+        assert!(call_request(
+            &engine,
+            Some(r#"{"name": "ping", "arguments": []}"#),
+            &params
+        )
+        .is_err());
+        let request = call_request(&engine, Some(r#"{"name": "ping"}"#), &params).unwrap();
+        assert_eq!(request.arguments, None);
+    }
+}
@@ -0,0 +1,413 @@
+//! Remote MCP server import
+//!
+//! Turns the `tools/list` of a remote MCP server into a [`ToolImport`] with
+//! one `MCP` or `MCP-SSE` tool per remote tool (see
+//! [`mcp_client`](crate::services::mcp_client)):
+//!
+//! - the body is the remote tool's `tools/call` request, each property of
+//!   its input schema a typed `{{type:name}}` placeholder keeping its
+//!   description and enum as parameter metadata
+//! - the server URL and headers are copied into every tool; their
+//!   placeholders, typically credentials, are meant to be bound to server
+//!   globals
+//!
+//! As with other imports, optional arguments are left out (and listed as
+//! skipped) unless [`ImportOptions::include_optional`] is set. Once the tools
+//! are saved, [`param_configs`] binds one of them for an instance: URL and
+//! header placeholders to server globals, pinned arguments to fixed values
+//! and the other arguments exposed to clients.
+
+use crate::models::{ParamConfig, ParameterMetadata};
+use crate::services::mcp_client::{McpTransport, RemoteServer};
+use crate::services::tool_import::{
+    snake_case, unique_name, ImportOptions, ImportedParameter, ImportedTool, ToolImport,
+};
+use crate::services::variable_engine::TypedVariableEngine;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+#[derive(Debug, thiserror::Error)]
+pub enum McpImportError {
+    #[error("Enter the URL of the MCP server")]
+    MissingUrl,
+
+    #[error("Headers must be a JSON object of strings")]
+    InvalidHeaders,
+
+    #[error("The MCP server offers no tools")]
+    NoTools,
+}
+
+/// Where the remote server is and how to reach it
+#[derive(Debug, Clone)]
+pub struct McpSource {
+    pub transport: McpTransport,
+    pub url: String,
+    /// Headers sent with every request, with placeholders for server globals
+    pub headers: Vec<(String, String)>,
+}
+
+impl McpSource {
+    /// Reads a source from the import form fields
+    ///
+    /// `headers` is a JSON object of header templates, empty for none.
+    pub fn parse(
+        transport: McpTransport,
+        url: &str,
+        headers: &str,
+    ) -> Result<Self, McpImportError> {
+        let url = url.trim();
+        if url.is_empty() {
+            return Err(McpImportError::MissingUrl);
+        }
+
+        let headers = match headers.trim() {
+            "" => Vec::new(),
+            text => match serde_json::from_str::<Map<String, Value>>(text) {
+                Ok(object) => object
+                    .into_iter()
+                    .map(|(name, value)| match value {
+                        Value::String(value) => Ok((name, value)),
+                        _ => Err(McpImportError::InvalidHeaders),
+                    })
+                    .collect::<Result<_, _>>()?,
+                Err(_) => return Err(McpImportError::InvalidHeaders),
+            },
+        };
+
+        Ok(Self {
+            transport,
+            url: url.to_string(),
+            headers,
+        })
+    }
+
+    /// Headers as the JSON object tools store
+    pub fn headers_json(&self) -> String {
+        let headers: Map<String, Value> = self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect();
+        Value::Object(headers).to_string()
+    }
+}
+
+/// Builds the proposed toolkit for the tools of a remote server
+pub fn build(
+    source: &McpSource,
+    server: &RemoteServer,
+    options: ImportOptions,
+) -> Result<ToolImport, McpImportError> {
+    if server.tools.is_empty() {
+        return Err(McpImportError::NoTools);
+    }
+
+    let engine = TypedVariableEngine::new();
+    let mut connection = Vec::new();
+    for (template, location) in std::iter::once((&source.url, "path"))
+        .chain(source.headers.iter().map(|(_, value)| (value, "header")))
+    {
+        for (param_type, name) in engine.find_variables(template) {
+            if !connection
+                .iter()
+                .any(|p: &ImportedParameter| p.name == name)
+            {
+                connection.push(ImportedParameter {
+                    name,
+                    param_type: param_type.unwrap_or_else(|| "string".to_string()),
+                    location: location.to_string(),
+                    metadata: ParameterMetadata::default(),
+                });
+            }
+        }
+    }
+
+    let mut import = ToolImport {
+        title: server
+            .title
+            .clone()
+            .unwrap_or_else(|| "MCP server".to_string()),
+        description: server.instructions.clone(),
+        ..Default::default()
+    };
+
+    for remote in &server.tools {
+        let mut tool = ImportedTool {
+            key: remote.name.to_string(),
+            name: snake_case(&remote.name),
+            description: remote
+                .description
+                .as_deref()
+                .or(remote.title.as_deref())
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string),
+            method: source.transport.method().to_string(),
+            url: source.url.clone(),
+            headers: source.headers.clone(),
+            parameters: connection.clone(),
+            ..Default::default()
+        };
+
+        let required: HashSet<&str> = remote
+            .input_schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut used: HashSet<String> = connection.iter().map(|p| p.name.clone()).collect();
+        let mut arguments = Map::new();
+        let properties = remote
+            .input_schema
+            .get("properties")
+            .and_then(Value::as_object);
+        for (argument, schema) in properties.into_iter().flatten() {
+            if !required.contains(argument.as_str()) && !options.include_optional {
+                tool.skipped.push(argument.clone());
+                continue;
+            }
+
+            let parameter = ImportedParameter {
+                name: unique_name(&snake_case(argument), &mut used),
+                param_type: placeholder_type(schema).to_string(),
+                location: "body".to_string(),
+                metadata: metadata(schema),
+            };
+            arguments.insert(argument.clone(), Value::String(parameter.placeholder()));
+            tool.parameters.push(parameter);
+        }
+
+        tool.body = Some(
+            serde_json::json!({
+                "name": remote.name,
+                "arguments": arguments,
+            })
+            .to_string(),
+        );
+        import.tools.push(tool);
+    }
+
+    import.dedupe_tool_names();
+    Ok(import)
+}
+
+/// Parameter bindings of an instance of an imported tool
+///
+/// URL and header placeholders take the server global of the same name.
+/// Arguments with a non-empty value in `pins` are fixed to it, the others
+/// are exposed to clients.
+pub fn param_configs(tool: &ImportedTool, pins: &[(String, String)]) -> Vec<ParamConfig> {
+    tool.parameters
+        .iter()
+        .map(|parameter| {
+            let pinned = pins
+                .iter()
+                .find(|(name, value)| *name == parameter.name && !value.trim().is_empty())
+                .map(|(_, value)| value.trim().to_string());
+
+            let source = match (parameter.location.as_str(), &pinned) {
+                ("path" | "header", _) => "server",
+                (_, Some(_)) => "instance",
+                (_, None) => "exposed",
+            };
+            ParamConfig {
+                name: parameter.name.clone(),
+                source: source.to_string(),
+                value: pinned.filter(|_| source == "instance"),
+            }
+        })
+        .collect()
+}
+
+/// Placeholder type for a JSON Schema property
+fn placeholder_type(schema: &Value) -> &'static str {
+    // A list of types, such as ["string", "null"]
+    let declared = match schema.get("type") {
+        Some(Value::String(t)) => Some(t.as_str()),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null"),
+        _ => None,
+    };
+
+    match declared {
+        Some("integer") => "integer",
+        Some("number") => "number",
+        Some("boolean") => "boolean",
+        Some("object") | Some("array") => "json",
+        Some(_) => "string",
+        None if schema.get("properties").is_some() || schema.get("items").is_some() => "json",
+        None => "string",
+    }
+}
+
+fn metadata(schema: &Value) -> ParameterMetadata {
+    ParameterMetadata {
+        description: schema
+            .get("description")
+            .and_then(Value::as_str)
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty()),
+        enum_values: match schema.get("enum") {
+            Some(Value::Array(values)) => values.clone(),
+            _ => Vec::new(),
+        },
+        lookup: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Tool;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn remote_tool(name: &str, description: &str, schema: Value) -> Tool {
+        let Value::Object(schema) = schema else {
+            panic!("schema must be an object");
+        };
+        Tool::new(name.to_string(), description.to_string(), Arc::new(schema))
+    }
+
+    fn source() -> McpSource {
+        McpSource::parse(
+            McpTransport::StreamableHttp,
+            "https://mcp.example.com/mcp",
+            r#"{"Authorization": "Bearer {{weather_token}}"}"#,
+        )
+        .unwrap()
+    }
+
+    fn server() -> RemoteServer {
+        RemoteServer {
+            title: Some("Weather".to_string()),
+            instructions: None,
+            tools: vec![
+                remote_tool(
+                    "getForecast",
+                    "Forecast for a city",
+                    json!({
+                        "type": "object",
+                        "properties": {
+                            "city": {"type": "string", "description": "City name"},
+                            "days": {"type": "integer"},
+                            "units": {"type": "string", "enum": ["metric", "imperial"]},
+                            "weather_token": {"type": "string"}
+                        },
+                        "required": ["city", "days", "weather_token"]
+                    }),
+                ),
+                remote_tool("get-forecast", "", json!({"type": "object"})),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_remote_tools_become_mcp_tools() {
+        let import = build(&source(), &server(), ImportOptions::default()).unwrap();
+        assert_eq!(import.title, "Weather");
+        assert_eq!(import.tools.len(), 2);
+
+        let tool = &import.tools[0];
+        assert_eq!(tool.key, "getForecast");
+        assert_eq!(tool.name, "get_forecast");
+        assert_eq!(tool.method, "MCP");
+        assert_eq!(tool.url, "https://mcp.example.com/mcp");
+        assert_eq!(tool.description.as_deref(), Some("Forecast for a city"));
+        assert_eq!(tool.skipped, vec!["units"]);
+        assert_eq!(
+            tool.parameters_summary(),
+            "weather_token (string, header), city (string, body), days (integer, body), \
+             weather_token_2 (string, body)"
+        );
+        assert_eq!(
+            tool.parameters[1].metadata.description.as_deref(),
+            Some("City name")
+        );
+
+        let body: Value = serde_json::from_str(tool.body.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            json!({"name": "getForecast", "arguments": {
+                "city": "{{string:city}}",
+                "days": "{{integer:days}}",
+                "weather_token": "{{string:weather_token_2}}"
+            }})
+        );
+
+        // Remote names that collapse to the same tool name are numbered
+        assert_eq!(import.tools[1].name, "get_forecast_2");
+        assert_eq!(import.tools[1].description, None);
+    }
+
+    #[test]
+    fn test_include_optional_arguments() {
+        let options = ImportOptions {
+            include_optional: true,
+            ..Default::default()
+        };
+        let import = build(&source(), &server(), options).unwrap();
+        let units = import.tools[0]
+            .parameters
+            .iter()
+            .find(|p| p.name == "units")
+            .unwrap();
+        assert_eq!(
+            units.metadata.enum_values,
+            vec![json!("metric"), json!("imperial")]
+        );
+        assert!(import.tools[0].skipped.is_empty());
+    }
+
+    #[test]
+    fn test_param_configs_bind_globals_pins_and_exposed() {
+        let import = build(&source(), &server(), ImportOptions::default()).unwrap();
+        let pins = vec![
+            ("days".to_string(), "3".to_string()),
+            ("city".to_string(), "  ".to_string()),
+            ("weather_token".to_string(), "ignored".to_string()),
+        ];
+
+        let configs: Vec<(String, String, Option<String>)> = param_configs(&import.tools[0], &pins)
+            .into_iter()
+            .map(|c| (c.name, c.source, c.value))
+            .collect();
+        assert_eq!(
+            configs,
+            vec![
+                ("weather_token".to_string(), "server".to_string(), None),
+                ("city".to_string(), "exposed".to_string(), None),
+                (
+                    "days".to_string(),
+                    "instance".to_string(),
+                    Some("3".to_string())
+                ),
+                ("weather_token_2".to_string(), "exposed".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejects_unusable_sources() {
+        assert!(matches!(
+            McpSource::parse(McpTransport::Sse, " ", ""),
+            Err(McpImportError::MissingUrl)
+        ));
+        assert!(matches!(
+            McpSource::parse(McpTransport::Sse, "https://x", r#"{"X-Key": 1}"#),
+            Err(McpImportError::InvalidHeaders)
+        ));
+        assert!(matches!(
+            build(
+                &source(),
+                &RemoteServer::default(),
+                ImportOptions::default()
+            ),
+            Err(McpImportError::NoTools)
+        ));
+    }
+}
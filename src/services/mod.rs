@@ -1,36 +1,78 @@
 pub mod auth_service;
 pub mod auth_token_service;
+pub mod circuit_breaker;
+pub mod concurrency_limiter;
+pub mod curl_import;
 pub mod dashboard_service;
+pub mod egress_client;
+pub mod egress_policy;
 pub mod email_service;
+pub mod endpoint_health;
+pub mod execution_context;
 pub mod execution_tracker;
+pub mod har_import;
 pub mod http_executor;
 pub mod instance_executor;
 pub mod instance_service;
+pub mod mcp_client;
+pub mod mcp_import;
 pub mod oauth_service;
+pub mod openapi_export;
+pub mod openapi_import;
 pub mod parameter_resolver;
+pub mod postman_import;
+pub mod rate_limiter;
 pub mod schema_generator;
 pub mod secrets_manager;
+pub mod server_config;
 pub mod server_service;
+pub mod stream_decoder;
+pub mod tool_import;
 pub mod tool_service;
 pub mod tool_test_service;
+pub mod toolkit_bundle;
 pub mod toolkit_service;
 pub mod user_service;
 pub mod variable_engine;
 
 pub use auth_service::{AuthService, AuthServiceError};
 pub use auth_token_service::{AuthTokenError, AuthTokenService};
+pub use circuit_breaker::{
+    CircuitBreakerConfig, CircuitBreakerRegistry, CircuitOpen, CircuitScope, CircuitSnapshot,
+    CircuitState,
+};
+pub use concurrency_limiter::{ConcurrencyLimited, ConcurrencyLimiter, ConcurrencyRule};
+pub use curl_import::{CurlImport, CurlImportError};
 pub use dashboard_service::{DashboardService, DashboardStats, RecentExecution, ServerStats};
+pub use egress_client::{EgressClient, EgressClientCache, EgressConfig, EgressConfigError};
+pub use egress_policy::{EgressBlocked, EgressPolicy, HostRule};
 pub use email_service::{create_email_service, EmailError, EmailService};
+pub use endpoint_health::{EndpointHealth, EndpointHealthRegistry};
+pub use execution_context::{ClientRequests, ExecutionContext, LogLevel, NotificationSink};
 pub use execution_tracker::{ExecutionStatus, ExecutionTracker};
-pub use http_executor::{ExecutionResult, HttpExecutor, HttpExecutorError};
+pub use har_import::HarImportError;
+pub use http_executor::{ExecutionResult, Failover, HttpExecutor, HttpExecutorError, StreamChunk};
 pub use instance_executor::InstanceExecutor;
 pub use instance_service::{InstanceService, ParameterUsageCount, ToolWithParams};
+pub use mcp_client::{McpTransport, RemoteServer};
+pub use mcp_import::{McpImportError, McpSource};
 pub use oauth_service::{ClientRegistrationRequest, ClientRegistrationResponse, OAuthService};
-pub use parameter_resolver::ParameterResolver;
+pub use openapi_export::{Binding, OpenApiExport, OperationSpec};
+pub use openapi_import::OpenApiImportError;
+pub use parameter_resolver::{ParameterResolver, UnresolvedParameters};
+pub use postman_import::PostmanImportError;
+pub use rate_limiter::{RateLimitRule, RateLimited, RateLimiter};
 pub use schema_generator::SchemaGenerator;
 pub use secrets_manager::SecretsManager;
-pub use server_service::ServerService;
+pub use server_config::{ConfigChange, ConfigPlan, ServerConfig, ServerConfigError, ToolCatalog};
+pub use server_service::{PromptError, ResourceError, ServerService};
+pub use tool_import::{
+    ImportOptions, ImportedParameter, ImportedTool, SuggestedGlobal, ToolImport,
+};
 pub use tool_service::ToolService;
+pub use toolkit_bundle::{
+    BundleError, BundleImportSummary, BundlePlan, BundleTarget, BundledTool, ToolkitBundle,
+};
 pub use toolkit_service::ToolkitService;
 pub use user_service::{UserService, UserServiceError};
 pub use variable_engine::{TypedVariableEngine, VariableType};
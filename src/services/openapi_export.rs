@@ -0,0 +1,867 @@
+//! OpenAPI 3.1 export of toolkits and servers
+//!
+//! A tool is an HTTP request template, so it maps onto one OpenAPI
+//! operation: the URL splits into a server origin, a templated path and
+//! query parameters, header placeholders become header parameters and the
+//! body template becomes a request body schema. Placeholder types and the
+//! parameter documentation stored with the tool give the schemas.
+//!
+//! Exporting a server documents its tool instances the way MCP clients see
+//! them. Values bound by the instance or by plain server globals are fixed,
+//! secret globals become security schemes and only exposed parameters are
+//! left for the caller.
+
+use crate::models::{ParameterMetadataMap, Tool, Toolkit};
+use crate::services::{InstanceService, McpTransport, SchemaGenerator, ServerService, ToolService};
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// OpenAPI version of generated documents
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+// Hardcoded regex pattern - guaranteed to be valid at compile time
+// Using unwrap here is safe because the pattern is a compile-time constant
+#[allow(clippy::unwrap_used)]
+static PLACEHOLDER_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{(?:([a-z]+):)?([a-zA-Z_][a-zA-Z0-9_]*)\}\}").unwrap());
+
+// Stands in for a body placeholder while the JSON template is parsed
+const BODY_TOKEN_PREFIX: &str = "@@saramcp:";
+const BODY_TOKEN_SUFFIX: &str = "@@";
+
+/// How a server fills a placeholder its callers do not provide
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binding {
+    /// Fixed by the instance or by a plain server global
+    Value(String),
+    /// Filled from a secret global, never written into the document
+    Secret,
+}
+
+/// A tool exported as one operation
+#[derive(Debug, Clone)]
+pub struct OperationSpec<'a> {
+    pub tool: &'a Tool,
+    pub operation_id: String,
+    pub description: Option<String>,
+    /// Placeholders the server fills; every other one is a caller parameter
+    pub bindings: HashMap<String, Binding>,
+}
+
+pub struct OpenApiExport;
+
+impl OpenApiExport {
+    /// Document every tool of a toolkit, all placeholders as parameters
+    pub fn for_toolkit(toolkit: &Toolkit, tools: &[Tool]) -> Value {
+        let specs: Vec<OperationSpec> = tools
+            .iter()
+            .map(|tool| OperationSpec {
+                tool,
+                operation_id: tool.name.clone(),
+                description: tool.description.clone(),
+                bindings: HashMap::new(),
+            })
+            .collect();
+
+        Self::build(&toolkit.title, toolkit.description.as_deref(), &specs)
+    }
+
+    /// Document the tool instances of a server with their exposed parameters
+    pub async fn for_server(
+        server_service: &ServerService,
+        instance_service: &InstanceService,
+        tool_service: &ToolService,
+        server_id: i64,
+        user_id: i64,
+    ) -> Result<Value> {
+        let server = server_service
+            .get_server(server_id, user_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Server not found or unauthorized"))?;
+
+        let globals: HashMap<String, Binding> = server_service
+            .get_server_globals(server_id)
+            .await?
+            .into_iter()
+            .map(|g| {
+                let binding = if g.is_secret.unwrap_or(false) {
+                    Binding::Secret
+                } else {
+                    Binding::Value(g.value)
+                };
+                (g.key, binding)
+            })
+            .collect();
+
+        let instances = instance_service.list_instances_by_server(server_id).await?;
+        let mut tools = Vec::with_capacity(instances.len());
+        for instance in &instances {
+            let (tool, _) = tool_service
+                .get_tool(instance.tool_id, user_id)
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!("Tool of instance '{}': {}", instance.instance_name, e)
+                })?;
+            tools.push(tool);
+        }
+
+        let specs: Vec<OperationSpec> = instances
+            .iter()
+            .zip(&tools)
+            .map(|(instance, tool)| OperationSpec {
+                tool,
+                operation_id: instance.instance_name.clone(),
+                description: instance
+                    .description
+                    .clone()
+                    .or_else(|| tool.description.clone()),
+                bindings: instance
+                    .params
+                    .iter()
+                    .filter_map(|p| match p.source.as_str() {
+                        "instance" => Some((
+                            p.param_name.clone(),
+                            Binding::Value(p.value.clone().unwrap_or_default()),
+                        )),
+                        "server" => globals
+                            .get(&p.param_name)
+                            .map(|binding| (p.param_name.clone(), binding.clone())),
+                        _ => None,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let description = server.description.filter(|d| !d.is_empty());
+        Ok(Self::build(&server.name, description.as_deref(), &specs))
+    }
+
+    /// Assemble a document from operations
+    ///
+    /// Operations sharing one origin get it as the document's server,
+    /// otherwise each operation names its own. Two operations cannot share a
+    /// method and path, so the later one is left out and listed in the
+    /// description, as are tools forwarding to a remote MCP server.
+    pub fn build(title: &str, description: Option<&str>, specs: &[OperationSpec]) -> Value {
+        let (mut remote, mut specs): (Vec<&OperationSpec>, Vec<&OperationSpec>) = specs
+            .iter()
+            .partition(|spec| McpTransport::from_method(&spec.tool.method).is_some());
+        specs.sort_by(|a, b| a.operation_id.cmp(&b.operation_id));
+        remote.sort_by(|a, b| a.operation_id.cmp(&b.operation_id));
+        let operations: Vec<Operation> = specs.into_iter().map(Operation::analyze).collect();
+
+        let origins: BTreeSet<&str> = operations.iter().map(|op| op.origin.as_str()).collect();
+        let shared_origin = match origins.len() {
+            1 => operations.first().map(|op| op.server()),
+            _ => None,
+        };
+
+        let mut paths: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+        let mut schemes = Map::new();
+        let mut skipped = Vec::new();
+
+        for op in &operations {
+            let item = paths.entry(op.path.clone()).or_default();
+            if item.contains_key(&op.method) {
+                skipped.push(op.operation_id.clone());
+                continue;
+            }
+
+            for (name, scheme) in &op.schemes {
+                schemes.insert(name.clone(), scheme.clone());
+            }
+            item.insert(op.method.clone(), op.to_json(shared_origin.is_none()));
+        }
+
+        let mut info = Map::new();
+        info.insert("title".to_string(), json!(title));
+        info.insert("version".to_string(), json!("1.0.0"));
+        let mut notes: Vec<String> = description.map(str::to_string).into_iter().collect();
+        if !skipped.is_empty() {
+            notes.push(format!(
+                "Left out because another operation uses the same method and path: {}",
+                skipped.join(", ")
+            ));
+        }
+        if !remote.is_empty() {
+            notes.push(format!(
+                "Left out because they call a remote MCP server: {}",
+                remote
+                    .iter()
+                    .map(|spec| spec.operation_id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if !notes.is_empty() {
+            info.insert("description".to_string(), json!(notes.join("\n\n")));
+        }
+
+        let mut document = Map::new();
+        document.insert("openapi".to_string(), json!(OPENAPI_VERSION));
+        document.insert("info".to_string(), Value::Object(info));
+        if let Some(server) = shared_origin {
+            document.insert("servers".to_string(), json!([server]));
+        }
+        document.insert("paths".to_string(), json!(paths));
+        if !schemes.is_empty() {
+            document.insert(
+                "components".to_string(),
+                json!({ "securitySchemes": schemes }),
+            );
+        }
+
+        Value::Object(document)
+    }
+
+    /// Serialize a document: YAML when asked, JSON otherwise
+    ///
+    /// Returns the text with its content type and file extension.
+    pub fn render(
+        document: &Value,
+        format: Option<&str>,
+    ) -> Result<(String, &'static str, &'static str)> {
+        match format {
+            Some("yaml") => Ok((serde_yaml::to_string(document)?, "application/yaml", "yaml")),
+            _ => Ok((
+                serde_json::to_string_pretty(document)?,
+                "application/json",
+                "json",
+            )),
+        }
+    }
+}
+
+/// A template fragment after the server's values are filled in
+enum Fragment {
+    /// Exactly one placeholder
+    Placeholder { name: String, var_type: String },
+    /// No placeholders left
+    Constant(String),
+    /// Text mixed with placeholders, written OpenAPI style as `{name}`
+    Format(String),
+}
+
+struct Operation {
+    operation_id: String,
+    description: Option<String>,
+    method: String,
+    origin: String,
+    server_variables: Map<String, Value>,
+    path: String,
+    parameters: Vec<Value>,
+    request_body: Option<Value>,
+    security: Vec<String>,
+    schemes: Vec<(String, Value)>,
+}
+
+impl Operation {
+    fn analyze(spec: &OperationSpec) -> Self {
+        let ctx = Context {
+            bindings: &spec.bindings,
+            metadata: spec.tool.parameter_metadata_map(),
+        };
+
+        let mut op = Operation {
+            operation_id: spec.operation_id.clone(),
+            description: spec.description.clone().filter(|d| !d.is_empty()),
+            method: spec.tool.method.to_lowercase(),
+            origin: String::new(),
+            server_variables: Map::new(),
+            path: "/".to_string(),
+            parameters: Vec::new(),
+            request_body: None,
+            security: Vec::new(),
+            schemes: Vec::new(),
+        };
+
+        let url = ctx.bind(spec.tool.url.as_deref().unwrap_or_default());
+        let (base, query) = match url.split_once('?') {
+            Some((base, query)) => (base, Some(query)),
+            None => (url.as_str(), None),
+        };
+        op.add_location(&ctx, base);
+        if let Some(query) = query {
+            op.add_query(&ctx, query);
+        }
+
+        let headers: Map<String, Value> = spec
+            .tool
+            .headers
+            .as_deref()
+            .and_then(|h| serde_json::from_str(h).ok())
+            .unwrap_or_default();
+        let mut content_type = "application/json".to_string();
+        for (name, value) in &headers {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            match name.to_lowercase().as_str() {
+                "content-type" => content_type = ctx.bind(&value),
+                "accept" => {}
+                "authorization" => op.add_authorization(&value),
+                _ => op.add_header(&ctx, name, &value),
+            }
+        }
+
+        let body = spec.tool.body.as_deref().unwrap_or_default();
+        if !body.trim().is_empty() && !matches!(op.method.as_str(), "get" | "head") {
+            let schema = if content_type.contains("json") {
+                ctx.json_body_schema(body)
+            } else if content_type.contains("x-www-form-urlencoded") {
+                ctx.form_body_schema(body)
+            } else {
+                json!({ "type": "string", "example": ctx.bind(body) })
+            };
+            op.request_body = Some(json!({
+                "required": true,
+                "content": { content_type: { "schema": schema } }
+            }));
+        }
+
+        op
+    }
+
+    /// Split the URL before the query into origin and path
+    fn add_location(&mut self, ctx: &Context, base: &str) {
+        let split = match base.find("://") {
+            Some(scheme_end) => base[scheme_end + 3..]
+                .find('/')
+                .map(|i| scheme_end + 3 + i)
+                .unwrap_or(base.len()),
+            // A leading placeholder such as {{url:base_url}} holds the origin
+            None => PLACEHOLDER_PATTERN
+                .find(base)
+                .filter(|m| m.start() == 0)
+                .map(|m| m.end())
+                .unwrap_or(0),
+        };
+        let (origin, path) = base.split_at(split);
+
+        self.origin = PLACEHOLDER_PATTERN
+            .replace_all(origin, |caps: &regex::Captures| {
+                let name = &caps[2];
+                self.server_variables.insert(
+                    name.to_string(),
+                    json!({ "default": "", "description": ctx.describe(name) }),
+                );
+                format!("{{{}}}", name)
+            })
+            .into_owned();
+
+        let mut path_params = Vec::new();
+        let path = PLACEHOLDER_PATTERN
+            .replace_all(path, |caps: &regex::Captures| {
+                let var_type = caps.get(1).map_or("string", |m| m.as_str());
+                path_params.push(json!({
+                    "name": &caps[2],
+                    "in": "path",
+                    "required": true,
+                    "schema": ctx.placeholder_schema(&caps[2], var_type),
+                }));
+                format!("{{{}}}", &caps[2])
+            })
+            .into_owned();
+        self.path = if path.starts_with('/') {
+            path
+        } else {
+            format!("/{}", path)
+        };
+        for param in path_params {
+            self.add_parameter(param);
+        }
+    }
+
+    fn add_query(&mut self, ctx: &Context, query: &str) {
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let fragment = ctx.fragment(value);
+            if let Some(secret) = ctx.secret_name(&fragment) {
+                self.add_scheme(
+                    secret,
+                    json!({ "type": "apiKey", "in": "query", "name": key }),
+                );
+                continue;
+            }
+            self.add_parameter(json!({
+                "name": key,
+                "in": "query",
+                "required": true,
+                "schema": ctx.fragment_schema(&fragment),
+            }));
+        }
+    }
+
+    fn add_header(&mut self, ctx: &Context, name: &str, value: &str) {
+        let fragment = ctx.fragment(value);
+        if let Some(secret) = ctx.secret_name(&fragment) {
+            self.add_scheme(
+                secret,
+                json!({ "type": "apiKey", "in": "header", "name": name }),
+            );
+            return;
+        }
+        self.add_parameter(json!({
+            "name": name,
+            "in": "header",
+            "required": true,
+            "schema": ctx.fragment_schema(&fragment),
+        }));
+    }
+
+    /// Credentials in the Authorization header are never written out,
+    /// even when a plain global holds them
+    fn add_authorization(&mut self, value: &str) {
+        let scheme = value
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match scheme.as_str() {
+            "bearer" => {
+                self.add_scheme("bearerAuth", json!({ "type": "http", "scheme": "bearer" }))
+            }
+            "basic" => self.add_scheme("basicAuth", json!({ "type": "http", "scheme": "basic" })),
+            _ => self.add_scheme(
+                "authorization",
+                json!({ "type": "apiKey", "in": "header", "name": "Authorization" }),
+            ),
+        }
+    }
+
+    fn add_parameter(&mut self, param: Value) {
+        let duplicate = self
+            .parameters
+            .iter()
+            .any(|p| p["name"] == param["name"] && p["in"] == param["in"]);
+        if !duplicate {
+            self.parameters.push(param);
+        }
+    }
+
+    fn add_scheme(&mut self, name: &str, scheme: Value) {
+        self.security.push(name.to_string());
+        self.schemes.push((name.to_string(), scheme));
+    }
+
+    fn server(&self) -> Value {
+        let mut server = Map::new();
+        server.insert("url".to_string(), json!(self.origin));
+        if !self.server_variables.is_empty() {
+            server.insert(
+                "variables".to_string(),
+                Value::Object(self.server_variables.clone()),
+            );
+        }
+        Value::Object(server)
+    }
+
+    fn to_json(&self, with_server: bool) -> Value {
+        let mut op = Map::new();
+        op.insert("operationId".to_string(), json!(self.operation_id));
+        if let Some(description) = &self.description {
+            let summary = description.lines().next().unwrap_or_default();
+            op.insert("summary".to_string(), json!(summary));
+            op.insert("description".to_string(), json!(description));
+        }
+        if with_server {
+            op.insert("servers".to_string(), json!([self.server()]));
+        }
+        if !self.parameters.is_empty() {
+            op.insert("parameters".to_string(), json!(self.parameters));
+        }
+        if let Some(body) = &self.request_body {
+            op.insert("requestBody".to_string(), body.clone());
+        }
+        if !self.security.is_empty() {
+            let requirement: Map<String, Value> = self
+                .security
+                .iter()
+                .map(|name| (name.clone(), json!([])))
+                .collect();
+            op.insert("security".to_string(), json!([requirement]));
+        }
+        op.insert(
+            "responses".to_string(),
+            json!({ "default": { "description": "Response of the upstream API" } }),
+        );
+        Value::Object(op)
+    }
+}
+
+struct Context<'a> {
+    bindings: &'a HashMap<String, Binding>,
+    metadata: ParameterMetadataMap,
+}
+
+impl Context<'_> {
+    /// Fill in the values the server fixes, leaving secrets as placeholders
+    fn bind(&self, template: &str) -> String {
+        PLACEHOLDER_PATTERN
+            .replace_all(template, |caps: &regex::Captures| {
+                match self.bindings.get(&caps[2]) {
+                    Some(Binding::Value(value)) => value.clone(),
+                    _ => caps[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+
+    fn fragment(&self, template: &str) -> Fragment {
+        let bound = self.bind(template);
+        if let Some(caps) = PLACEHOLDER_PATTERN.captures(&bound) {
+            if caps[0].len() == bound.len() {
+                return Fragment::Placeholder {
+                    name: caps[2].to_string(),
+                    var_type: caps.get(1).map_or("string", |m| m.as_str()).to_string(),
+                };
+            }
+            return Fragment::Format(PLACEHOLDER_PATTERN.replace_all(&bound, "{$2}").into_owned());
+        }
+        Fragment::Constant(bound)
+    }
+
+    /// Name of the secret global filling the fragment, if that is all it is
+    fn secret_name<'f>(&self, fragment: &'f Fragment) -> Option<&'f str> {
+        match fragment {
+            Fragment::Placeholder { name, .. }
+                if self.bindings.get(name) == Some(&Binding::Secret) =>
+            {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    fn describe(&self, name: &str) -> String {
+        self.metadata
+            .get(name)
+            .and_then(|m| m.description.clone())
+            .unwrap_or_else(|| format!("Parameter: {}", name))
+    }
+
+    fn placeholder_schema(&self, name: &str, var_type: &str) -> Value {
+        match self.bindings.get(name) {
+            Some(Binding::Value(value)) => {
+                let value = match var_type {
+                    "string" | "url" => json!(value),
+                    _ => serde_json::from_str(value).unwrap_or_else(|_| json!(value)),
+                };
+                json!({ "const": value })
+            }
+            Some(Binding::Secret) => json!({
+                "type": "string",
+                "description": format!("Secret credential '{}', held by the server", name),
+            }),
+            None => SchemaGenerator::build_property_schema(name, var_type, self.metadata.get(name)),
+        }
+    }
+
+    fn fragment_schema(&self, fragment: &Fragment) -> Value {
+        match fragment {
+            Fragment::Placeholder { name, var_type } => self.placeholder_schema(name, var_type),
+            Fragment::Constant(value) => json!({ "type": "string", "const": value }),
+            Fragment::Format(format) => json!({
+                "type": "string",
+                "description": format!("Formatted as `{}`", format),
+            }),
+        }
+    }
+
+    /// Schema of a JSON body template
+    ///
+    /// Placeholders may stand for whole values (`{"age": {{integer:age}}}`),
+    /// so each one is swapped for a token string before parsing and the
+    /// parsed document is walked to build the schema.
+    fn json_body_schema(&self, body: &str) -> Value {
+        let mut placeholders = Vec::new();
+        let mut tokenized = String::with_capacity(body.len());
+        let (mut in_string, mut escaped) = (false, false);
+        let mut last = 0;
+
+        for caps in PLACEHOLDER_PATTERN.captures_iter(body) {
+            let Some(whole) = caps.get(0) else {
+                continue;
+            };
+            for c in body[last..whole.start()].chars() {
+                if escaped {
+                    escaped = false;
+                } else if in_string && c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    in_string = !in_string;
+                }
+            }
+            tokenized.push_str(&body[last..whole.start()]);
+
+            let token = format!(
+                "{}{}{}",
+                BODY_TOKEN_PREFIX,
+                placeholders.len(),
+                BODY_TOKEN_SUFFIX
+            );
+            if in_string {
+                tokenized.push_str(&token);
+            } else {
+                tokenized.push_str(&format!("\"{}\"", token));
+            }
+            placeholders.push(whole.as_str().to_string());
+            last = whole.end();
+        }
+        tokenized.push_str(&body[last..]);
+
+        match serde_json::from_str::<Value>(&tokenized) {
+            Ok(value) => self.value_schema(&value, &placeholders),
+            Err(_) => json!({ "type": "string", "example": self.bind(body) }),
+        }
+    }
+
+    fn value_schema(&self, value: &Value, placeholders: &[String]) -> Value {
+        match value {
+            Value::String(s) => {
+                let restored =
+                    placeholders
+                        .iter()
+                        .enumerate()
+                        .fold(s.clone(), |text, (i, placeholder)| {
+                            text.replace(
+                                &format!("{}{}{}", BODY_TOKEN_PREFIX, i, BODY_TOKEN_SUFFIX),
+                                placeholder,
+                            )
+                        });
+                match self.fragment(&restored) {
+                    Fragment::Placeholder { name, var_type } => {
+                        self.placeholder_schema(&name, &var_type)
+                    }
+                    // Bound whole values keep their JSON type
+                    Fragment::Constant(_) if restored != *s => {
+                        self.placeholder_schema_from(&restored)
+                    }
+                    fragment => self.fragment_schema(&fragment),
+                }
+            }
+            Value::Array(items) => match items.first() {
+                Some(first) => json!({
+                    "type": "array",
+                    "items": self.value_schema(first, placeholders),
+                }),
+                None => json!({ "type": "array" }),
+            },
+            Value::Object(fields) => {
+                let properties: Map<String, Value> = fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), self.value_schema(value, placeholders)))
+                    .collect();
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": fields.keys().collect::<Vec<_>>(),
+                })
+            }
+            Value::Null => json!({ "type": "null" }),
+            other => json!({ "const": other }),
+        }
+    }
+
+    /// Schema of a bound placeholder that was the whole string
+    fn placeholder_schema_from(&self, placeholder: &str) -> Value {
+        match PLACEHOLDER_PATTERN.captures(placeholder) {
+            Some(caps) if caps[0].len() == placeholder.len() => {
+                self.placeholder_schema(&caps[2], caps.get(1).map_or("string", |m| m.as_str()))
+            }
+            _ => self.fragment_schema(&self.fragment(placeholder)),
+        }
+    }
+
+    fn form_body_schema(&self, body: &str) -> Value {
+        let mut properties = Map::new();
+        for pair in body.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            properties.insert(key.to_string(), self.fragment_schema(&self.fragment(value)));
+        }
+        let required: Vec<&String> = properties.keys().collect();
+        json!({ "type": "object", "properties": properties, "required": required })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, method: &str, url: &str, headers: &str, body: Option<&str>) -> Tool {
+        Tool {
+            id: 1,
+            toolkit_id: 1,
+            name: name.to_string(),
+            description: Some(format!("Calls {}", name)),
+            method: method.to_string(),
+            url: Some(url.to_string()),
+            headers: Some(headers.to_string()),
+            body: body.map(str::to_string),
+            timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: Some(
+                r#"{"city": {"description": "City name", "enum": ["Paris", "Oslo"]}}"#.to_string(),
+            ),
+            created_at: chrono::NaiveDateTime::default(),
+            updated_at: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    fn spec<'a>(tool: &'a Tool, bindings: &[(&str, Binding)]) -> OperationSpec<'a> {
+        OperationSpec {
+            tool,
+            operation_id: tool.name.clone(),
+            description: tool.description.clone(),
+            bindings: bindings
+                .iter()
+                .map(|(name, binding)| (name.to_string(), binding.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_maps_url_and_headers_to_parameters() {
+        let tool = tool(
+            "current_weather",
+            "GET",
+            "https://api.example.com/v1/{{string:country}}/weather?q={{string:city}}&units=metric&days={{integer:days}}",
+            r#"{"X-Trace": "{{string:trace}}", "Accept": "application/json"}"#,
+            None,
+        );
+        let doc = OpenApiExport::build("Weather", None, &[spec(&tool, &[])]);
+
+        assert_eq!(doc["openapi"], "3.1.0");
+        assert_eq!(doc["servers"][0]["url"], "https://api.example.com");
+
+        let op = &doc["paths"]["/v1/{country}/weather"]["get"];
+        assert_eq!(op["operationId"], "current_weather");
+        let params = op["parameters"].as_array().unwrap();
+        let find = |name: &str| params.iter().find(|p| p["name"] == name).unwrap().clone();
+
+        assert_eq!(find("country")["in"], "path");
+        assert_eq!(find("q")["schema"]["description"], "City name");
+        assert_eq!(find("q")["schema"]["enum"], json!(["Paris", "Oslo"]));
+        assert_eq!(find("units")["schema"]["const"], "metric");
+        assert_eq!(find("days")["schema"]["type"], "integer");
+        assert_eq!(find("X-Trace")["in"], "header");
+        assert!(params.iter().all(|p| p["name"] != "Accept"));
+    }
+
+    #[test]
+    fn test_builds_body_schema_from_json_template() {
+        let tool = tool(
+            "create_order",
+            "POST",
+            "{{url:base_url}}/orders",
+            r#"{"Content-Type": "application/json"}"#,
+            Some(
+                r#"{"item": "{{string:item}}", "qty": {{integer:qty}}, "note": "for {{string:who}}", "tags": ["a"]}"#,
+            ),
+        );
+        let doc = OpenApiExport::build("Shop", None, &[spec(&tool, &[])]);
+
+        assert_eq!(doc["servers"][0]["url"], "{base_url}");
+        assert!(doc["servers"][0]["variables"]["base_url"].is_object());
+
+        let schema = &doc["paths"]["/orders"]["post"]["requestBody"]["content"]["application/json"]
+            ["schema"];
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["properties"]["item"]["type"], "string");
+        assert_eq!(schema["properties"]["qty"]["type"], "integer");
+        assert_eq!(
+            schema["properties"]["note"]["description"],
+            "Formatted as `for {who}`"
+        );
+        assert_eq!(schema["properties"]["tags"]["items"]["const"], "a");
+    }
+
+    #[test]
+    fn test_server_bindings_fix_values_and_hide_secrets() {
+        let tool = tool(
+            "current_weather",
+            "POST",
+            "https://api.example.com/weather?q={{string:city}}&key={{string:api_key}}",
+            r#"{"Authorization": "Bearer {{string:token}}", "X-Region": "{{string:region}}"}"#,
+            Some(r#"{"days": {{integer:days}}}"#),
+        );
+        let doc = OpenApiExport::build(
+            "Prod",
+            None,
+            &[spec(
+                &tool,
+                &[
+                    ("api_key", Binding::Secret),
+                    ("token", Binding::Value("s3cret".to_string())),
+                    ("region", Binding::Value("eu".to_string())),
+                    ("days", Binding::Value("3".to_string())),
+                ],
+            )],
+        );
+        let text = doc.to_string();
+        assert!(!text.contains("s3cret"));
+
+        let op = &doc["paths"]["/weather"]["post"];
+        let params = op["parameters"].as_array().unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0]["name"], "q");
+        assert_eq!(params[1]["schema"]["const"], "eu");
+        assert_eq!(
+            op["security"][0],
+            json!({ "api_key": [], "bearerAuth": [] })
+        );
+        assert_eq!(
+            doc["components"]["securitySchemes"]["api_key"],
+            json!({ "type": "apiKey", "in": "query", "name": "key" })
+        );
+        assert_eq!(
+            op["requestBody"]["content"]["application/json"]["schema"]["properties"]["days"]
+                ["const"],
+            3
+        );
+    }
+
+    #[test]
+    fn test_separates_origins_and_skips_clashing_operations() {
+        let a = tool("a", "GET", "https://one.example.com/items", "{}", None);
+        let b = tool("b", "GET", "https://two.example.com/items", "{}", None);
+        let c = tool("c", "GET", "https://two.example.com/other", "{}", None);
+        let doc = OpenApiExport::build(
+            "Mixed",
+            Some("Two hosts"),
+            &[spec(&a, &[]), spec(&b, &[]), spec(&c, &[])],
+        );
+
+        assert!(doc.get("servers").is_none());
+        let op = &doc["paths"]["/items"]["get"];
+        assert_eq!(op["operationId"], "a");
+        assert_eq!(op["servers"][0]["url"], "https://one.example.com");
+        assert_eq!(
+            doc["paths"]["/other"]["get"]["servers"][0]["url"],
+            "https://two.example.com"
+        );
+        assert!(doc["info"]["description"]
+            .as_str()
+            .unwrap()
+            .ends_with("same method and path: b"));
+    }
+
+    #[test]
+    fn test_leaves_out_remote_mcp_tools() {
+        let a = tool("a", "GET", "https://api.example.com/items", "{}", None);
+        let b = tool("b", "MCP", "https://mcp.example.com/mcp", "{}", None);
+        let doc = OpenApiExport::build("Gateway", None, &[spec(&a, &[]), spec(&b, &[])]);
+
+        assert_eq!(doc["paths"].as_object().unwrap().len(), 1);
+        assert_eq!(
+            doc["info"]["description"],
+            "Left out because they call a remote MCP server: b"
+        );
+    }
+}
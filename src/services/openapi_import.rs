@@ -0,0 +1,994 @@
+//! OpenAPI 3 import
+//!
+//! Reads an OpenAPI 3.0 or 3.1 document, in JSON or YAML, into a
+//! [`ToolImport`] with one tool per operation:
+//!
+//! - path, query, header and cookie parameters and request body properties
+//!   become typed `{{type:name}}` placeholders, keeping their description and
+//!   enum as parameter metadata
+//! - security schemes become headers or query parameters whose placeholders
+//!   are suggested as secret server globals
+//! - the first server URL is the base URL; relative or missing ones are
+//!   prefixed with a `base_url` placeholder, also suggested as a global
+//!
+//! Every placeholder of a tool must be filled at call time, so optional
+//! parameters are left out (and listed as skipped) unless
+//! [`ImportOptions::include_optional`] is set, which makes them required.
+//! Local `$ref`s are followed; external ones are reported as warnings.
+
+use crate::models::ParameterMetadata;
+use crate::services::tool_import::{
+    snake_case, unique_name, ImportOptions, ImportedParameter, ImportedTool, SuggestedGlobal,
+    ToolImport,
+};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashSet};
+
+/// HTTP methods tools support, in the order operations are listed
+const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+/// Longest chain of `$ref`s followed before giving up
+const MAX_REF_DEPTH: usize = 32;
+
+/// Header parameters OpenAPI says to ignore, they are set by other means
+const IGNORED_HEADERS: [&str; 3] = ["accept", "content-type", "authorization"];
+
+static NULL: Value = Value::Null;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OpenApiImportError {
+    #[error("Document is neither valid JSON nor YAML: {0}")]
+    InvalidDocument(String),
+
+    #[error("Swagger 2.0 documents are not supported, convert them to OpenAPI 3 first")]
+    Swagger2,
+
+    #[error("Not an OpenAPI 3 document (openapi: {0})")]
+    UnsupportedVersion(String),
+
+    #[error("Document has no GET, POST, PUT, PATCH or DELETE operations")]
+    NoOperations,
+}
+
+/// Parses an OpenAPI document into a proposed toolkit
+pub fn parse(text: &str, options: ImportOptions) -> Result<ToolImport, OpenApiImportError> {
+    let root = parse_document(text)?;
+
+    match root.get("openapi").and_then(Value::as_str) {
+        Some(version) if version.starts_with("3.") => {}
+        Some(version) => return Err(OpenApiImportError::UnsupportedVersion(version.to_string())),
+        None if root.get("swagger").is_some() => return Err(OpenApiImportError::Swagger2),
+        None => {
+            return Err(OpenApiImportError::UnsupportedVersion(
+                "missing".to_string(),
+            ))
+        }
+    }
+
+    let document = Document {
+        root: &root,
+        options,
+        unresolved: RefCell::default(),
+    };
+    let mut import = document.build();
+
+    for reference in document.unresolved.into_inner() {
+        import
+            .warnings
+            .push(format!("Could not resolve reference {}", reference));
+    }
+
+    if import.tools.is_empty() {
+        return Err(OpenApiImportError::NoOperations);
+    }
+    Ok(import)
+}
+
+/// Reads JSON, falling back to YAML
+fn parse_document(text: &str) -> Result<Value, OpenApiImportError> {
+    if let Ok(value) = serde_json::from_str::<Value>(text) {
+        return Ok(value);
+    }
+    serde_yaml::from_str::<Value>(text)
+        .map_err(|e| OpenApiImportError::InvalidDocument(e.to_string()))
+}
+
+struct Document<'a> {
+    root: &'a Value,
+    options: ImportOptions,
+    unresolved: RefCell<BTreeSet<String>>,
+}
+
+/// Where a placeholder goes in the request
+enum Slot {
+    Path(String),
+    Query(String),
+    /// A header whose value is the placeholder after `prefix`, e.g. "Bearer "
+    Header {
+        name: String,
+        prefix: String,
+    },
+    Cookie(String),
+}
+
+impl<'a> Document<'a> {
+    fn build(&self) -> ToolImport {
+        let info = self.root.get("info").unwrap_or(&NULL);
+        let mut import = ToolImport {
+            title: info
+                .get("title")
+                .and_then(Value::as_str)
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or("Imported API")
+                .trim()
+                .to_string(),
+            description: info
+                .get("description")
+                .and_then(Value::as_str)
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            ..Default::default()
+        };
+
+        if let Some(paths) = self.root.get("paths").and_then(Value::as_object) {
+            for (path, item) in paths {
+                let item = self.resolve(item);
+                for method in METHODS {
+                    if let Some(operation) = item.get(method) {
+                        let tool = self.build_tool(path, method, item, operation, &mut import);
+                        import.tools.push(tool);
+                    }
+                }
+            }
+        }
+
+        import.dedupe_tool_names();
+        import
+    }
+
+    fn build_tool(
+        &self,
+        path: &str,
+        method: &str,
+        item: &'a Value,
+        operation: &'a Value,
+        import: &mut ToolImport,
+    ) -> ImportedTool {
+        let mut tool = ImportedTool {
+            key: format!("{} {}", method.to_uppercase(), path),
+            name: operation
+                .get("operationId")
+                .and_then(Value::as_str)
+                .map(snake_case)
+                .unwrap_or_else(|| snake_case(&format!("{} {}", method, path))),
+            description: describe(operation),
+            method: method.to_uppercase(),
+            ..Default::default()
+        };
+
+        let mut used = HashSet::new();
+        let mut path = path.to_string();
+        let mut query = Vec::new();
+        let mut cookies = Vec::new();
+
+        // Security first, so credentials keep the global's exact name
+        for (slot, parameter) in self.security(operation, import, &mut used) {
+            self.place(
+                slot,
+                parameter,
+                &mut tool,
+                &mut path,
+                &mut query,
+                &mut cookies,
+            );
+        }
+
+        for parameter in self.parameters(item, operation) {
+            let name = parameter.get("name").and_then(Value::as_str).unwrap_or("");
+            let location = parameter.get("in").and_then(Value::as_str).unwrap_or("");
+            if name.is_empty()
+                || (location == "header" && IGNORED_HEADERS.contains(&name.to_lowercase().as_str()))
+            {
+                continue;
+            }
+
+            let required = location == "path"
+                || parameter.get("required").and_then(Value::as_bool) == Some(true);
+            if !required && !self.options.include_optional {
+                tool.skipped.push(format!("{} ({})", name, location));
+                continue;
+            }
+
+            let schema = self.parameter_schema(parameter);
+            let imported = ImportedParameter {
+                name: unique_name(&snake_case(name), &mut used),
+                param_type: self.placeholder_type(schema).to_string(),
+                location: location.to_string(),
+                metadata: self.metadata(parameter.get("description"), schema),
+            };
+            let slot = match location {
+                "path" => Slot::Path(name.to_string()),
+                "query" => Slot::Query(name.to_string()),
+                "header" => Slot::Header {
+                    name: name.to_string(),
+                    prefix: String::new(),
+                },
+                "cookie" => Slot::Cookie(name.to_string()),
+                _ => continue,
+            };
+            self.place(
+                slot,
+                imported,
+                &mut tool,
+                &mut path,
+                &mut query,
+                &mut cookies,
+            );
+        }
+
+        self.request_body(operation, &mut tool, &mut used);
+
+        let base_url = self.base_url(item, operation, import);
+        tool.url = format!("{}{}", base_url, path);
+        if !query.is_empty() {
+            tool.url.push('?');
+            tool.url.push_str(&query.join("&"));
+        }
+        if !cookies.is_empty() {
+            tool.headers
+                .push(("Cookie".to_string(), cookies.join("; ")));
+        }
+
+        tool
+    }
+
+    /// Puts a parameter's placeholder where its value is sent
+    fn place(
+        &self,
+        slot: Slot,
+        parameter: ImportedParameter,
+        tool: &mut ImportedTool,
+        path: &mut String,
+        query: &mut Vec<String>,
+        cookies: &mut Vec<String>,
+    ) {
+        let placeholder = parameter.placeholder();
+        match slot {
+            Slot::Path(name) => *path = path.replace(&format!("{{{}}}", name), &placeholder),
+            Slot::Query(name) => query.push(format!("{}={}", name, placeholder)),
+            Slot::Header { name, prefix } => tool
+                .headers
+                .push((name, format!("{}{}", prefix, placeholder))),
+            Slot::Cookie(name) => cookies.push(format!("{}={}", name, placeholder)),
+        }
+        tool.parameters.push(parameter);
+    }
+
+    /// Path item parameters, overridden by operation ones with the same name and location
+    fn parameters(&self, item: &'a Value, operation: &'a Value) -> Vec<&'a Value> {
+        let list = |value: &'a Value| -> Vec<&'a Value> {
+            value
+                .get("parameters")
+                .and_then(Value::as_array)
+                .map(|params| params.iter().map(|p| self.resolve(p)).collect())
+                .unwrap_or_default()
+        };
+        let identity = |p: &Value| (p.get("name").cloned(), p.get("in").cloned());
+
+        let own = list(operation);
+        let mut merged: Vec<&Value> = list(item)
+            .into_iter()
+            .filter(|shared| !own.iter().any(|p| identity(p) == identity(shared)))
+            .collect();
+        merged.extend(own);
+        merged
+    }
+
+    fn parameter_schema(&self, parameter: &'a Value) -> &'a Value {
+        if let Some(schema) = parameter.get("schema") {
+            return self.resolve(schema);
+        }
+        // Parameters may describe their value as a media type instead
+        parameter
+            .get("content")
+            .and_then(Value::as_object)
+            .and_then(|content| content.values().next())
+            .and_then(|media| media.get("schema"))
+            .map(|schema| self.resolve(schema))
+            .unwrap_or(&NULL)
+    }
+
+    /// Credentials required by the operation, from its first security requirement
+    fn security(
+        &self,
+        operation: &Value,
+        import: &mut ToolImport,
+        used: &mut HashSet<String>,
+    ) -> Vec<(Slot, ImportedParameter)> {
+        let requirement = operation
+            .get("security")
+            .or_else(|| self.root.get("security"))
+            .and_then(Value::as_array)
+            .and_then(|alternatives| alternatives.first())
+            .and_then(Value::as_object);
+        let Some(requirement) = requirement else {
+            return Vec::new();
+        };
+
+        let mut credentials = Vec::new();
+        for scheme_name in requirement.keys() {
+            let scheme = self
+                .root
+                .get("components")
+                .and_then(|c| c.get("securitySchemes"))
+                .and_then(|schemes| schemes.get(scheme_name))
+                .map(|scheme| self.resolve(scheme))
+                .unwrap_or(&NULL);
+
+            let authorization = |prefix: &str| Slot::Header {
+                name: "Authorization".to_string(),
+                prefix: prefix.to_string(),
+            };
+            let (slot, description) = match scheme.get("type").and_then(Value::as_str) {
+                Some("apiKey") => {
+                    let name = scheme
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .unwrap_or(scheme_name)
+                        .to_string();
+                    let description = format!("API key sent as {}", name);
+                    let slot = match scheme.get("in").and_then(Value::as_str) {
+                        Some("query") => Slot::Query(name),
+                        Some("cookie") => Slot::Cookie(name),
+                        _ => Slot::Header {
+                            name,
+                            prefix: String::new(),
+                        },
+                    };
+                    (slot, description)
+                }
+                Some("http") => {
+                    let auth = scheme
+                        .get("scheme")
+                        .and_then(Value::as_str)
+                        .unwrap_or("bearer");
+                    match auth.to_lowercase().as_str() {
+                        "basic" => (
+                            authorization("Basic "),
+                            "Base64-encoded username:password".to_string(),
+                        ),
+                        "bearer" => (authorization("Bearer "), "Bearer token".to_string()),
+                        _ => (
+                            authorization(&format!("{} ", auth)),
+                            format!("{} credentials", auth),
+                        ),
+                    }
+                }
+                Some("oauth2") | Some("openIdConnect") => {
+                    (authorization("Bearer "), "OAuth access token".to_string())
+                }
+                Some("mutualTLS") => {
+                    import.warnings.push(format!(
+                        "{} uses mutual TLS, set up a client certificate in the server's network settings",
+                        scheme_name
+                    ));
+                    continue;
+                }
+                _ => {
+                    import.warnings.push(format!(
+                        "Security scheme {} is missing or not supported",
+                        scheme_name
+                    ));
+                    continue;
+                }
+            };
+
+            let name = snake_case(scheme_name);
+            let description = scheme
+                .get("description")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or(description);
+            import.suggest_global(SuggestedGlobal {
+                name: name.clone(),
+                description: description.clone(),
+                secret: true,
+                value: String::new(),
+            });
+            used.insert(name.clone());
+
+            let location = match slot {
+                Slot::Query(_) => "query",
+                Slot::Cookie(_) => "cookie",
+                _ => "header",
+            };
+            credentials.push((
+                slot,
+                ImportedParameter {
+                    name,
+                    param_type: "string".to_string(),
+                    location: location.to_string(),
+                    metadata: ParameterMetadata {
+                        description: Some(description),
+                        enum_values: Vec::new(),
+                        lookup: None,
+                    },
+                },
+            ));
+        }
+        credentials
+    }
+
+    /// Adds the request body, preferring JSON over form-encoded content
+    fn request_body(
+        &self,
+        operation: &'a Value,
+        tool: &mut ImportedTool,
+        used: &mut HashSet<String>,
+    ) {
+        let request_body = operation
+            .get("requestBody")
+            .map(|body| self.resolve(body))
+            .unwrap_or(&NULL);
+        let Some(content) = request_body
+            .get("content")
+            .and_then(Value::as_object)
+            .filter(|content| !content.is_empty())
+        else {
+            return;
+        };
+
+        let media_type = |wanted: fn(&str) -> bool| {
+            content.iter().find(|(media, _)| {
+                wanted(&media.split(';').next().unwrap_or("").trim().to_lowercase())
+            })
+        };
+        let (media, form) = match media_type(|m| m == "application/json" || m.ends_with("+json")) {
+            Some(found) => (found, false),
+            None => match media_type(|m| m == "application/x-www-form-urlencoded") {
+                Some(found) => (found, true),
+                None => {
+                    let media = content.keys().cloned().collect::<Vec<_>>().join(", ");
+                    tool.skipped.push(format!("request body ({})", media));
+                    return;
+                }
+            },
+        };
+
+        let (media_type, media) = media;
+        tool.headers
+            .push(("Content-Type".to_string(), media_type.clone()));
+        let schema = media
+            .get("schema")
+            .map(|s| self.resolve(s))
+            .unwrap_or(&NULL);
+        let (properties, required) = self.object_properties(schema);
+
+        if properties.is_empty() {
+            // Free-form or non-object bodies are passed through whole
+            let param_type = self.placeholder_type(schema);
+            let parameter = ImportedParameter {
+                name: unique_name("body", used),
+                param_type: param_type.to_string(),
+                location: "body".to_string(),
+                metadata: self.metadata(request_body.get("description"), schema),
+            };
+            tool.body = Some(if param_type == "string" && !form {
+                format!("\"{}\"", parameter.placeholder())
+            } else {
+                parameter.placeholder()
+            });
+            tool.parameters.push(parameter);
+            return;
+        }
+
+        let mut fields = Vec::new();
+        for (name, property) in properties {
+            if property.get("readOnly").and_then(Value::as_bool) == Some(true) {
+                continue;
+            }
+            if !required.contains(name) && !self.options.include_optional {
+                tool.skipped.push(format!("{} (body)", name));
+                continue;
+            }
+
+            let parameter = ImportedParameter {
+                name: unique_name(&snake_case(name), used),
+                param_type: self.placeholder_type(property).to_string(),
+                location: "body".to_string(),
+                metadata: self.metadata(None, property),
+            };
+            let placeholder = parameter.placeholder();
+            fields.push(if form {
+                format!("{}={}", name, placeholder)
+            } else if matches!(parameter.param_type.as_str(), "string" | "url") {
+                format!("  {}: \"{}\"", Value::from(name.as_str()), placeholder)
+            } else {
+                format!("  {}: {}", Value::from(name.as_str()), placeholder)
+            });
+            tool.parameters.push(parameter);
+        }
+
+        tool.body = Some(if form {
+            fields.join("&")
+        } else if fields.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}\n}}", fields.join(",\n"))
+        });
+    }
+
+    /// Properties and required property names of an object schema, merging `allOf`
+    fn object_properties(
+        &self,
+        schema: &'a Value,
+    ) -> (Vec<(&'a String, &'a Value)>, HashSet<String>) {
+        let mut properties = Vec::new();
+        let mut required = HashSet::new();
+        self.collect_properties(schema, &mut properties, &mut required, 0);
+        (properties, required)
+    }
+
+    fn collect_properties(
+        &self,
+        schema: &'a Value,
+        properties: &mut Vec<(&'a String, &'a Value)>,
+        required: &mut HashSet<String>,
+        depth: usize,
+    ) {
+        if depth > MAX_REF_DEPTH {
+            return;
+        }
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            for part in parts {
+                self.collect_properties(self.resolve(part), properties, required, depth + 1);
+            }
+        }
+        if let Some(own) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in own {
+                if !properties.iter().any(|(existing, _)| *existing == name) {
+                    properties.push((name, self.resolve(property)));
+                }
+            }
+        }
+        if let Some(names) = schema.get("required").and_then(Value::as_array) {
+            required.extend(names.iter().filter_map(Value::as_str).map(str::to_string));
+        }
+    }
+
+    /// Base URL of an operation, from the closest `servers` list
+    fn base_url(&self, item: &Value, operation: &Value, import: &mut ToolImport) -> String {
+        let server = [operation, item, self.root]
+            .into_iter()
+            .filter_map(|v| v.get("servers").and_then(Value::as_array))
+            .find_map(|servers| servers.first());
+
+        let mut url = server
+            .and_then(|s| s.get("url"))
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string();
+        if let Some(variables) = server
+            .and_then(|s| s.get("variables"))
+            .and_then(Value::as_object)
+        {
+            for (name, variable) in variables {
+                if let Some(default) = variable.get("default").and_then(Value::as_str) {
+                    url = url.replace(&format!("{{{}}}", name), default);
+                }
+            }
+        }
+        let url = url.trim_end_matches('/').to_string();
+
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return url;
+        }
+
+        import.suggest_global(SuggestedGlobal {
+            name: "base_url".to_string(),
+            description: "Base URL of the API, e.g. https://api.example.com".to_string(),
+            secret: false,
+            value: String::new(),
+        });
+        format!("{{{{url:base_url}}}}{}", url)
+    }
+
+    /// Follows local `$ref`s; unresolvable ones yield `null`
+    fn resolve(&self, mut value: &'a Value) -> &'a Value {
+        for _ in 0..MAX_REF_DEPTH {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                return value;
+            };
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                Some(target) => value = target,
+                None => {
+                    self.unresolved.borrow_mut().insert(reference.to_string());
+                    return &NULL;
+                }
+            }
+        }
+        &NULL
+    }
+
+    /// Placeholder type for a schema
+    fn placeholder_type(&self, schema: &Value) -> &'static str {
+        let schema = self.resolve(schema);
+        for combinator in ["allOf", "oneOf", "anyOf"] {
+            if let Some(first) = schema
+                .get(combinator)
+                .and_then(Value::as_array)
+                .and_then(|variants| variants.first())
+            {
+                return self.placeholder_type(first);
+            }
+        }
+
+        // OpenAPI 3.1 allows a list of types, such as ["string", "null"]
+        let declared = match schema.get("type") {
+            Some(Value::String(t)) => Some(t.as_str()),
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|t| *t != "null"),
+            _ => None,
+        };
+        let format = schema.get("format").and_then(Value::as_str);
+
+        match declared {
+            Some("string") if matches!(format, Some("uri") | Some("url")) => "url",
+            Some("integer") => "integer",
+            Some("number") => "number",
+            Some("boolean") => "boolean",
+            Some("object") | Some("array") => "json",
+            Some(_) => "string",
+            None if schema.get("properties").is_some() || schema.get("items").is_some() => "json",
+            None => match schema
+                .get("enum")
+                .and_then(Value::as_array)
+                .and_then(|values| values.first())
+            {
+                Some(Value::Number(n)) if n.is_i64() || n.is_u64() => "integer",
+                Some(Value::Number(_)) => "number",
+                Some(Value::Bool(_)) => "boolean",
+                _ => "string",
+            },
+        }
+    }
+
+    /// Description and allowed values, preferring the parameter's own description
+    fn metadata(&self, description: Option<&Value>, schema: &Value) -> ParameterMetadata {
+        let schema = self.resolve(schema);
+        let description = description
+            .or_else(|| schema.get("description"))
+            .and_then(Value::as_str)
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+
+        let enum_values = match (schema.get("enum"), schema.get("const")) {
+            (Some(Value::Array(values)), _) => values.clone(),
+            (None, Some(value)) => vec![value.clone()],
+            _ => Vec::new(),
+        };
+
+        ParameterMetadata {
+            description,
+            enum_values,
+            lookup: None,
+        }
+    }
+}
+
+/// Summary and description of an operation, as one tool description
+fn describe(operation: &Value) -> Option<String> {
+    let parts: Vec<&str> = ["summary", "description"]
+        .iter()
+        .filter_map(|key| operation.get(*key).and_then(Value::as_str))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match parts.as_slice() {
+        [] => None,
+        [summary, description] if summary == description => Some(summary.to_string()),
+        parts => Some(parts.join("\n\n")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const PETSTORE: &str = r#"
+openapi: 3.0.3
+info:
+  title: Petstore
+  description: Pets for sale
+servers:
+  - url: https://{region}.petstore.example.com/v1/
+    variables:
+      region:
+        default: eu
+security:
+  - ApiKeyAuth: []
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      summary: List pets
+      parameters:
+        - name: status
+          in: query
+          required: true
+          description: Status to filter by
+          schema:
+            type: string
+            enum: [available, sold]
+        - $ref: '#/components/parameters/Limit'
+    post:
+      operationId: createPet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/NewPet'
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        schema:
+          type: integer
+    delete:
+      security:
+        - BearerAuth: []
+      parameters:
+        - name: X-Request-ID
+          in: header
+          required: true
+          schema:
+            type: string
+components:
+  parameters:
+    Limit:
+      name: limit
+      in: query
+      schema:
+        type: integer
+  schemas:
+    NewPet:
+      type: object
+      required: [name]
+      properties:
+        id:
+          type: integer
+          readOnly: true
+        name:
+          type: string
+        tag:
+          type: string
+  securitySchemes:
+    ApiKeyAuth:
+      type: apiKey
+      in: header
+      name: X-API-Key
+    BearerAuth:
+      type: http
+      scheme: bearer
+"#;
+
+    fn tool<'a>(import: &'a ToolImport, key: &str) -> &'a ImportedTool {
+        import.tools.iter().find(|t| t.key == key).unwrap()
+    }
+
+    #[test]
+    fn test_parse_yaml_operations() {
+        let import = parse(PETSTORE, ImportOptions::default()).unwrap();
+
+        assert_eq!(import.title, "Petstore");
+        assert_eq!(import.description.as_deref(), Some("Pets for sale"));
+        let keys: Vec<&str> = import.tools.iter().map(|t| t.key.as_str()).collect();
+        assert_eq!(keys, ["GET /pets", "POST /pets", "DELETE /pets/{petId}"]);
+
+        let list = tool(&import, "GET /pets");
+        assert_eq!(list.name, "list_pets");
+        assert_eq!(list.description.as_deref(), Some("List pets"));
+        assert_eq!(
+            list.url,
+            "https://eu.petstore.example.com/v1/pets?status={{string:status}}"
+        );
+        assert_eq!(list.skipped, ["limit (query)"]);
+
+        let status = list.parameters.iter().find(|p| p.name == "status").unwrap();
+        assert_eq!(
+            status.metadata.description.as_deref(),
+            Some("Status to filter by")
+        );
+        assert_eq!(
+            status.metadata.enum_values,
+            [json!("available"), json!("sold")]
+        );
+    }
+
+    #[test]
+    fn test_security_schemes_become_secret_globals() {
+        let import = parse(PETSTORE, ImportOptions::default()).unwrap();
+
+        let list = tool(&import, "GET /pets");
+        assert!(list.headers.contains(&(
+            "X-API-Key".to_string(),
+            "{{string:api_key_auth}}".to_string()
+        )));
+
+        // Operation security replaces the document default
+        let delete = tool(&import, "DELETE /pets/{petId}");
+        assert_eq!(
+            delete.url,
+            "https://eu.petstore.example.com/v1/pets/{{integer:pet_id}}"
+        );
+        assert_eq!(
+            delete.headers,
+            [
+                (
+                    "Authorization".to_string(),
+                    "Bearer {{string:bearer_auth}}".to_string()
+                ),
+                (
+                    "X-Request-ID".to_string(),
+                    "{{string:x_request_id}}".to_string()
+                ),
+            ]
+        );
+
+        let globals: Vec<(&str, bool)> = import
+            .globals
+            .iter()
+            .map(|g| (g.name.as_str(), g.secret))
+            .collect();
+        assert_eq!(globals, [("api_key_auth", true), ("bearer_auth", true)]);
+    }
+
+    #[test]
+    fn test_json_body_from_referenced_schema() {
+        let import = parse(PETSTORE, ImportOptions::default()).unwrap();
+
+        let create = tool(&import, "POST /pets");
+        assert_eq!(
+            create.body.as_deref(),
+            Some("{\n  \"name\": \"{{string:name}}\"\n}")
+        );
+        assert!(create
+            .headers
+            .contains(&("Content-Type".to_string(), "application/json".to_string())));
+        assert_eq!(create.skipped, ["tag (body)"]);
+
+        let request = create.to_request();
+        assert!(crate::models::Tool::validate_body_template(
+            request.body.as_deref().unwrap(),
+            request.headers.as_deref()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_include_optional_parameters() {
+        let options = ImportOptions {
+            include_optional: true,
+            ..Default::default()
+        };
+        let import = parse(PETSTORE, options).unwrap();
+
+        let list = tool(&import, "GET /pets");
+        assert!(list
+            .url
+            .ends_with("?status={{string:status}}&limit={{integer:limit}}"));
+        assert!(list.skipped.is_empty());
+
+        let create = tool(&import, "POST /pets");
+        assert!(create
+            .body
+            .as_deref()
+            .unwrap()
+            .contains(r#""tag": "{{string:tag}}""#));
+    }
+
+    #[test]
+    fn test_parse_json_with_relative_server_and_form_body() {
+        let document = json!({
+            "openapi": "3.1.0",
+            "info": {"title": "Tokens"},
+            "servers": [{"url": "/api"}],
+            "paths": {
+                "/token": {
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/x-www-form-urlencoded": {
+                                    "schema": {
+                                        "type": "object",
+                                        "required": ["grant_type", "scope"],
+                                        "properties": {
+                                            "grant_type": {"const": "client_credentials"},
+                                            "scope": {"type": ["string", "null"]}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/upload": {
+                    "put": {
+                        "requestBody": {
+                            "content": {"multipart/form-data": {"schema": {"type": "object"}}}
+                        }
+                    }
+                }
+            }
+        });
+        let import = parse(&document.to_string(), ImportOptions::default()).unwrap();
+
+        let token = tool(&import, "POST /token");
+        assert_eq!(token.name, "post_token");
+        assert_eq!(token.url, "{{url:base_url}}/api/token");
+        assert_eq!(
+            token.body.as_deref(),
+            Some("grant_type={{string:grant_type}}&scope={{string:scope}}")
+        );
+        assert_eq!(
+            token.parameters[0].metadata.enum_values,
+            [json!("client_credentials")]
+        );
+        assert_eq!(import.globals[0].name, "base_url");
+        assert!(!import.globals[0].secret);
+
+        let upload = tool(&import, "PUT /upload");
+        assert_eq!(upload.body, None);
+        assert_eq!(upload.skipped, ["request body (multipart/form-data)"]);
+    }
+
+    #[test]
+    fn test_unresolved_references_are_reported() {
+        let document = json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Refs"},
+            "servers": [{"url": "https://api.example.com"}],
+            "paths": {
+                "/things": {
+                    "get": {"parameters": [{"$ref": "common.yaml#/parameters/Page"}]}
+                }
+            }
+        });
+        let import = parse(&document.to_string(), ImportOptions::default()).unwrap();
+
+        assert_eq!(import.tools[0].url, "https://api.example.com/things");
+        assert_eq!(
+            import.warnings,
+            ["Could not resolve reference common.yaml#/parameters/Page"]
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_documents() {
+        assert!(matches!(
+            parse("swagger: '2.0'", ImportOptions::default()),
+            Err(OpenApiImportError::Swagger2)
+        ));
+        assert!(matches!(
+            parse(r#"{"openapi": "2.5"}"#, ImportOptions::default()),
+            Err(OpenApiImportError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            parse("openapi: 3.0.0\npaths: {}", ImportOptions::default()),
+            Err(OpenApiImportError::NoOperations)
+        ));
+        assert!(matches!(
+            parse("{ not: [valid", ImportOptions::default()),
+            Err(OpenApiImportError::InvalidDocument(_))
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::models::{InstanceParam, ServerGlobal, Tool};
+use crate::models::{InstanceParam, ParameterMetadata, ServerGlobal, Tool};
 use crate::services::{
     variable_engine::{TypedVariableEngine, VariableType},
     SecretsManager,
@@ -8,6 +8,51 @@ use serde_json::Value;
 use sqlx::SqlitePool;
 use std::collections::HashMap;
 
+/// Exposed parameters a call left out or gave an invalid value
+///
+/// Returned (inside the `anyhow::Error`) by
+/// [`ParameterResolver::resolve_parameters`], so callers able to ask the
+/// user for the values can tell this failure apart.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnresolvedParameters {
+    pub missing: Vec<String>,
+    /// Parameter names with the reason their value was rejected
+    pub invalid: Vec<(String, String)>,
+}
+
+impl UnresolvedParameters {
+    fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.invalid.is_empty()
+    }
+
+    /// Names of all unresolved parameters
+    pub fn names(&self) -> Vec<&str> {
+        self.missing
+            .iter()
+            .chain(self.invalid.iter().map(|(name, _)| name))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+impl std::fmt::Display for UnresolvedParameters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if !self.missing.is_empty() {
+            parts.push(format!(
+                "Missing required parameter(s): {}",
+                self.missing.join(", ")
+            ));
+        }
+        for (name, reason) in &self.invalid {
+            parts.push(format!("Invalid parameter '{}': {}", name, reason));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+impl std::error::Error for UnresolvedParameters {}
+
 #[derive(Clone)]
 pub struct ParameterResolver {
     engine: TypedVariableEngine,
@@ -27,6 +72,10 @@ impl ParameterResolver {
     /// 1. Instance-level fixed values (with variable substitution)
     /// 2. Server-level defaults
     /// 3. Exposed parameters (provided by LLM at execution time)
+    ///
+    /// Fails with [`UnresolvedParameters`] when exposed parameters are
+    /// missing, or their values don't fit the placeholder type or the
+    /// parameter's allowed values.
     pub async fn resolve_parameters(
         &self,
         pool: &SqlitePool,
@@ -65,6 +114,8 @@ impl ParameterResolver {
 
         // Extract parameters dynamically from tool templates
         let extracted_params = tool.extract_parameters();
+        let metadata = tool.parameter_metadata_map();
+        let mut unresolved = UnresolvedParameters::default();
 
         // Build a map of param_name -> param_type for quick lookup
         let param_types: HashMap<String, String> = extracted_params
@@ -97,11 +148,26 @@ impl ParameterResolver {
                     }
                 }
                 "exposed" => {
-                    // Will be provided at execution time
-                    if let Some(llm_values) = &llm_provided {
-                        llm_values.get(&config.param_name).cloned()
-                    } else {
-                        None
+                    // Provided at execution time
+                    let provided = llm_provided
+                        .as_ref()
+                        .and_then(|values| values.get(&config.param_name))
+                        .filter(|value| !value.is_null());
+                    match provided {
+                        None => {
+                            unresolved.missing.push(config.param_name.clone());
+                            None
+                        }
+                        Some(value) => {
+                            let meta = metadata.get(&config.param_name);
+                            match self.check_exposed(value, param_type, meta) {
+                                Ok(()) => Some(value.clone()),
+                                Err(reason) => {
+                                    unresolved.invalid.push((config.param_name.clone(), reason));
+                                    None
+                                }
+                            }
+                        }
                     }
                 }
                 _ => None,
@@ -113,9 +179,56 @@ impl ParameterResolver {
             }
         }
 
+        if !unresolved.is_empty() {
+            return Err(unresolved.into());
+        }
         Ok(resolved)
     }
 
+    /// Checks a provided value against its placeholder type and allowed values
+    fn check_exposed(
+        &self,
+        value: &Value,
+        param_type: &str,
+        metadata: Option<&ParameterMetadata>,
+    ) -> std::result::Result<(), String> {
+        let text = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+
+        let structured = matches!(
+            param_type.to_lowercase().as_str(),
+            "json" | "object" | "array"
+        );
+        if !structured {
+            if value.is_array() || value.is_object() {
+                return Err(format!("expected a {} value", param_type));
+            }
+            self.cast_value(&text, param_type)
+                .map_err(|e| e.to_string())?;
+        }
+
+        if let Some(allowed) = metadata.map(|m| &m.enum_values).filter(|v| !v.is_empty()) {
+            let matches = allowed.iter().any(|a| match a {
+                Value::String(s) => *s == text,
+                // Numbers and booleans may be given as text
+                other => {
+                    other == value
+                        || serde_json::from_str::<Value>(&text).is_ok_and(|v| v == *other)
+                }
+            });
+            if !matches {
+                let allowed: Vec<String> = allowed
+                    .iter()
+                    .map(|a| a.as_str().map_or_else(|| a.to_string(), str::to_string))
+                    .collect();
+                return Err(format!("must be one of {}", allowed.join(", ")));
+            }
+        }
+        Ok(())
+    }
+
     /// Get exposed parameters for an instance
     pub async fn get_exposed_params(pool: &SqlitePool, instance_id: i64) -> Result<Vec<String>> {
         let params = InstanceParam::list_by_instance(pool, instance_id).await?;
@@ -155,6 +268,17 @@ impl ParameterResolver {
         var_type.cast(value)
     }
 
+    /// Decrypted values of a server's secret globals, for redacting them
+    pub async fn secret_values(&self, pool: &SqlitePool, server_id: i64) -> Result<Vec<String>> {
+        let mut values = Vec::new();
+        for record in ServerGlobal::list_by_server(pool, server_id).await? {
+            if record.is_secret.unwrap_or(false) {
+                values.push(self.secrets.decrypt(&record.value)?);
+            }
+        }
+        Ok(values)
+    }
+
     /// Load server globals including decrypted secrets
     async fn load_globals(
         &self,
@@ -257,18 +381,28 @@ mod tests {
         .await
         .unwrap();
 
-        // Resolve parameters without LLM input
-        let resolved = resolver
+        // Without LLM input the exposed parameter is missing
+        let err = resolver
             .resolve_parameters(&pool, instance_id, None)
             .await
-            .unwrap();
-
-        assert_eq!(resolved.get("url").unwrap().as_str().unwrap(), "endpoint");
+            .unwrap_err();
         assert_eq!(
-            resolved.get("timeout").unwrap().as_i64().unwrap(),
-            3000 // From server global
+            err.downcast_ref::<UnresolvedParameters>(),
+            Some(&UnresolvedParameters {
+                missing: vec!["debug".to_string()],
+                invalid: Vec::new(),
+            })
         );
-        // debug is exposed, so not in resolved without LLM input
+
+        // A value that isn't of the placeholder type is invalid
+        let mut llm_provided = HashMap::new();
+        llm_provided.insert("debug".to_string(), Value::from("maybe"));
+        let err = resolver
+            .resolve_parameters(&pool, instance_id, Some(llm_provided))
+            .await
+            .unwrap_err();
+        let unresolved = err.downcast_ref::<UnresolvedParameters>().unwrap();
+        assert_eq!(unresolved.names(), vec!["debug"]);
 
         // Resolve with LLM input
         let mut llm_provided = HashMap::new();
@@ -280,5 +414,10 @@ mod tests {
             .unwrap();
 
         assert!(resolved.get("debug").unwrap().as_bool().unwrap());
+        assert_eq!(resolved.get("url").unwrap().as_str().unwrap(), "endpoint");
+        assert_eq!(
+            resolved.get("timeout").unwrap().as_i64().unwrap(),
+            3000 // From server global
+        );
     }
 }
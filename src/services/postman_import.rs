@@ -0,0 +1,1037 @@
+//! Postman collection import
+//!
+//! Reads a Postman collection (format v2.1) into a [`ToolImport`] with one
+//! tool per request:
+//!
+//! - `{{variable}}` references become typed placeholders, `{{url:name}}` for
+//!   variables holding a URL and `{{string:name}}` otherwise; `:name` path
+//!   variables become placeholders too
+//! - collection variables used by the requests are suggested as server
+//!   globals, with their value as default; literal credentials from auth
+//!   settings go to secret globals instead of being hard-coded
+//! - raw, urlencoded and formdata bodies become raw, form-encoded and
+//!   multipart bodies with the matching `Content-Type`
+//! - folders prefix tool names, or with
+//!   [`ImportOptions::folders_as_toolkits`] put each top-level folder into its
+//!   own toolkit
+//!
+//! Variables the collection does not define (usually environment variables)
+//! stay plain parameters. Dynamic variables such as `{{$guid}}` have no
+//! equivalent and are reported as warnings.
+
+use crate::models::ParameterMetadata;
+use crate::services::tool_import::{
+    snake_case, ImportOptions, ImportedParameter, ImportedTool, SuggestedGlobal, ToolImport,
+};
+use base64::Engine;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashSet};
+
+// Hardcoded regex patterns - guaranteed to be valid at compile time
+#[allow(clippy::unwrap_used)]
+static VARIABLE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([^{}]+?)\s*\}\}").unwrap());
+
+#[allow(clippy::unwrap_used)]
+static PATH_VARIABLE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"/:([A-Za-z_][A-Za-z0-9_]*)").unwrap());
+
+#[allow(clippy::unwrap_used)]
+static PLACEHOLDER_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{(?:\w+:)?\w+\}\}").unwrap());
+
+/// Boundary of multipart bodies converted from form data
+const MULTIPART_BOUNDARY: &str = "saramcp-form-boundary";
+
+const METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// Variable names that hint at credentials
+const SECRET_HINTS: [&str; 6] = ["token", "secret", "password", "apikey", "api_key", "auth"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum PostmanImportError {
+    #[error("Collection is not valid JSON: {0}")]
+    InvalidJson(String),
+
+    #[error("Not a Postman v2.1 collection, export it as \"Collection v2.1\" from Postman")]
+    UnsupportedFormat,
+
+    #[error("Collection has no GET, POST, PUT, PATCH or DELETE requests")]
+    NoRequests,
+}
+
+/// Parses a Postman collection into proposed toolkits
+pub fn parse(text: &str, options: ImportOptions) -> Result<ToolImport, PostmanImportError> {
+    let root: Value =
+        serde_json::from_str(text).map_err(|e| PostmanImportError::InvalidJson(e.to_string()))?;
+
+    let info = root.get("info").unwrap_or(&Value::Null);
+    let schema = info.get("schema").and_then(Value::as_str).unwrap_or("");
+    let Some(items) = root.get("item").and_then(Value::as_array) else {
+        return Err(PostmanImportError::UnsupportedFormat);
+    };
+    if !schema.contains("v2.1") {
+        return Err(PostmanImportError::UnsupportedFormat);
+    }
+
+    let mut collection = Collection {
+        variables: root
+            .get("variable")
+            .and_then(Value::as_array)
+            .map(|vars| vars.iter().filter_map(Variable::from_json).collect())
+            .unwrap_or_default(),
+        options,
+        keys: HashSet::new(),
+        dynamic: BTreeSet::new(),
+    };
+
+    let mut import = ToolImport {
+        title: info
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or("Postman Collection")
+            .to_string(),
+        description: description(info),
+        ..Default::default()
+    };
+
+    collection.walk(items, &mut Vec::new(), root.get("auth"), &mut import);
+    collection.finish(&mut import);
+
+    if import.tools.is_empty() {
+        return Err(PostmanImportError::NoRequests);
+    }
+    Ok(import)
+}
+
+/// A collection variable
+struct Variable {
+    key: String,
+    name: String,
+    value: String,
+    description: Option<String>,
+    secret: bool,
+    used: bool,
+}
+
+impl Variable {
+    fn from_json(json: &Value) -> Option<Self> {
+        if json.get("disabled").and_then(Value::as_bool) == Some(true) {
+            return None;
+        }
+        let key = json.get("key").and_then(Value::as_str)?.to_string();
+        let value = match json.get("value") {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        let lowered = key.to_lowercase();
+
+        Some(Self {
+            name: variable_name(&key),
+            secret: json.get("type").and_then(Value::as_str) == Some("secret")
+                || SECRET_HINTS.iter().any(|hint| lowered.contains(hint)),
+            description: description(json),
+            value,
+            key,
+            used: false,
+        })
+    }
+
+    fn param_type(&self) -> &'static str {
+        if self.value.starts_with("http://") || self.value.starts_with("https://") {
+            "url"
+        } else {
+            "string"
+        }
+    }
+}
+
+struct Collection {
+    variables: Vec<Variable>,
+    options: ImportOptions,
+    /// Tool keys handed out, to keep them unique
+    keys: HashSet<String>,
+    /// Dynamic variables such as `$guid`, which cannot be mapped
+    dynamic: BTreeSet<String>,
+}
+
+impl Collection {
+    fn walk(
+        &mut self,
+        items: &[Value],
+        folders: &mut Vec<String>,
+        auth: Option<&Value>,
+        import: &mut ToolImport,
+    ) {
+        for item in items {
+            let name = item
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("Untitled")
+                .trim()
+                .to_string();
+            let auth = item.get("auth").or(auth);
+
+            if let Some(children) = item.get("item").and_then(Value::as_array) {
+                folders.push(name);
+                self.walk(children, folders, auth, import);
+                folders.pop();
+            } else if let Some(request) = item.get("request") {
+                if let Some(tool) = self.build_tool(&name, request, folders, auth, import) {
+                    import.tools.push(tool);
+                }
+            }
+        }
+    }
+
+    /// Suggests the used collection variables and reports what was not mapped
+    fn finish(&mut self, import: &mut ToolImport) {
+        for variable in self.variables.iter().filter(|v| v.used) {
+            import.suggest_global(SuggestedGlobal {
+                name: variable.name.clone(),
+                description: variable
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| format!("Collection variable {}", variable.key)),
+                secret: variable.secret,
+                value: variable.value.clone(),
+            });
+        }
+
+        for dynamic in &self.dynamic {
+            import.warnings.push(format!(
+                "Dynamic variable {{{{{}}}}} has no equivalent and is sent as-is",
+                dynamic
+            ));
+        }
+
+        import.dedupe_tool_names();
+    }
+
+    fn build_tool(
+        &mut self,
+        name: &str,
+        request: &Value,
+        folders: &[String],
+        auth: Option<&Value>,
+        import: &mut ToolImport,
+    ) -> Option<ImportedTool> {
+        // A request may be given as a bare URL
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or("GET")
+            .to_uppercase();
+
+        let path: Vec<&str> = folders
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(name))
+            .collect();
+        let mut key = path.join(" / ");
+        if !METHODS.contains(&method.as_str()) {
+            import
+                .warnings
+                .push(format!("{}: {} requests are not supported", key, method));
+            return None;
+        }
+        let mut n = 2;
+        while self.keys.contains(&key) {
+            key = format!("{} ({})", path.join(" / "), n);
+            n += 1;
+        }
+        self.keys.insert(key.clone());
+
+        let (group, prefix) = match folders.split_first() {
+            Some((first, rest)) if self.options.folders_as_toolkits => (Some(first.clone()), rest),
+            _ => (None, folders),
+        };
+        let tool_name = snake_case(
+            &prefix
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(name))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+
+        let mut tool = ImportedTool {
+            key,
+            name: tool_name,
+            description: description(request),
+            group,
+            method,
+            ..Default::default()
+        };
+
+        tool.url = self.url(request.get("url").or(Some(request)), &mut tool);
+
+        if let Some(headers) = request.get("header").and_then(Value::as_array) {
+            for header in headers {
+                if header.get("disabled").and_then(Value::as_bool) == Some(true) {
+                    continue;
+                }
+                let Some(name) = header.get("key").and_then(Value::as_str) else {
+                    continue;
+                };
+                let value = header.get("value").and_then(Value::as_str).unwrap_or("");
+                let value = self.convert(value, "header", &mut tool);
+                tool.headers.push((name.to_string(), value));
+            }
+        }
+
+        if let Some(auth) = request.get("auth").or(auth) {
+            self.auth(auth, &mut tool, import);
+        }
+        if let Some(body) = request.get("body") {
+            self.body(body, &mut tool);
+        }
+
+        Some(tool)
+    }
+
+    fn url(&mut self, url: Option<&Value>, tool: &mut ImportedTool) -> String {
+        let (raw, path_variables) = match url {
+            Some(Value::String(raw)) => (raw.clone(), None),
+            Some(url) => (
+                url.get("raw")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| url_from_parts(url)),
+                url.get("variable").and_then(Value::as_array),
+            ),
+            None => (String::new(), None),
+        };
+        let raw = raw.trim();
+
+        // Variables first, so that `{{baseUrl}}` is not taken for a path variable.
+        // Path variables (`/users/:id`) come before the query string only.
+        let url = self.convert(raw, "url", tool);
+        let (path, query) = match url.find('?') {
+            Some(i) => url.split_at(i),
+            None => (url.as_str(), ""),
+        };
+        let path = PATH_VARIABLE_PATTERN.replace_all(path, |caps: &Captures| {
+            let variable = &caps[1];
+            let documented = path_variables
+                .into_iter()
+                .flatten()
+                .find(|v| v.get("key").and_then(Value::as_str) == Some(variable));
+            let name = variable_name(variable);
+            if !tool.parameters.iter().any(|p| p.name == name) {
+                tool.parameters.push(ImportedParameter {
+                    name: name.clone(),
+                    param_type: "string".to_string(),
+                    location: "path".to_string(),
+                    metadata: ParameterMetadata {
+                        description: documented.and_then(description),
+                        enum_values: Vec::new(),
+                        lookup: None,
+                    },
+                });
+            }
+            format!("/{{{{string:{}}}}}", name)
+        });
+
+        let url = format!("{}{}", path, query);
+        if url.starts_with("http://") || url.starts_with("https://") || url.starts_with("{{") {
+            url
+        } else {
+            format!("https://{}", url)
+        }
+    }
+
+    /// Moves the request's auth settings into headers or query parameters
+    fn auth(&mut self, auth: &Value, tool: &mut ImportedTool, import: &mut ToolImport) {
+        let auth_type = auth.get("type").and_then(Value::as_str).unwrap_or("noauth");
+        let has_authorization = tool
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("authorization"));
+
+        match auth_type {
+            "noauth" | "inherit" => {}
+            "bearer" if !has_authorization => {
+                let token = auth_attribute(auth, "bearer", "token").unwrap_or_default();
+                let token = self.credential(&token, "bearer_token", "Bearer token", tool, import);
+                tool.headers
+                    .push(("Authorization".to_string(), format!("Bearer {}", token)));
+            }
+            "basic" if !has_authorization => {
+                let username = auth_attribute(auth, "basic", "username").unwrap_or_default();
+                let password = auth_attribute(auth, "basic", "password").unwrap_or_default();
+                let literal =
+                    !VARIABLE_PATTERN.is_match(&username) && !VARIABLE_PATTERN.is_match(&password);
+                if !literal {
+                    import.warnings.push(format!(
+                        "{}: basic auth uses variables, set basic_auth to the Base64 of username:password",
+                        tool.key
+                    ));
+                }
+                let encoded = if literal {
+                    base64::engine::general_purpose::STANDARD
+                        .encode(format!("{}:{}", username, password))
+                } else {
+                    String::new()
+                };
+                let credentials = self.credential(
+                    &encoded,
+                    "basic_auth",
+                    "Base64-encoded username:password",
+                    tool,
+                    import,
+                );
+                tool.headers.push((
+                    "Authorization".to_string(),
+                    format!("Basic {}", credentials),
+                ));
+            }
+            "apikey" => {
+                let name = auth_attribute(auth, "apikey", "key")
+                    .unwrap_or_else(|| "X-API-Key".to_string());
+                let value = auth_attribute(auth, "apikey", "value").unwrap_or_default();
+                let value = self.credential(
+                    &value,
+                    "api_key",
+                    &format!("API key sent as {}", name),
+                    tool,
+                    import,
+                );
+                if auth_attribute(auth, "apikey", "in").as_deref() == Some("query") {
+                    let separator = if tool.url.contains('?') { '&' } else { '?' };
+                    tool.url = format!("{}{}{}={}", tool.url, separator, name, value);
+                } else {
+                    tool.headers.push((name, value));
+                }
+            }
+            "bearer" | "basic" => {}
+            other => import.warnings.push(format!(
+                "{}: {} auth is not supported, add the credentials by hand",
+                tool.key, other
+            )),
+        }
+    }
+
+    /// Placeholder for a credential, never the literal value
+    ///
+    /// Values referencing variables keep them (marked secret); literal
+    /// values go to a secret global named `global` with the value as default.
+    fn credential(
+        &mut self,
+        value: &str,
+        global: &str,
+        description: &str,
+        tool: &mut ImportedTool,
+        import: &mut ToolImport,
+    ) -> String {
+        if VARIABLE_PATTERN.is_match(value) {
+            for caps in VARIABLE_PATTERN.captures_iter(value) {
+                if let Some(variable) = self.variables.iter_mut().find(|v| v.key == caps[1].trim())
+                {
+                    variable.secret = true;
+                }
+            }
+            return self.convert(value, "header", tool);
+        }
+
+        import.suggest_global(SuggestedGlobal {
+            name: global.to_string(),
+            description: description.to_string(),
+            secret: true,
+            value: value.to_string(),
+        });
+        if !tool.parameters.iter().any(|p| p.name == global) {
+            tool.parameters.push(ImportedParameter {
+                name: global.to_string(),
+                param_type: "string".to_string(),
+                location: "header".to_string(),
+                metadata: ParameterMetadata {
+                    description: Some(description.to_string()),
+                    enum_values: Vec::new(),
+                    lookup: None,
+                },
+            });
+        }
+        format!("{{{{string:{}}}}}", global)
+    }
+
+    fn body(&mut self, body: &Value, tool: &mut ImportedTool) {
+        if body.get("disabled").and_then(Value::as_bool) == Some(true) {
+            return;
+        }
+
+        match body.get("mode").and_then(Value::as_str) {
+            Some("raw") => {
+                let raw = body.get("raw").and_then(Value::as_str).unwrap_or("");
+                if raw.trim().is_empty() {
+                    return;
+                }
+                let text = self.convert(raw, "body", tool);
+                let language = body
+                    .pointer("/options/raw/language")
+                    .and_then(Value::as_str);
+                let content_type = match language {
+                    Some("json") => "application/json",
+                    Some("xml") => "application/xml",
+                    Some("html") => "text/html",
+                    Some("javascript") => "application/javascript",
+                    Some(_) => "text/plain",
+                    None => {
+                        let filled = PLACEHOLDER_PATTERN.replace_all(&text, "0");
+                        if serde_json::from_str::<Value>(&filled).is_ok() {
+                            "application/json"
+                        } else {
+                            "text/plain"
+                        }
+                    }
+                };
+                set_default_content_type(tool, content_type);
+                tool.body = Some(text);
+            }
+            Some("urlencoded") => {
+                let mut fields = Vec::new();
+                for (key, value) in enabled_fields(body.get("urlencoded"), tool) {
+                    let value = self.convert(&value, "body", tool);
+                    fields.push(format!(
+                        "{}={}",
+                        form_encode(&key),
+                        form_encode_template(&value)
+                    ));
+                }
+                set_default_content_type(tool, "application/x-www-form-urlencoded");
+                tool.body = Some(fields.join("&"));
+            }
+            Some("formdata") => {
+                let mut parts = String::new();
+                for (key, value) in enabled_fields(body.get("formdata"), tool) {
+                    let value = self.convert(&value, "body", tool);
+                    parts.push_str(&format!(
+                        "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                        MULTIPART_BOUNDARY,
+                        key.replace('"', "%22"),
+                        value
+                    ));
+                }
+                parts.push_str(&format!("--{}--\r\n", MULTIPART_BOUNDARY));
+
+                // The boundary must match the body, so any Content-Type is replaced
+                tool.headers
+                    .retain(|(name, _)| !name.eq_ignore_ascii_case("content-type"));
+                tool.headers.push((
+                    "Content-Type".to_string(),
+                    format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+                ));
+                tool.body = Some(parts);
+            }
+            Some("graphql") => {
+                let query = body
+                    .pointer("/graphql/query")
+                    .and_then(Value::as_str)
+                    .unwrap_or("");
+                let variables = body
+                    .pointer("/graphql/variables")
+                    .and_then(Value::as_str)
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .unwrap_or("{}");
+                let text = format!(
+                    "{{\"query\": {}, \"variables\": {}}}",
+                    Value::from(query),
+                    variables
+                );
+                let text = self.convert(&text, "body", tool);
+                set_default_content_type(tool, "application/json");
+                tool.body = Some(text);
+            }
+            Some(mode) => tool.skipped.push(format!("request body ({})", mode)),
+            None => {}
+        }
+    }
+
+    /// Replaces `{{variable}}` references with typed placeholders
+    fn convert(&mut self, text: &str, location: &str, tool: &mut ImportedTool) -> String {
+        VARIABLE_PATTERN
+            .replace_all(text, |caps: &Captures| {
+                let key = caps[1].trim();
+                if key.starts_with('$') {
+                    self.dynamic.insert(key.to_string());
+                    return caps[0].to_string();
+                }
+
+                let variable = self.variables.iter_mut().find(|v| v.key == key);
+                let (name, param_type, description) = match variable {
+                    Some(variable) => {
+                        variable.used = true;
+                        (
+                            variable.name.clone(),
+                            variable.param_type(),
+                            variable.description.clone(),
+                        )
+                    }
+                    None => (variable_name(key), "string", None),
+                };
+
+                if !tool.parameters.iter().any(|p| p.name == name) {
+                    tool.parameters.push(ImportedParameter {
+                        name: name.clone(),
+                        param_type: param_type.to_string(),
+                        location: location.to_string(),
+                        metadata: ParameterMetadata {
+                            description,
+                            enum_values: Vec::new(),
+                            lookup: None,
+                        },
+                    });
+                }
+                format!("{{{{{}:{}}}}}", param_type, name)
+            })
+            .into_owned()
+    }
+}
+
+/// Keeps identifiers usable as placeholder names as they are, others in snake_case
+fn variable_name(key: &str) -> String {
+    let valid = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        key.to_string()
+    } else {
+        snake_case(key)
+    }
+}
+
+/// Description of an item, which may be a string or `{"content": ...}`
+fn description(json: &Value) -> Option<String> {
+    let description = json.get("description")?;
+    description
+        .as_str()
+        .or_else(|| description.get("content").and_then(Value::as_str))
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+}
+
+/// Builds a raw URL from its parts when a collection leaves `raw` out
+fn url_from_parts(url: &Value) -> String {
+    let join = |key: &str, separator: &str| match url.get(key) {
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(separator),
+        Some(Value::String(part)) => part.clone(),
+        _ => String::new(),
+    };
+
+    let mut raw = String::new();
+    if let Some(protocol) = url.get("protocol").and_then(Value::as_str) {
+        raw.push_str(&format!("{}://", protocol));
+    }
+    raw.push_str(&join("host", "."));
+    let path = join("path", "/");
+    if !path.is_empty() {
+        raw.push('/');
+        raw.push_str(&path);
+    }
+
+    let query: Vec<String> = url
+        .get("query")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|q| q.get("disabled").and_then(Value::as_bool) != Some(true))
+        .filter_map(|q| {
+            let key = q.get("key").and_then(Value::as_str)?;
+            let value = q.get("value").and_then(Value::as_str).unwrap_or("");
+            Some(format!("{}={}", key, value))
+        })
+        .collect();
+    if !query.is_empty() {
+        raw.push('?');
+        raw.push_str(&query.join("&"));
+    }
+    raw
+}
+
+/// Value of an auth setting, e.g. the `token` of a bearer auth
+fn auth_attribute(auth: &Value, auth_type: &str, key: &str) -> Option<String> {
+    auth.get(auth_type)?
+        .as_array()?
+        .iter()
+        .find(|attribute| attribute.get("key").and_then(Value::as_str) == Some(key))
+        .and_then(|attribute| attribute.get("value"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Enabled text fields of a urlencoded or formdata body; file fields are skipped
+fn enabled_fields(fields: Option<&Value>, tool: &mut ImportedTool) -> Vec<(String, String)> {
+    let mut enabled = Vec::new();
+    for field in fields.and_then(Value::as_array).into_iter().flatten() {
+        if field.get("disabled").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        let Some(key) = field.get("key").and_then(Value::as_str) else {
+            continue;
+        };
+        if field.get("type").and_then(Value::as_str) == Some("file") {
+            tool.skipped.push(format!("{} (file)", key));
+            continue;
+        }
+        let value = field.get("value").and_then(Value::as_str).unwrap_or("");
+        enabled.push((key.to_string(), value.to_string()));
+    }
+    enabled
+}
+
+fn set_default_content_type(tool: &mut ImportedTool, content_type: &str) {
+    if !tool
+        .headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+        tool.headers
+            .push(("Content-Type".to_string(), content_type.to_string()));
+    }
+}
+
+fn form_encode(text: &str) -> String {
+    form_urlencoded::byte_serialize(text.as_bytes()).collect()
+}
+
+/// Form-encodes the literal parts of a template, leaving placeholders intact
+fn form_encode_template(template: &str) -> String {
+    let mut encoded = String::new();
+    let mut last = 0;
+    for placeholder in PLACEHOLDER_PATTERN.find_iter(template) {
+        encoded.push_str(&form_encode(&template[last..placeholder.start()]));
+        encoded.push_str(placeholder.as_str());
+        last = placeholder.end();
+    }
+    encoded.push_str(&form_encode(&template[last..]));
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn collection(items: Value) -> String {
+        json!({
+            "info": {
+                "name": "Shop API",
+                "description": "Shop endpoints",
+                "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+            },
+            "variable": [
+                {"key": "baseUrl", "value": "https://shop.example.com"},
+                {"key": "api-token", "value": "abc123", "description": "Personal access token"},
+                {"key": "unused", "value": "x"}
+            ],
+            "item": items
+        })
+        .to_string()
+    }
+
+    fn shop() -> String {
+        collection(json!([
+            {
+                "name": "Orders",
+                "item": [
+                    {
+                        "name": "Get Order",
+                        "request": {
+                            "method": "GET",
+                            "header": [
+                                {"key": "Authorization", "value": "Bearer {{api-token}}"},
+                                {"key": "X-Debug", "value": "1", "disabled": true}
+                            ],
+                            "url": {
+                                "raw": "{{baseUrl}}/orders/:orderId?expand={{expand}}",
+                                "variable": [{"key": "orderId", "description": "Order number"}]
+                            },
+                            "description": "Fetch one order"
+                        }
+                    },
+                    {
+                        "name": "Items",
+                        "item": [{
+                            "name": "Add Item",
+                            "request": {
+                                "method": "POST",
+                                "url": "{{baseUrl}}/orders/:orderId/items",
+                                "body": {
+                                    "mode": "raw",
+                                    "raw": "{\"sku\": \"{{sku}}\", \"quantity\": {{quantity}}}",
+                                    "options": {"raw": {"language": "json"}}
+                                }
+                            }
+                        }]
+                    }
+                ]
+            },
+            {
+                "name": "Health",
+                "request": {"method": "HEAD", "url": "{{baseUrl}}/health"}
+            }
+        ]))
+    }
+
+    #[test]
+    fn test_folders_prefix_tool_names() {
+        let import = parse(&shop(), ImportOptions::default()).unwrap();
+
+        assert_eq!(import.title, "Shop API");
+        assert_eq!(import.description.as_deref(), Some("Shop endpoints"));
+        let names: Vec<&str> = import.tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["orders_get_order", "orders_items_add_item"]);
+        assert!(import.tools.iter().all(|t| t.group.is_none()));
+        assert_eq!(import.warnings, ["Health: HEAD requests are not supported"]);
+    }
+
+    #[test]
+    fn test_folders_as_toolkits() {
+        let options = ImportOptions {
+            folders_as_toolkits: true,
+            ..Default::default()
+        };
+        let import = parse(&shop(), options).unwrap();
+
+        let tool = &import.tools[1];
+        assert_eq!(tool.key, "Orders / Items / Add Item");
+        assert_eq!(tool.group.as_deref(), Some("Orders"));
+        assert_eq!(tool.name, "items_add_item");
+    }
+
+    #[test]
+    fn test_variables_become_placeholders_and_globals() {
+        let import = parse(&shop(), ImportOptions::default()).unwrap();
+
+        let get = &import.tools[0];
+        assert_eq!(get.description.as_deref(), Some("Fetch one order"));
+        assert_eq!(
+            get.url,
+            "{{url:baseUrl}}/orders/{{string:orderId}}?expand={{string:expand}}"
+        );
+        assert_eq!(
+            get.headers,
+            [(
+                "Authorization".to_string(),
+                "Bearer {{string:api_token}}".to_string()
+            )]
+        );
+        let order_id = get.parameters.iter().find(|p| p.name == "orderId").unwrap();
+        assert_eq!(order_id.location, "path");
+        assert_eq!(
+            order_id.metadata.description.as_deref(),
+            Some("Order number")
+        );
+
+        // Only used collection variables are offered, undefined ones stay parameters
+        let globals: Vec<(&str, &str, bool)> = import
+            .globals
+            .iter()
+            .map(|g| (g.name.as_str(), g.value.as_str(), g.secret))
+            .collect();
+        assert_eq!(
+            globals,
+            [
+                ("baseUrl", "https://shop.example.com", false),
+                ("api_token", "abc123", true)
+            ]
+        );
+        assert_eq!(import.globals[1].description, "Personal access token");
+    }
+
+    #[test]
+    fn test_raw_json_body() {
+        let import = parse(&shop(), ImportOptions::default()).unwrap();
+
+        let add = &import.tools[1];
+        assert_eq!(
+            add.body.as_deref(),
+            Some(r#"{"sku": "{{string:sku}}", "quantity": {{string:quantity}}}"#)
+        );
+        assert_eq!(
+            add.headers,
+            [("Content-Type".to_string(), "application/json".to_string())]
+        );
+
+        let request = add.to_request();
+        assert!(crate::models::Tool::validate_body_template(
+            request.body.as_deref().unwrap(),
+            request.headers.as_deref()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_urlencoded_and_formdata_bodies() {
+        let text = collection(json!([
+            {
+                "name": "Login",
+                "request": {
+                    "method": "POST",
+                    "url": "https://shop.example.com/login",
+                    "body": {
+                        "mode": "urlencoded",
+                        "urlencoded": [
+                            {"key": "user name", "value": "{{user}}"},
+                            {"key": "note", "value": "a&b {{note}}"},
+                            {"key": "old", "value": "x", "disabled": true}
+                        ]
+                    }
+                }
+            },
+            {
+                "name": "Upload",
+                "request": {
+                    "method": "POST",
+                    "header": [{"key": "Content-Type", "value": "multipart/form-data"}],
+                    "url": "shop.example.com/upload",
+                    "body": {
+                        "mode": "formdata",
+                        "formdata": [
+                            {"key": "title", "value": "{{title}}", "type": "text"},
+                            {"key": "file", "src": "/tmp/a.png", "type": "file"}
+                        ]
+                    }
+                }
+            }
+        ]));
+        let import = parse(&text, ImportOptions::default()).unwrap();
+
+        let login = &import.tools[0];
+        assert_eq!(
+            login.body.as_deref(),
+            Some("user+name={{string:user}}&note=a%26b+{{string:note}}")
+        );
+        assert_eq!(
+            login.headers,
+            [(
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded".to_string()
+            )]
+        );
+
+        let upload = &import.tools[1];
+        assert_eq!(upload.url, "https://shop.example.com/upload");
+        assert_eq!(
+            upload.body.as_deref(),
+            Some(
+                "--saramcp-form-boundary\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\n{{string:title}}\r\n--saramcp-form-boundary--\r\n"
+            )
+        );
+        assert_eq!(
+            upload.headers,
+            [(
+                "Content-Type".to_string(),
+                "multipart/form-data; boundary=saramcp-form-boundary".to_string()
+            )]
+        );
+        assert_eq!(upload.skipped, ["file (file)"]);
+    }
+
+    #[test]
+    fn test_literal_auth_goes_to_secret_globals() {
+        let text = json!({
+            "info": {
+                "name": "Auth",
+                "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+            },
+            "auth": {
+                "type": "basic",
+                "basic": [
+                    {"key": "username", "value": "admin"},
+                    {"key": "password", "value": "pw"}
+                ]
+            },
+            "item": [
+                {"name": "Me", "request": {"method": "GET", "url": "https://a.example.com/me"}},
+                {
+                    "name": "Search",
+                    "request": {
+                        "method": "GET",
+                        "url": "https://a.example.com/search",
+                        "auth": {
+                            "type": "apikey",
+                            "apikey": [
+                                {"key": "key", "value": "key"},
+                                {"key": "value", "value": "k-123"},
+                                {"key": "in", "value": "query"}
+                            ]
+                        }
+                    }
+                }
+            ]
+        })
+        .to_string();
+        let import = parse(&text, ImportOptions::default()).unwrap();
+
+        assert_eq!(
+            import.tools[0].headers,
+            [(
+                "Authorization".to_string(),
+                "Basic {{string:basic_auth}}".to_string()
+            )]
+        );
+        assert_eq!(
+            import.tools[1].url,
+            "https://a.example.com/search?key={{string:api_key}}"
+        );
+
+        let basic = &import.globals[0];
+        assert_eq!(basic.name, "basic_auth");
+        assert_eq!(basic.value, "YWRtaW46cHc=");
+        assert!(basic.secret);
+        assert_eq!(import.globals[1].value, "k-123");
+    }
+
+    #[test]
+    fn test_dynamic_variables_are_reported() {
+        let text = collection(json!([{
+            "name": "Create",
+            "request": {
+                "method": "POST",
+                "url": "https://shop.example.com/things",
+                "header": [{"key": "Idempotency-Key", "value": "{{$guid}}"}]
+            }
+        }]));
+        let import = parse(&text, ImportOptions::default()).unwrap();
+
+        assert_eq!(import.tools[0].headers[0].1, "{{$guid}}");
+        assert_eq!(
+            import.warnings,
+            ["Dynamic variable {{$guid}} has no equivalent and is sent as-is"]
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_documents() {
+        assert!(matches!(
+            parse("not json", ImportOptions::default()),
+            Err(PostmanImportError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            parse(r#"{"openapi": "3.0.0"}"#, ImportOptions::default()),
+            Err(PostmanImportError::UnsupportedFormat)
+        ));
+        let v2 = r#"{"info": {"schema": "https://schema.getpostman.com/json/collection/v2.0.0/collection.json"}, "item": []}"#;
+        assert!(matches!(
+            parse(v2, ImportOptions::default()),
+            Err(PostmanImportError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            parse(&collection(json!([])), ImportOptions::default()),
+            Err(PostmanImportError::NoRequests)
+        ));
+    }
+}
@@ -0,0 +1,518 @@
+//! Token-bucket rate limiting of upstream calls
+//!
+//! Agents can fire dozens of tool calls per second, which gets API keys
+//! banned by third-party services. Tools, toolkits and servers can declare a
+//! [`RateLimitConfig`]; before a request is handed to the
+//! [`HttpExecutor`], [`RateLimiter::acquire`] takes one token from every
+//! applicable bucket:
+//!
+//! - `tool:{id}` - the tool's own limit
+//! - `toolkit:{id}:{host}` - the toolkit's limit, per upstream host
+//! - `server:{id}` - the server's limit, shared by all its tools
+//!
+//! A call that finds a bucket empty either waits for the next token or fails
+//! immediately, depending on the bucket's [`RateLimitMode`]. Waiting longer
+//! than `max_wait_ms` always fails.
+//!
+//! Upstream services also tell us when to slow down. After every response,
+//! [`RateLimiter::observe_response`] reads `Retry-After` and
+//! `X-RateLimit-Remaining`/`X-RateLimit-Reset` and pauses further calls to
+//! that host until the announced time.
+//!
+//! Buckets are kept in memory and shared by every executor of the process
+//! through [`RateLimiter::shared`].
+//!
+//! [`HttpExecutor`]: crate::services::HttpExecutor
+
+use crate::models::rate_limit::{RateLimitConfig, RateLimitMode, DEFAULT_MAX_WAIT_MS};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Longest pause honoured from upstream rate-limit headers
+const MAX_UPSTREAM_BACKOFF: Duration = Duration::from_secs(3600);
+
+/// Pause after a 429 response that gives no hint about when to retry
+const DEFAULT_TOO_MANY_REQUESTS_BACKOFF: Duration = Duration::from_secs(1);
+
+/// `X-RateLimit-Reset` values above this are Unix timestamps, not seconds
+const EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+static SHARED_LIMITER: Lazy<RateLimiter> = Lazy::new(RateLimiter::new);
+
+/// A rate limit applying to a call, with the bucket it draws from
+#[derive(Debug, Clone)]
+pub struct RateLimitRule {
+    key: String,
+    scope: String,
+    config: RateLimitConfig,
+}
+
+impl RateLimitRule {
+    pub fn tool(tool_id: i64, config: RateLimitConfig) -> Self {
+        Self {
+            key: format!("tool:{}", tool_id),
+            scope: "tool".to_string(),
+            config,
+        }
+    }
+
+    pub fn toolkit(toolkit_id: i64, host: &str, config: RateLimitConfig) -> Self {
+        Self {
+            key: format!("toolkit:{}:{}", toolkit_id, host),
+            scope: format!("toolkit for {}", host),
+            config,
+        }
+    }
+
+    pub fn server(server_id: i64, config: RateLimitConfig) -> Self {
+        Self {
+            key: format!("server:{}", server_id),
+            scope: "server".to_string(),
+            config,
+        }
+    }
+}
+
+/// A call refused because a rate limit is exhausted
+#[derive(Debug, Clone)]
+pub struct RateLimited {
+    /// Which limit refused the call (tool, toolkit, server or upstream host)
+    pub scope: String,
+    /// When a token will be available again
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rate limit exceeded, retry in {} ms",
+            self.scope,
+            self.retry_after.as_millis()
+        )
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+#[derive(Debug)]
+struct Bucket {
+    config: RateLimitConfig,
+    /// Available tokens; negative while calls are waiting for reserved ones
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn new(config: &RateLimitConfig, now: Instant) -> Self {
+        Self {
+            config: config.clone(),
+            tokens: config.capacity(),
+            updated_at: now,
+        }
+    }
+
+    /// Adds the tokens earned since the last update, picking up config edits
+    fn refill(&mut self, config: &RateLimitConfig, now: Instant) {
+        if &self.config != config {
+            self.tokens = self.tokens.min(config.capacity());
+            self.config = config.clone();
+        }
+
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * self.config.refill_per_second()).min(self.config.capacity());
+        self.updated_at = now;
+    }
+
+    /// How long until one token is available
+    fn wait(&self) -> Duration {
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.config.refill_per_second())
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    buckets: HashMap<String, Bucket>,
+    /// Hosts that asked us to back off, with the time calls may resume
+    upstream_cooldowns: HashMap<String, Instant>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl RateLimiter {
+    /// Creates an empty limiter, independent from the shared one
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the process-wide limiter
+    pub fn shared() -> Self {
+        SHARED_LIMITER.clone()
+    }
+
+    /// Takes a token from every rule's bucket, waiting if needed
+    ///
+    /// `host` is the upstream host of the request; calls to a host that
+    /// announced a rate limit are delayed until it resets. Returns how long
+    /// the call waited.
+    ///
+    /// Fails without consuming tokens when a reject-mode bucket is empty or
+    /// the wait would exceed the smallest `max_wait_ms` of the rules.
+    pub async fn acquire(
+        &self,
+        rules: &[RateLimitRule],
+        host: Option<&str>,
+    ) -> Result<Duration, RateLimited> {
+        let wait = self.reserve(rules, host, Instant::now())?;
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(wait)
+    }
+
+    fn reserve(
+        &self,
+        rules: &[RateLimitRule],
+        host: Option<&str>,
+        now: Instant,
+    ) -> Result<Duration, RateLimited> {
+        let Ok(mut state) = self.state.lock() else {
+            return Ok(Duration::ZERO);
+        };
+
+        let max_wait = Duration::from_millis(
+            rules
+                .iter()
+                .map(|rule| rule.config.max_wait_ms)
+                .min()
+                .unwrap_or(DEFAULT_MAX_WAIT_MS),
+        );
+        let rejects = rules
+            .iter()
+            .any(|rule| rule.config.mode == RateLimitMode::Reject);
+
+        let mut wait = Duration::ZERO;
+
+        for rule in rules {
+            let bucket = state
+                .buckets
+                .entry(rule.key.clone())
+                .or_insert_with(|| Bucket::new(&rule.config, now));
+            bucket.refill(&rule.config, now);
+
+            let bucket_wait = bucket.wait();
+            if !bucket_wait.is_zero()
+                && (rule.config.mode == RateLimitMode::Reject || bucket_wait > max_wait)
+            {
+                return Err(RateLimited {
+                    scope: rule.scope.clone(),
+                    retry_after: bucket_wait,
+                });
+            }
+            wait = wait.max(bucket_wait);
+        }
+
+        if let Some(host) = host {
+            match state.upstream_cooldowns.get(host).copied() {
+                Some(until) if until > now => {
+                    let cooldown = until - now;
+                    if rejects || cooldown > max_wait {
+                        return Err(RateLimited {
+                            scope: format!("upstream {}", host),
+                            retry_after: cooldown,
+                        });
+                    }
+                    wait = wait.max(cooldown);
+                }
+                Some(_) => {
+                    state.upstream_cooldowns.remove(host);
+                }
+                None => {}
+            }
+        }
+
+        for rule in rules {
+            if let Some(bucket) = state.buckets.get_mut(&rule.key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+
+        Ok(wait)
+    }
+
+    /// Pauses calls to `host` when a response announces a rate limit
+    ///
+    /// `headers` are the response headers as returned by the
+    /// [`HttpExecutor`](crate::services::HttpExecutor).
+    pub fn observe_response(&self, host: &str, status: u16, headers: &HashMap<String, String>) {
+        let Some(backoff) = upstream_backoff(status, headers, SystemTime::now()) else {
+            return;
+        };
+
+        tracing::info!(
+            host,
+            backoff_ms = backoff.as_millis() as u64,
+            "Upstream rate limit reached, pausing calls"
+        );
+
+        if let Ok(mut state) = self.state.lock() {
+            let until = Instant::now() + backoff;
+            let entry = state
+                .upstream_cooldowns
+                .entry(host.to_string())
+                .or_insert(until);
+            *entry = (*entry).max(until);
+        }
+    }
+
+    /// Remaining pause of a host that announced a rate limit, if any
+    pub fn upstream_cooldown(&self, host: &str) -> Option<Duration> {
+        let state = self.state.lock().ok()?;
+        let until = *state.upstream_cooldowns.get(host)?;
+        until
+            .checked_duration_since(Instant::now())
+            .filter(|d| !d.is_zero())
+    }
+}
+
+/// How long to stop calling a host, from its rate-limit response headers
+///
+/// Understands `Retry-After` (seconds or HTTP date) and a zero
+/// `X-RateLimit-Remaining` / `RateLimit-Remaining` with the matching
+/// `*-Reset` header (seconds, or a Unix timestamp). A 429 without any hint
+/// pauses for one second.
+fn upstream_backoff(
+    status: u16,
+    headers: &HashMap<String, String>,
+    now: SystemTime,
+) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    };
+
+    let backoff = if let Some(retry_after) = header("retry-after") {
+        parse_retry_after(retry_after, now)
+    } else {
+        let remaining = header("x-ratelimit-remaining").or_else(|| header("ratelimit-remaining"));
+        match remaining.and_then(|r| r.parse::<f64>().ok()) {
+            Some(remaining) if remaining <= 0.0 => header("x-ratelimit-reset")
+                .or_else(|| header("ratelimit-reset"))
+                .and_then(|reset| parse_reset(reset, now)),
+            _ => None,
+        }
+    };
+
+    let backoff = match backoff {
+        Some(backoff) => backoff,
+        None if status == 429 => DEFAULT_TOO_MANY_REQUESTS_BACKOFF,
+        None => return None,
+    };
+
+    Some(backoff.min(MAX_UPSTREAM_BACKOFF)).filter(|d| !d.is_zero())
+}
+
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let at = UNIX_EPOCH + Duration::from_secs(u64::try_from(date.timestamp()).ok()?);
+    Some(at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+fn parse_reset(value: &str, now: SystemTime) -> Option<Duration> {
+    let reset = value.parse::<f64>().ok().filter(|r| *r >= 0.0)?;
+
+    if reset >= EPOCH_THRESHOLD as f64 {
+        let at = UNIX_EPOCH + Duration::from_secs_f64(reset);
+        Some(at.duration_since(now).unwrap_or(Duration::ZERO))
+    } else {
+        Some(Duration::from_secs_f64(reset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(json: &str) -> RateLimitConfig {
+        RateLimitConfig::from_json(json).unwrap()
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_bucket_allows_burst_then_waits() {
+        let limiter = RateLimiter::new();
+        let rules = [RateLimitRule::tool(
+            1,
+            config(r#"{"requests": 2, "period_seconds": 1}"#),
+        )];
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve(&rules, None, now).unwrap(), Duration::ZERO);
+        assert_eq!(limiter.reserve(&rules, None, now).unwrap(), Duration::ZERO);
+
+        // The third call waits for the next token, the fourth for the one after
+        let third = limiter.reserve(&rules, None, now).unwrap();
+        assert_eq!(third, Duration::from_millis(500));
+        let fourth = limiter.reserve(&rules, None, now).unwrap();
+        assert_eq!(fourth, Duration::from_secs(1));
+
+        // Tokens come back over time
+        let later = now + Duration::from_secs(3);
+        assert_eq!(
+            limiter.reserve(&rules, None, later).unwrap(),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_reject_mode_fails_without_consuming() {
+        let limiter = RateLimiter::new();
+        let rules = [RateLimitRule::server(
+            1,
+            config(r#"{"requests": 1, "period_seconds": 8, "mode": "reject"}"#),
+        )];
+        let now = Instant::now();
+
+        limiter.reserve(&rules, None, now).unwrap();
+        let err = limiter.reserve(&rules, None, now).unwrap_err();
+        assert_eq!(err.scope, "server");
+        assert_eq!(err.retry_after, Duration::from_secs(8));
+
+        // The refused call did not push the next token further away
+        let err = limiter
+            .reserve(&rules, None, now + Duration::from_secs(4))
+            .unwrap_err();
+        assert_eq!(err.retry_after, Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_wait_longer_than_max_wait_fails() {
+        let limiter = RateLimiter::new();
+        let rules = [RateLimitRule::tool(
+            1,
+            config(r#"{"requests": 1, "period_seconds": 60, "max_wait_ms": 1000}"#),
+        )];
+        let now = Instant::now();
+
+        limiter.reserve(&rules, None, now).unwrap();
+        assert!(limiter.reserve(&rules, None, now).is_err());
+    }
+
+    #[test]
+    fn test_buckets_are_independent_per_key() {
+        let limiter = RateLimiter::new();
+        let limit = config(r#"{"requests": 1, "period_seconds": 60, "mode": "reject"}"#);
+        let now = Instant::now();
+
+        let a = [RateLimitRule::toolkit(1, "a.example.com", limit.clone())];
+        let b = [RateLimitRule::toolkit(1, "b.example.com", limit)];
+
+        limiter.reserve(&a, None, now).unwrap();
+        limiter.reserve(&b, None, now).unwrap();
+        assert_eq!(
+            limiter.reserve(&a, None, now).unwrap_err().scope,
+            "toolkit for a.example.com"
+        );
+    }
+
+    #[test]
+    fn test_upstream_cooldown_delays_or_rejects() {
+        let limiter = RateLimiter::new();
+        limiter.observe_response("api.example.com", 429, &headers(&[("retry-after", "2")]));
+
+        let cooldown = limiter.upstream_cooldown("api.example.com").unwrap();
+        assert!(cooldown > Duration::from_secs(1) && cooldown <= Duration::from_secs(2));
+        assert!(limiter.upstream_cooldown("other.example.com").is_none());
+
+        let wait = limiter
+            .reserve(&[], Some("api.example.com"), Instant::now())
+            .unwrap();
+        assert!(wait > Duration::from_secs(1));
+
+        let reject = [RateLimitRule::tool(
+            1,
+            config(r#"{"requests": 10, "mode": "reject"}"#),
+        )];
+        let err = limiter
+            .reserve(&reject, Some("api.example.com"), Instant::now())
+            .unwrap_err();
+        assert_eq!(err.scope, "upstream api.example.com");
+    }
+
+    #[test]
+    fn test_upstream_backoff_headers() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        assert_eq!(
+            upstream_backoff(503, &headers(&[("Retry-After", "30")]), now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            upstream_backoff(
+                429,
+                &headers(&[("retry-after", "Tue, 14 Nov 2023 22:14:20 GMT")]),
+                now
+            ),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            upstream_backoff(
+                200,
+                &headers(&[
+                    ("x-ratelimit-remaining", "0"),
+                    ("x-ratelimit-reset", "1700000005")
+                ]),
+                now
+            ),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            upstream_backoff(
+                200,
+                &headers(&[("ratelimit-remaining", "0"), ("ratelimit-reset", "7")]),
+                now
+            ),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            upstream_backoff(
+                200,
+                &headers(&[("x-ratelimit-remaining", "12"), ("x-ratelimit-reset", "7")]),
+                now
+            ),
+            None
+        );
+        assert_eq!(
+            upstream_backoff(429, &HashMap::new(), now),
+            Some(DEFAULT_TOO_MANY_REQUESTS_BACKOFF)
+        );
+        assert_eq!(upstream_backoff(200, &HashMap::new(), now), None);
+        assert_eq!(
+            upstream_backoff(429, &headers(&[("retry-after", "999999")]), now),
+            Some(MAX_UPSTREAM_BACKOFF)
+        );
+    }
+}
@@ -50,6 +50,7 @@
 
 use crate::error::McpServiceError;
 use crate::models::instance::InstanceParam;
+use crate::models::parameter_metadata::ParameterMetadata;
 use crate::models::tool::Tool;
 use serde_json::{json, Value};
 use sqlx::SqlitePool;
@@ -96,24 +97,34 @@ impl SchemaGenerator {
     /// Build schema for a single property
     ///
     /// Creates a complete JSON Schema property definition for a parameter,
-    /// including type information and a description field.
+    /// including type information and a description field. Documented
+    /// parameters use their own description and allowed values.
     ///
     /// # Arguments
     ///
     /// * `param_name` - The name of the parameter
     /// * `param_type` - The SaraMCP type of the parameter
+    /// * `metadata` - Documentation stored with the tool, if any
     ///
     /// # Returns
     ///
     /// A JSON Schema property definition with type and description
-    fn build_property_schema(param_name: &str, param_type: &str) -> Value {
+    pub(crate) fn build_property_schema(
+        param_name: &str,
+        param_type: &str,
+        metadata: Option<&ParameterMetadata>,
+    ) -> Value {
         let mut schema = Self::map_variable_type_to_json_schema(param_type);
 
         if let Some(obj) = schema.as_object_mut() {
-            obj.insert(
-                "description".to_string(),
-                json!(format!("Parameter: {}", param_name)),
-            );
+            let description = metadata
+                .and_then(|m| m.description.clone())
+                .unwrap_or_else(|| format!("Parameter: {}", param_name));
+            obj.insert("description".to_string(), json!(description));
+
+            if let Some(metadata) = metadata.filter(|m| !m.enum_values.is_empty()) {
+                obj.insert("enum".to_string(), json!(metadata.enum_values));
+            }
         }
 
         schema
@@ -179,6 +190,7 @@ impl SchemaGenerator {
 
         let instance_params = InstanceParam::list_by_instance(pool, instance_id).await?;
 
+        let metadata = tool.parameter_metadata_map();
         let extracted = tool.extract_parameters();
         let param_types: HashMap<String, String> = extracted
             .into_iter()
@@ -201,7 +213,11 @@ impl SchemaGenerator {
 
             properties.insert(
                 param.param_name.clone(),
-                Self::build_property_schema(&param.param_name, param_type),
+                Self::build_property_schema(
+                    &param.param_name,
+                    param_type,
+                    metadata.get(&param.param_name),
+                ),
             );
 
             required.push(param.param_name.clone());
@@ -0,0 +1,1049 @@
+//! Declarative server configuration
+//!
+//! A server is exported as a YAML document listing its access level,
+//! installed toolkits, tool instances with their parameter bindings and its
+//! globals. Secret globals are listed by name only. Applying a document
+//! reconciles the database to it: the plan lists every change as a diff line
+//! before anything is saved, and applying the same document twice changes
+//! nothing the second time.
+
+use crate::models::{
+    ConcurrencyLimit, ConfigureInstanceForm, CreateServerForm, InstanceParam, ParamConfig, Server,
+    ServerGlobal, ServerToolkit, ToolInstance, UpdateServerForm,
+};
+use crate::services::{InstanceService, ServerService, ToolService, ToolkitService};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+pub const CONFIG_FORMAT: &str = "saramcp-server";
+pub const CONFIG_VERSION: u32 = 1;
+
+const ACCESS_LEVELS: [&str; 3] = ["private", "organization", "public"];
+const PARAM_SOURCES: [&str; 3] = ["exposed", "server", "instance"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ServerConfigError {
+    #[error("Configuration is not valid YAML or JSON: {0}")]
+    Invalid(String),
+
+    #[error("Not a SaraMCP server configuration, export one from a server page")]
+    UnsupportedFormat,
+
+    #[error(
+        "Configuration version {0} is newer than this deployment supports (version {CONFIG_VERSION})"
+    )]
+    UnsupportedVersion(u32),
+
+    #[error("Could not write the configuration: {0}")]
+    Serialize(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerConfig {
+    pub format: String,
+    pub version: u32,
+    pub server: ConfigServer,
+    /// Titles of the installed toolkits
+    #[serde(default)]
+    pub toolkits: Vec<String>,
+    #[serde(default)]
+    pub instances: Vec<ConfigInstance>,
+    #[serde(default)]
+    pub globals: Vec<ConfigGlobal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigServer {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "default_access_level")]
+    pub access_level: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigInstance {
+    pub name: String,
+    pub toolkit: String,
+    pub tool: String,
+    /// Omitted when the instance uses the tool's description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Parameters not listed are exposed to the client
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, ConfigParam>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency_limit: Option<ConcurrencyLimit>,
+    /// Database id of an exported instance, never part of the document
+    #[serde(skip)]
+    pub id: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigParam {
+    /// `exposed`, `server` (bound to the global of the same name) or `instance`
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigGlobal {
+    pub name: String,
+    /// Only set for plain globals; secret values never leave the database
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub secret: bool,
+}
+
+fn default_access_level() -> String {
+    "private".to_string()
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// The toolkits and tools of a user, by title and name
+#[derive(Debug, Default)]
+pub struct ToolCatalog {
+    toolkits: HashMap<String, CatalogToolkit>,
+}
+
+#[derive(Debug, Default)]
+struct CatalogToolkit {
+    id: i64,
+    tools: HashMap<String, CatalogTool>,
+}
+
+#[derive(Debug)]
+struct CatalogTool {
+    id: i64,
+    description: Option<String>,
+    params: Vec<String>,
+}
+
+impl ToolCatalog {
+    pub async fn load(
+        toolkit_service: &ToolkitService,
+        tool_service: &ToolService,
+        user_id: i64,
+    ) -> Result<Self> {
+        let mut catalog = ToolCatalog::default();
+        for toolkit in toolkit_service.list_toolkits(user_id).await? {
+            let tools = tool_service.list_tools(toolkit.id, user_id).await?;
+            catalog.toolkits.insert(
+                toolkit.title,
+                CatalogToolkit {
+                    id: toolkit.id,
+                    tools: tools
+                        .iter()
+                        .map(|tool| {
+                            let mut params: Vec<String> = tool
+                                .extract_parameters()
+                                .into_iter()
+                                .map(|p| p.name)
+                                .collect();
+                            params.dedup();
+                            let entry = CatalogTool {
+                                id: tool.id,
+                                description: tool.description.clone(),
+                                params,
+                            };
+                            (tool.name.clone(), entry)
+                        })
+                        .collect(),
+                },
+            );
+        }
+        Ok(catalog)
+    }
+
+    fn tool(&self, toolkit: &str, tool: &str) -> Option<&CatalogTool> {
+        self.toolkits.get(toolkit)?.tools.get(tool)
+    }
+}
+
+/// One change needed to bring a server in line with a document
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    CreateServer {
+        name: String,
+        description: Option<String>,
+    },
+    UpdateServer {
+        name: String,
+        description: Option<String>,
+    },
+    SetAccessLevel {
+        from: String,
+        to: String,
+    },
+    InstallToolkit {
+        toolkit_id: i64,
+        title: String,
+    },
+    RemoveToolkit {
+        toolkit_id: i64,
+        title: String,
+    },
+    CreateInstance {
+        instance: ConfigInstance,
+        tool_id: i64,
+    },
+    UpdateInstance {
+        instance_id: i64,
+        instance: ConfigInstance,
+        fields: Vec<&'static str>,
+    },
+    DeleteInstance {
+        instance_id: i64,
+        name: String,
+    },
+    SetGlobal {
+        name: String,
+        value: String,
+        existed: bool,
+    },
+    DeleteGlobal {
+        name: String,
+    },
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigChange::CreateServer { name, .. } => write!(f, "+ server {}", name),
+            ConfigChange::UpdateServer { name, .. } => write!(f, "~ server {}", name),
+            ConfigChange::SetAccessLevel { from, to } => {
+                write!(f, "~ access_level: {} -> {}", from, to)
+            }
+            ConfigChange::InstallToolkit { title, .. } => write!(f, "+ toolkit {}", title),
+            ConfigChange::RemoveToolkit { title, .. } => write!(f, "- toolkit {}", title),
+            ConfigChange::CreateInstance { instance, .. } => write!(
+                f,
+                "+ instance {} ({}/{})",
+                instance.name, instance.toolkit, instance.tool
+            ),
+            ConfigChange::UpdateInstance {
+                instance, fields, ..
+            } => write!(f, "~ instance {}: {}", instance.name, fields.join(", ")),
+            ConfigChange::DeleteInstance { name, .. } => write!(f, "- instance {}", name),
+            ConfigChange::SetGlobal { name, existed, .. } => {
+                write!(f, "{} global {}", if *existed { "~" } else { "+" }, name)
+            }
+            ConfigChange::DeleteGlobal { name } => write!(f, "- global {}", name),
+        }
+    }
+}
+
+/// Changes, problems and notes found when comparing a document to a server
+#[derive(Debug, Default)]
+pub struct ConfigPlan {
+    pub changes: Vec<ConfigChange>,
+    /// Problems that prevent applying the document
+    pub errors: Vec<String>,
+    /// Things the document cannot do on its own, such as setting secrets
+    pub warnings: Vec<String>,
+}
+
+impl ConfigPlan {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Diff lines, one per change
+    pub fn diff(&self) -> Vec<String> {
+        self.changes.iter().map(ToString::to_string).collect()
+    }
+}
+
+impl ServerConfig {
+    /// Exports the current configuration of a server
+    pub async fn export(
+        server_service: &ServerService,
+        instance_service: &InstanceService,
+        server_id: i64,
+        user_id: i64,
+    ) -> Result<Self> {
+        let server = server_service
+            .get_server(server_id, user_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Server not found or unauthorized"))?;
+
+        let toolkits = server_service
+            .get_server_toolkits(server_id)
+            .await?
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+
+        let instances = instance_service
+            .list_instances_by_server(server_id)
+            .await?
+            .into_iter()
+            .map(|detail| ConfigInstance {
+                description: detail
+                    .description
+                    .filter(|d| Some(d) != detail.tool_description.as_ref()),
+                params: detail
+                    .params
+                    .into_iter()
+                    .map(|p| {
+                        let value = p.value.filter(|_| p.source == "instance");
+                        (
+                            p.param_name,
+                            ConfigParam {
+                                source: p.source,
+                                value,
+                            },
+                        )
+                    })
+                    .collect(),
+                concurrency_limit: ConcurrencyLimit::parse(detail.concurrency_limit.as_deref()),
+                name: detail.instance_name,
+                toolkit: detail.toolkit_name,
+                tool: detail.tool_name,
+                id: Some(detail.id),
+            })
+            .collect();
+
+        let globals = server_service
+            .get_server_globals(server_id)
+            .await?
+            .into_iter()
+            .map(|g| {
+                let secret = g.is_secret.unwrap_or(false);
+                ConfigGlobal {
+                    name: g.key,
+                    value: (!secret).then_some(g.value),
+                    secret,
+                }
+            })
+            .collect();
+
+        Ok(ServerConfig {
+            format: CONFIG_FORMAT.to_string(),
+            version: CONFIG_VERSION,
+            server: ConfigServer {
+                name: server.name,
+                description: server.description.filter(|d| !d.is_empty()),
+                access_level: server.access_level.unwrap_or_else(default_access_level),
+            },
+            toolkits,
+            instances,
+            globals,
+        })
+    }
+
+    /// Reads a document from YAML or JSON
+    pub fn parse(text: &str) -> std::result::Result<Self, ServerConfigError> {
+        let value: Value = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|e| ServerConfigError::Invalid(e.to_string()))?
+        } else {
+            serde_yaml::from_str(text).map_err(|e| ServerConfigError::Invalid(e.to_string()))?
+        };
+
+        if value.get("format").and_then(Value::as_str) != Some(CONFIG_FORMAT) {
+            return Err(ServerConfigError::UnsupportedFormat);
+        }
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or(ServerConfigError::UnsupportedFormat)?;
+        if version > CONFIG_VERSION as u64 {
+            return Err(ServerConfigError::UnsupportedVersion(
+                u32::try_from(version).unwrap_or(u32::MAX),
+            ));
+        }
+
+        serde_json::from_value(value).map_err(|e| ServerConfigError::Invalid(e.to_string()))
+    }
+
+    pub fn to_yaml(&self) -> std::result::Result<String, ServerConfigError> {
+        serde_yaml::to_string(self).map_err(|e| ServerConfigError::Serialize(e.to_string()))
+    }
+
+    /// Compares the document to the current state of a server
+    ///
+    /// `current` is `None` when the server does not exist yet. Nothing is
+    /// saved; every problem is reported at once.
+    pub fn plan(&self, current: Option<&ServerConfig>, catalog: &ToolCatalog) -> ConfigPlan {
+        let mut plan = ConfigPlan::default();
+        self.check_server(current, &mut plan);
+        self.check_toolkits(current, catalog, &mut plan);
+        self.check_instances(current, catalog, &mut plan);
+        self.check_globals(current, &mut plan);
+        plan
+    }
+
+    fn check_server(&self, current: Option<&ServerConfig>, plan: &mut ConfigPlan) {
+        let desired = &self.server;
+        let description = desired.description.clone().filter(|d| !d.is_empty());
+
+        if desired.name.trim().is_empty() {
+            plan.errors.push("Server: name is required".to_string());
+        }
+        if !ACCESS_LEVELS.contains(&desired.access_level.as_str()) {
+            plan.errors.push(format!(
+                "Server: access_level must be one of {}",
+                ACCESS_LEVELS.join(", ")
+            ));
+        }
+
+        let current_access = match current {
+            None => {
+                plan.changes.push(ConfigChange::CreateServer {
+                    name: desired.name.clone(),
+                    description,
+                });
+                default_access_level()
+            }
+            Some(current) => {
+                if current.server.name != desired.name || current.server.description != description
+                {
+                    plan.changes.push(ConfigChange::UpdateServer {
+                        name: desired.name.clone(),
+                        description,
+                    });
+                }
+                current.server.access_level.clone()
+            }
+        };
+        if current_access != desired.access_level {
+            plan.changes.push(ConfigChange::SetAccessLevel {
+                from: current_access,
+                to: desired.access_level.clone(),
+            });
+        }
+    }
+
+    fn check_toolkits(
+        &self,
+        current: Option<&ServerConfig>,
+        catalog: &ToolCatalog,
+        plan: &mut ConfigPlan,
+    ) {
+        let installed: Vec<&str> = current
+            .map(|c| c.toolkits.iter().map(String::as_str).collect())
+            .unwrap_or_default();
+
+        let mut seen = HashSet::new();
+        for title in &self.toolkits {
+            if !seen.insert(title.as_str()) {
+                plan.errors
+                    .push(format!("Toolkit {}: listed more than once", title));
+                continue;
+            }
+            match catalog.toolkits.get(title) {
+                None => plan
+                    .errors
+                    .push(format!("Toolkit {}: no toolkit with this title", title)),
+                Some(toolkit) if !installed.contains(&title.as_str()) => {
+                    plan.changes.push(ConfigChange::InstallToolkit {
+                        toolkit_id: toolkit.id,
+                        title: title.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for title in installed {
+            if !seen.contains(title) {
+                if let Some(toolkit) = catalog.toolkits.get(title) {
+                    plan.changes.push(ConfigChange::RemoveToolkit {
+                        toolkit_id: toolkit.id,
+                        title: title.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn check_instances(
+        &self,
+        current: Option<&ServerConfig>,
+        catalog: &ToolCatalog,
+        plan: &mut ConfigPlan,
+    ) {
+        let existing: BTreeMap<&str, &ConfigInstance> = current
+            .map(|c| c.instances.iter().map(|i| (i.name.as_str(), i)).collect())
+            .unwrap_or_default();
+        let globals: HashSet<&str> = self.globals.iter().map(|g| g.name.as_str()).collect();
+
+        let mut seen = HashSet::new();
+        for instance in &self.instances {
+            let name = &instance.name;
+            if name.trim().is_empty() {
+                plan.errors.push("Instance: name is required".to_string());
+                continue;
+            }
+            if !seen.insert(name.as_str()) {
+                plan.errors
+                    .push(format!("Instance {}: listed more than once", name));
+                continue;
+            }
+            if !self.toolkits.contains(&instance.toolkit) {
+                plan.errors.push(format!(
+                    "Instance {}: toolkit {} is not listed under toolkits",
+                    name, instance.toolkit
+                ));
+                continue;
+            }
+            let Some(tool) = catalog.tool(&instance.toolkit, &instance.tool) else {
+                plan.errors.push(format!(
+                    "Instance {}: toolkit {} has no tool {}",
+                    name, instance.toolkit, instance.tool
+                ));
+                continue;
+            };
+
+            for (param, binding) in &instance.params {
+                if !tool.params.contains(param) {
+                    plan.errors.push(format!(
+                        "Instance {}: tool {} has no parameter {}",
+                        name, instance.tool, param
+                    ));
+                } else if !PARAM_SOURCES.contains(&binding.source.as_str()) {
+                    plan.errors.push(format!(
+                        "Instance {}: source of {} must be one of {}",
+                        name,
+                        param,
+                        PARAM_SOURCES.join(", ")
+                    ));
+                } else if binding.source == "instance" && binding.value.is_none() {
+                    plan.errors
+                        .push(format!("Instance {}: {} needs a value", name, param));
+                } else if binding.source == "server" && !globals.contains(param.as_str()) {
+                    plan.warnings.push(format!(
+                        "Instance {}: {} is bound to global {}, which is not listed",
+                        name, param, param
+                    ));
+                }
+            }
+            if let Some(ref limit) = instance.concurrency_limit {
+                if let Err(e) = limit.validate() {
+                    plan.errors.push(format!("Instance {}: {}", name, e));
+                }
+            }
+
+            // The MCP schema only offers parameters stored as exposed, so
+            // unlisted ones are saved that way explicitly
+            let mut instance = instance.clone();
+            for param in &tool.params {
+                instance
+                    .params
+                    .entry(param.clone())
+                    .or_insert_with(|| ConfigParam {
+                        source: "exposed".to_string(),
+                        value: None,
+                    });
+            }
+            let instance = &instance;
+
+            match existing.get(name.as_str()) {
+                None => plan.changes.push(ConfigChange::CreateInstance {
+                    instance: instance.clone(),
+                    tool_id: tool.id,
+                }),
+                // Instances cannot switch tools, so they are recreated
+                Some(old) if old.toolkit != instance.toolkit || old.tool != instance.tool => {
+                    plan.changes.push(ConfigChange::DeleteInstance {
+                        instance_id: old.id.unwrap_or_default(),
+                        name: name.clone(),
+                    });
+                    plan.changes.push(ConfigChange::CreateInstance {
+                        instance: instance.clone(),
+                        tool_id: tool.id,
+                    });
+                }
+                Some(old) => {
+                    let mut fields = Vec::new();
+                    let description = |i: &ConfigInstance| {
+                        i.description.clone().or_else(|| tool.description.clone())
+                    };
+                    if description(old) != description(instance) {
+                        fields.push("description");
+                    }
+                    if bound_params(old) != bound_params(instance) {
+                        fields.push("params");
+                    }
+                    if old.concurrency_limit != instance.concurrency_limit {
+                        fields.push("concurrency_limit");
+                    }
+                    if !fields.is_empty() {
+                        plan.changes.push(ConfigChange::UpdateInstance {
+                            instance_id: old.id.unwrap_or_default(),
+                            instance: instance.clone(),
+                            fields,
+                        });
+                    }
+                }
+            }
+        }
+
+        for (name, old) in existing {
+            if !seen.contains(name) {
+                plan.changes.push(ConfigChange::DeleteInstance {
+                    instance_id: old.id.unwrap_or_default(),
+                    name: name.to_string(),
+                });
+            }
+        }
+    }
+
+    fn check_globals(&self, current: Option<&ServerConfig>, plan: &mut ConfigPlan) {
+        let existing: BTreeMap<&str, &ConfigGlobal> = current
+            .map(|c| c.globals.iter().map(|g| (g.name.as_str(), g)).collect())
+            .unwrap_or_default();
+
+        let mut seen = HashSet::new();
+        for global in &self.globals {
+            let name = &global.name;
+            if name.trim().is_empty() {
+                plan.errors.push("Global: name is required".to_string());
+                continue;
+            }
+            if !seen.insert(name.as_str()) {
+                plan.errors
+                    .push(format!("Global {}: listed more than once", name));
+                continue;
+            }
+
+            let old = existing.get(name.as_str());
+            if global.secret {
+                if global.value.is_some() {
+                    plan.errors.push(format!(
+                        "Global {}: secret values do not belong in the configuration, set it on the bindings tab",
+                        name
+                    ));
+                } else if old.is_some_and(|g| !g.secret) {
+                    // The plain value would stay stored, and exported, as is
+                    plan.errors.push(format!(
+                        "Global {}: holds a plain value on this server, remove it from the configuration and apply before marking it secret",
+                        name
+                    ));
+                } else if old.is_none() {
+                    plan.warnings.push(format!(
+                        "Global {}: secret has no value on this server yet, set it on the bindings tab",
+                        name
+                    ));
+                }
+                continue;
+            }
+
+            let Some(ref value) = global.value else {
+                plan.errors
+                    .push(format!("Global {}: value is required", name));
+                continue;
+            };
+            if old.is_none_or(|g| g.secret || g.value.as_ref() != Some(value)) {
+                plan.changes.push(ConfigChange::SetGlobal {
+                    name: name.clone(),
+                    value: value.clone(),
+                    existed: old.is_some(),
+                });
+            }
+        }
+
+        for name in existing.keys() {
+            if !seen.contains(name) {
+                plan.changes.push(ConfigChange::DeleteGlobal {
+                    name: name.to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Bindings that differ from the default of exposing the parameter
+fn bound_params(instance: &ConfigInstance) -> BTreeMap<&str, &ConfigParam> {
+    instance
+        .params
+        .iter()
+        .filter(|(_, p)| p.source != "exposed")
+        .map(|(name, p)| (name.as_str(), p))
+        .collect()
+}
+
+impl ConfigInstance {
+    fn param_configs(&self) -> Vec<ParamConfig> {
+        self.params
+            .iter()
+            .map(|(name, p)| ParamConfig {
+                name: name.clone(),
+                source: p.source.clone(),
+                value: p.value.clone().filter(|_| p.source == "instance"),
+            })
+            .collect()
+    }
+
+    /// The concurrency limit as stored in the `tool_instances` table
+    fn concurrency_limit_json(&self) -> Result<Option<String>> {
+        let Some(limit) = &self.concurrency_limit else {
+            return Ok(None);
+        };
+        limit.validate().map_err(anyhow::Error::msg)?;
+        Ok(Some(serde_json::to_string(limit)?))
+    }
+}
+
+impl ConfigPlan {
+    /// Saves the planned changes and returns the id of the server
+    ///
+    /// `server_id` is `None` when the plan creates the server. Removals run
+    /// before additions so renamed instances do not collide. Every change is
+    /// saved in one transaction, so a failure leaves the server as it was.
+    /// The server's configuration version is bumped for the web process to
+    /// reload it, see [`McpServerRegistry::reload_changed_configs`].
+    ///
+    /// [`McpServerRegistry::reload_changed_configs`]: crate::mcp::McpServerRegistry::reload_changed_configs
+    pub async fn apply(
+        &self,
+        pool: &SqlitePool,
+        user_id: i64,
+        server_id: Option<i64>,
+    ) -> Result<i64> {
+        if !self.is_valid() {
+            anyhow::bail!(self.errors.join("; "));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let mut server_id = server_id;
+        for change in &self.changes {
+            if let ConfigChange::CreateServer { name, description } = change {
+                let form = CreateServerForm {
+                    name: name.clone(),
+                    description: description.clone().unwrap_or_default(),
+                    csrf_token: String::new(),
+                };
+                server_id = Some(Server::create_with_toolkits(&mut *tx, user_id, form).await?);
+            }
+        }
+        let server_id =
+            server_id.ok_or_else(|| anyhow::anyhow!("Server not found or unauthorized"))?;
+        let owns_server = sqlx::query!(
+            "SELECT 1 as result FROM servers WHERE id = ? AND user_id = ?",
+            server_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .is_some();
+        if !owns_server {
+            anyhow::bail!("Server not found or unauthorized");
+        }
+
+        let mut changes: Vec<&ConfigChange> = self.changes.iter().collect();
+        changes.sort_by_key(|change| match change {
+            ConfigChange::DeleteInstance { .. } | ConfigChange::DeleteGlobal { .. } => 0,
+            ConfigChange::RemoveToolkit { .. } => 2,
+            _ => 1,
+        });
+
+        for change in changes {
+            match change {
+                ConfigChange::CreateServer { .. } => {}
+                ConfigChange::UpdateServer { name, description } => {
+                    let form = UpdateServerForm {
+                        name: name.clone(),
+                        description: description.clone().unwrap_or_default(),
+                        csrf_token: String::new(),
+                    };
+                    Server::update(&mut *tx, server_id, user_id, form).await?;
+                }
+                ConfigChange::SetAccessLevel { to, .. } => {
+                    Server::set_access_level(&mut *tx, server_id, user_id, to).await?;
+                }
+                ConfigChange::InstallToolkit { toolkit_id, .. } => {
+                    let owns_toolkit = sqlx::query!(
+                        "SELECT 1 as result FROM toolkits WHERE id = ? AND user_id = ?",
+                        toolkit_id,
+                        user_id
+                    )
+                    .fetch_optional(&mut *tx)
+                    .await?
+                    .is_some();
+                    if !owns_toolkit {
+                        anyhow::bail!("Unauthorized");
+                    }
+                    ServerToolkit::add(&mut *tx, server_id, *toolkit_id).await?;
+                }
+                ConfigChange::RemoveToolkit { toolkit_id, .. } => {
+                    ServerToolkit::remove(&mut *tx, server_id, *toolkit_id).await?;
+                }
+                ConfigChange::CreateInstance { instance, tool_id } => {
+                    let form = ConfigureInstanceForm {
+                        instance_name: instance.name.clone(),
+                        description: instance.description.clone(),
+                        tool_id: *tool_id,
+                        param_configs: instance.param_configs(),
+                        csrf_token: String::new(),
+                    };
+                    let instance_id =
+                        ToolInstance::create_with_config(&mut tx, server_id, form).await?;
+                    let limit = instance.concurrency_limit_json()?;
+                    if limit.is_some() {
+                        ToolInstance::set_concurrency_limit(
+                            &mut *tx,
+                            instance_id,
+                            limit.as_deref(),
+                        )
+                        .await?;
+                    }
+                }
+                ConfigChange::UpdateInstance {
+                    instance_id,
+                    instance,
+                    ..
+                } => {
+                    let description = match instance.description {
+                        Some(ref description) => Some(description.clone()),
+                        None => ToolInstance::get_detail(&mut tx, *instance_id)
+                            .await?
+                            .and_then(|detail| detail.tool_description),
+                    };
+                    ToolInstance::update(
+                        &mut *tx,
+                        *instance_id,
+                        &instance.name,
+                        description.as_deref(),
+                    )
+                    .await?;
+                    let params = instance
+                        .param_configs()
+                        .into_iter()
+                        .map(|p| InstanceParam {
+                            id: None,
+                            instance_id: *instance_id,
+                            param_name: p.name,
+                            source: p.source,
+                            value: p.value,
+                        })
+                        .collect();
+                    InstanceParam::replace_all(&mut tx, *instance_id, params).await?;
+                    let limit = instance.concurrency_limit_json()?;
+                    ToolInstance::set_concurrency_limit(&mut *tx, *instance_id, limit.as_deref())
+                        .await?;
+                }
+                ConfigChange::DeleteInstance { instance_id, .. } => {
+                    ToolInstance::delete(&mut *tx, *instance_id).await?;
+                }
+                ConfigChange::SetGlobal { name, value, .. } => {
+                    ServerGlobal::upsert(&mut *tx, server_id, name, value, false).await?;
+                }
+                ConfigChange::DeleteGlobal { name } => {
+                    ServerGlobal::delete(&mut *tx, server_id, name).await?;
+                }
+            }
+        }
+
+        Server::bump_config_version(&mut *tx, server_id).await?;
+        tx.commit().await?;
+        Ok(server_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"format: saramcp-server
+version: 1
+server:
+  name: Weather
+  access_level: public
+toolkits:
+  - Forecasts
+instances:
+  - name: now
+    toolkit: Forecasts
+    tool: current
+    params:
+      api_key:
+        source: server
+      units:
+        source: instance
+        value: metric
+globals:
+  - name: api_key
+    secret: true
+  - name: base_url
+    value: https://api.example.com
+"#;
+
+    fn catalog() -> ToolCatalog {
+        let tool = CatalogTool {
+            id: 7,
+            description: Some("Current weather".to_string()),
+            params: vec!["api_key".into(), "city".into(), "units".into()],
+        };
+        let mut toolkit = CatalogToolkit {
+            id: 3,
+            ..Default::default()
+        };
+        toolkit.tools.insert("current".to_string(), tool);
+        let mut catalog = ToolCatalog::default();
+        catalog.toolkits.insert("Forecasts".to_string(), toolkit);
+        catalog
+    }
+
+    /// The state the document describes, as an export would return it
+    fn applied() -> ServerConfig {
+        let mut current = ServerConfig::parse(CONFIG).unwrap();
+        current.instances[0].id = Some(11);
+        current.instances[0].description = None;
+        current
+    }
+
+    #[test]
+    fn test_new_server_plan() {
+        let config = ServerConfig::parse(CONFIG).unwrap();
+        let plan = config.plan(None, &catalog());
+
+        assert!(plan.is_valid(), "{:?}", plan.errors);
+        assert_eq!(
+            plan.diff(),
+            vec![
+                "+ server Weather",
+                "~ access_level: private -> public",
+                "+ toolkit Forecasts",
+                "+ instance now (Forecasts/current)",
+                "+ global base_url",
+            ]
+        );
+        assert_eq!(plan.warnings.len(), 1);
+        assert!(plan.warnings[0].starts_with("Global api_key: secret has no value"));
+    }
+
+    #[test]
+    fn test_applied_config_has_no_changes() {
+        let config = ServerConfig::parse(CONFIG).unwrap();
+        let plan = config.plan(Some(&applied()), &catalog());
+
+        assert!(plan.changes.is_empty(), "{:?}", plan.diff());
+        assert!(plan.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_changed_config_diff() {
+        let mut config = ServerConfig::parse(CONFIG).unwrap();
+        config.instances[0].params.remove("units");
+        config.instances[0].params.insert(
+            "city".to_string(),
+            ConfigParam {
+                source: "exposed".to_string(),
+                value: None,
+            },
+        );
+        config.instances[0].description = Some("Current weather".to_string());
+        config.globals.retain(|g| g.name != "base_url");
+        config.globals.push(ConfigGlobal {
+            name: "region".to_string(),
+            value: Some("eu".to_string()),
+            secret: false,
+        });
+
+        let plan = config.plan(Some(&applied()), &catalog());
+        assert_eq!(
+            plan.diff(),
+            vec![
+                "~ instance now: params",
+                "+ global region",
+                "- global base_url"
+            ]
+        );
+        match &plan.changes[0] {
+            ConfigChange::UpdateInstance { instance_id, .. } => assert_eq!(*instance_id, 11),
+            change => panic!("unexpected change {}", change),
+        }
+    }
+
+    #[test]
+    fn test_removed_instance_and_toolkit() {
+        let mut config = ServerConfig::parse(CONFIG).unwrap();
+        config.toolkits.clear();
+        config.instances.clear();
+
+        let plan = config.plan(Some(&applied()), &catalog());
+        assert_eq!(plan.diff(), vec!["- toolkit Forecasts", "- instance now"]);
+    }
+
+    #[test]
+    fn test_invalid_config_errors() {
+        let mut config = ServerConfig::parse(CONFIG).unwrap();
+        config.server.access_level = "everyone".to_string();
+        config.instances[0].params.insert(
+            "zip".to_string(),
+            ConfigParam {
+                source: "exposed".to_string(),
+                value: None,
+            },
+        );
+        config.instances.push(ConfigInstance {
+            name: "later".to_string(),
+            toolkit: "Forecasts".to_string(),
+            tool: "tomorrow".to_string(),
+            description: None,
+            params: BTreeMap::new(),
+            concurrency_limit: None,
+            id: None,
+        });
+        config.globals[0].value = Some("hunter2".to_string());
+
+        let plan = config.plan(Some(&applied()), &catalog());
+        assert_eq!(
+            plan.errors,
+            vec![
+                "Server: access_level must be one of private, organization, public",
+                "Instance now: tool current has no parameter zip",
+                "Instance later: toolkit Forecasts has no tool tomorrow",
+                "Global api_key: secret values do not belong in the configuration, set it on the bindings tab",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plain_global_marked_secret() {
+        let mut config = ServerConfig::parse(CONFIG).unwrap();
+        config.globals[1].value = None;
+        config.globals[1].secret = true;
+
+        let plan = config.plan(Some(&applied()), &catalog());
+        assert!(plan.changes.is_empty(), "{:?}", plan.diff());
+        assert_eq!(
+            plan.errors,
+            vec!["Global base_url: holds a plain value on this server, remove it from the configuration and apply before marking it secret"]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_other_documents() {
+        assert!(matches!(
+            ServerConfig::parse("format: saramcp-toolkit\nversion: 1\n"),
+            Err(ServerConfigError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            ServerConfig::parse("format: saramcp-server\nversion: 2\n"),
+            Err(ServerConfigError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            ServerConfig::parse(
+                "format: saramcp-server\nversion: 1\nserver: {name: x}\nextra: 1\n"
+            ),
+            Err(ServerConfigError::Invalid(_))
+        ));
+    }
+}
@@ -1,10 +1,46 @@
 use crate::models::{
-    CreateServerForm, GlobalsForm, Server, ServerGlobal, ServerSummary, ServerToolkit,
-    UpdateServerForm,
+    ConcurrencyLimit, CreateServerForm, GlobalsForm, NetworkSettingsForm, PromptForm,
+    PublishInstanceForm, RateLimitConfig, ResourceDocumentForm, Server, ServerGlobal,
+    ServerNetworkSettings, ServerPrompt, ServerResource, ServerSummary, ServerToolkit, Tool,
+    ToolInstance, UpdateServerForm,
+};
+use crate::services::{
+    EgressClientCache, EgressConfig, EgressConfigError, EgressPolicy, HttpExecutor, McpSource,
+    RemoteServer, SecretsManager,
 };
-use crate::services::SecretsManager;
 use anyhow::Result;
 use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+/// Why a resource could not be attached to a server
+#[derive(Debug, thiserror::Error)]
+pub enum ResourceError {
+    #[error("Resource names may only use letters, digits, '_', '-' and '.': '{0}'")]
+    InvalidName(String),
+    #[error("A resource named '{0}' already exists")]
+    NameTaken(String),
+    #[error("Document content is empty")]
+    EmptyContent,
+    #[error("Tool instance not found on this server")]
+    InstanceNotFound,
+    #[error("Only GET tools can be read as resources, '{0}' uses {1}")]
+    NotReadable(String, String),
+}
+
+/// Why a prompt could not be saved
+#[derive(Debug, thiserror::Error)]
+pub enum PromptError {
+    #[error("Prompt names may only use letters, digits, '_', '-' and '.': '{0}'")]
+    InvalidName(String),
+    #[error("Prompt template is empty")]
+    EmptyTemplate,
+    #[error("{0}")]
+    InvalidArguments(String),
+    #[error("'{0}' is not an argument of the template")]
+    UnknownArgument(String),
+    #[error("'{0}' is a secret global and cannot be used in a prompt")]
+    SecretGlobal(String),
+}
 
 #[derive(Clone)]
 pub struct ServerService {
@@ -59,20 +95,7 @@ impl ServerService {
             .await?
             .ok_or_else(|| anyhow::anyhow!("Server not found or unauthorized"))?;
 
-        sqlx::query!(
-            r#"
-            UPDATE servers
-            SET access_level = ?, updated_at = unixepoch()
-            WHERE id = ? AND user_id = ?
-            "#,
-            access_level,
-            server_id,
-            user_id
-        )
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        Server::set_access_level(&self.pool, server_id, user_id, access_level).await
     }
 
     // Server toolkits
@@ -109,15 +132,7 @@ impl ServerService {
             anyhow::bail!("Unauthorized");
         }
 
-        sqlx::query!(
-            "INSERT OR IGNORE INTO server_toolkits (server_id, toolkit_id) VALUES (?, ?)",
-            server_id,
-            toolkit_id
-        )
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        ServerToolkit::add(&self.pool, server_id, toolkit_id).await
     }
 
     pub async fn remove_toolkit_from_server(
@@ -140,15 +155,7 @@ impl ServerService {
             anyhow::bail!("Unauthorized");
         }
 
-        sqlx::query!(
-            "DELETE FROM server_toolkits WHERE server_id = ? AND toolkit_id = ?",
-            server_id,
-            toolkit_id
-        )
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        ServerToolkit::remove(&self.pool, server_id, toolkit_id).await
     }
 
     // Server globals management
@@ -168,6 +175,40 @@ impl ServerService {
         Ok(globals)
     }
 
+    /// Lists the tools of a remote MCP server, as the server's tools would reach it
+    ///
+    /// The source headers are rendered with the server globals and requests
+    /// go through the server's network settings.
+    pub async fn list_remote_mcp_tools(
+        &self,
+        server_id: i64,
+        source: &McpSource,
+    ) -> Result<RemoteServer> {
+        let globals: HashMap<String, serde_json::Value> = self
+            .get_server_globals_decrypted(server_id)
+            .await?
+            .into_iter()
+            .map(|g| (g.key, serde_json::Value::String(g.value)))
+            .collect();
+
+        let executor = match EgressClientCache::shared()
+            .client_for_server(&self.pool, server_id, &self.secrets)
+            .await?
+        {
+            Some(egress) => HttpExecutor::new().with_egress_client(egress),
+            None => HttpExecutor::new(),
+        };
+
+        Ok(executor
+            .list_mcp_tools(
+                source.transport,
+                &source.url,
+                Some(&source.headers_json()),
+                &globals,
+            )
+            .await?)
+    }
+
     pub async fn save_server_globals(
         &self,
         server_id: i64,
@@ -235,6 +276,441 @@ impl ServerService {
         Ok(())
     }
 
+    /// Sets individual globals, keeping the server's other globals
+    ///
+    /// Each entry is `(key, value, is_secret)`; existing keys are overwritten.
+    pub async fn set_server_globals(
+        &self,
+        server_id: i64,
+        user_id: i64,
+        globals: &[(String, String, bool)],
+    ) -> Result<()> {
+        if !self.user_owns_server(server_id, user_id).await? {
+            anyhow::bail!("Unauthorized");
+        }
+
+        let mut tx = self.pool.begin().await?;
+        for (key, value, is_secret) in globals {
+            let value = if *is_secret {
+                self.secrets.encrypt(value)?
+            } else {
+                value.clone()
+            };
+
+            ServerGlobal::upsert(&mut *tx, server_id, key, &value, *is_secret).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn delete_server_global(
+        &self,
+        server_id: i64,
+        user_id: i64,
+        key: &str,
+    ) -> Result<()> {
+        if !self.user_owns_server(server_id, user_id).await? {
+            anyhow::bail!("Unauthorized");
+        }
+        ServerGlobal::delete(&self.pool, server_id, key).await
+    }
+
+    // Server network settings management
+    pub async fn get_network_settings_decrypted(
+        &self,
+        server_id: i64,
+    ) -> Result<Option<EgressConfig>> {
+        match ServerNetworkSettings::get(&self.pool, server_id).await? {
+            Some(settings) => Ok(Some(EgressConfig::decrypt(&settings, &self.secrets)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Validates and stores the proxy, CA bundle, client identity and rate
+    /// limit of a server
+    ///
+    /// An empty proxy, certificate or key field keeps the stored value. Validation
+    /// failures are returned as [`EgressConfigError`](crate::services::EgressConfigError).
+    pub async fn save_network_settings(
+        &self,
+        server_id: i64,
+        user_id: i64,
+        form: NetworkSettingsForm,
+    ) -> Result<()> {
+        if !self.user_owns_server(server_id, user_id).await? {
+            anyhow::bail!("Unauthorized");
+        }
+
+        let existing = self
+            .get_network_settings_decrypted(server_id)
+            .await?
+            .unwrap_or_default();
+
+        let non_empty = |value: &str| {
+            let trimmed = value.trim();
+            (!trimmed.is_empty()).then(|| trimmed.to_string())
+        };
+
+        let (client_cert, client_key) = if form.clear_client_identity.is_some() {
+            (None, None)
+        } else {
+            (
+                non_empty(&form.client_cert).or(existing.client_cert),
+                non_empty(&form.client_key).or(existing.client_key),
+            )
+        };
+
+        let proxy_url = if form.clear_proxy.is_some() {
+            None
+        } else {
+            non_empty(&form.proxy_url).or(existing.proxy_url)
+        };
+
+        let config = EgressConfig {
+            proxy_url,
+            ca_bundle: non_empty(&form.ca_bundle),
+            client_cert,
+            client_key,
+            allowed_hosts: existing.allowed_hosts,
+            denied_hosts: existing.denied_hosts,
+            rate_limit: RateLimitConfig::json_from_form(
+                form.rate_limit_requests.as_deref(),
+                form.rate_limit_period.as_deref(),
+                form.rate_limit_burst.as_deref(),
+                form.rate_limit_mode.as_deref(),
+                form.rate_limit_max_wait.as_deref(),
+            ),
+            concurrency_limit: ConcurrencyLimit::json_from_form(
+                form.concurrency_max.as_deref(),
+                form.concurrency_queue.as_deref(),
+                form.concurrency_timeout.as_deref(),
+            ),
+        };
+        config.validate()?;
+
+        let encrypt = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|v| self.secrets.encrypt(v))
+                .transpose()
+        };
+        let proxy_url = encrypt(&config.proxy_url)?;
+        let client_cert = encrypt(&config.client_cert)?;
+        let client_key = encrypt(&config.client_key)?;
+
+        if proxy_url.is_none()
+            && config.ca_bundle.is_none()
+            && client_cert.is_none()
+            && client_key.is_none()
+            && config.allowed_hosts.is_none()
+            && config.denied_hosts.is_none()
+            && config.rate_limit.is_none()
+            && config.concurrency_limit.is_none()
+        {
+            ServerNetworkSettings::delete(&self.pool, server_id).await?;
+        } else {
+            ServerNetworkSettings::upsert(
+                &self.pool,
+                server_id,
+                proxy_url.as_deref(),
+                config.ca_bundle.as_deref(),
+                client_cert.as_deref(),
+                client_key.as_deref(),
+                config.rate_limit.as_deref(),
+                config.concurrency_limit.as_deref(),
+            )
+            .await?;
+        }
+
+        EgressClientCache::shared().invalidate(server_id);
+        Ok(())
+    }
+
+    /// Replaces the egress host rules of a server (operator setting)
+    ///
+    /// Rules are validated and stored one per line; empty lists are cleared.
+    /// Validation failures are returned as [`EgressConfigError`](crate::services::EgressConfigError).
+    pub async fn set_egress_host_rules(
+        &self,
+        server_id: i64,
+        allowed_hosts: &[String],
+        denied_hosts: &[String],
+    ) -> Result<()> {
+        let normalize = |rules: &[String]| -> Result<Option<String>> {
+            let parsed = EgressPolicy::parse_rules(&rules.join("\n"))
+                .map_err(EgressConfigError::InvalidHostRule)?;
+            Ok((!parsed.is_empty()).then(|| {
+                parsed
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }))
+        };
+        let allowed = normalize(allowed_hosts)?;
+        let denied = normalize(denied_hosts)?;
+
+        ServerNetworkSettings::set_host_rules(
+            &self.pool,
+            server_id,
+            allowed.as_deref(),
+            denied.as_deref(),
+        )
+        .await?;
+
+        if ServerNetworkSettings::get(&self.pool, server_id)
+            .await?
+            .is_some_and(|settings| settings.is_empty())
+        {
+            ServerNetworkSettings::delete(&self.pool, server_id).await?;
+        }
+
+        EgressClientCache::shared().invalidate(server_id);
+        Ok(())
+    }
+
+    // Server resources management
+    pub async fn list_resources(&self, server_id: i64) -> Result<Vec<ServerResource>> {
+        ServerResource::list_by_server(&self.pool, server_id).await
+    }
+
+    /// Attaches a document to a server, replacing the document of the same name
+    ///
+    /// Validation failures are returned as [`ResourceError`].
+    pub async fn save_resource_document(
+        &self,
+        server_id: i64,
+        user_id: i64,
+        form: &ResourceDocumentForm,
+    ) -> Result<i64> {
+        if !self.user_owns_server(server_id, user_id).await? {
+            anyhow::bail!("Unauthorized");
+        }
+
+        let name = form.name.trim();
+        if !ServerResource::is_valid_name(name) {
+            return Err(ResourceError::InvalidName(name.to_string()).into());
+        }
+        if form.content.trim().is_empty() {
+            return Err(ResourceError::EmptyContent.into());
+        }
+        let description = form
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty());
+        let mime_type = form
+            .mime_type
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .unwrap_or("text/markdown");
+
+        match ServerResource::get_by_name(&self.pool, server_id, name).await? {
+            Some(existing) if existing.is_document() => {
+                let id = existing.id.unwrap_or_default();
+                ServerResource::update_document(
+                    &self.pool,
+                    id,
+                    description,
+                    mime_type,
+                    &form.content,
+                )
+                .await?;
+                Ok(id)
+            }
+            Some(_) => Err(ResourceError::NameTaken(name.to_string()).into()),
+            None => {
+                ServerResource::create_document(
+                    &self.pool,
+                    server_id,
+                    name,
+                    description,
+                    mime_type,
+                    &form.content,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Publishes a GET tool instance of the server as a resource
+    ///
+    /// Validation failures are returned as [`ResourceError`].
+    pub async fn publish_instance_resource(
+        &self,
+        server_id: i64,
+        user_id: i64,
+        form: &PublishInstanceForm,
+    ) -> Result<i64> {
+        if !self.user_owns_server(server_id, user_id).await? {
+            anyhow::bail!("Unauthorized");
+        }
+
+        let instance = ToolInstance::get_by_id(&self.pool, form.instance_id)
+            .await?
+            .filter(|i| i.server_id == server_id)
+            .ok_or(ResourceError::InstanceNotFound)?;
+        let tool = Tool::get_by_id(&self.pool, instance.tool_id)
+            .await?
+            .ok_or(ResourceError::InstanceNotFound)?;
+        if !tool.method.eq_ignore_ascii_case("GET") {
+            return Err(ResourceError::NotReadable(instance.instance_name, tool.method).into());
+        }
+
+        let name = form
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or(&instance.instance_name);
+        if !ServerResource::is_valid_name(name) {
+            return Err(ResourceError::InvalidName(name.to_string()).into());
+        }
+        if ServerResource::get_by_name(&self.pool, server_id, name)
+            .await?
+            .is_some()
+        {
+            return Err(ResourceError::NameTaken(name.to_string()).into());
+        }
+        let mime_type = form
+            .mime_type
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .unwrap_or("application/json");
+
+        ServerResource::create_for_instance(
+            &self.pool,
+            server_id,
+            name,
+            instance.description.as_deref(),
+            mime_type,
+            form.instance_id,
+        )
+        .await
+    }
+
+    pub async fn delete_resource(
+        &self,
+        server_id: i64,
+        user_id: i64,
+        resource_id: i64,
+    ) -> Result<()> {
+        if !self.user_owns_server(server_id, user_id).await? {
+            anyhow::bail!("Unauthorized");
+        }
+        ServerResource::delete(&self.pool, server_id, resource_id).await
+    }
+
+    // Server prompts management
+    pub async fn list_prompts(&self, server_id: i64) -> Result<Vec<ServerPrompt>> {
+        ServerPrompt::list_by_server(&self.pool, server_id).await
+    }
+
+    /// Creates a prompt, or replaces the prompt of the same name
+    ///
+    /// Placeholders naming a plain global are filled from it, so only the
+    /// remaining ones can be documented as arguments. Validation failures
+    /// are returned as [`PromptError`].
+    pub async fn save_prompt(
+        &self,
+        server_id: i64,
+        user_id: i64,
+        form: &PromptForm,
+    ) -> Result<i64> {
+        if !self.user_owns_server(server_id, user_id).await? {
+            anyhow::bail!("Unauthorized");
+        }
+
+        let name = form.name.trim();
+        if !ServerPrompt::is_valid_name(name) {
+            return Err(PromptError::InvalidName(name.to_string()).into());
+        }
+        if form.template.trim().is_empty() {
+            return Err(PromptError::EmptyTemplate.into());
+        }
+        let description = form
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty());
+        let specs = ServerPrompt::parse_arguments_text(form.arguments.as_deref().unwrap_or(""))
+            .map_err(PromptError::InvalidArguments)?;
+
+        let globals = ServerGlobal::list_by_server(&self.pool, server_id).await?;
+        let candidate = ServerPrompt {
+            id: None,
+            server_id,
+            name: name.to_string(),
+            description: None,
+            template: form.template.clone(),
+            arguments: None,
+            created_at: None,
+            updated_at: None,
+        };
+        let placeholders = candidate.placeholders();
+        for (_, placeholder) in &placeholders {
+            let global = globals.iter().find(|g| &g.key == placeholder);
+            if global.is_some_and(|g| g.is_secret.unwrap_or(false)) {
+                return Err(PromptError::SecretGlobal(placeholder.clone()).into());
+            }
+        }
+        for (argument, spec) in &specs {
+            let placeholder = placeholders
+                .iter()
+                .find(|(_, name)| name == argument)
+                .filter(|_| !globals.iter().any(|g| &g.key == argument));
+            match placeholder {
+                None => return Err(PromptError::UnknownArgument(argument.clone()).into()),
+                // Absent optional arguments are left empty, which only strings allow
+                Some((var_type, _)) if spec.optional && var_type != "string" => {
+                    return Err(PromptError::InvalidArguments(format!(
+                        "Optional argument '{}' must be a string, not {}",
+                        argument, var_type
+                    ))
+                    .into())
+                }
+                Some(_) => {}
+            }
+        }
+        let arguments = ServerPrompt::specs_to_json(&specs);
+
+        match ServerPrompt::get_by_name(&self.pool, server_id, name).await? {
+            Some(existing) => {
+                let id = existing.id.unwrap_or_default();
+                ServerPrompt::update(
+                    &self.pool,
+                    id,
+                    description,
+                    &form.template,
+                    arguments.as_deref(),
+                )
+                .await?;
+                Ok(id)
+            }
+            None => {
+                ServerPrompt::create(
+                    &self.pool,
+                    server_id,
+                    name,
+                    description,
+                    &form.template,
+                    arguments.as_deref(),
+                )
+                .await
+            }
+        }
+    }
+
+    pub async fn delete_prompt(&self, server_id: i64, user_id: i64, prompt_id: i64) -> Result<()> {
+        if !self.user_owns_server(server_id, user_id).await? {
+            anyhow::bail!("Unauthorized");
+        }
+        ServerPrompt::delete(&self.pool, server_id, prompt_id).await
+    }
+
     // Check if user owns a server
     pub async fn user_owns_server(&self, server_id: i64, user_id: i64) -> Result<bool> {
         let owns = sqlx::query!(
@@ -0,0 +1,295 @@
+//! Incremental decoding of streamed upstream responses
+//!
+//! Streaming tools receive their response body in arbitrary network chunks.
+//! [`StreamDecoder`] buffers those bytes and splits them into logical
+//! messages based on the response content type:
+//!
+//! - `text/event-stream` - one message per SSE event (joined `data:` lines)
+//! - `application/x-ndjson`, `application/jsonl` - one message per line
+//! - anything else - one message per chunk of valid UTF-8 text
+//!
+//! The decoder also keeps the aggregated result that is returned to the
+//! MCP client once the stream ends.
+
+/// Framing used by a streamed response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// Server-Sent Events (`text/event-stream`)
+    EventStream,
+    /// Newline-delimited JSON or JSON Lines
+    NdJson,
+    /// Unframed text, e.g. chunked transfer of plain text
+    Raw,
+}
+
+impl StreamFormat {
+    /// Detects the stream format from a `Content-Type` header value
+    pub fn from_content_type(content_type: Option<&str>) -> Self {
+        let mime = content_type
+            .and_then(|ct| ct.split(';').next())
+            .map(|m| m.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        match mime.as_str() {
+            "text/event-stream" => StreamFormat::EventStream,
+            "application/x-ndjson"
+            | "application/ndjson"
+            | "application/jsonl"
+            | "application/x-jsonlines"
+            | "application/json-seq" => StreamFormat::NdJson,
+            _ => StreamFormat::Raw,
+        }
+    }
+}
+
+/// Buffers streamed bytes and yields complete messages
+#[derive(Debug)]
+pub struct StreamDecoder {
+    format: StreamFormat,
+    buffer: Vec<u8>,
+    messages: Vec<String>,
+}
+
+impl StreamDecoder {
+    pub fn new(format: StreamFormat) -> Self {
+        Self {
+            format,
+            buffer: Vec::new(),
+            messages: Vec::new(),
+        }
+    }
+
+    pub fn format(&self) -> StreamFormat {
+        self.format
+    }
+
+    /// Feeds a chunk of bytes and returns the messages it completed
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let decoded = match self.format {
+            StreamFormat::EventStream => self.drain_events(false),
+            StreamFormat::NdJson => self.drain_lines(false),
+            StreamFormat::Raw => self.drain_text(),
+        };
+
+        self.messages.extend(decoded.iter().cloned());
+        decoded
+    }
+
+    /// Flushes any trailing message left in the buffer at end of stream
+    pub fn finish(&mut self) -> Vec<String> {
+        let decoded = match self.format {
+            StreamFormat::EventStream => self.drain_events(true),
+            StreamFormat::NdJson => self.drain_lines(true),
+            StreamFormat::Raw => {
+                let rest = String::from_utf8_lossy(&self.buffer).to_string();
+                self.buffer.clear();
+                if rest.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![rest]
+                }
+            }
+        };
+
+        self.messages.extend(decoded.iter().cloned());
+        decoded
+    }
+
+    /// Returns the aggregated result of every message decoded so far
+    ///
+    /// Raw streams are concatenated as-is; framed streams are joined with
+    /// newlines so each event or line stays distinguishable.
+    pub fn aggregate(&self) -> String {
+        match self.format {
+            StreamFormat::Raw => self.messages.concat(),
+            StreamFormat::EventStream | StreamFormat::NdJson => self.messages.join("\n"),
+        }
+    }
+
+    fn drain_text(&mut self) -> Vec<String> {
+        let valid_up_to = match std::str::from_utf8(&self.buffer) {
+            Ok(_) => self.buffer.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            // Invalid sequence (not just a truncated one): decode lossily
+            Err(_) => self.buffer.len(),
+        };
+
+        if valid_up_to == 0 {
+            return Vec::new();
+        }
+
+        let bytes: Vec<u8> = self.buffer.drain(..valid_up_to).collect();
+        vec![String::from_utf8_lossy(&bytes).to_string()]
+    }
+
+    fn drain_lines(&mut self, at_eof: bool) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let text = String::from_utf8_lossy(&line).trim().to_string();
+            if !text.is_empty() {
+                lines.push(text);
+            }
+        }
+
+        if at_eof {
+            let text = String::from_utf8_lossy(&self.buffer).trim().to_string();
+            self.buffer.clear();
+            if !text.is_empty() {
+                lines.push(text);
+            }
+        }
+
+        lines
+    }
+
+    fn drain_events(&mut self, at_eof: bool) -> Vec<String> {
+        let mut events = Vec::new();
+
+        // Work on bytes so multi-byte characters split across chunks survive
+        while let Some((end, separator_len)) = Self::find_event_boundary(&self.buffer) {
+            let raw_event: Vec<u8> = self.buffer.drain(..end + separator_len).collect();
+            let text = String::from_utf8_lossy(&raw_event[..end]).replace("\r\n", "\n");
+
+            if let Some(data) = Self::event_data(&text) {
+                events.push(data);
+            }
+        }
+
+        if at_eof {
+            let text = String::from_utf8_lossy(&self.buffer).replace("\r\n", "\n");
+            self.buffer.clear();
+            if let Some(data) = Self::event_data(text.trim_end_matches('\n')) {
+                events.push(data);
+            }
+        }
+
+        events
+    }
+
+    /// Finds the first blank line ending an SSE event
+    ///
+    /// Returns the event length and the length of the separator.
+    fn find_event_boundary(buffer: &[u8]) -> Option<(usize, usize)> {
+        let lf = buffer
+            .windows(2)
+            .position(|w| w == b"\n\n")
+            .map(|pos| (pos, 2));
+        let crlf = buffer
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .map(|pos| (pos, 4));
+
+        match (lf, crlf) {
+            (Some(a), Some(b)) => Some(if a.0 <= b.0 { a } else { b }),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Extracts the joined `data:` payload of one SSE event
+    ///
+    /// Comments, events without data and the `[DONE]` sentinel used by
+    /// LLM-style token streams are skipped.
+    fn event_data(raw_event: &str) -> Option<String> {
+        let data_lines: Vec<&str> = raw_event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|value| value.strip_prefix(' ').unwrap_or(value))
+            .collect();
+
+        if data_lines.is_empty() {
+            return None;
+        }
+
+        let data = data_lines.join("\n");
+        if data.trim() == "[DONE]" {
+            None
+        } else {
+            Some(data)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(
+            StreamFormat::from_content_type(Some("text/event-stream; charset=utf-8")),
+            StreamFormat::EventStream
+        );
+        assert_eq!(
+            StreamFormat::from_content_type(Some("application/x-ndjson")),
+            StreamFormat::NdJson
+        );
+        assert_eq!(
+            StreamFormat::from_content_type(Some("text/plain")),
+            StreamFormat::Raw
+        );
+        assert_eq!(StreamFormat::from_content_type(None), StreamFormat::Raw);
+    }
+
+    #[test]
+    fn test_event_stream_split_across_chunks() {
+        let mut decoder = StreamDecoder::new(StreamFormat::EventStream);
+
+        assert!(decoder.push(b"event: token\ndata: Hel").is_empty());
+        assert_eq!(
+            decoder.push(b"lo\n\ndata: world\r\n\r\n"),
+            vec!["Hello", "world"]
+        );
+        assert!(decoder.push(b": keep-alive\n\ndata: [DONE]\n\n").is_empty());
+        assert!(decoder.finish().is_empty());
+
+        assert_eq!(decoder.aggregate(), "Hello\nworld");
+    }
+
+    #[test]
+    fn test_event_stream_multibyte_split_across_chunks() {
+        let mut decoder = StreamDecoder::new(StreamFormat::EventStream);
+        let bytes = "data: café\n\n".as_bytes();
+
+        // Split inside the two-byte 'é'
+        assert!(decoder.push(&bytes[..10]).is_empty());
+        assert_eq!(decoder.push(&bytes[10..]), vec!["café"]);
+    }
+
+    #[test]
+    fn test_event_stream_multiline_data_and_trailing_event() {
+        let mut decoder = StreamDecoder::new(StreamFormat::EventStream);
+
+        assert_eq!(decoder.push(b"data: a\ndata: b\n\n"), vec!["a\nb"]);
+        assert!(decoder.push(b"data: tail").is_empty());
+        assert_eq!(decoder.finish(), vec!["tail"]);
+    }
+
+    #[test]
+    fn test_ndjson_lines() {
+        let mut decoder = StreamDecoder::new(StreamFormat::NdJson);
+
+        assert_eq!(decoder.push(b"{\"n\":1}\n{\"n\""), vec!["{\"n\":1}"]);
+        assert_eq!(decoder.push(b":2}\n\n"), vec!["{\"n\":2}"]);
+        assert_eq!(decoder.push(b"{\"n\":3}"), Vec::<String>::new());
+        assert_eq!(decoder.finish(), vec!["{\"n\":3}"]);
+
+        assert_eq!(decoder.aggregate(), "{\"n\":1}\n{\"n\":2}\n{\"n\":3}");
+    }
+
+    #[test]
+    fn test_raw_text_keeps_multibyte_characters_intact() {
+        let mut decoder = StreamDecoder::new(StreamFormat::Raw);
+        let bytes = "héllo".as_bytes();
+
+        // Split inside the two-byte 'é'
+        assert_eq!(decoder.push(&bytes[..2]), vec!["h"]);
+        assert_eq!(decoder.push(&bytes[2..]), vec!["éllo"]);
+        assert!(decoder.finish().is_empty());
+
+        assert_eq!(decoder.aggregate(), "héllo");
+    }
+}
@@ -0,0 +1,357 @@
+//! Building toolkits from imported API descriptions
+//!
+//! Importers (such as [`openapi_import`](crate::services::openapi_import))
+//! turn a foreign format into a [`ToolImport`]: a proposed toolkit with one
+//! [`ImportedTool`] per request, plus the server globals its placeholders
+//! expect. The user previews the proposal, picks the tools to keep, and
+//! [`ToolImport::create_toolkits`] saves them through the regular toolkit and
+//! tool services, so imported tools are validated like hand-written ones.
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    CreateToolRequest, CreateToolkitRequest, ParameterMetadata, ParameterMetadataMap,
+};
+use crate::services::{ToolService, ToolkitService};
+use std::collections::HashSet;
+
+/// Longest tool or toolkit name accepted by the services
+const MAX_NAME_LEN: usize = 100;
+
+/// Choices made by the user before importing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    /// Import optional parameters too, as required placeholders
+    pub include_optional: bool,
+    /// Create a toolkit per top-level folder instead of prefixing tool names
+    pub folders_as_toolkits: bool,
+}
+
+/// A proposed toolkit built from an imported document
+#[derive(Debug, Clone, Default)]
+pub struct ToolImport {
+    pub title: String,
+    pub description: Option<String>,
+    pub tools: Vec<ImportedTool>,
+    /// Server globals the tools' placeholders are meant to be bound to
+    pub globals: Vec<SuggestedGlobal>,
+    /// Parts of the document that could not be imported
+    pub warnings: Vec<String>,
+}
+
+/// A tool proposed by an import
+#[derive(Debug, Clone, Default)]
+pub struct ImportedTool {
+    /// Identifies the tool in the preview selection, e.g. "GET /pets/{id}"
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Toolkit the tool goes to when an import is split, e.g. a folder name
+    pub group: Option<String>,
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    pub parameters: Vec<ImportedParameter>,
+    /// Parameters of the source left out of the tool, e.g. optional ones
+    pub skipped: Vec<String>,
+}
+
+/// A placeholder of an imported tool
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImportedParameter {
+    pub name: String,
+    pub param_type: String,
+    /// Where the value goes: path, query, header, cookie or body
+    pub location: String,
+    pub metadata: ParameterMetadata,
+}
+
+/// A server global suggested by an import, e.g. for an API key
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SuggestedGlobal {
+    pub name: String,
+    pub description: String,
+    pub secret: bool,
+    /// Value found in the document, offered as the default
+    pub value: String,
+}
+
+impl ImportedParameter {
+    /// The `{{type:name}}` placeholder for this parameter
+    pub fn placeholder(&self) -> String {
+        format!("{{{{{}:{}}}}}", self.param_type, self.name)
+    }
+}
+
+impl ImportedTool {
+    pub fn to_request(&self) -> CreateToolRequest {
+        let headers: serde_json::Map<String, serde_json::Value> = self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), serde_json::Value::String(value.clone())))
+            .collect();
+
+        let metadata: ParameterMetadataMap = self
+            .parameters
+            .iter()
+            .map(|p| (p.name.clone(), p.metadata.clone()))
+            .collect();
+
+        CreateToolRequest {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            method: self.method.clone(),
+            url: Some(self.url.clone()),
+            headers: Some(serde_json::Value::Object(headers).to_string()),
+            body: self.body.clone(),
+            timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: ParameterMetadata::map_to_json(&metadata),
+        }
+    }
+
+    /// Short parameter list for the preview, e.g. "petId (integer, path)"
+    pub fn parameters_summary(&self) -> String {
+        self.parameters
+            .iter()
+            .map(|p| format!("{} ({}, {})", p.name, p.param_type, p.location))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn skipped_summary(&self) -> String {
+        self.skipped.join(", ")
+    }
+}
+
+impl ToolImport {
+    /// Adds a suggested global unless one with the same name exists
+    pub fn suggest_global(&mut self, global: SuggestedGlobal) {
+        if !self.globals.iter().any(|g| g.name == global.name) {
+            self.globals.push(global);
+        }
+    }
+
+    /// Gives every tool a unique name, suffixing repeats with a number
+    pub fn dedupe_tool_names(&mut self) {
+        let mut used = HashSet::new();
+        for tool in &mut self.tools {
+            tool.name = unique_name(&tool.name, &mut used);
+        }
+    }
+
+    /// Creates private toolkits holding the selected tools
+    ///
+    /// Tools without a group go to a toolkit named `title`; grouped tools
+    /// to one toolkit per group, named "{title} - {group}". `selected` holds
+    /// tool keys; all tools are created when it is `None`. Created toolkits
+    /// are removed again if any tool fails validation, so a failed import
+    /// leaves nothing behind.
+    pub async fn create_toolkits(
+        &self,
+        toolkit_service: &ToolkitService,
+        tool_service: &ToolService,
+        user_id: i64,
+        title: &str,
+        selected: Option<&[String]>,
+    ) -> Result<Vec<i64>> {
+        let mut groups: Vec<(Option<&str>, Vec<&ImportedTool>)> = Vec::new();
+        for tool in self
+            .tools
+            .iter()
+            .filter(|t| selected.is_none_or(|keys| keys.contains(&t.key)))
+        {
+            let group = tool.group.as_deref();
+            match groups.iter_mut().find(|(g, _)| *g == group) {
+                Some((_, tools)) => tools.push(tool),
+                None => groups.push((group, vec![tool])),
+            }
+        }
+        if groups.is_empty() {
+            return Err(AppError::Validation(
+                "Select at least one tool to import".to_string(),
+            ));
+        }
+
+        let mut created = Vec::new();
+        for (group, tools) in groups {
+            let title = match group {
+                Some(group) => format!("{} - {}", title.trim(), group),
+                None => title.trim().to_string(),
+            };
+            match self
+                .create_toolkit(toolkit_service, tool_service, user_id, &title, &tools)
+                .await
+            {
+                Ok(id) => created.push(id),
+                Err(e) => {
+                    for id in created {
+                        toolkit_service.delete_toolkit(id, user_id).await?;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(created)
+    }
+
+    async fn create_toolkit(
+        &self,
+        toolkit_service: &ToolkitService,
+        tool_service: &ToolService,
+        user_id: i64,
+        title: &str,
+        tools: &[&ImportedTool],
+    ) -> Result<i64> {
+        let toolkit_id = toolkit_service
+            .create_toolkit(
+                user_id,
+                CreateToolkitRequest {
+                    title: truncate(title, MAX_NAME_LEN),
+                    description: self.description.clone(),
+                    visibility: "private".to_string(),
+                },
+            )
+            .await?;
+
+        for tool in tools {
+            if let Err(e) = tool_service
+                .create_tool(toolkit_id, user_id, tool.to_request())
+                .await
+            {
+                toolkit_service.delete_toolkit(toolkit_id, user_id).await?;
+                return Err(match e {
+                    AppError::Validation(msg) => {
+                        AppError::Validation(format!("{}: {}", tool.key, msg))
+                    }
+                    e => e,
+                });
+            }
+        }
+
+        Ok(toolkit_id)
+    }
+}
+
+/// Turns an identifier into a snake_case placeholder or tool name
+///
+/// `getPetById` becomes `get_pet_by_id` and `X-Request-ID` becomes
+/// `x_request_id`; anything outside `\w` separates words.
+pub fn snake_case(input: &str) -> String {
+    let mut out = String::new();
+    let mut prev: Option<char> = None;
+    let chars: Vec<char> = input.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_alphanumeric() {
+            let boundary = c.is_ascii_uppercase()
+                && prev.is_some_and(|p| {
+                    p.is_ascii_lowercase()
+                        || p.is_ascii_digit()
+                        || (p.is_ascii_uppercase()
+                            && chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase()))
+                });
+            if boundary && !out.is_empty() && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+        prev = Some(c);
+    }
+
+    let out = out.trim_end_matches('_').to_string();
+    match out.chars().next() {
+        None => "param".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("p_{}", out),
+        Some(_) => truncate(&out, MAX_NAME_LEN),
+    }
+}
+
+/// Returns `name`, or `name_2`, `name_3`... if already used, and marks it used
+pub fn unique_name(name: &str, used: &mut HashSet<String>) -> String {
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while used.contains(&candidate) {
+        let suffix = format!("_{}", n);
+        candidate = format!("{}{}", truncate(name, MAX_NAME_LEN - suffix.len()), suffix);
+        n += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    s.chars().take(max).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("getPetById"), "get_pet_by_id");
+        assert_eq!(snake_case("X-Request-ID"), "x_request_id");
+        assert_eq!(snake_case("HTMLParser"), "html_parser");
+        assert_eq!(snake_case("list pets (v2)"), "list_pets_v2");
+        assert_eq!(snake_case("2fa_code"), "p_2fa_code");
+        assert_eq!(snake_case("--"), "param");
+    }
+
+    #[test]
+    fn test_unique_name() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_name("list_pets", &mut used), "list_pets");
+        assert_eq!(unique_name("list_pets", &mut used), "list_pets_2");
+        assert_eq!(unique_name("list_pets", &mut used), "list_pets_3");
+    }
+
+    #[test]
+    fn test_to_request_keeps_metadata_of_documented_parameters() {
+        let tool = ImportedTool {
+            key: "GET /pets".to_string(),
+            name: "list_pets".to_string(),
+            method: "GET".to_string(),
+            url: "https://api.example.com/pets?limit={{integer:limit}}".to_string(),
+            headers: vec![("X-Api-Key".to_string(), "{{string:api_key}}".to_string())],
+            parameters: vec![
+                ImportedParameter {
+                    name: "limit".to_string(),
+                    param_type: "integer".to_string(),
+                    location: "query".to_string(),
+                    metadata: ParameterMetadata {
+                        description: Some("Page size".to_string()),
+                        enum_values: Vec::new(),
+                        lookup: None,
+                    },
+                },
+                ImportedParameter {
+                    name: "api_key".to_string(),
+                    param_type: "string".to_string(),
+                    location: "header".to_string(),
+                    metadata: ParameterMetadata::default(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let request = tool.to_request();
+        assert_eq!(
+            request.headers.as_deref(),
+            Some(r#"{"X-Api-Key":"{{string:api_key}}"}"#)
+        );
+        assert_eq!(
+            request.parameter_metadata.as_deref(),
+            Some(r#"{"limit":{"description":"Page size"}}"#)
+        );
+        assert_eq!(
+            tool.parameters_summary(),
+            "limit (integer, query), api_key (string, header)"
+        );
+    }
+}
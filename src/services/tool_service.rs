@@ -1,8 +1,23 @@
 use crate::error::{AppError, Result};
-use crate::models::{CreateToolRequest, ExtractedParameter, Tool, UpdateToolRequest};
+use crate::models::{
+    CreateToolRequest, EndpointConfig, ExtractedParameter, ParameterMetadata, RateLimitConfig,
+    Tool, UpdateToolRequest,
+};
 use crate::repositories::{ToolRepository, ToolkitRepository};
+use crate::services::mcp_client::{MCP_METHOD, MCP_SSE_METHOD};
 use std::sync::Arc;
 
+/// HTTP methods, and the methods of tools forwarding to a remote MCP server
+const METHODS: [&str; 7] = [
+    "GET",
+    "POST",
+    "PUT",
+    "DELETE",
+    "PATCH",
+    MCP_METHOD,
+    MCP_SSE_METHOD,
+];
+
 pub struct ToolService {
     tool_repository: Arc<dyn ToolRepository>,
     toolkit_repository: Arc<dyn ToolkitRepository>,
@@ -35,6 +50,16 @@ impl ToolService {
             return Err(AppError::UserNotFound);
         }
 
+        Self::validate_request(&request)?;
+
+        // Create tool with parameters
+        self.tool_repository.create(toolkit_id, request).await
+    }
+
+    /// Validates a tool before it is created, without saving anything
+    ///
+    /// Lets importers check every tool up front and report all problems.
+    pub fn validate_request(request: &CreateToolRequest) -> Result<()> {
         // Validate input
         if request.name.trim().is_empty() {
             return Err(AppError::Validation("Tool name is required".to_string()));
@@ -47,7 +72,7 @@ impl ToolService {
         }
 
         // Validate HTTP method
-        if !["GET", "POST", "PUT", "DELETE", "PATCH"].contains(&request.method.as_str()) {
+        if !METHODS.contains(&request.method.as_str()) {
             return Err(AppError::Validation("Invalid HTTP method".to_string()));
         }
 
@@ -62,15 +87,28 @@ impl ToolService {
             }
         }
 
-        // Validate JSON format for body
+        // Validate body format, allowing placeholders as JSON values
         if let Some(ref body) = request.body {
-            if !body.trim().is_empty() && serde_json::from_str::<serde_json::Value>(body).is_err() {
-                return Err(AppError::Validation("Body must be valid JSON".to_string()));
-            }
+            Tool::validate_body_template(body, request.headers.as_deref())
+                .map_err(AppError::Validation)?;
         }
 
-        // Create tool with parameters
-        self.tool_repository.create(toolkit_id, request).await
+        // Validate multi-endpoint configuration
+        if let Some(ref endpoints) = request.endpoints {
+            EndpointConfig::from_json(endpoints).map_err(AppError::Validation)?;
+        }
+
+        // Validate rate limit
+        if let Some(ref rate_limit) = request.rate_limit {
+            RateLimitConfig::from_json(rate_limit).map_err(AppError::Validation)?;
+        }
+
+        // Validate parameter documentation
+        if let Some(ref metadata) = request.parameter_metadata {
+            ParameterMetadata::map_from_json(metadata).map_err(AppError::Validation)?;
+        }
+
+        Ok(())
     }
 
     pub async fn get_tool(&self, id: i64, user_id: i64) -> Result<(Tool, Vec<ExtractedParameter>)> {
@@ -140,7 +178,7 @@ impl ToolService {
         }
 
         // Validate HTTP method
-        if !["GET", "POST", "PUT", "DELETE", "PATCH"].contains(&request.method.as_str()) {
+        if !METHODS.contains(&request.method.as_str()) {
             return Err(AppError::Validation("Invalid HTTP method".to_string()));
         }
 
@@ -155,11 +193,20 @@ impl ToolService {
             }
         }
 
-        // Validate JSON format for body
+        // Validate body format, allowing placeholders as JSON values
         if let Some(ref body) = request.body {
-            if !body.trim().is_empty() && serde_json::from_str::<serde_json::Value>(body).is_err() {
-                return Err(AppError::Validation("Body must be valid JSON".to_string()));
-            }
+            Tool::validate_body_template(body, request.headers.as_deref())
+                .map_err(AppError::Validation)?;
+        }
+
+        // Validate multi-endpoint configuration
+        if let Some(ref endpoints) = request.endpoints {
+            EndpointConfig::from_json(endpoints).map_err(AppError::Validation)?;
+        }
+
+        // Validate rate limit
+        if let Some(ref rate_limit) = request.rate_limit {
+            RateLimitConfig::from_json(rate_limit).map_err(AppError::Validation)?;
         }
 
         // Update tool
@@ -199,4 +246,32 @@ impl ToolService {
             Err(AppError::UserNotFound)
         }
     }
+
+    /// Replaces the parameter documentation of a tool
+    ///
+    /// Kept apart from [`ToolService::update_tool`] since the edit form does
+    /// not show parameter documentation and must not clear it.
+    pub async fn set_parameter_metadata(
+        &self,
+        id: i64,
+        user_id: i64,
+        parameter_metadata: Option<String>,
+    ) -> Result<()> {
+        let (tool, _) = self.get_tool(id, user_id).await?;
+
+        if let Some(ref metadata) = parameter_metadata {
+            ParameterMetadata::map_from_json(metadata).map_err(AppError::Validation)?;
+        }
+
+        let updated = self
+            .tool_repository
+            .set_parameter_metadata(tool.id, parameter_metadata)
+            .await?;
+
+        if updated {
+            Ok(())
+        } else {
+            Err(AppError::UserNotFound)
+        }
+    }
 }
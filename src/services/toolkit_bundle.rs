@@ -0,0 +1,611 @@
+//! Portable toolkit bundles
+//!
+//! A bundle is a versioned YAML or JSON document holding a toolkit and its
+//! tools, so a toolkit can be kept in git and imported into another
+//! deployment:
+//!
+//! ```yaml
+//! format: saramcp-toolkit
+//! version: 1
+//! toolkit:
+//!   title: Weather
+//! tools:
+//!   - name: current_weather
+//!     method: GET
+//!     url: https://api.example.com/weather?q={{string:city}}
+//!     parameters:
+//!       city:
+//!         description: City name
+//! ```
+//!
+//! Bundles hold no secrets: credentials live in server globals and instance
+//! bindings, which are not part of a toolkit. Ownership, visibility and
+//! clone statistics belong to the deployment and are left out too.
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    CreateToolRequest, CreateToolkitRequest, EndpointConfig, ParameterMetadata,
+    ParameterMetadataMap, RateLimitConfig, Tool, Toolkit, UpdateToolRequest, UpdateToolkitRequest,
+};
+use crate::services::{ToolService, ToolkitService};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+/// Value of the `format` field identifying a bundle
+pub const BUNDLE_FORMAT: &str = "saramcp-toolkit";
+
+/// Current bundle version; older versions are read, newer ones refused
+pub const BUNDLE_VERSION: u32 = 1;
+
+const DEFAULT_TIMEOUT_MS: i32 = 30000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("Bundle is not valid JSON or YAML: {0}")]
+    Invalid(String),
+
+    #[error("Not a SaraMCP toolkit bundle, export one from a toolkit page")]
+    UnsupportedFormat,
+
+    #[error(
+        "Bundle version {0} is newer than this deployment supports (version {BUNDLE_VERSION})"
+    )]
+    UnsupportedVersion(u32),
+
+    #[error("Could not write the bundle: {0}")]
+    Serialize(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolkitBundle {
+    pub format: String,
+    pub version: u32,
+    pub toolkit: BundledToolkit,
+    #[serde(default)]
+    pub tools: Vec<BundledTool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundledToolkit {
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoints: Option<EndpointConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundledTool {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub method: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: i32,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub streaming: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoints: Option<EndpointConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Documentation of the placeholders, keyed by parameter name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: ParameterMetadataMap,
+}
+
+fn default_timeout_ms() -> i32 {
+    DEFAULT_TIMEOUT_MS
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Where an imported bundle goes
+#[derive(Debug, Clone, PartialEq)]
+pub enum BundleTarget {
+    /// A new private toolkit with this title
+    NewToolkit { title: String },
+    /// An existing toolkit; tools named like existing ones replace them
+    /// when `replace` is set and are skipped otherwise
+    Merge { toolkit_id: i64, replace: bool },
+}
+
+/// What importing a bundle into a toolkit would do
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BundlePlan {
+    /// Tools not in the target toolkit yet
+    pub new_tools: Vec<String>,
+    /// Tools named like a tool of the target toolkit
+    pub conflicts: Vec<String>,
+    /// Problems that prevent the import, e.g. invalid templates
+    pub errors: Vec<String>,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BundleImportSummary {
+    pub toolkit_id: i64,
+    pub created: Vec<String>,
+    pub replaced: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+impl BundlePlan {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl ToolkitBundle {
+    /// Builds a bundle from a toolkit and its tools
+    pub fn from_toolkit(toolkit: &Toolkit, tools: &[Tool]) -> Self {
+        ToolkitBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            toolkit: BundledToolkit {
+                title: toolkit.title.clone(),
+                description: toolkit.description.clone(),
+                endpoints: EndpointConfig::parse(toolkit.endpoints.as_deref()),
+                rate_limit: RateLimitConfig::parse(toolkit.rate_limit.as_deref()),
+            },
+            tools: tools.iter().map(BundledTool::from_tool).collect(),
+        }
+    }
+
+    /// Reads a bundle from YAML or JSON
+    pub fn parse(text: &str) -> std::result::Result<Self, BundleError> {
+        let value: Value = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|e| BundleError::Invalid(e.to_string()))?
+        } else {
+            serde_yaml::from_str(text).map_err(|e| BundleError::Invalid(e.to_string()))?
+        };
+
+        // Format and version first, so a newer bundle is reported as such
+        // rather than as an unknown field
+        if value.get("format").and_then(Value::as_str) != Some(BUNDLE_FORMAT) {
+            return Err(BundleError::UnsupportedFormat);
+        }
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or(BundleError::UnsupportedFormat)?;
+        if version > BUNDLE_VERSION as u64 {
+            return Err(BundleError::UnsupportedVersion(
+                u32::try_from(version).unwrap_or(u32::MAX),
+            ));
+        }
+
+        serde_json::from_value(value).map_err(|e| BundleError::Invalid(e.to_string()))
+    }
+
+    pub fn to_yaml(&self) -> std::result::Result<String, BundleError> {
+        serde_yaml::to_string(self).map_err(|e| BundleError::Serialize(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> std::result::Result<String, BundleError> {
+        serde_json::to_string_pretty(self).map_err(|e| BundleError::Serialize(e.to_string()))
+    }
+
+    /// Checks the bundle against the tools of the target toolkit
+    ///
+    /// Every tool is validated like a hand-written one, so all problems are
+    /// reported before anything is saved.
+    pub fn plan(&self, existing: &[Tool]) -> BundlePlan {
+        let mut plan = BundlePlan::default();
+
+        if let Some(ref endpoints) = self.toolkit.endpoints {
+            if let Err(e) = validate_endpoints(endpoints) {
+                plan.errors.push(format!("Toolkit: {}", e));
+            }
+        }
+        if let Some(ref rate_limit) = self.toolkit.rate_limit {
+            if let Err(e) = validate_rate_limit(rate_limit) {
+                plan.errors.push(format!("Toolkit: {}", e));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for tool in &self.tools {
+            if !seen.insert(tool.name.as_str()) {
+                plan.errors
+                    .push(format!("{}: appears more than once", tool.name));
+                continue;
+            }
+            if let Err(e) = ToolService::validate_request(&tool.to_create_request()) {
+                let message = match e {
+                    AppError::Validation(msg) => msg,
+                    e => e.to_string(),
+                };
+                plan.errors.push(format!("{}: {}", tool.name, message));
+            }
+
+            if existing.iter().any(|t| t.name == tool.name) {
+                plan.conflicts.push(tool.name.clone());
+            } else {
+                plan.new_tools.push(tool.name.clone());
+            }
+        }
+
+        plan
+    }
+
+    /// Imports the bundle into a new or an existing toolkit
+    ///
+    /// Nothing is saved when the bundle has errors. A new toolkit is removed
+    /// again if a tool fails to save. Merging keeps the settings of the
+    /// existing toolkit and only adds or replaces tools.
+    pub async fn import(
+        &self,
+        toolkit_service: &ToolkitService,
+        tool_service: &ToolService,
+        user_id: i64,
+        target: &BundleTarget,
+    ) -> Result<BundleImportSummary> {
+        match target {
+            BundleTarget::NewToolkit { title } => {
+                let plan = self.plan(&[]);
+                if !plan.is_valid() {
+                    return Err(AppError::Validation(plan.errors.join("; ")));
+                }
+
+                let title = match title.trim() {
+                    "" => self.toolkit.title.trim(),
+                    title => title,
+                };
+                let toolkits = toolkit_service.list_toolkits(user_id).await?;
+                if toolkits.iter().any(|t| t.title == title) {
+                    return Err(AppError::Validation(format!(
+                        "A toolkit named '{}' already exists, choose another title or merge into it",
+                        title
+                    )));
+                }
+                let toolkit_id = toolkit_service
+                    .create_toolkit(
+                        user_id,
+                        CreateToolkitRequest {
+                            title: title.to_string(),
+                            description: self.toolkit.description.clone(),
+                            visibility: "private".to_string(),
+                        },
+                    )
+                    .await?;
+
+                match self
+                    .fill_new_toolkit(toolkit_service, tool_service, user_id, toolkit_id, title)
+                    .await
+                {
+                    Ok(created) => Ok(BundleImportSummary {
+                        toolkit_id,
+                        created,
+                        ..Default::default()
+                    }),
+                    Err(e) => {
+                        toolkit_service.delete_toolkit(toolkit_id, user_id).await?;
+                        Err(e)
+                    }
+                }
+            }
+            BundleTarget::Merge {
+                toolkit_id,
+                replace,
+            } => {
+                let existing = tool_service.list_tools(*toolkit_id, user_id).await?;
+                let plan = self.plan(&existing);
+                if !plan.is_valid() {
+                    return Err(AppError::Validation(plan.errors.join("; ")));
+                }
+
+                let mut summary = BundleImportSummary {
+                    toolkit_id: *toolkit_id,
+                    ..Default::default()
+                };
+                for tool in &self.tools {
+                    match existing.iter().find(|t| t.name == tool.name) {
+                        Some(current) if *replace => {
+                            tool_service
+                                .update_tool(current.id, user_id, tool.to_update_request())
+                                .await?;
+                            tool_service
+                                .set_parameter_metadata(
+                                    current.id,
+                                    user_id,
+                                    ParameterMetadata::map_to_json(&tool.parameters),
+                                )
+                                .await?;
+                            summary.replaced.push(tool.name.clone());
+                        }
+                        Some(_) => summary.skipped.push(tool.name.clone()),
+                        None => {
+                            tool_service
+                                .create_tool(*toolkit_id, user_id, tool.to_create_request())
+                                .await?;
+                            summary.created.push(tool.name.clone());
+                        }
+                    }
+                }
+                Ok(summary)
+            }
+        }
+    }
+
+    async fn fill_new_toolkit(
+        &self,
+        toolkit_service: &ToolkitService,
+        tool_service: &ToolService,
+        user_id: i64,
+        toolkit_id: i64,
+        title: &str,
+    ) -> Result<Vec<String>> {
+        if self.toolkit.endpoints.is_some() || self.toolkit.rate_limit.is_some() {
+            toolkit_service
+                .update_toolkit(
+                    toolkit_id,
+                    user_id,
+                    UpdateToolkitRequest {
+                        title: title.to_string(),
+                        description: self.toolkit.description.clone(),
+                        visibility: "private".to_string(),
+                        endpoints: self.toolkit.endpoints.as_ref().and_then(to_json),
+                        rate_limit: self.toolkit.rate_limit.as_ref().and_then(to_json),
+                    },
+                )
+                .await?;
+        }
+
+        let mut created = Vec::new();
+        for tool in &self.tools {
+            tool_service
+                .create_tool(toolkit_id, user_id, tool.to_create_request())
+                .await
+                .map_err(|e| match e {
+                    AppError::Validation(msg) => {
+                        AppError::Validation(format!("{}: {}", tool.name, msg))
+                    }
+                    e => e,
+                })?;
+            created.push(tool.name.clone());
+        }
+        Ok(created)
+    }
+}
+
+impl BundledTool {
+    fn from_tool(tool: &Tool) -> Self {
+        let headers = tool
+            .headers
+            .as_deref()
+            .and_then(|h| serde_json::from_str::<serde_json::Map<String, Value>>(h).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                (name, value)
+            })
+            .collect();
+
+        BundledTool {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            method: tool.method.clone(),
+            url: tool.url.clone().unwrap_or_default(),
+            headers,
+            body: tool.body.clone().filter(|b| !b.trim().is_empty()),
+            timeout_ms: tool.timeout_ms,
+            streaming: tool.streaming,
+            endpoints: tool.endpoint_config(),
+            rate_limit: tool.rate_limit_config(),
+            parameters: ParameterMetadata::parse_map(tool.parameter_metadata.as_deref()),
+        }
+    }
+
+    fn headers_json(&self) -> String {
+        let headers: serde_json::Map<String, Value> = self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect();
+        Value::Object(headers).to_string()
+    }
+
+    pub fn to_create_request(&self) -> CreateToolRequest {
+        CreateToolRequest {
+            name: self.name.trim().to_string(),
+            description: self.description.clone(),
+            method: self.method.to_uppercase(),
+            url: Some(self.url.clone()),
+            headers: Some(self.headers_json()),
+            body: self.body.clone(),
+            timeout_ms: self.timeout_ms,
+            streaming: self.streaming,
+            endpoints: self.endpoints.as_ref().and_then(to_json),
+            rate_limit: self.rate_limit.as_ref().and_then(to_json),
+            parameter_metadata: ParameterMetadata::map_to_json(&self.parameters),
+        }
+    }
+
+    pub fn to_update_request(&self) -> UpdateToolRequest {
+        let request = self.to_create_request();
+        UpdateToolRequest {
+            name: request.name,
+            description: request.description,
+            method: request.method,
+            url: request.url,
+            headers: request.headers,
+            body: request.body,
+            timeout_ms: request.timeout_ms,
+            streaming: request.streaming,
+            endpoints: request.endpoints,
+            rate_limit: request.rate_limit,
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_string(value).ok()
+}
+
+fn validate_endpoints(endpoints: &EndpointConfig) -> std::result::Result<(), String> {
+    EndpointConfig::from_json(&to_json(endpoints).unwrap_or_default()).map(|_| ())
+}
+
+fn validate_rate_limit(rate_limit: &RateLimitConfig) -> std::result::Result<(), String> {
+    RateLimitConfig::from_json(&to_json(rate_limit).unwrap_or_default()).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUNDLE: &str = r#"format: saramcp-toolkit
+version: 1
+toolkit:
+  title: Weather
+  description: Forecasts
+  rate_limit:
+    requests: 5
+tools:
+  - name: current_weather
+    method: GET
+    url: https://api.example.com/weather?q={{string:city}}
+    headers:
+      Accept: application/json
+    parameters:
+      city:
+        description: City name
+  - name: report
+    description: Send a report
+    method: POST
+    url: https://api.example.com/reports
+    body: '{"text": {{json:text}}}'
+    timeout_ms: 5000
+    streaming: true
+"#;
+
+    fn tool(name: &str) -> Tool {
+        Tool {
+            id: 1,
+            toolkit_id: 1,
+            name: name.to_string(),
+            description: None,
+            method: "GET".to_string(),
+            url: Some("https://example.com".to_string()),
+            headers: Some(r#"{"X-Count": 3}"#.to_string()),
+            body: None,
+            timeout_ms: 30000,
+            streaming: false,
+            endpoints: None,
+            rate_limit: None,
+            parameter_metadata: Some(r#"{"id":{"description":"Item"}}"#.to_string()),
+            created_at: chrono::NaiveDateTime::default(),
+            updated_at: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_yaml_bundle() {
+        let bundle = ToolkitBundle::parse(BUNDLE).unwrap();
+
+        assert_eq!(bundle.toolkit.title, "Weather");
+        assert_eq!(bundle.toolkit.rate_limit.as_ref().unwrap().requests, 5);
+        assert_eq!(bundle.tools.len(), 2);
+
+        let weather = &bundle.tools[0];
+        assert_eq!(weather.timeout_ms, 30000);
+        assert_eq!(weather.headers["Accept"], "application/json");
+        assert_eq!(
+            weather.parameters["city"].description.as_deref(),
+            Some("City name")
+        );
+
+        let report = bundle.tools[1].to_create_request();
+        assert_eq!(report.timeout_ms, 5000);
+        assert!(report.streaming);
+        assert_eq!(report.headers.as_deref(), Some("{}"));
+        assert!(report.parameter_metadata.is_none());
+    }
+
+    #[test]
+    fn test_yaml_and_json_round_trip() {
+        let bundle = ToolkitBundle::parse(BUNDLE).unwrap();
+
+        let yaml = bundle.to_yaml().unwrap();
+        assert_eq!(ToolkitBundle::parse(&yaml).unwrap(), bundle);
+
+        let json = bundle.to_json().unwrap();
+        assert!(json.starts_with('{'));
+        assert_eq!(ToolkitBundle::parse(&json).unwrap(), bundle);
+    }
+
+    #[test]
+    fn test_export_keeps_metadata_and_header_values() {
+        let bundled = BundledTool::from_tool(&tool("lookup"));
+
+        assert_eq!(bundled.headers["X-Count"], "3");
+        assert_eq!(
+            bundled.parameters["id"].description.as_deref(),
+            Some("Item")
+        );
+        assert!(!serde_yaml::to_string(&bundled)
+            .unwrap()
+            .contains("streaming"));
+    }
+
+    #[test]
+    fn test_plan_reports_conflicts_and_errors() {
+        let mut bundle = ToolkitBundle::parse(BUNDLE).unwrap();
+        bundle.tools.push(BundledTool {
+            method: "TRACE".to_string(),
+            ..bundle.tools[0].clone()
+        });
+        bundle.tools.push(BundledTool {
+            name: "broken".to_string(),
+            method: "TRACE".to_string(),
+            ..bundle.tools[0].clone()
+        });
+
+        let plan = bundle.plan(&[tool("report")]);
+        assert_eq!(plan.new_tools, ["current_weather", "broken"]);
+        assert_eq!(plan.conflicts, ["report"]);
+        assert_eq!(
+            plan.errors,
+            [
+                "current_weather: appears more than once",
+                "broken: Invalid HTTP method",
+            ]
+        );
+        assert!(!plan.is_valid());
+    }
+
+    #[test]
+    fn test_rejects_other_documents() {
+        assert!(matches!(
+            ToolkitBundle::parse("{not json"),
+            Err(BundleError::Invalid(_))
+        ));
+        assert!(matches!(
+            ToolkitBundle::parse("openapi: 3.1.0"),
+            Err(BundleError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            ToolkitBundle::parse("format: saramcp-toolkit\nversion: 2\ntoolkit: {title: X}"),
+            Err(BundleError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            ToolkitBundle::parse("format: saramcp-toolkit\nversion: 1\ntools: []"),
+            Err(BundleError::Invalid(_))
+        ));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::error::{AppError, Result};
 use crate::models::{
-    CreateToolkitRequest, PublicToolkitDetails, Toolkit, ToolkitSummary, ToolkitWithStats,
-    UpdateToolkitRequest,
+    CreateToolkitRequest, EndpointConfig, PublicToolkitDetails, RateLimitConfig, Toolkit,
+    ToolkitSummary, ToolkitWithStats, UpdateToolkitRequest,
 };
 use crate::repositories::{ToolRepository, ToolkitRepository};
 use std::sync::Arc;
@@ -82,6 +82,16 @@ impl ToolkitService {
             ));
         }
 
+        // Validate multi-endpoint configuration
+        if let Some(ref endpoints) = request.endpoints {
+            EndpointConfig::from_json(endpoints).map_err(AppError::Validation)?;
+        }
+
+        // Validate rate limit
+        if let Some(ref rate_limit) = request.rate_limit {
+            RateLimitConfig::from_json(rate_limit).map_err(AppError::Validation)?;
+        }
+
         // Update toolkit
         let updated = self.repository.update(id, user_id, request).await?;
 
@@ -153,6 +153,16 @@ impl TypedVariableEngine {
         }
     }
 
+    /// Type and name of the variable when `text` is a single placeholder
+    pub fn sole_variable<'a>(&self, text: &'a str) -> Option<(Option<&'a str>, &'a str)> {
+        let cap = self.pattern.captures(text)?;
+        let whole = cap.get(0)?;
+        if whole.start() != 0 || whole.end() != text.len() {
+            return None;
+        }
+        Some((cap.get(1).map(|m| m.as_str()), cap.get(2)?.as_str()))
+    }
+
     pub fn find_variables(&self, template: &str) -> Vec<(Option<String>, String)> {
         let mut vars = Vec::new();
         for cap in self.pattern.captures_iter(template) {
@@ -239,4 +249,17 @@ mod tests {
         assert_eq!(vars[1], (Some("url".to_string()), "api_base".to_string()));
         assert_eq!(vars[2], (Some("integer".to_string()), "id".to_string()));
     }
+
+    #[test]
+    fn test_sole_variable() {
+        let engine = TypedVariableEngine::new();
+
+        assert_eq!(
+            engine.sole_variable("{{integer:days}}"),
+            Some((Some("integer"), "days"))
+        );
+        assert_eq!(engine.sole_variable("{{city}}"), Some((None, "city")));
+        assert_eq!(engine.sole_variable("Bearer {{token}}"), None);
+        assert_eq!(engine.sole_variable("{{a}}{{b}}"), None);
+    }
 }
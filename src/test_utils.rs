@@ -1,7 +1,37 @@
 pub mod test_helpers {
+    use crate::repositories::{
+        tool_repository::SqliteToolRepository, toolkit_repository::SqliteToolkitRepository,
+        ToolRepository, ToolkitRepository,
+    };
+    use crate::services::{
+        InstanceService, SecretsManager, ServerService, ToolService, ToolkitService,
+    };
     use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+    use std::sync::Arc;
     use tempfile::NamedTempFile;
 
+    /// Egress policy allowing loopback hosts
+    ///
+    /// Mock upstreams listen on 127.0.0.1, which the default egress policy
+    /// blocks.
+    pub fn loopback_egress_policy() -> crate::services::EgressPolicy {
+        use crate::services::{EgressPolicy, HostRule};
+
+        let loopback = ["localhost", "127.0.0.0/8", "::1"]
+            .iter()
+            .filter_map(|rule| HostRule::parse(rule).ok())
+            .collect();
+        EgressPolicy::default().with_rules(loopback, Vec::new())
+    }
+
+    /// Allow tool requests to loopback hosts process-wide
+    ///
+    /// For tests calling mock upstreams through executors they do not build
+    /// themselves; tests of the egress policy pass an explicit policy.
+    pub fn allow_loopback_egress() {
+        crate::services::EgressPolicy::set_global(loopback_egress_policy());
+    }
+
     /// Create a new in-memory SQLite database for testing
     pub async fn create_test_db() -> Result<SqlitePool, sqlx::Error> {
         let pool = SqlitePoolOptions::new()
@@ -36,6 +66,37 @@ pub mod test_helpers {
         Ok((pool, temp_file))
     }
 
+    /// Services over a test database, built as the web handlers build them
+    pub struct Services {
+        pub pool: SqlitePool,
+        pub toolkits: ToolkitService,
+        pub tools: ToolService,
+        pub servers: ServerService,
+        pub instances: InstanceService,
+    }
+
+    /// Create a test database with a verified user and the services over it
+    ///
+    /// Returns the services and the ID of the user.
+    pub async fn setup_services() -> anyhow::Result<(Services, i64)> {
+        let pool = create_test_db().await?;
+        let user_id = insert_test_user(&pool, "test@example.com", "password", true).await?;
+
+        let toolkit_repository: Arc<dyn ToolkitRepository> =
+            Arc::new(SqliteToolkitRepository::new(pool.clone()));
+        let tool_repository: Arc<dyn ToolRepository> =
+            Arc::new(SqliteToolRepository::new(pool.clone()));
+
+        let services = Services {
+            toolkits: ToolkitService::new(toolkit_repository.clone(), tool_repository.clone()),
+            tools: ToolService::new(tool_repository, toolkit_repository),
+            servers: ServerService::new(pool.clone(), SecretsManager::new()?),
+            instances: InstanceService::new(pool.clone(), SecretsManager::new()?),
+            pool,
+        };
+        Ok((services, user_id))
+    }
+
     /// Insert a test user with hashed password
     pub async fn insert_test_user(
         pool: &SqlitePool,
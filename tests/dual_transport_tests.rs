@@ -18,6 +18,14 @@ use saramcp::{mcp, AppState};
 
 /// Create test server with a configured MCP instance
 async fn setup_test_server(pool: &SqlitePool) -> (String, Router) {
+    let (uuid, app, _) = setup_test_server_with_registry(pool).await;
+    (uuid, app)
+}
+
+/// Create test server, also returning the registry serving it
+async fn setup_test_server_with_registry(
+    pool: &SqlitePool,
+) -> (String, Router, Arc<RwLock<mcp::McpServerRegistry>>) {
     // Create test user
     let user_record = sqlx::query!(
         "INSERT INTO users (email, password_hash) VALUES (?, ?) RETURNING id",
@@ -104,6 +112,8 @@ async fn setup_test_server(pool: &SqlitePool) -> (String, Router) {
         .route(
             "/s/{uuid}",
             axum::routing::post(mcp::http_transport::handle_streamable_http)
+                .get(mcp::http_transport::handle_streamable_http_get)
+                .delete(mcp::http_transport::handle_streamable_http_delete)
                 .options(mcp::http_transport::handle_streamable_http_options),
         )
         .with_state(mcp_registry.clone());
@@ -121,7 +131,7 @@ async fn setup_test_server(pool: &SqlitePool) -> (String, Router) {
     // Merge routers
     let app = http_routes.merge(sse_router);
 
-    (server_uuid.to_string(), app)
+    (server_uuid.to_string(), app, mcp_registry)
 }
 
 #[sqlx::test]
@@ -263,8 +273,529 @@ async fn test_http_transport_options_request(pool: SqlitePool) {
         headers
             .get("access-control-allow-methods")
             .map(|h| h.to_str().unwrap()),
-        Some("POST, OPTIONS")
+        Some("GET, POST, DELETE, OPTIONS")
+    );
+}
+
+/// POST a raw body to the HTTP transport, returning the status and parsed body
+async fn post_message(
+    app: Router,
+    uuid: &str,
+    body: &str,
+    protocol_version: Option<&str>,
+) -> (StatusCode, Option<serde_json::Value>) {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri(format!("/s/{}", uuid))
+        .header("content-type", "application/json");
+    if let Some(version) = protocol_version {
+        request = request.header("mcp-protocol-version", version);
+    }
+    let request = request.body(Body::from(body.to_string())).unwrap();
+
+    let response = app
+        .oneshot(request)
+        .await
+        .expect("Failed to execute request");
+    let status = response.status();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("Failed to read response body");
+    let body = (!body_bytes.is_empty())
+        .then(|| serde_json::from_slice(&body_bytes).expect("Failed to parse JSON"));
+    (status, body)
+}
+
+#[sqlx::test]
+async fn test_http_transport_batch(pool: SqlitePool) {
+    let (uuid, app) = setup_test_server(&pool).await;
+
+    let batch = json!([
+        {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+        {"jsonrpc": "2.0", "method": "notifications/initialized"},
+        {"jsonrpc": "2.0", "id": "two", "method": "tools/list"},
+        {"jsonrpc": "2.0", "id": 3, "method": "unknown/method"},
+        {"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {}},
+        {"id": 5, "method": "ping"}
+    ]);
+    let (status, body) = post_message(app, &uuid, &batch.to_string(), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let responses = body.unwrap();
+    let responses = responses.as_array().unwrap();
+    assert_eq!(responses.len(), 5, "The notification gets no response");
+    assert_eq!(
+        responses[0],
+        json!({"jsonrpc": "2.0", "id": 1, "result": {}})
+    );
+    assert_eq!(responses[1]["id"], "two");
+    assert!(responses[1]["result"]["tools"].is_array());
+    assert_eq!(responses[2]["error"]["code"], -32601);
+    assert_eq!(responses[3]["error"]["code"], -32602);
+    assert_eq!(responses[4]["error"]["code"], -32600);
+}
+
+#[sqlx::test]
+async fn test_http_transport_notifications_get_no_body(pool: SqlitePool) {
+    let (uuid, app) = setup_test_server(&pool).await;
+
+    let notification = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+    let (status, body) = post_message(app.clone(), &uuid, &notification.to_string(), None).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert!(body.is_none());
+
+    let batch = json!([notification, {"jsonrpc": "2.0", "method": "notifications/cancelled", "params": {"requestId": 9}}]);
+    let (status, body) = post_message(app, &uuid, &batch.to_string(), None).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert!(body.is_none());
+}
+
+#[sqlx::test]
+async fn test_http_transport_rejects_malformed_messages(pool: SqlitePool) {
+    let (uuid, app) = setup_test_server(&pool).await;
+
+    let (status, body) = post_message(app.clone(), &uuid, "{\"jsonrpc\": ", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let body = body.unwrap();
+    assert_eq!(body["error"]["code"], -32700);
+    assert!(body["id"].is_null());
+
+    let (status, body) = post_message(app, &uuid, "[]", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body.unwrap()["error"]["code"], -32600);
+}
+
+#[sqlx::test]
+async fn test_http_transport_negotiates_protocol_version(pool: SqlitePool) {
+    let (uuid, app) = setup_test_server(&pool).await;
+    let initialize = |version: &str| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {"protocolVersion": version, "capabilities": {}}
+        })
+        .to_string()
+    };
+
+    let (_, body) = post_message(app.clone(), &uuid, &initialize("2024-11-05"), None).await;
+    assert_eq!(body.unwrap()["result"]["protocolVersion"], "2024-11-05");
+
+    let (_, body) = post_message(app.clone(), &uuid, &initialize("2099-01-01"), None).await;
+    let version = body.unwrap()["result"]["protocolVersion"].clone();
+    assert_ne!(version, "2099-01-01");
+    assert!(mcp::service::SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .any(|supported| supported.to_string() == version));
+
+    let ping = json!({"jsonrpc": "2.0", "id": 2, "method": "ping"}).to_string();
+    let (status, _) = post_message(app.clone(), &uuid, &ping, Some("2025-06-18")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = post_message(app, &uuid, &ping, Some("1999-01-01")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body.unwrap()["error"]["code"], -32600);
+}
+
+fn initialize_message() -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {"protocolVersion": "2025-06-18", "capabilities": {}}
+    })
+    .to_string()
+}
+
+/// Request to the transport endpoint naming a session
+fn session_request(method: &str, uuid: &str, session_id: &str) -> axum::http::request::Builder {
+    Request::builder()
+        .method(method)
+        .uri(format!("/s/{}", uuid))
+        .header("mcp-session-id", session_id)
+}
+
+/// Initializes over JSON, returning the issued session ID
+async fn open_session(app: Router, uuid: &str) -> String {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/s/{}", uuid))
+        .header("content-type", "application/json")
+        .body(Body::from(initialize_message()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response
+        .headers()
+        .get("mcp-session-id")
+        .expect("initialize should issue a session")
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+/// `(id, data)` of the SSE events in a response body
+fn sse_events(body: &str) -> Vec<(u64, serde_json::Value)> {
+    body.split("\n\n")
+        .filter_map(|frame| {
+            let id = frame.lines().find_map(|l| l.strip_prefix("id: "))?;
+            let data = frame.lines().find_map(|l| l.strip_prefix("data: "))?;
+            Some((id.parse().unwrap(), serde_json::from_str(data).unwrap()))
+        })
+        .collect()
+}
+
+/// Message of the next SSE event arriving on a streamed body
+async fn next_message(body: &mut axum::body::BodyDataStream) -> serde_json::Value {
+    use futures::StreamExt;
+
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+        .await
+        .expect("No event on the stream")
+        .unwrap()
+        .unwrap();
+    sse_events(std::str::from_utf8(&frame).unwrap()).remove(0).1
+}
+
+#[sqlx::test]
+async fn test_http_transport_sessions(pool: SqlitePool) {
+    let (uuid, app) = setup_test_server(&pool).await;
+    let session_id = open_session(app.clone(), &uuid).await;
+
+    // Failed initialization and other requests don't open sessions
+    let ping = json!({"jsonrpc": "2.0", "id": 2, "method": "ping"}).to_string();
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/s/{}", uuid))
+        .body(Body::from(ping.clone()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert!(response.headers().get("mcp-session-id").is_none());
+
+    let request = session_request("POST", &uuid, &session_id)
+        .body(Body::from(ping.clone()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = session_request("POST", &uuid, "unknown-session")
+        .body(Body::from(ping.clone()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = session_request("DELETE", &uuid, &session_id)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // An ended session is gone
+    for method in ["POST", "DELETE"] {
+        let request = session_request(method, &uuid, &session_id)
+            .body(Body::from(ping.clone()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
+
+#[sqlx::test]
+async fn test_http_transport_streams_responses(pool: SqlitePool) {
+    let (uuid, app) = setup_test_server(&pool).await;
+
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/s/{}", uuid))
+        .header("content-type", "application/json")
+        .header("accept", "application/json, text/event-stream")
+        .body(Body::from(initialize_message()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("mcp-session-id").is_some());
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
     );
+
+    // The stream ends after the last response
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let events = sse_events(std::str::from_utf8(&body).unwrap());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].1["id"], 1);
+    assert!(events[0].1["result"]["serverInfo"].is_object());
+}
+
+#[sqlx::test]
+async fn test_http_transport_failed_streamed_initialize_opens_no_session(pool: SqlitePool) {
+    let (uuid, app) = setup_test_server(&pool).await;
+
+    let initialize = json!({
+        "jsonrpc": "1.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {"protocolVersion": "2025-06-18", "capabilities": {}}
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/s/{}", uuid))
+        .header("content-type", "application/json")
+        .header("accept", "application/json, text/event-stream")
+        .body(Body::from(initialize.to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("mcp-session-id").is_none());
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let events = sse_events(std::str::from_utf8(&body).unwrap());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].1["error"]["code"], -32600);
+}
+
+#[sqlx::test]
+async fn test_http_transport_get_stream(pool: SqlitePool) {
+    use futures::StreamExt;
+
+    let (uuid, app, registry) = setup_test_server_with_registry(&pool).await;
+    let session_id = open_session(app.clone(), &uuid).await;
+
+    let request = session_request("GET", &uuid, &session_id)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let request = session_request("GET", &uuid, &session_id)
+        .header("accept", "text/event-stream")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let notification = json!({"jsonrpc": "2.0", "method": "notifications/tools/list_changed"});
+    registry
+        .read()
+        .await
+        .sessions()
+        .broadcast(&uuid, &notification);
+
+    let mut body = response.into_body().into_data_stream();
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+        .await
+        .expect("No event on the GET stream")
+        .unwrap()
+        .unwrap();
+    let events = sse_events(std::str::from_utf8(&frame).unwrap());
+    assert_eq!(events[0].1, notification);
+}
+
+#[sqlx::test]
+async fn test_http_transport_notifies_list_changes(pool: SqlitePool) {
+    use futures::StreamExt;
+
+    let (uuid, app, registry) = setup_test_server_with_registry(&pool).await;
+
+    let (_, body) = post_message(app.clone(), &uuid, &initialize_message(), None).await;
+    let capabilities = body.unwrap()["result"]["capabilities"].clone();
+    for list in ["tools", "resources", "prompts"] {
+        assert_eq!(capabilities[list]["listChanged"], true, "{}", list);
+    }
+
+    let session_id = open_session(app.clone(), &uuid).await;
+    let request = session_request("GET", &uuid, &session_id)
+        .header("accept", "text/event-stream")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    registry.write().await.reload_tools(&uuid).await.unwrap();
+
+    let mut body = response.into_body().into_data_stream();
+    let mut methods = Vec::new();
+    while methods.len() < 2 {
+        let frame = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .expect("No list_changed notification")
+            .unwrap()
+            .unwrap();
+        for (_, message) in sse_events(std::str::from_utf8(&frame).unwrap()) {
+            methods.push(message["method"].as_str().unwrap().to_string());
+        }
+    }
+    assert_eq!(
+        methods,
+        vec![
+            "notifications/tools/list_changed",
+            "notifications/resources/list_changed"
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn test_http_transport_sets_log_level(pool: SqlitePool) {
+    let (uuid, app, registry) = setup_test_server_with_registry(&pool).await;
+
+    let (_, body) = post_message(app.clone(), &uuid, &initialize_message(), None).await;
+    assert!(body.unwrap()["result"]["capabilities"]["logging"].is_object());
+
+    let session_id = open_session(app.clone(), &uuid).await;
+    let set_level = |level: &str| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "logging/setLevel",
+            "params": {"level": level}
+        })
+        .to_string()
+    };
+
+    let request = session_request("POST", &uuid, &session_id)
+        .body(Body::from(set_level("warning")))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["result"], json!({}));
+
+    let session = registry
+        .read()
+        .await
+        .sessions()
+        .get(&uuid, &session_id, None)
+        .unwrap();
+    assert_eq!(
+        session.log_level.get(),
+        Some(rmcp::model::LoggingLevel::Warning)
+    );
+
+    let (_, body) = post_message(app, &uuid, &set_level("loud"), None).await;
+    assert_eq!(body.unwrap()["error"]["code"], -32602);
+}
+
+#[sqlx::test]
+async fn test_http_transport_elicits_missing_parameters(pool: SqlitePool) {
+    let (uuid, app) = setup_test_server(&pool).await;
+    sqlx::query(
+        "INSERT INTO instance_params (instance_id, param_name, source, value)
+         SELECT id, 'city', 'exposed', NULL FROM tool_instances WHERE instance_name = 'test_instance'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let call = json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {"name": "test_instance", "arguments": {}}
+    })
+    .to_string();
+
+    // Without the capability the call fails right away
+    let session_id = open_session(app.clone(), &uuid).await;
+    let request = session_request("POST", &uuid, &session_id)
+        .body(Body::from(call.clone()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["code"], -32602);
+
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {"protocolVersion": "2025-06-18", "capabilities": {"elicitation": {}}}
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/s/{}", uuid))
+        .body(Body::from(initialize.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let session_id = response.headers()["mcp-session-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let request = session_request("POST", &uuid, &session_id)
+        .header("accept", "application/json, text/event-stream")
+        .body(Body::from(call))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let mut body = response.into_body().into_data_stream();
+    // The user is asked for the parameter on the request's stream
+    let elicitation = next_message(&mut body).await;
+    assert_eq!(elicitation["method"], "elicitation/create");
+    assert_eq!(
+        elicitation["params"]["requestedSchema"]["required"],
+        json!(["city"])
+    );
+
+    let answer = json!({
+        "jsonrpc": "2.0",
+        "id": elicitation["id"],
+        "result": {"action": "decline"}
+    });
+    let request = session_request("POST", &uuid, &session_id)
+        .body(Body::from(answer.to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let result = next_message(&mut body).await;
+    assert_eq!(result["id"], 2);
+    assert_eq!(result["error"]["code"], -32602);
+    assert!(result["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("declined"));
+}
+
+#[sqlx::test]
+async fn test_http_transport_resumes_streams(pool: SqlitePool) {
+    let (uuid, app) = setup_test_server(&pool).await;
+    let session_id = open_session(app.clone(), &uuid).await;
+
+    let batch = json!([
+        {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+        {"jsonrpc": "2.0", "id": 2, "method": "ping"}
+    ]);
+    let request = session_request("POST", &uuid, &session_id)
+        .header("accept", "text/event-stream")
+        .body(Body::from(batch.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let events = sse_events(std::str::from_utf8(&body).unwrap());
+    assert_eq!(events.len(), 2);
+
+    // Resuming after the first response replays the second, then ends
+    let request = session_request("GET", &uuid, &session_id)
+        .header("accept", "text/event-stream")
+        .header("last-event-id", events[0].0.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let replayed = sse_events(std::str::from_utf8(&body).unwrap());
+    assert_eq!(replayed, vec![events[1].clone()]);
 }
 
 #[sqlx::test]
@@ -362,7 +893,7 @@ async fn test_service_handle_request_unknown_method(pool: SqlitePool) {
     assert_eq!(response["jsonrpc"], "2.0");
     assert_eq!(response["id"], 1);
     assert!(response["error"].is_object());
-    assert_eq!(response["error"]["code"], -32603);
+    assert_eq!(response["error"]["code"], -32601);
 }
 
 #[sqlx::test]
@@ -60,7 +60,7 @@ impl<'a> TestToolBuilder<'a> {
             r#"
             INSERT INTO tools (toolkit_id, name, description, method, url, headers, body, timeout_ms)
             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            RETURNING id, toolkit_id, name, description, method, url, headers, body, timeout_ms, created_at, updated_at
+            RETURNING id, toolkit_id, name, description, method, url, headers, body, timeout_ms, streaming, endpoints, rate_limit, created_at, updated_at
             "#,
         )
         .bind(self.toolkit_id)
@@ -85,6 +85,10 @@ impl<'a> TestToolBuilder<'a> {
             headers: row.get("headers"),
             body: row.get("body"),
             timeout_ms: row.get("timeout_ms"),
+            streaming: row.get("streaming"),
+            endpoints: row.get("endpoints"),
+            rate_limit: row.get("rate_limit"),
+            parameter_metadata: None,
             created_at: chrono::DateTime::from_timestamp(row.get::<i64, _>("created_at"), 0)
                 .map(|dt| dt.naive_utc())
                 .unwrap_or_default(),
@@ -95,6 +99,11 @@ impl<'a> TestToolBuilder<'a> {
     }
 }
 
+// Executor allowed to call the loopback mock upstreams
+fn loopback_executor() -> HttpExecutor {
+    HttpExecutor::new().with_egress_policy(test_helpers::loopback_egress_policy())
+}
+
 // Helper to create params HashMap
 fn create_params(pairs: Vec<(&str, Value)>) -> HashMap<String, Value> {
     pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
@@ -129,7 +138,7 @@ async fn test_execute_get_request_success() {
         .await;
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -173,7 +182,7 @@ async fn test_execute_post_with_json_body() {
         .await;
 
     // Execute with parameters
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = create_params(vec![("username", json!("alice")), ("age", json!(25))]);
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -214,7 +223,7 @@ async fn test_parameter_substitution_in_url() {
         .await;
 
     // Execute with parameters
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = create_params(vec![("user_id", json!(123))]);
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -257,7 +266,7 @@ async fn test_parameter_substitution_in_headers() {
         .await;
 
     // Execute with parameters
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = create_params(vec![("token", json!("abc123"))]);
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -302,7 +311,7 @@ async fn test_parameter_substitution_in_body() {
         .await;
 
     // Execute with parameters
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = create_params(vec![
         ("username", json!("alice")),
         ("age", json!(25)),
@@ -330,7 +339,7 @@ async fn test_all_http_methods() {
 
     // Start wiremock server
     let mock_server = MockServer::start().await;
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
 
     // Test GET
@@ -443,7 +452,7 @@ async fn test_execute_timeout() {
         .await;
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -472,7 +481,7 @@ async fn test_invalid_url_error() {
         .await;
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -510,7 +519,7 @@ async fn test_invalid_http_method() {
     tool.method = "INVALID METHOD".to_string();
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -556,7 +565,7 @@ async fn test_response_headers_captured() {
         .await;
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -600,7 +609,7 @@ async fn test_non_success_status_code() {
         .await;
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -641,7 +650,7 @@ async fn test_request_without_body() {
         .await;
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -684,7 +693,7 @@ async fn test_complex_url_with_multiple_parameters() {
         .await;
 
     // Execute with multiple parameters
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = create_params(vec![
         ("version", json!("v1")),
         ("user_id", json!(42)),
@@ -723,7 +732,7 @@ async fn test_invalid_headers_json() {
         .await;
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -763,7 +772,7 @@ async fn test_empty_headers_and_body() {
         .await;
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -805,7 +814,7 @@ async fn test_parameter_with_special_characters() {
         .await;
 
     // Execute with special characters in parameter
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = create_params(vec![("message", json!("Hello, World! @#$%"))]);
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -844,7 +853,7 @@ async fn test_empty_response_body() {
         .await;
 
     // Execute tool
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = HashMap::new();
     let result = executor.execute_tool(&tool, &params).await;
 
@@ -889,7 +898,7 @@ async fn test_multiple_headers_substitution() {
         .await;
 
     // Execute with parameters
-    let executor = HttpExecutor::new();
+    let executor = loopback_executor();
     let params = create_params(vec![
         ("token", json!("token123")),
         ("api_key", json!("api-key-456")),
@@ -902,3 +911,493 @@ async fn test_multiple_headers_substitution() {
     assert_eq!(execution_result.status, 200);
     assert_eq!(execution_result.body, "authorized");
 }
+
+// ============================================================================
+// Streaming Tests
+// ============================================================================
+
+fn drain_chunks(
+    mut rx: tokio::sync::mpsc::UnboundedReceiver<saramcp::services::StreamChunk>,
+) -> Vec<saramcp::services::StreamChunk> {
+    let mut chunks = Vec::new();
+    while let Ok(chunk) = rx.try_recv() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+#[tokio::test]
+async fn test_execute_streaming_event_stream() {
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let mock_server = MockServer::start().await;
+
+    let sse_body = "data: Hello\n\n: keep-alive\n\nevent: token\ndata: world\n\ndata: [DONE]\n\n";
+    Mock::given(method("GET"))
+        .and(path("/stream"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(sse_body, "text/event-stream"))
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/stream", mock_server.uri());
+    let tool = TestToolBuilder::new(&pool, toolkit_id, "stream_tokens", "GET")
+        .url(&tool_url)
+        .build()
+        .await;
+
+    let executor = loopback_executor();
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let result = executor
+        .execute_tool_streaming(&tool, &HashMap::new(), tx)
+        .await
+        .unwrap();
+
+    assert_eq!(result.status, 200);
+    assert!(result.is_success);
+    assert_eq!(result.body, "Hello\nworld");
+
+    let chunks = drain_chunks(rx);
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].sequence, 1);
+    assert_eq!(chunks[0].data, "Hello");
+    assert_eq!(chunks[1].sequence, 2);
+    assert_eq!(chunks[1].data, "world");
+    assert_eq!(chunks[1].bytes_received, sse_body.len() as u64);
+}
+
+#[tokio::test]
+async fn test_execute_streaming_ndjson() {
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/export"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "{\"row\":1}\n{\"row\":2}\n{\"row\":3}",
+            "application/x-ndjson",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/export", mock_server.uri());
+    let tool = TestToolBuilder::new(&pool, toolkit_id, "export_rows", "POST")
+        .url(&tool_url)
+        .build()
+        .await;
+
+    let executor = loopback_executor();
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let result = executor
+        .execute_tool_streaming(&tool, &HashMap::new(), tx)
+        .await
+        .unwrap();
+
+    assert_eq!(result.body, "{\"row\":1}\n{\"row\":2}\n{\"row\":3}");
+
+    let data: Vec<String> = drain_chunks(rx).into_iter().map(|c| c.data).collect();
+    assert_eq!(data, vec!["{\"row\":1}", "{\"row\":2}", "{\"row\":3}"]);
+}
+
+#[tokio::test]
+async fn test_execute_streaming_error_response_not_streamed() {
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/stream"))
+        .respond_with(
+            ResponseTemplate::new(503).set_body_raw("data: overloaded\n\n", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/stream", mock_server.uri());
+    let tool = TestToolBuilder::new(&pool, toolkit_id, "stream_tokens", "GET")
+        .url(&tool_url)
+        .build()
+        .await;
+
+    let executor = loopback_executor();
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let result = executor
+        .execute_tool_streaming(&tool, &HashMap::new(), tx)
+        .await
+        .unwrap();
+
+    assert_eq!(result.status, 503);
+    assert!(!result.is_success);
+    assert_eq!(result.body, "data: overloaded\n\n");
+    assert!(drain_chunks(rx).is_empty());
+}
+
+#[tokio::test]
+async fn test_execute_streaming_timeout() {
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/slow-stream"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw("data: late\n\n", "text/event-stream")
+                .set_delay(std::time::Duration::from_millis(300)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/slow-stream", mock_server.uri());
+    let tool = TestToolBuilder::new(&pool, toolkit_id, "slow_stream", "GET")
+        .url(&tool_url)
+        .timeout_ms(100)
+        .build()
+        .await;
+
+    let executor = loopback_executor();
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let result = executor
+        .execute_tool_streaming(&tool, &HashMap::new(), tx)
+        .await;
+
+    match result {
+        Err(HttpExecutorError::Timeout(ms)) => assert_eq!(ms, 100),
+        other => panic!("Expected Timeout error, got {:?}", other.map(|r| r.status)),
+    }
+}
+
+// ============================================================================
+// Failover Tests
+// ============================================================================
+
+/// Base URL nothing listens on, to provoke connection errors
+async fn unreachable_base_url() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    format!("http://{}", addr)
+}
+
+fn endpoints_json(base_urls: &[&str], strategy: &str) -> String {
+    json!({
+        "base_urls": base_urls,
+        "strategy": strategy,
+        "failover_status_codes": [503],
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn test_failover_on_status_code() {
+    use saramcp::services::EndpointHealthRegistry;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let primary = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/status"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("down"))
+        .expect(1)
+        .mount(&primary)
+        .await;
+
+    let secondary = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/status"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&secondary)
+        .await;
+
+    let mut tool = TestToolBuilder::new(&pool, toolkit_id, "status", "GET")
+        .url("https://api.example.com/api/status")
+        .build()
+        .await;
+    tool.endpoints = Some(endpoints_json(
+        &[&primary.uri(), &secondary.uri()],
+        "primary-failover",
+    ));
+
+    let health = EndpointHealthRegistry::new();
+    let executor = loopback_executor().with_health_registry(health.clone());
+    let result = executor.execute_tool(&tool, &HashMap::new()).await.unwrap();
+
+    assert_eq!(result.status, 200);
+    assert_eq!(result.body, "ok");
+    assert_eq!(result.endpoint.as_deref(), Some(secondary.uri().as_str()));
+
+    let primary_health = health.get(&primary.uri()).unwrap();
+    assert_eq!(primary_health.consecutive_failures, 1);
+    assert!(!primary_health.is_healthy());
+    assert_eq!(health.get(&secondary.uri()).unwrap().total_successes, 1);
+}
+
+#[tokio::test]
+async fn test_failover_on_connection_error() {
+    use saramcp::services::EndpointHealthRegistry;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/status"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .mount(&mock_server)
+        .await;
+
+    let dead = unreachable_base_url().await;
+
+    let mut tool = TestToolBuilder::new(&pool, toolkit_id, "status", "GET")
+        .url("/api/status")
+        .build()
+        .await;
+    tool.endpoints = Some(endpoints_json(
+        &[&dead, &mock_server.uri()],
+        "primary-failover",
+    ));
+
+    let health = EndpointHealthRegistry::new();
+    let executor = loopback_executor().with_health_registry(health.clone());
+
+    let result = executor.execute_tool(&tool, &HashMap::new()).await.unwrap();
+    assert_eq!(result.body, "ok");
+    assert_eq!(result.endpoint.as_deref(), Some(mock_server.uri().as_str()));
+    assert_eq!(health.get(&dead).unwrap().total_failures, 1);
+
+    // The dead endpoint is cooling down, so the next call goes straight to
+    // the healthy one
+    let result = executor.execute_tool(&tool, &HashMap::new()).await.unwrap();
+    assert_eq!(result.endpoint.as_deref(), Some(mock_server.uri().as_str()));
+    assert_eq!(health.get(&dead).unwrap().total_failures, 1);
+}
+
+#[tokio::test]
+async fn test_failover_all_endpoints_down() {
+    use saramcp::services::EndpointHealthRegistry;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let first = unreachable_base_url().await;
+    let second = unreachable_base_url().await;
+
+    let mut tool = TestToolBuilder::new(&pool, toolkit_id, "status", "GET")
+        .url("/api/status")
+        .build()
+        .await;
+    tool.endpoints = Some(endpoints_json(&[&first, &second], "primary-failover"));
+
+    let executor = loopback_executor().with_health_registry(EndpointHealthRegistry::new());
+    let result = executor.execute_tool(&tool, &HashMap::new()).await;
+
+    assert!(matches!(result, Err(HttpExecutorError::RequestFailed(_))));
+}
+
+#[tokio::test]
+async fn test_round_robin_distributes_requests() {
+    use saramcp::services::EndpointHealthRegistry;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let first = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("first"))
+        .expect(2)
+        .mount(&first)
+        .await;
+
+    let second = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("second"))
+        .expect(2)
+        .mount(&second)
+        .await;
+
+    let mut tool = TestToolBuilder::new(&pool, toolkit_id, "items", "GET")
+        .url("https://api.example.com/items")
+        .build()
+        .await;
+    tool.endpoints = Some(endpoints_json(
+        &[&first.uri(), &second.uri()],
+        "round-robin",
+    ));
+
+    let executor = loopback_executor().with_health_registry(EndpointHealthRegistry::new());
+
+    let mut bodies = Vec::new();
+    for _ in 0..4 {
+        let result = executor.execute_tool(&tool, &HashMap::new()).await.unwrap();
+        bodies.push(result.body);
+    }
+
+    assert_eq!(bodies, vec!["first", "second", "first", "second"]);
+}
+
+// ============================================================================
+// Egress Policy Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_egress_blocks_private_ip_literal() {
+    use saramcp::services::EgressPolicy;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let tool = TestToolBuilder::new(&pool, toolkit_id, "metadata", "GET")
+        .url("http://169.254.169.254/latest/meta-data/")
+        .build()
+        .await;
+
+    let executor = HttpExecutor::new().with_egress_policy(EgressPolicy::default());
+    let result = executor.execute_tool(&tool, &HashMap::new()).await;
+
+    match result {
+        Err(HttpExecutorError::EgressBlocked(blocked)) => {
+            assert_eq!(blocked.host, "169.254.169.254");
+        }
+        other => panic!("Expected EgressBlocked, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_egress_checks_resolved_addresses() {
+    use saramcp::services::{EgressPolicy, HostRule};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/admin"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("admin"))
+        .mount(&mock_server)
+        .await;
+
+    // A host name resolving to loopback is blocked like the IP itself
+    let port = mock_server.address().port();
+    let tool_url = format!("http://localhost:{}/admin", port);
+    let tool = TestToolBuilder::new(&pool, toolkit_id, "admin", "GET")
+        .url(&tool_url)
+        .build()
+        .await;
+
+    let blocked = HttpExecutor::new().with_egress_policy(EgressPolicy::default());
+    let result = blocked.execute_tool(&tool, &HashMap::new()).await;
+    assert!(matches!(result, Err(HttpExecutorError::EgressBlocked(_))));
+    assert!(mock_server.received_requests().await.unwrap().is_empty());
+
+    // Allowlisted destinations are reachable
+    let allowed = HttpExecutor::new().with_egress_policy(
+        EgressPolicy::default().with_rules(vec![HostRule::parse("localhost").unwrap()], vec![]),
+    );
+    let result = allowed.execute_tool(&tool, &HashMap::new()).await.unwrap();
+    assert_eq!(result.body, "admin");
+
+    // The denylist wins over the allowlist
+    let denied = HttpExecutor::new().with_egress_policy(EgressPolicy::default().with_rules(
+        vec![HostRule::parse("localhost").unwrap()],
+        vec![HostRule::parse("127.0.0.0/8").unwrap()],
+    ));
+    let result = denied.execute_tool(&tool, &HashMap::new()).await;
+    assert!(matches!(result, Err(HttpExecutorError::EgressBlocked(_))));
+}
+
+#[tokio::test]
+async fn test_egress_blocks_redirect_to_private_address() {
+    use saramcp::services::{EgressPolicy, HostRule};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/start"))
+        .respond_with(
+            ResponseTemplate::new(302)
+                .insert_header("location", "http://169.254.169.254/latest/meta-data/"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/start", mock_server.uri());
+    let tool = TestToolBuilder::new(&pool, toolkit_id, "redirect", "GET")
+        .url(&tool_url)
+        .build()
+        .await;
+
+    let executor = HttpExecutor::new().with_egress_policy(
+        EgressPolicy::default().with_rules(vec![HostRule::parse("127.0.0.1").unwrap()], vec![]),
+    );
+    let result = executor.execute_tool(&tool, &HashMap::new()).await;
+
+    match result {
+        Err(HttpExecutorError::EgressBlocked(blocked)) => {
+            assert_eq!(blocked.host, "169.254.169.254");
+        }
+        other => panic!("Expected EgressBlocked, got {:?}", other),
+    }
+}
@@ -32,6 +32,7 @@ async fn test_execute_simple_get_request() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let mock_url = format!("{}/api/users", mock_server.uri());
 
@@ -98,6 +99,7 @@ async fn test_execute_with_instance_param() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -181,6 +183,7 @@ async fn test_execute_with_server_param() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -275,6 +278,7 @@ async fn test_execute_with_exposed_param() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -365,6 +369,7 @@ async fn test_execute_with_mixed_params() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -470,6 +475,7 @@ async fn test_execute_with_secret_param() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -553,6 +559,214 @@ async fn test_execute_with_secret_param() {
     }
 }
 
+#[tokio::test]
+async fn test_execute_logs_to_client_with_secrets_redacted() {
+    use rmcp::model::LoggingLevel;
+    use saramcp::services::{ExecutionContext, LogLevel};
+    use std::sync::{Arc, Mutex};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/data"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("data"))
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/api/data?key={{{{api_key}}}}", mock_server.uri());
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "get_data",
+        "GET",
+        Some(&tool_url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Test Server", None)
+        .await
+        .unwrap();
+
+    let secrets = SecretsManager::new().unwrap();
+    let encrypted = secrets.encrypt("s3cret/key").unwrap();
+    sqlx::query!(
+        "INSERT INTO server_globals (server_id, key, value, is_secret)
+         VALUES (?, 'api_key', ?, true)",
+        server_id,
+        encrypted
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'get_data', 'Get data')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+    sqlx::query!(
+        "INSERT INTO instance_params (instance_id, param_name, source, value)
+         VALUES (?, 'api_key', 'server', NULL)",
+        instance_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let sink_messages = Arc::clone(&messages);
+    let log_level = LogLevel::default();
+    let context = ExecutionContext::default().with_logging(
+        log_level.clone(),
+        Some(Arc::new(move |message| {
+            sink_messages.lock().unwrap().push(message)
+        })),
+    );
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets);
+
+    // Nothing is sent before the client chooses a level
+    executor.execute_with_context(None, &context).await.unwrap();
+    assert!(messages.lock().unwrap().is_empty());
+
+    log_level.set(LoggingLevel::Debug);
+    executor.execute_with_context(None, &context).await.unwrap();
+    let logged: Vec<serde_json::Value> = messages.lock().unwrap().drain(..).collect();
+    assert_eq!(logged.len(), 2);
+    assert!(logged
+        .iter()
+        .all(|m| m["method"] == "notifications/message"));
+
+    let request = &logged[0]["params"];
+    assert_eq!(request["level"], "debug");
+    let url = request["data"]["url"].as_str().unwrap();
+    assert!(url.ends_with("/api/data?key=[REDACTED]"), "{}", url);
+    assert!(!logged[0].to_string().contains("s3cret"));
+
+    let outcome = &logged[1]["params"];
+    assert_eq!(outcome["level"], "info");
+    assert_eq!(outcome["data"]["status"], 200);
+    assert!(outcome["data"]["duration_ms"].is_u64());
+
+    // Successful calls are below the warning level
+    log_level.set(LoggingLevel::Warning);
+    executor.execute_with_context(None, &context).await.unwrap();
+    assert!(messages.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_execute_failure_log_redacts_secrets() {
+    use rmcp::model::LoggingLevel;
+    use saramcp::services::{ExecutionContext, LogLevel};
+    use std::sync::{Arc, Mutex};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    // Nothing listens on the port, so the request error quotes the URL
+    test_helpers::allow_loopback_egress();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+
+    let tool_url = format!("http://{}/api/data?key={{{{api_key}}}}", address);
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "get_data",
+        "GET",
+        Some(&tool_url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Test Server", None)
+        .await
+        .unwrap();
+
+    let secrets = SecretsManager::new().unwrap();
+    let encrypted = secrets.encrypt("s3cret/key").unwrap();
+    sqlx::query!(
+        "INSERT INTO server_globals (server_id, key, value, is_secret)
+         VALUES (?, 'api_key', ?, true)",
+        server_id,
+        encrypted
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'get_data', 'Get data')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+    sqlx::query!(
+        "INSERT INTO instance_params (instance_id, param_name, source, value)
+         VALUES (?, 'api_key', 'server', NULL)",
+        instance_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let sink_messages = Arc::clone(&messages);
+    let log_level = LogLevel::default();
+    log_level.set(LoggingLevel::Error);
+    let context = ExecutionContext::default().with_logging(
+        log_level,
+        Some(Arc::new(move |message| {
+            sink_messages.lock().unwrap().push(message)
+        })),
+    );
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets);
+
+    let _ = executor.execute_with_context(None, &context).await;
+    let logged: Vec<serde_json::Value> = messages.lock().unwrap().drain(..).collect();
+    assert_eq!(logged.len(), 1);
+
+    let outcome = &logged[0]["params"];
+    assert_eq!(outcome["level"], "error");
+    let message = outcome["data"]["message"].as_str().unwrap();
+    assert!(message.starts_with("Failed after"), "{}", message);
+    assert!(message.contains("[REDACTED]"), "{}", message);
+    assert!(!logged[0].to_string().contains("s3cret"));
+}
+
 // ============================================================================
 // HTTP Method Tests
 // ============================================================================
@@ -571,6 +785,7 @@ async fn test_execute_post_with_body() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -666,6 +881,7 @@ async fn test_execute_http_error_status() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let mock_url = format!("{}/api/notfound", mock_server.uri());
 
@@ -739,6 +955,7 @@ async fn test_execute_missing_exposed_param() {
         .unwrap();
 
     // Start mock server (won't be reached)
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let tool_url = format!("{}/users/{{{{integer:user_id}}}}", mock_server.uri());
 
@@ -803,3 +1020,858 @@ async fn test_execute_missing_exposed_param() {
         err.message
     );
 }
+
+#[tokio::test]
+async fn test_execute_elicits_missing_exposed_param() {
+    use saramcp::services::{ClientRequests, ExecutionContext};
+    use std::sync::{Arc, Mutex};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/users/42"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Ada"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/users/{{{{integer:user_id}}}}", mock_server.uri());
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "get_user",
+        "GET",
+        Some(&tool_url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Test Server", None)
+        .await
+        .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'get_user', 'Get user')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+    sqlx::query!(
+        "INSERT INTO instance_params (instance_id, param_name, source, value)
+         VALUES (?, 'user_id', 'exposed', NULL)",
+        instance_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // The client accepts the first request and declines the second
+    let asked = Arc::new(Mutex::new(Vec::new()));
+    let answers = Arc::new(Mutex::new(vec![
+        json!({"action": "decline"}),
+        json!({"action": "accept", "content": {"user_id": 42}}),
+    ]));
+    let requests: ClientRequests = {
+        let asked = Arc::clone(&asked);
+        Arc::new(move |method, params| {
+            asked.lock().unwrap().push((method.to_string(), params));
+            let answer = answers.lock().unwrap().pop().unwrap();
+            Box::pin(async move { Ok(answer) })
+        })
+    };
+    let context = ExecutionContext::default().with_requests(Some(requests));
+
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets);
+
+    let mut params = serde_json::Map::new();
+    params.insert("user_id".to_string(), json!("forty-two"));
+    let result = executor
+        .execute_with_context(Some(params), &context)
+        .await
+        .unwrap();
+    assert_eq!(result.content[0].as_text().unwrap().text, "Ada");
+
+    {
+        let asked = asked.lock().unwrap();
+        let (method, params) = &asked[0];
+        assert_eq!(method, "elicitation/create");
+        assert!(params["message"].as_str().unwrap().contains("user_id"));
+        assert_eq!(
+            params["requestedSchema"]["properties"]["user_id"]["type"],
+            "integer"
+        );
+        assert_eq!(params["requestedSchema"]["required"], json!(["user_id"]));
+    }
+
+    let err = executor
+        .execute_with_context(None, &context)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+    assert!(err.message.contains("declined"));
+    assert_eq!(asked.lock().unwrap().len(), 2);
+}
+
+// ============================================================================
+// Streaming Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_execute_streaming_tool_returns_aggregated_result() {
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    let mock_url = format!("{}/completions", mock_server.uri());
+
+    Mock::given(method("POST"))
+        .and(path("/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "data: {\"token\":\"Hi\"}\n\ndata: {\"token\":\"!\"}\n\ndata: [DONE]\n\n",
+            "text/event-stream",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "complete",
+        "POST",
+        Some(&mock_url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+
+    sqlx::query("UPDATE tools SET streaming = TRUE WHERE id = ?")
+        .bind(tool_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+    assert!(tool.streaming);
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Test Server", None)
+        .await
+        .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'complete', 'Stream a completion')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets);
+    let call_result = executor.execute(None).await.unwrap();
+
+    assert!(call_result.is_error.is_none() || !call_result.is_error.unwrap());
+    let content = &call_result.content[0];
+    let text = if let rmcp::model::RawContent::Text(text) = &**content {
+        text.text.clone()
+    } else {
+        panic!("Expected text content");
+    };
+    assert_eq!(text, "{\"token\":\"Hi\"}\n{\"token\":\"!\"}");
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].status, "success");
+    assert_eq!(history[0].response_body.as_deref(), Some(text.as_str()));
+}
+
+// ============================================================================
+// Cancellation Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_execute_cancelled_aborts_request_and_records_cancelled() {
+    use saramcp::services::ExecutionContext;
+    use std::time::{Duration, Instant};
+    use tokio_util::sync::CancellationToken;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    let mock_url = format!("{}/slow", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(10)))
+        .mount(&mock_server)
+        .await;
+
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "slow",
+        "GET",
+        Some(&mock_url),
+        None,
+        None,
+        30000,
+    )
+    .await
+    .unwrap();
+
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Test Server", None)
+        .await
+        .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'slow', 'Slow endpoint')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets);
+
+    let token = CancellationToken::new();
+    let context = ExecutionContext::with_cancellation(token.clone());
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        token.cancel();
+    });
+
+    let started = Instant::now();
+    let result = executor.execute_with_context(None, &context).await;
+
+    assert!(result.is_err(), "Cancelled call should not return a result");
+    assert!(
+        started.elapsed() < Duration::from_secs(5),
+        "Cancellation should abort the upstream request"
+    );
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].status, "cancelled");
+    assert!(history[0].http_status_code.is_none());
+}
+
+// ============================================================================
+// Multiple Endpoint Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_execute_uses_toolkit_endpoints_and_records_endpoint() {
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let primary = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/regions/current"))
+        .respond_with(ResponseTemplate::new(502))
+        .mount(&primary)
+        .await;
+
+    let secondary = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/regions/current"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("secondary"))
+        .mount(&secondary)
+        .await;
+
+    let endpoints = json!({
+        "base_urls": [primary.uri(), secondary.uri()],
+        "strategy": "primary-failover",
+    })
+    .to_string();
+
+    sqlx::query("UPDATE toolkits SET endpoints = ? WHERE id = ?")
+        .bind(&endpoints)
+        .bind(toolkit_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "current_region",
+        "GET",
+        Some("https://api.example.com/regions/current"),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+    assert!(tool.endpoints.is_none());
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Test Server", None)
+        .await
+        .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'current_region', 'Current region')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets);
+    let call_result = executor.execute(None).await.unwrap();
+
+    assert!(call_result.is_error.is_none() || !call_result.is_error.unwrap());
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].status, "success");
+    assert_eq!(
+        history[0].endpoint.as_deref(),
+        Some(secondary.uri().as_str())
+    );
+}
+
+#[tokio::test]
+async fn test_execute_blocked_by_server_egress_rules_records_blocked() {
+    use saramcp::services::ServerService;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("internal"))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let mock_url = format!("{}/internal", mock_server.uri());
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "internal",
+        "GET",
+        Some(&mock_url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Test Server", None)
+        .await
+        .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'internal', 'Internal call')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let secrets = SecretsManager::new().unwrap();
+    ServerService::new(pool.clone(), secrets.clone())
+        .set_egress_host_rules(server_id, &[], &["127.0.0.1".to_string()])
+        .await
+        .unwrap();
+
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets);
+    let error = executor.execute(None).await.unwrap_err();
+
+    assert_eq!(error.code, rmcp::model::ErrorCode::INVALID_REQUEST);
+    assert!(error.message.contains("Blocked by egress policy"));
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].status, "blocked");
+    assert!(history[0]
+        .error_message
+        .as_deref()
+        .unwrap()
+        .contains("127.0.0.1"));
+}
+
+// ============================================================================
+// Rate Limit Tests
+// ============================================================================
+
+/// Creates a server with one instance of a GET tool calling `url`
+async fn setup_rate_limit_instance(pool: &sqlx::SqlitePool, url: &str) -> (i64, i64, i64, i64) {
+    let user_id = test_helpers::insert_test_user(pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+    let tool_id = test_helpers::create_test_tool(
+        pool,
+        toolkit_id,
+        "limited",
+        "GET",
+        Some(url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+    let (server_id, _) = test_helpers::create_test_server(pool, user_id, "Test Server", None)
+        .await
+        .unwrap();
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'limited', 'Rate limited call')",
+        server_id,
+        tool_id
+    )
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    (toolkit_id, tool_id, server_id, instance_id)
+}
+
+#[tokio::test]
+async fn test_execute_over_tool_rate_limit_fails_fast_and_records_throttled() {
+    use saramcp::services::RateLimiter;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/limited", mock_server.uri());
+    let (_, tool_id, server_id, instance_id) = setup_rate_limit_instance(&pool, &url).await;
+
+    sqlx::query!(
+        r#"UPDATE tools SET rate_limit = '{"requests": 1, "period_seconds": 60, "mode": "reject"}' WHERE id = ?"#,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets)
+        .with_rate_limiter(RateLimiter::new());
+
+    assert!(executor.execute(None).await.is_ok());
+
+    let error = executor.execute(None).await.unwrap_err();
+    assert_eq!(error.code, rmcp::model::ErrorCode::INVALID_REQUEST);
+    assert!(error.message.contains("Rate limited"));
+    assert!(error.data.unwrap()["retry_after_ms"].as_u64().unwrap() > 0);
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    let statuses: Vec<&str> = history.iter().map(|h| h.status.as_str()).collect();
+    assert_eq!(history.len(), 2);
+    assert!(statuses.contains(&"success"));
+    assert!(statuses.contains(&"throttled"));
+}
+
+#[tokio::test]
+async fn test_execute_waits_for_server_rate_limit_and_records_wait() {
+    use saramcp::services::RateLimiter;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/limited", mock_server.uri());
+    let (_, tool_id, server_id, instance_id) = setup_rate_limit_instance(&pool, &url).await;
+
+    sqlx::query!(
+        r#"INSERT INTO server_network_settings (server_id, rate_limit)
+           VALUES (?, '{"requests": 4, "period_seconds": 1, "burst": 1}')"#,
+        server_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets)
+        .with_rate_limiter(RateLimiter::new());
+
+    let started = std::time::Instant::now();
+    assert!(executor.execute(None).await.is_ok());
+    assert!(executor.execute(None).await.is_ok());
+    assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(history.iter().all(|h| h.status == "success"));
+    let waits: Vec<i64> = history.iter().filter_map(|h| h.throttled_ms).collect();
+    assert_eq!(waits.len(), 1);
+    assert!(waits[0] > 0);
+}
+
+#[tokio::test]
+async fn test_upstream_retry_after_pauses_toolkit_host() {
+    use saramcp::services::RateLimiter;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "120")
+                .set_body_string("slow down"),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/limited", mock_server.uri());
+    let (toolkit_id, tool_id, server_id, instance_id) =
+        setup_rate_limit_instance(&pool, &url).await;
+
+    sqlx::query!(
+        r#"UPDATE toolkits SET rate_limit = '{"requests": 100, "mode": "reject"}' WHERE id = ?"#,
+        toolkit_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+    let secrets = SecretsManager::new().unwrap();
+    let rate_limiter = RateLimiter::new();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets)
+        .with_rate_limiter(rate_limiter.clone());
+
+    // The 429 is returned to the client as a tool error
+    let result = executor.execute(None).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert!(rate_limiter.upstream_cooldown("127.0.0.1").is_some());
+
+    // Further calls to the host are refused without reaching it
+    let error = executor.execute(None).await.unwrap_err();
+    assert!(error.message.contains("upstream 127.0.0.1"));
+}
+
+// ============================================================================
+// Circuit Breaker Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_execute_short_circuits_failing_upstream() {
+    use saramcp::services::{
+        CircuitBreakerConfig, CircuitBreakerRegistry, CircuitScope, CircuitState,
+    };
+    use std::time::Duration;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    // Only the calls made before the circuit opens reach the upstream
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("down"))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/flaky", mock_server.uri());
+    let (_, tool_id, server_id, instance_id) = setup_rate_limit_instance(&pool, &url).await;
+
+    let circuits = CircuitBreakerRegistry::new(CircuitBreakerConfig {
+        scope: Some(CircuitScope::Host),
+        failure_threshold: 2,
+        open_duration: Duration::from_secs(60),
+        ..Default::default()
+    });
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets)
+        .with_circuit_breakers(circuits.clone());
+
+    for _ in 0..2 {
+        let result = executor.execute(None).await.unwrap();
+        assert_eq!(result.is_error, Some(true));
+    }
+
+    let error = executor.execute(None).await.unwrap_err();
+    assert_eq!(error.code, rmcp::model::ErrorCode::INTERNAL_ERROR);
+    assert!(error.message.contains("Circuit open"));
+    assert!(error.message.contains("HTTP 503"));
+    let data = error.data.unwrap();
+    assert_eq!(data["state"], "open");
+    assert!(data["circuit"]
+        .as_str()
+        .unwrap()
+        .starts_with("host:127.0.0.1:"));
+
+    let snapshots = circuits.snapshots_for_servers(&[server_id]);
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].state, CircuitState::Open);
+    assert_eq!(snapshots[0].consecutive_failures, 2);
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 3);
+    assert!(history.iter().all(|h| h.status == "error"));
+    assert!(history.iter().any(|h| h
+        .error_message
+        .as_deref()
+        .unwrap_or("")
+        .contains("Circuit open")));
+}
+
+#[tokio::test]
+async fn test_client_errors_do_not_open_circuit() {
+    use saramcp::services::{
+        CircuitBreakerConfig, CircuitBreakerRegistry, CircuitScope, CircuitState,
+    };
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("missing"))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/missing", mock_server.uri());
+    let (_, tool_id, server_id, instance_id) = setup_rate_limit_instance(&pool, &url).await;
+
+    let circuits = CircuitBreakerRegistry::new(CircuitBreakerConfig {
+        scope: Some(CircuitScope::Tool),
+        failure_threshold: 2,
+        ..Default::default()
+    });
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets)
+        .with_circuit_breakers(circuits.clone());
+
+    for _ in 0..3 {
+        assert!(executor.execute(None).await.is_ok());
+    }
+
+    let snapshots = circuits.snapshots_for_servers(&[server_id]);
+    assert_eq!(snapshots[0].key, format!("tool:{}", tool_id));
+    assert_eq!(snapshots[0].state, CircuitState::Closed);
+}
+
+// ============================================================================
+// Concurrency Limit Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_execute_over_concurrency_limit_without_queue_fails_fast() {
+    use saramcp::services::ConcurrencyLimiter;
+    use std::time::Duration;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("ok")
+                .set_delay(Duration::from_millis(300)),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/slow", mock_server.uri());
+    let (_, tool_id, server_id, instance_id) = setup_rate_limit_instance(&pool, &url).await;
+
+    sqlx::query!(
+        r#"UPDATE tool_instances SET concurrency_limit = '{"max_concurrent": 1, "max_queue": 0}' WHERE id = ?"#,
+        instance_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets)
+        .with_concurrency_limiter(ConcurrencyLimiter::new());
+
+    let running = tokio::spawn({
+        let executor = executor.clone();
+        async move { executor.execute(None).await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let error = executor.execute(None).await.unwrap_err();
+    assert_eq!(error.code, rmcp::model::ErrorCode::INVALID_REQUEST);
+    assert!(error.message.contains("Concurrency limit reached"));
+    assert!(error.message.contains("queue is full"));
+
+    assert!(running.await.unwrap().is_ok());
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    let mut statuses: Vec<&str> = history.iter().map(|h| h.status.as_str()).collect();
+    statuses.sort();
+    assert_eq!(statuses, vec!["success", "throttled"]);
+}
+
+#[tokio::test]
+async fn test_execute_queued_call_records_queue_wait() {
+    use saramcp::services::ConcurrencyLimiter;
+    use std::time::Duration;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("ok")
+                .set_delay(Duration::from_millis(300)),
+        )
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/slow", mock_server.uri());
+    let (_, tool_id, server_id, instance_id) = setup_rate_limit_instance(&pool, &url).await;
+
+    // The server-wide limit applies to every instance of the server
+    sqlx::query!(
+        r#"INSERT INTO server_network_settings (server_id, concurrency_limit)
+           VALUES (?, '{"max_concurrent": 1, "max_queue": 1, "queue_timeout_ms": 5000}')"#,
+        server_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let tool = Tool::get_by_id(&pool, tool_id).await.unwrap().unwrap();
+    let secrets = SecretsManager::new().unwrap();
+    let executor = InstanceExecutor::new(pool.clone(), server_id, instance_id, tool, secrets)
+        .with_concurrency_limiter(ConcurrencyLimiter::new());
+
+    let (first, second) = tokio::join!(executor.execute(None), executor.execute(None));
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(history.iter().all(|h| h.status == "success"));
+
+    // One call waited for the other; its queue time is kept out of its duration
+    let queued: Vec<i64> = history.iter().filter_map(|h| h.queued_ms).collect();
+    assert_eq!(queued.len(), 1);
+    assert!(queued[0] >= 200);
+    assert!(history
+        .iter()
+        .all(|h| h.duration_ms.is_some_and(|ms| ms < 500)));
+}
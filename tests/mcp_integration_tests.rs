@@ -33,6 +33,7 @@ async fn test_full_mcp_flow() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -166,6 +167,7 @@ async fn test_parameter_resolution_all_sources() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -269,6 +271,7 @@ async fn test_variable_substitution_in_instance_values() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -358,6 +361,7 @@ async fn test_multiple_instances_isolated() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -497,6 +501,7 @@ async fn test_schema_matches_execution() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -599,6 +604,7 @@ async fn test_type_casting_in_execution() {
         .unwrap();
 
     // Start mock server
+    test_helpers::allow_loopback_egress();
     let mock_server = MockServer::start().await;
     let base_url = mock_server.uri();
 
@@ -674,3 +680,877 @@ async fn test_type_casting_in_execution() {
     let call_result = result.unwrap();
     assert!(call_result.is_error.is_none() || !call_result.is_error.unwrap());
 }
+
+// ============================================================================
+// Progress Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_streaming_tool_sends_progress_notifications() {
+    use serde_json::Value;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "LLM Toolkit")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "data: {\"token\":\"Hi\"}\n\ndata: {\"token\":\"!\"}\n\ndata: [DONE]\n\n",
+            "text/event-stream",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/completions", mock_server.uri());
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "complete",
+        "POST",
+        Some(&tool_url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+    sqlx::query("UPDATE tools SET streaming = TRUE WHERE id = ?")
+        .bind(tool_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (server_id, server_uuid) =
+        test_helpers::create_test_server(&pool, user_id, "LLM API", None)
+            .await
+            .unwrap();
+    sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'complete', 'Stream a completion')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (client, server) = tokio::io::duplex(64 * 1024);
+    let serving_pool = pool.clone();
+    tokio::spawn(async move {
+        saramcp::mcp::stdio::serve(serving_pool, &server_uuid, tokio::io::split(server)).await
+    });
+
+    let (client_read, mut client_write) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_read).lines();
+    for message in [
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": {"name": "test", "version": "1.0"}
+            }
+        }),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "complete",
+                "arguments": {},
+                "_meta": {"progressToken": "completion-1"}
+            }
+        }),
+    ] {
+        client_write
+            .write_all(format!("{}\n", message).as_bytes())
+            .await
+            .unwrap();
+    }
+
+    // Chunks arrive as progress notifications before the call's result
+    let mut progress = Vec::new();
+    let response = loop {
+        let line = lines.next_line().await.unwrap().unwrap();
+        let message: Value = serde_json::from_str(&line).unwrap();
+        if message["method"] == "notifications/progress" {
+            progress.push(message["params"].clone());
+        } else if message["id"] == 2 {
+            break message;
+        }
+    };
+
+    assert_eq!(progress.len(), 2);
+    assert!(progress
+        .iter()
+        .all(|p| p["progressToken"] == "completion-1"));
+    assert_eq!(progress[0]["message"], "{\"token\":\"Hi\"}");
+    assert_eq!(progress[1]["message"], "{\"token\":\"!\"}");
+    assert!(progress[0]["progress"].as_f64() < progress[1]["progress"].as_f64());
+
+    assert_eq!(response["result"]["isError"], false);
+    assert_eq!(
+        response["result"]["content"][0]["text"],
+        "{\"token\":\"Hi\"}\n{\"token\":\"!\"}"
+    );
+}
+
+// ============================================================================
+// Cancellation Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_http_transport_cancelled_notification_aborts_tool_call() {
+    use saramcp::mcp::SaraMcpService;
+    use std::time::{Duration, Instant};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "API Toolkit")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(10)))
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/slow", mock_server.uri());
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "slow",
+        "GET",
+        Some(&tool_url),
+        None,
+        None,
+        30000,
+    )
+    .await
+    .unwrap();
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Slow API", None)
+        .await
+        .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'slow', 'Slow endpoint')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let service = SaraMcpService::new(server_id, pool.clone()).await.unwrap();
+
+    let call_service = service.clone();
+    let started = Instant::now();
+    let call = tokio::spawn(async move {
+        call_service
+            .handle_request(json!({
+                "jsonrpc": "2.0",
+                "id": "call-1",
+                "method": "tools/call",
+                "params": {"name": "slow", "arguments": {}}
+            }))
+            .await
+    });
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let ack = service
+        .handle_request(json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/cancelled",
+            "params": {"requestId": "call-1", "reason": "user aborted"}
+        }))
+        .await
+        .unwrap();
+    assert!(ack.is_null(), "Notifications get no response");
+
+    let response = call.await.unwrap().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(response["id"], "call-1");
+    assert!(response["error"].is_object());
+
+    let history = saramcp::models::ExecutionHistory::list_by_instance(&pool, instance_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].status, "cancelled");
+}
+
+#[tokio::test]
+async fn test_http_transport_clients_cannot_cancel_each_others_calls() {
+    use saramcp::mcp::service::RequestOrigin;
+    use saramcp::mcp::SaraMcpService;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "API Toolkit")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(10)))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/fast"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("done"))
+        .mount(&mock_server)
+        .await;
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Shared API", None)
+        .await
+        .unwrap();
+    for name in ["slow", "fast"] {
+        let tool_url = format!("{}/{}", mock_server.uri(), name);
+        let tool_id = test_helpers::create_test_tool(
+            &pool,
+            toolkit_id,
+            name,
+            "GET",
+            Some(&tool_url),
+            None,
+            None,
+            30000,
+        )
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(server_id)
+        .bind(tool_id)
+        .bind(name)
+        .bind(name)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let service = Arc::new(SaraMcpService::new(server_id, pool.clone()).await.unwrap());
+    let client_a = RequestOrigin {
+        client: "client-a".to_string(),
+        ..Default::default()
+    };
+    let client_b = RequestOrigin {
+        client: "client-b".to_string(),
+        ..Default::default()
+    };
+    let cancel = json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/cancelled",
+        "params": {"requestId": 1, "reason": "user aborted"}
+    });
+
+    let call_service = Arc::clone(&service);
+    let call_origin = client_a.clone();
+    let started = Instant::now();
+    let call = tokio::spawn(async move {
+        call_service
+            .handle_request_from(
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tools/call",
+                    "params": {"name": "slow", "arguments": {}}
+                }),
+                &call_origin,
+            )
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Client B reuses the request ID: its call and its cancel leave A's call alone
+    let response = service
+        .handle_request_from(
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "fast", "arguments": {}}
+            }),
+            &client_b,
+        )
+        .await
+        .unwrap();
+    assert_eq!(response["result"]["content"][0]["text"], "done");
+    service
+        .handle_request_from(cancel.clone(), &client_b)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!call.is_finished(), "Client B cancelled client A's call");
+
+    // Client A can still cancel its own call
+    service
+        .handle_request_from(cancel, &client_a)
+        .await
+        .unwrap();
+    let response = call.await.unwrap().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(response["error"].is_object());
+}
+
+#[tokio::test]
+async fn test_http_transport_serves_resources() {
+    use saramcp::mcp::SaraMcpService;
+    use saramcp::models::{PublishInstanceForm, ResourceDocumentForm};
+    use saramcp::services::ServerService;
+    use wiremock::matchers::query_param;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Weather")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/forecast"))
+        .and(query_param("q", "New York"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"temp": 21}"#))
+        .mount(&mock_server)
+        .await;
+
+    let tool_url = format!("{}/forecast?q={{{{string:city}}}}", mock_server.uri());
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "forecast",
+        "GET",
+        Some(&tool_url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Weather", None)
+        .await
+        .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'forecast', 'Forecast for a city')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+    sqlx::query!(
+        "INSERT INTO instance_params (instance_id, param_name, source, value)
+         VALUES (?, 'city', 'exposed', NULL)",
+        instance_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let server_service = ServerService::new(pool.clone(), SecretsManager::new().unwrap());
+    server_service
+        .save_resource_document(
+            server_id,
+            user_id,
+            &ResourceDocumentForm {
+                name: "notes.md".to_string(),
+                description: Some("How to use the forecasts".to_string()),
+                mime_type: None,
+                content: "# Notes\nTemperatures are in Celsius.".to_string(),
+                csrf_token: String::new(),
+            },
+        )
+        .await
+        .unwrap();
+    server_service
+        .publish_instance_resource(
+            server_id,
+            user_id,
+            &PublishInstanceForm {
+                instance_id,
+                name: None,
+                mime_type: None,
+                csrf_token: String::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+    let service = SaraMcpService::new(server_id, pool.clone()).await.unwrap();
+    let request = |id: i64, method: &str, params: serde_json::Value| json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+
+    let init = service
+        .handle_request(request(1, "initialize", json!({})))
+        .await
+        .unwrap();
+    assert!(init["result"]["capabilities"]["resources"].is_object());
+
+    let list = service
+        .handle_request(request(2, "resources/list", json!({})))
+        .await
+        .unwrap();
+    let resources = list["result"]["resources"].as_array().unwrap();
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0]["uri"], "saramcp://resources/notes.md");
+    assert_eq!(resources[0]["mimeType"], "text/markdown");
+
+    let templates = service
+        .handle_request(request(3, "resources/templates/list", json!({})))
+        .await
+        .unwrap();
+    assert_eq!(
+        templates["result"]["resourceTemplates"][0]["uriTemplate"],
+        "saramcp://resources/forecast{?city}"
+    );
+
+    let notes = service
+        .handle_request(request(
+            4,
+            "resources/read",
+            json!({"uri": "saramcp://resources/notes.md"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        notes["result"]["contents"][0]["text"],
+        "# Notes\nTemperatures are in Celsius."
+    );
+
+    let forecast = service
+        .handle_request(request(
+            5,
+            "resources/read",
+            json!({"uri": "saramcp://resources/forecast?city=New%20York"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(forecast["result"]["contents"][0]["text"], r#"{"temp": 21}"#);
+    assert_eq!(
+        forecast["result"]["contents"][0]["mimeType"],
+        "application/json"
+    );
+
+    let missing = service
+        .handle_request(request(
+            6,
+            "resources/read",
+            json!({"uri": "saramcp://resources/unknown"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(missing["error"]["code"], -32002);
+}
+
+#[tokio::test]
+async fn test_only_get_instances_are_published_as_resources() {
+    use saramcp::models::PublishInstanceForm;
+    use saramcp::services::{ResourceError, ServerService};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Orders")
+        .await
+        .unwrap();
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "create_order",
+        "POST",
+        Some("https://api.example.com/orders"),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Orders", None)
+        .await
+        .unwrap();
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name)
+         VALUES (?, ?, 'create_order')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let server_service = ServerService::new(pool.clone(), SecretsManager::new().unwrap());
+    let err = server_service
+        .publish_instance_resource(
+            server_id,
+            user_id,
+            &PublishInstanceForm {
+                instance_id,
+                name: None,
+                mime_type: None,
+                csrf_token: String::new(),
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ResourceError>(),
+        Some(ResourceError::NotReadable(_, _))
+    ));
+}
+
+#[tokio::test]
+async fn test_http_transport_serves_prompts() {
+    use saramcp::mcp::SaraMcpService;
+    use saramcp::models::PromptForm;
+    use saramcp::services::{PromptError, ServerService};
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Jira", None)
+        .await
+        .unwrap();
+
+    let server_service = ServerService::new(pool.clone(), SecretsManager::new().unwrap());
+    server_service
+        .set_server_globals(
+            server_id,
+            user_id,
+            &[
+                ("project".to_string(), "OPS".to_string(), false),
+                ("jira_token".to_string(), "s3cret".to_string(), true),
+            ],
+        )
+        .await
+        .unwrap();
+
+    let form = |template: &str, arguments: &str| PromptForm {
+        name: "triage".to_string(),
+        description: Some("Triage a ticket".to_string()),
+        template: template.to_string(),
+        arguments: Some(arguments.to_string()),
+        csrf_token: String::new(),
+    };
+
+    let err = server_service
+        .save_prompt(server_id, user_id, &form("Use {{jira_token}}", ""))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PromptError>(),
+        Some(PromptError::SecretGlobal(_))
+    ));
+
+    let err = server_service
+        .save_prompt(
+            server_id,
+            user_id,
+            &form("Triage {{ticket}}", "project: Project"),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PromptError>(),
+        Some(PromptError::UnknownArgument(_))
+    ));
+
+    server_service
+        .save_prompt(
+            server_id,
+            user_id,
+            &form(
+                "Triage {{ticket}} in {{project}} using the jira_* tools, within {{integer:days}} days.{{notes}}",
+                "ticket: The ticket key\nnotes?: Extra context",
+            ),
+        )
+        .await
+        .unwrap();
+
+    let service = SaraMcpService::new(server_id, pool.clone()).await.unwrap();
+    let request = |id: i64, method: &str, params: serde_json::Value| json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+
+    let init = service
+        .handle_request(request(1, "initialize", json!({})))
+        .await
+        .unwrap();
+    assert!(init["result"]["capabilities"]["prompts"].is_object());
+
+    let list = service
+        .handle_request(request(2, "prompts/list", json!({})))
+        .await
+        .unwrap();
+    let prompt = &list["result"]["prompts"][0];
+    assert_eq!(prompt["name"], "triage");
+    let arguments: Vec<(&str, bool)> = prompt["arguments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| {
+            (
+                a["name"].as_str().unwrap(),
+                a["required"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        arguments,
+        vec![("ticket", true), ("days", true), ("notes", false)]
+    );
+
+    let rendered = service
+        .handle_request(request(
+            3,
+            "prompts/get",
+            json!({"name": "triage", "arguments": {"ticket": "OPS-42", "days": "3"}}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(rendered["result"]["description"], "Triage a ticket");
+    assert_eq!(rendered["result"]["messages"][0]["role"], "user");
+    assert_eq!(
+        rendered["result"]["messages"][0]["content"]["text"],
+        "Triage OPS-42 in OPS using the jira_* tools, within 3 days."
+    );
+
+    let invalid = service
+        .handle_request(request(
+            4,
+            "prompts/get",
+            json!({"name": "triage", "arguments": {"ticket": "OPS-42", "days": "soon"}}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(invalid["error"]["code"], -32602);
+
+    let missing = service
+        .handle_request(request(5, "prompts/get", json!({"name": "triage"})))
+        .await
+        .unwrap();
+    assert_eq!(missing["error"]["code"], -32602);
+    assert!(missing["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("ticket, days"));
+}
+
+#[tokio::test]
+async fn test_http_transport_completes_arguments() {
+    use saramcp::mcp::SaraMcpService;
+    use saramcp::models::{PromptForm, PublishInstanceForm};
+    use saramcp::services::ServerService;
+    use wiremock::matchers::query_param;
+
+    let pool = test_helpers::create_test_db().await.unwrap();
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Weather")
+        .await
+        .unwrap();
+
+    test_helpers::allow_loopback_egress();
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/cities"))
+        .and(query_param("prefix", "Os"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"["Oslo", "Osaka"]"#))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let forecast_url = format!("{}/forecast?q={{{{string:city}}}}", mock_server.uri());
+    let forecast_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "forecast",
+        "GET",
+        Some(&forecast_url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+    sqlx::query!(
+        r#"UPDATE tools SET parameter_metadata = '{"city": {"lookup": "find_cities"}}' WHERE id = ?"#,
+        forecast_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let cities_url = format!("{}/cities?prefix={{{{string:prefix}}}}", mock_server.uri());
+    let cities_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "find_cities",
+        "GET",
+        Some(&cities_url),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Weather", None)
+        .await
+        .unwrap();
+    let mut instance_ids = Vec::new();
+    for (tool_id, name, param) in [
+        (forecast_id, "forecast", "city"),
+        (cities_id, "find_cities", "prefix"),
+    ] {
+        let instance_id = sqlx::query!(
+            "INSERT INTO tool_instances (server_id, tool_id, instance_name) VALUES (?, ?, ?)",
+            server_id,
+            tool_id,
+            name
+        )
+        .execute(&pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        sqlx::query!(
+            "INSERT INTO instance_params (instance_id, param_name, source, value)
+             VALUES (?, ?, 'exposed', NULL)",
+            instance_id,
+            param
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        instance_ids.push(instance_id);
+    }
+
+    let server_service = ServerService::new(pool.clone(), SecretsManager::new().unwrap());
+    server_service
+        .publish_instance_resource(
+            server_id,
+            user_id,
+            &PublishInstanceForm {
+                instance_id: instance_ids[0],
+                name: None,
+                mime_type: None,
+                csrf_token: String::new(),
+            },
+        )
+        .await
+        .unwrap();
+    server_service
+        .save_prompt(
+            server_id,
+            user_id,
+            &PromptForm {
+                name: "triage".to_string(),
+                description: None,
+                template: "Triage {{ticket}} with status {{status}}".to_string(),
+                arguments: Some("status: Ticket status; enum: open, closed, on-hold".to_string()),
+                csrf_token: String::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+    let service = SaraMcpService::new(server_id, pool.clone()).await.unwrap();
+    let complete = |id: i64, reference: serde_json::Value, name: &str, value: &str| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "completion/complete",
+            "params": {"ref": reference, "argument": {"name": name, "value": value}}
+        })
+    };
+
+    let init = service
+        .handle_request(json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await
+        .unwrap();
+    assert!(init["result"]["capabilities"]["completions"].is_object());
+
+    let prompt_ref = json!({"type": "ref/prompt", "name": "triage"});
+    let status = service
+        .handle_request(complete(2, prompt_ref.clone(), "status", "o"))
+        .await
+        .unwrap();
+    assert_eq!(
+        status["result"]["completion"],
+        json!({"values": ["open", "on-hold"], "total": 2, "hasMore": false})
+    );
+
+    let ticket = service
+        .handle_request(complete(3, prompt_ref, "ticket", "OPS"))
+        .await
+        .unwrap();
+    assert_eq!(ticket["result"]["completion"]["values"], json!([]));
+
+    // The second completion is answered from the cache
+    let resource_ref =
+        json!({"type": "ref/resource", "uri": "saramcp://resources/forecast{?city}"});
+    for id in [4, 5] {
+        let city = service
+            .handle_request(complete(id, resource_ref.clone(), "city", "Os"))
+            .await
+            .unwrap();
+        assert_eq!(
+            city["result"]["completion"]["values"],
+            json!(["Oslo", "Osaka"])
+        );
+    }
+
+    let unknown = service
+        .handle_request(complete(
+            6,
+            json!({"type": "ref/prompt", "name": "unknown"}),
+            "status",
+            "",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(unknown["error"]["code"], -32602);
+}
@@ -0,0 +1,377 @@
+//! Tests for remote MCP servers used as tool sources
+//!
+//! A local rmcp server, guarded by a bearer token, stands in for a
+//! third-party MCP server. Its tools are imported into a SaraMCP server and
+//! called through [`InstanceExecutor`], over streamable HTTP and HTTP+SSE.
+
+use axum::{
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+    routing::get,
+    Router,
+};
+use futures::stream::{self, StreamExt};
+use rmcp::{
+    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolResult, Content, ServerCapabilities, ServerInfo},
+    schemars, tool, tool_handler, tool_router,
+    transport::{
+        sse_server::{SseServer, SseServerConfig},
+        streamable_http_server::{session::local::LocalSessionManager, StreamableHttpService},
+    },
+    ServerHandler,
+};
+use saramcp::models::{ConfigureInstanceForm, ExecutionHistory, Tool, ToolInstance};
+use saramcp::services::{
+    mcp_import, ImportOptions, InstanceExecutor, McpSource, McpTransport, SecretsManager,
+};
+use saramcp::test_utils::test_helpers;
+use serde::Deserialize;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+use wiremock::MockServer;
+
+const TOKEN: &str = "s3cret";
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct ForecastRequest {
+    /// City to forecast
+    city: String,
+    days: i64,
+    units: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct WeatherServer {
+    tool_router: ToolRouter<Self>,
+}
+
+impl WeatherServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+}
+
+#[tool_router]
+impl WeatherServer {
+    #[tool(description = "Forecast for a city")]
+    async fn get_forecast(
+        &self,
+        Parameters(request): Parameters<ForecastRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        if request.city == "Atlantis" {
+            return Ok(CallToolResult::error(vec![Content::text("Unknown city")]));
+        }
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{} days of sun in {} ({})",
+            request.days,
+            request.city,
+            request.units.as_deref().unwrap_or("metric")
+        ))]))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for WeatherServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: rmcp::model::Implementation {
+                name: "weather".to_string(),
+                version: "1.0.0".to_string(),
+                title: Some("Weather".to_string()),
+                website_url: None,
+                icons: None,
+            },
+            instructions: Some("Forecasts for any city".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+async fn require_token(request: Request, next: Next) -> Response {
+    let expected = format!("Bearer {}", TOKEN);
+    match request.headers().get(header::AUTHORIZATION) {
+        Some(value) if value.as_bytes() == expected.as_bytes() => next.run(request).await,
+        _ => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
+
+/// Serves the weather server on a loopback port, returning its endpoint URL
+async fn start_remote(transport: McpTransport) -> String {
+    test_helpers::allow_loopback_egress();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (app, endpoint) = match transport {
+        McpTransport::StreamableHttp => {
+            let service = StreamableHttpService::new(
+                || Ok(WeatherServer::new()),
+                LocalSessionManager::default().into(),
+                Default::default(),
+            );
+            (Router::new().nest_service("/mcp", service), "/mcp")
+        }
+        McpTransport::Sse => {
+            let (sse_server, router) = SseServer::new(SseServerConfig {
+                bind: addr,
+                sse_path: "/sse".to_string(),
+                post_path: "/message".to_string(),
+                ct: CancellationToken::new(),
+                sse_keep_alive: None,
+            });
+            sse_server.with_service(WeatherServer::new);
+            (router, "/sse")
+        }
+    };
+
+    let app = app.layer(middleware::from_fn(require_token));
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    format!("http://{}{}", addr, endpoint)
+}
+
+struct Setup {
+    services: test_helpers::Services,
+    user_id: i64,
+    server_id: i64,
+}
+
+/// A SaraMCP server holding the remote server's token as a secret global
+async fn setup() -> anyhow::Result<Setup> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let (server_id, _) =
+        test_helpers::create_test_server(&services.pool, user_id, "Gateway", None).await?;
+    services
+        .servers
+        .set_server_globals(
+            server_id,
+            user_id,
+            &[("weather_token".to_string(), TOKEN.to_string(), true)],
+        )
+        .await?;
+
+    Ok(Setup {
+        services,
+        user_id,
+        server_id,
+    })
+}
+
+fn source(transport: McpTransport, url: &str) -> McpSource {
+    McpSource::parse(
+        transport,
+        url,
+        r#"{"Authorization": "Bearer {{weather_token}}"}"#,
+    )
+    .unwrap()
+}
+
+/// Imports the remote tools and adds `get_forecast` as an instance named
+/// `weather`, with `days` pinned to 2
+async fn import_forecast(setup: &Setup, source: &McpSource) -> anyhow::Result<(i64, Tool)> {
+    let remote = setup
+        .services
+        .servers
+        .list_remote_mcp_tools(setup.server_id, source)
+        .await?;
+    let import = mcp_import::build(source, &remote, ImportOptions::default())?;
+
+    let toolkit_ids = import
+        .create_toolkits(
+            &setup.services.toolkits,
+            &setup.services.tools,
+            setup.user_id,
+            "Weather",
+            None,
+        )
+        .await?;
+    setup
+        .services
+        .servers
+        .add_toolkit_to_server(setup.server_id, toolkit_ids[0], setup.user_id)
+        .await?;
+
+    let tool = setup
+        .services
+        .tools
+        .list_tools(toolkit_ids[0], setup.user_id)
+        .await?[0]
+        .clone();
+    let imported = &import.tools[0];
+    let instance_id = ToolInstance::create_with_config(
+        &mut *setup.services.pool.acquire().await?,
+        setup.server_id,
+        ConfigureInstanceForm {
+            instance_name: "weather".to_string(),
+            description: Some("Two-day forecast".to_string()),
+            tool_id: tool.id,
+            param_configs: mcp_import::param_configs(
+                imported,
+                &[("days".to_string(), "2".to_string())],
+            ),
+            csrf_token: String::new(),
+        },
+    )
+    .await?;
+
+    Ok((instance_id, tool))
+}
+
+fn text(result: &CallToolResult) -> String {
+    result.content[0].as_text().unwrap().text.clone()
+}
+
+#[tokio::test]
+async fn test_import_lists_remote_tools_with_server_credentials() -> anyhow::Result<()> {
+    let setup = setup().await?;
+    let url = start_remote(McpTransport::StreamableHttp).await;
+
+    let remote = setup
+        .services
+        .servers
+        .list_remote_mcp_tools(setup.server_id, &source(McpTransport::StreamableHttp, &url))
+        .await?;
+    assert_eq!(remote.title.as_deref(), Some("Weather"));
+
+    let import = mcp_import::build(
+        &source(McpTransport::StreamableHttp, &url),
+        &remote,
+        ImportOptions::default(),
+    )?;
+    assert_eq!(
+        import.description.as_deref(),
+        Some("Forecasts for any city")
+    );
+    let tool = &import.tools[0];
+    assert_eq!(tool.key, "get_forecast");
+    assert_eq!(tool.method, "MCP");
+    assert_eq!(tool.skipped, vec!["units"]);
+    assert_eq!(
+        tool.parameters_summary(),
+        "weather_token (string, header), city (string, body), days (integer, body)"
+    );
+
+    // Without the credentials the remote server refuses the session
+    let unauthenticated = McpSource::parse(McpTransport::StreamableHttp, &url, "")?;
+    assert!(setup
+        .services
+        .servers
+        .list_remote_mcp_tools(setup.server_id, &unauthenticated)
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_instance_forwards_calls_to_remote_tool() -> anyhow::Result<()> {
+    let setup = setup().await?;
+    let url = start_remote(McpTransport::StreamableHttp).await;
+    let (instance_id, tool) =
+        import_forecast(&setup, &source(McpTransport::StreamableHttp, &url)).await?;
+
+    let executor = InstanceExecutor::new(
+        setup.services.pool.clone(),
+        setup.server_id,
+        instance_id,
+        tool.clone(),
+        SecretsManager::new()?,
+    );
+    let result = executor
+        .execute(json!({"city": "Paris"}).as_object().cloned())
+        .await?;
+    assert_ne!(result.is_error, Some(true));
+    assert_eq!(text(&result), "2 days of sun in Paris (metric)");
+
+    // Errors of the remote tool come back as tool errors
+    let executor = InstanceExecutor::new(
+        setup.services.pool.clone(),
+        setup.server_id,
+        instance_id,
+        tool,
+        SecretsManager::new()?,
+    );
+    let result = executor
+        .execute(json!({"city": "Atlantis"}).as_object().cloned())
+        .await?;
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(text(&result), "Unknown city");
+
+    let history = ExecutionHistory::list_by_instance(&setup.services.pool, instance_id, 10).await?;
+    assert_eq!(history.len(), 2);
+    assert!(history
+        .iter()
+        .all(|h| h.transport.as_deref() == Some("http")));
+    assert!(history
+        .iter()
+        .all(|h| h.request_method.as_deref() == Some("MCP")));
+    assert!(history.iter().all(|h| h.http_status_code.is_none()));
+    let failed = history.iter().find(|h| h.status != "success").unwrap();
+    assert_eq!(
+        failed.error_message.as_deref(),
+        Some("MCP tool error - Unknown city")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_instance_forwards_calls_over_sse() -> anyhow::Result<()> {
+    let setup = setup().await?;
+    let url = start_remote(McpTransport::Sse).await;
+    let (instance_id, tool) = import_forecast(&setup, &source(McpTransport::Sse, &url)).await?;
+    assert_eq!(tool.method, "MCP-SSE");
+
+    let executor = InstanceExecutor::new(
+        setup.services.pool.clone(),
+        setup.server_id,
+        instance_id,
+        tool,
+        SecretsManager::new()?,
+    );
+    let result = executor
+        .execute(json!({"city": "Oslo"}).as_object().cloned())
+        .await?;
+    assert_eq!(text(&result), "2 days of sun in Oslo (metric)");
+
+    let history = ExecutionHistory::list_by_instance(&setup.services.pool, instance_id, 10).await?;
+    assert_eq!(history[0].transport.as_deref(), Some("sse"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sse_refuses_message_endpoint_on_another_origin() -> anyhow::Result<()> {
+    let setup = setup().await?;
+    let elsewhere = MockServer::start().await;
+
+    // An SSE server naming a message endpoint on another host
+    let endpoint = format!("{}/message", elsewhere.uri());
+    let app = Router::new().route(
+        "/sse",
+        get(move || {
+            let event = Event::default().event("endpoint").data(endpoint.clone());
+            let events = stream::once(async move { Ok::<_, std::convert::Infallible>(event) })
+                .chain(stream::pending());
+            async move { Sse::new(events) }
+        }),
+    );
+    test_helpers::allow_loopback_egress();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/sse", listener.local_addr()?);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let error = setup
+        .services
+        .servers
+        .list_remote_mcp_tools(setup.server_id, &source(McpTransport::Sse, &url))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("not on the origin"), "{error}");
+    assert!(elsewhere.received_requests().await.unwrap().is_empty());
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use saramcp::mcp::registry::{McpServerRegistry, RegistryError};
+use saramcp::models::Server;
 use saramcp::test_utils::test_helpers;
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -823,3 +824,103 @@ async fn test_concurrent_access(pool: SqlitePool) {
         );
     }
 }
+
+// Test 15: Servers a configuration was applied to are reloaded
+#[sqlx::test]
+async fn test_reload_changed_configs(pool: SqlitePool) {
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .expect("Failed to create test user");
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .expect("Failed to create toolkit");
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "tool1",
+        "GET",
+        Some("https://api.example.com/data"),
+        None,
+        None,
+        30000,
+    )
+    .await
+    .expect("Failed to create tool");
+    let (server_id, server_uuid) =
+        test_helpers::create_test_server(&pool, user_id, "Test Server", None)
+            .await
+            .expect("Failed to create test server");
+
+    let registry = RwLock::new(McpServerRegistry::new(pool.clone()));
+    registry
+        .write()
+        .await
+        .load_all_servers()
+        .await
+        .expect("Failed to load servers");
+
+    let tool_count = |registry: &McpServerRegistry| {
+        let service = registry
+            .get_instance(&server_uuid)
+            .expect("Server should be registered")
+            .get_service();
+        async move {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/list",
+                "params": {}
+            });
+            let response = service
+                .handle_request(request)
+                .await
+                .expect("tools/list should succeed");
+            response["result"]["tools"]
+                .as_array()
+                .expect("tools should be an array")
+                .len()
+        }
+    };
+    assert_eq!(tool_count(&*registry.read().await).await, 0);
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+        VALUES (?, ?, ?, ?)
+        "#,
+        server_id,
+        tool_id,
+        "get_data",
+        "Get data from API"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create instance");
+
+    // Unchanged configuration versions leave the loaded tools alone
+    McpServerRegistry::reload_changed_configs(&registry)
+        .await
+        .expect("reload_changed_configs should succeed");
+    assert_eq!(tool_count(&*registry.read().await).await, 0);
+
+    // As applying a configuration from the CLI does
+    Server::bump_config_version(&pool, server_id)
+        .await
+        .expect("Failed to bump config version");
+    McpServerRegistry::reload_changed_configs(&registry)
+        .await
+        .expect("reload_changed_configs should succeed");
+    assert_eq!(tool_count(&*registry.read().await).await, 1);
+
+    // Servers created by a configuration are registered
+    let (new_id, new_uuid) = test_helpers::create_test_server(&pool, user_id, "New Server", None)
+        .await
+        .expect("Failed to create test server");
+    Server::bump_config_version(&pool, new_id)
+        .await
+        .expect("Failed to bump config version");
+    McpServerRegistry::reload_changed_configs(&registry)
+        .await
+        .expect("reload_changed_configs should succeed");
+    assert!(registry.read().await.get_instance(&new_uuid).is_some());
+}
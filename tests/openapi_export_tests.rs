@@ -0,0 +1,150 @@
+//! Tests for the OpenAPI documents generated from toolkits and servers
+
+use saramcp::models::{CreateToolRequest, CreateToolkitRequest};
+use saramcp::services::{OpenApiExport, ServerConfig, ToolCatalog};
+use saramcp::test_utils::test_helpers::{self, Services};
+use serde_json::{json, Value};
+
+const CONFIG: &str = r#"format: saramcp-server
+version: 1
+server:
+  name: Weather
+  access_level: organization
+toolkits:
+  - Forecasts
+instances:
+  - name: weather_now
+    toolkit: Forecasts
+    tool: current
+    description: Weather right now
+    params:
+      city:
+        source: exposed
+      api_key:
+        source: server
+      units:
+        source: instance
+        value: metric
+globals:
+  - name: api_key
+    secret: true
+"#;
+
+async fn setup() -> anyhow::Result<(Services, i64, i64)> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+
+    let toolkit_id = services
+        .toolkits
+        .create_toolkit(
+            user_id,
+            CreateToolkitRequest {
+                title: "Forecasts".to_string(),
+                description: Some("Weather forecasts".to_string()),
+                visibility: "private".to_string(),
+            },
+        )
+        .await?;
+    services
+        .tools
+        .create_tool(
+            toolkit_id,
+            user_id,
+            CreateToolRequest {
+                name: "current".to_string(),
+                description: Some("Current weather".to_string()),
+                method: "GET".to_string(),
+                url: Some(
+                    "https://api.example.com/now?q={{string:city}}&units={{string:units}}&key={{string:api_key}}"
+                        .to_string(),
+                ),
+                headers: None,
+                body: None,
+                timeout_ms: 30000,
+                streaming: false,
+                endpoints: None,
+                rate_limit: None,
+                parameter_metadata: Some(
+                    r#"{"city": {"description": "City name"}}"#.to_string(),
+                ),
+            },
+        )
+        .await?;
+
+    Ok((services, user_id, toolkit_id))
+}
+
+fn parameter_names(operation: &Value) -> Vec<&str> {
+    operation["parameters"]
+        .as_array()
+        .map(|params| params.iter().filter_map(|p| p["name"].as_str()).collect())
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_toolkit_document_lists_every_placeholder() -> anyhow::Result<()> {
+    let (services, user_id, toolkit_id) = setup().await?;
+    let toolkit = services.toolkits.get_toolkit(toolkit_id, user_id).await?;
+    let tools = services.tools.list_tools(toolkit_id, user_id).await?;
+
+    let document = OpenApiExport::for_toolkit(&toolkit, &tools);
+    assert_eq!(document["openapi"], "3.1.0");
+    assert_eq!(document["info"]["title"], "Forecasts");
+    assert_eq!(
+        document["servers"],
+        json!([{ "url": "https://api.example.com" }])
+    );
+
+    let operation = &document["paths"]["/now"]["get"];
+    assert_eq!(operation["operationId"], "current");
+    assert_eq!(parameter_names(operation), vec!["q", "units", "key"]);
+    assert_eq!(
+        operation["parameters"][0]["schema"]["description"],
+        "City name"
+    );
+
+    let (yaml, content_type, _) = OpenApiExport::render(&document, Some("yaml"))?;
+    assert_eq!(content_type, "application/yaml");
+    assert!(yaml.contains("operationId: current"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_document_exposes_only_caller_parameters() -> anyhow::Result<()> {
+    let (services, user_id, _) = setup().await?;
+
+    let config = ServerConfig::parse(CONFIG)?;
+    let catalog = ToolCatalog::load(&services.toolkits, &services.tools, user_id).await?;
+    let plan = config.plan(None, &catalog);
+    let server_id = plan.apply(&services.pool, user_id, None).await?;
+    services
+        .servers
+        .set_server_globals(
+            server_id,
+            user_id,
+            &[("api_key".to_string(), "s3cret".to_string(), true)],
+        )
+        .await?;
+
+    let document = OpenApiExport::for_server(
+        &services.servers,
+        &services.instances,
+        &services.tools,
+        server_id,
+        user_id,
+    )
+    .await?;
+    assert!(!document.to_string().contains("s3cret"));
+    assert_eq!(document["info"]["title"], "Weather");
+
+    let operation = &document["paths"]["/now"]["get"];
+    assert_eq!(operation["operationId"], "weather_now");
+    assert_eq!(operation["summary"], "Weather right now");
+    assert_eq!(parameter_names(operation), vec!["q", "units"]);
+    assert_eq!(operation["parameters"][1]["schema"]["const"], "metric");
+    assert_eq!(operation["security"], json!([{ "api_key": [] }]));
+    assert_eq!(
+        document["components"]["securitySchemes"]["api_key"],
+        json!({ "type": "apiKey", "in": "query", "name": "key" })
+    );
+    Ok(())
+}
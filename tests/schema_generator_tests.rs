@@ -311,6 +311,77 @@ async fn test_generate_schema_all_types() {
     assert_eq!(properties["website"]["format"], "uri");
 }
 
+#[tokio::test]
+async fn test_generate_schema_uses_parameter_metadata() {
+    let pool = test_helpers::create_test_db().await.unwrap();
+
+    let user_id = test_helpers::insert_test_user(&pool, "test@example.com", "password", true)
+        .await
+        .unwrap();
+
+    let toolkit_id = test_helpers::create_test_toolkit(&pool, user_id, "Test Toolkit")
+        .await
+        .unwrap();
+
+    let tool_id = test_helpers::create_test_tool(
+        &pool,
+        toolkit_id,
+        "list_orders",
+        "GET",
+        Some("https://api.example.com/orders?status={{status}}&limit={{integer:limit}}"),
+        None,
+        None,
+        5000,
+    )
+    .await
+    .unwrap();
+
+    sqlx::query("UPDATE tools SET parameter_metadata = ? WHERE id = ?")
+        .bind(r#"{"status": {"description": "Order status", "enum": ["open", "closed"]}}"#)
+        .bind(tool_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (server_id, _) = test_helpers::create_test_server(&pool, user_id, "Test Server", None)
+        .await
+        .unwrap();
+
+    let instance_id = sqlx::query!(
+        "INSERT INTO tool_instances (server_id, tool_id, instance_name, description)
+         VALUES (?, ?, 'test_instance', 'Test instance')",
+        server_id,
+        tool_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    sqlx::query!(
+        "INSERT INTO instance_params (instance_id, param_name, source, value)
+         VALUES (?, 'status', 'exposed', NULL),
+                (?, 'limit', 'exposed', NULL)",
+        instance_id,
+        instance_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let schema = SchemaGenerator::generate_for_instance(&pool, instance_id)
+        .await
+        .unwrap();
+
+    let properties = schema["properties"].as_object().unwrap();
+    assert_eq!(properties["status"]["description"], "Order status");
+    assert_eq!(properties["status"]["enum"], json!(["open", "closed"]));
+
+    // Undocumented parameters keep the generic description
+    assert_eq!(properties["limit"]["description"], "Parameter: limit");
+    assert!(properties["limit"].get("enum").is_none());
+}
+
 // ============================================================================
 // Error Handling Tests
 // ============================================================================
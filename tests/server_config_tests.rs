@@ -0,0 +1,289 @@
+//! Tests for exporting server configuration and applying it declaratively
+
+use saramcp::models::{CreateToolRequest, CreateToolkitRequest};
+use saramcp::services::server_config::ConfigParam;
+use saramcp::services::{ServerConfig, ToolCatalog};
+use saramcp::test_utils::test_helpers::{self, Services};
+
+const CONFIG: &str = r#"format: saramcp-server
+version: 1
+server:
+  name: Weather
+  description: Forecasts for agents
+  access_level: organization
+toolkits:
+  - Forecasts
+instances:
+  - name: weather_now
+    toolkit: Forecasts
+    tool: current
+    params:
+      api_key:
+        source: server
+      units:
+        source: instance
+        value: metric
+    concurrency_limit:
+      max_concurrent: 2
+globals:
+  - name: api_key
+    secret: true
+  - name: region
+    value: eu
+"#;
+
+async fn setup() -> anyhow::Result<(Services, i64)> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+
+    let toolkit_id = services
+        .toolkits
+        .create_toolkit(
+            user_id,
+            CreateToolkitRequest {
+                title: "Forecasts".to_string(),
+                description: None,
+                visibility: "private".to_string(),
+            },
+        )
+        .await?;
+    services
+        .tools
+        .create_tool(
+            toolkit_id,
+            user_id,
+            CreateToolRequest {
+                name: "current".to_string(),
+                description: Some("Current weather".to_string()),
+                method: "GET".to_string(),
+                url: Some(
+                    "https://api.example.com/now?q={{string:city}}&units={{string:units}}&key={{string:api_key}}"
+                        .to_string(),
+                ),
+                headers: None,
+                body: None,
+                timeout_ms: 30000,
+                streaming: false,
+                endpoints: None,
+                rate_limit: None,
+                parameter_metadata: None,
+            },
+        )
+        .await?;
+
+    Ok((services, user_id))
+}
+
+/// Plans a document against the server and applies it, returning the diff
+async fn apply(
+    services: &Services,
+    user_id: i64,
+    server_id: Option<i64>,
+    config: &ServerConfig,
+) -> anyhow::Result<(i64, Vec<String>)> {
+    let current = match server_id {
+        Some(id) => {
+            Some(ServerConfig::export(&services.servers, &services.instances, id, user_id).await?)
+        }
+        None => None,
+    };
+    let catalog = ToolCatalog::load(&services.toolkits, &services.tools, user_id).await?;
+    let plan = config.plan(current.as_ref(), &catalog);
+    assert!(plan.is_valid(), "{:?}", plan.errors);
+
+    let server_id = plan.apply(&services.pool, user_id, server_id).await?;
+    Ok((server_id, plan.diff()))
+}
+
+#[tokio::test]
+async fn test_apply_creates_server_and_is_idempotent() -> anyhow::Result<()> {
+    let (services, user_id) = setup().await?;
+    let config = ServerConfig::parse(CONFIG)?;
+
+    let (server_id, diff) = apply(&services, user_id, None, &config).await?;
+    assert_eq!(diff.len(), 5);
+
+    let server = services
+        .servers
+        .get_server(server_id, user_id)
+        .await?
+        .expect("server");
+    assert_eq!(server.name, "Weather");
+    assert_eq!(server.access_level.as_deref(), Some("organization"));
+
+    let instances = services
+        .instances
+        .list_instances_by_server(server_id)
+        .await?;
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].instance_name, "weather_now");
+    assert_eq!(instances[0].description.as_deref(), Some("Current weather"));
+    let signature = services
+        .instances
+        .get_instance_signature(instances[0].id)
+        .await?;
+    assert_eq!(signature, "weather_now(city)");
+    let exposed = services
+        .instances
+        .get_exposed_params(instances[0].id)
+        .await?;
+    assert_eq!(exposed, vec!["city"]);
+
+    // Secrets are set outside the document; once set, the export is the
+    // document again and applying it changes nothing
+    services
+        .servers
+        .set_server_globals(
+            server_id,
+            user_id,
+            &[("api_key".to_string(), "s3cret".to_string(), true)],
+        )
+        .await?;
+    let exported =
+        ServerConfig::export(&services.servers, &services.instances, server_id, user_id).await?;
+    let mut expected = config.clone();
+    expected.instances[0].params.insert(
+        "city".to_string(),
+        ConfigParam {
+            source: "exposed".to_string(),
+            value: None,
+        },
+    );
+    assert_eq!(exported.to_yaml()?, expected.to_yaml()?);
+    let (_, diff) = apply(&services, user_id, Some(server_id), &config).await?;
+    assert!(diff.is_empty(), "{:?}", diff);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_apply_reconciles_changes_and_keeps_secrets() -> anyhow::Result<()> {
+    let (services, user_id) = setup().await?;
+    let config = ServerConfig::parse(CONFIG)?;
+    let (server_id, _) = apply(&services, user_id, None, &config).await?;
+    services
+        .servers
+        .set_server_globals(
+            server_id,
+            user_id,
+            &[
+                ("api_key".to_string(), "s3cret".to_string(), true),
+                ("stale".to_string(), "x".to_string(), false),
+            ],
+        )
+        .await?;
+
+    let exported =
+        ServerConfig::export(&services.servers, &services.instances, server_id, user_id).await?;
+    let yaml = exported.to_yaml()?;
+    assert!(!yaml.contains("s3cret"));
+
+    let mut changed = config.clone();
+    changed.instances[0].params.remove("units");
+    changed.instances[0].concurrency_limit = None;
+    changed.globals[1].value = Some("us".to_string());
+
+    let (_, diff) = apply(&services, user_id, Some(server_id), &changed).await?;
+    assert_eq!(
+        diff,
+        vec![
+            "~ instance weather_now: params, concurrency_limit",
+            "~ global region",
+            "- global stale",
+        ]
+    );
+
+    let instance = &services
+        .instances
+        .list_instances_by_server(server_id)
+        .await?[0];
+    assert!(instance.concurrency_limit.is_none());
+    let signature = services
+        .instances
+        .get_instance_signature(instance.id)
+        .await?;
+    assert_eq!(signature, "weather_now(city, units)");
+
+    let globals = services
+        .servers
+        .get_server_globals_decrypted(server_id)
+        .await?;
+    let values: Vec<(&str, &str)> = globals
+        .iter()
+        .map(|g| (g.key.as_str(), g.value.as_str()))
+        .collect();
+    assert_eq!(values, vec![("api_key", "s3cret"), ("region", "us")]);
+
+    // Dropping the toolkit and its instance
+    changed.toolkits.clear();
+    changed.instances.clear();
+    let (_, diff) = apply(&services, user_id, Some(server_id), &changed).await?;
+    assert_eq!(diff, vec!["- toolkit Forecasts", "- instance weather_now"]);
+    assert!(services
+        .servers
+        .get_server_toolkits(server_id)
+        .await?
+        .is_empty());
+    assert!(services
+        .instances
+        .list_instances_by_server(server_id)
+        .await?
+        .is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_apply_failure_leaves_server_unchanged() -> anyhow::Result<()> {
+    let (services, user_id) = setup().await?;
+    let config = ServerConfig::parse(CONFIG)?;
+    let (server_id, _) = apply(&services, user_id, None, &config).await?;
+
+    let alerts_id = services
+        .toolkits
+        .create_toolkit(
+            user_id,
+            CreateToolkitRequest {
+                title: "Alerts".to_string(),
+                description: None,
+                visibility: "private".to_string(),
+            },
+        )
+        .await?;
+
+    let mut changed = config.clone();
+    changed.server.name = "Renamed".to_string();
+    changed.toolkits.push("Alerts".to_string());
+    changed.instances.clear();
+    changed.globals[1].value = Some("us".to_string());
+
+    let current =
+        ServerConfig::export(&services.servers, &services.instances, server_id, user_id).await?;
+    let catalog = ToolCatalog::load(&services.toolkits, &services.tools, user_id).await?;
+    let plan = changed.plan(Some(&current), &catalog);
+    assert!(plan.is_valid(), "{:?}", plan.errors);
+
+    // The toolkit disappears between the preview and applying, after the
+    // rename and the instance removal were already saved
+    services.toolkits.delete_toolkit(alerts_id, user_id).await?;
+    assert!(plan
+        .apply(&services.pool, user_id, Some(server_id))
+        .await
+        .is_err());
+
+    let server = services
+        .servers
+        .get_server(server_id, user_id)
+        .await?
+        .expect("server");
+    assert_eq!(server.name, "Weather");
+    assert_eq!(
+        services
+            .instances
+            .list_instances_by_server(server_id)
+            .await?
+            .len(),
+        1
+    );
+    let exported =
+        ServerConfig::export(&services.servers, &services.instances, server_id, user_id).await?;
+    assert_eq!(exported.to_yaml()?, current.to_yaml()?);
+    Ok(())
+}
@@ -0,0 +1,268 @@
+//! Tests for exporting toolkits as bundles and importing them elsewhere
+
+use saramcp::error::AppError;
+use saramcp::models::{CreateToolRequest, CreateToolkitRequest, UpdateToolkitRequest};
+use saramcp::services::{BundleTarget, ToolkitBundle};
+use saramcp::test_utils::test_helpers::{self, Services};
+
+fn tool_request(name: &str, url: &str) -> CreateToolRequest {
+    CreateToolRequest {
+        name: name.to_string(),
+        description: Some(format!("The {} tool", name)),
+        method: "GET".to_string(),
+        url: Some(url.to_string()),
+        headers: Some(r#"{"Authorization":"Bearer {{string:token}}"}"#.to_string()),
+        body: None,
+        timeout_ms: 10000,
+        streaming: false,
+        endpoints: None,
+        rate_limit: None,
+        parameter_metadata: None,
+    }
+}
+
+/// A toolkit with two tools, one of them with parameter metadata
+async fn weather_toolkit(services: &Services, user_id: i64) -> anyhow::Result<i64> {
+    let toolkit_id = services
+        .toolkits
+        .create_toolkit(
+            user_id,
+            CreateToolkitRequest {
+                title: "Weather".to_string(),
+                description: Some("Forecasts".to_string()),
+                visibility: "private".to_string(),
+            },
+        )
+        .await?;
+    services
+        .toolkits
+        .update_toolkit(
+            toolkit_id,
+            user_id,
+            UpdateToolkitRequest {
+                title: "Weather".to_string(),
+                description: Some("Forecasts".to_string()),
+                visibility: "private".to_string(),
+                endpoints: None,
+                rate_limit: Some(r#"{"requests":5}"#.to_string()),
+            },
+        )
+        .await?;
+
+    let mut current = tool_request("current", "https://api.example.com/now?q={{string:city}}");
+    current.parameter_metadata =
+        Some(r#"{"city":{"description":"City name","enum_values":["Oslo","Lima"]}}"#.to_string());
+    services
+        .tools
+        .create_tool(toolkit_id, user_id, current)
+        .await?;
+    services
+        .tools
+        .create_tool(
+            toolkit_id,
+            user_id,
+            tool_request(
+                "forecast",
+                "https://api.example.com/forecast?days={{integer:days}}",
+            ),
+        )
+        .await?;
+    Ok(toolkit_id)
+}
+
+async fn export(
+    services: &Services,
+    user_id: i64,
+    toolkit_id: i64,
+) -> anyhow::Result<ToolkitBundle> {
+    let toolkit = services.toolkits.get_toolkit(toolkit_id, user_id).await?;
+    let tools = services.tools.list_tools(toolkit_id, user_id).await?;
+    Ok(ToolkitBundle::from_toolkit(&toolkit, &tools))
+}
+
+#[tokio::test]
+async fn test_bundle_round_trip_into_new_toolkit() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let toolkit_id = weather_toolkit(&services, user_id).await?;
+
+    // Through YAML text, as a bundle kept in git would be
+    let yaml = export(&services, user_id, toolkit_id).await?.to_yaml()?;
+    assert!(!yaml.contains("user_id"));
+    let bundle = ToolkitBundle::parse(&yaml)?;
+
+    // The bundle's own title is taken by the original
+    let result = bundle
+        .import(
+            &services.toolkits,
+            &services.tools,
+            user_id,
+            &BundleTarget::NewToolkit {
+                title: String::new(),
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let summary = bundle
+        .import(
+            &services.toolkits,
+            &services.tools,
+            user_id,
+            &BundleTarget::NewToolkit {
+                title: "Weather Copy".to_string(),
+            },
+        )
+        .await?;
+    assert_ne!(summary.toolkit_id, toolkit_id);
+    assert_eq!(summary.created.len(), 2);
+
+    let copy = services
+        .toolkits
+        .get_toolkit(summary.toolkit_id, user_id)
+        .await?;
+    assert_eq!(copy.title, "Weather Copy");
+    assert_eq!(copy.description.as_deref(), Some("Forecasts"));
+    assert_eq!(copy.visibility, "private");
+    assert!(copy.rate_limit.is_some());
+
+    let mut original = services.tools.list_tools(toolkit_id, user_id).await?;
+    let mut copied = services
+        .tools
+        .list_tools(summary.toolkit_id, user_id)
+        .await?;
+    original.sort_by(|a, b| a.name.cmp(&b.name));
+    copied.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(copied.len(), original.len());
+    for (copy, tool) in copied.iter().zip(&original) {
+        assert_eq!(copy.name, tool.name);
+        assert_eq!(copy.description, tool.description);
+        assert_eq!(copy.url, tool.url);
+        assert_eq!(copy.headers, tool.headers);
+        assert_eq!(copy.timeout_ms, tool.timeout_ms);
+        assert_eq!(copy.parameter_metadata_map(), tool.parameter_metadata_map());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bundle_merge_skips_or_replaces_conflicts() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let source_id = weather_toolkit(&services, user_id).await?;
+    let mut bundle = export(&services, user_id, source_id).await?;
+    bundle.tools.sort_by(|a, b| a.name.cmp(&b.name));
+    bundle.tools[0].url = "https://v2.example.com/now?q={{string:city}}".to_string();
+    bundle.tools[0]
+        .parameters
+        .get_mut("city")
+        .expect("city metadata")
+        .description = Some("Town".to_string());
+
+    let target_id = services
+        .toolkits
+        .create_toolkit(
+            user_id,
+            CreateToolkitRequest {
+                title: "Mine".to_string(),
+                description: None,
+                visibility: "private".to_string(),
+            },
+        )
+        .await?;
+    services
+        .tools
+        .create_tool(
+            target_id,
+            user_id,
+            tool_request("current", "https://old.example.com/now"),
+        )
+        .await?;
+
+    let existing = services.tools.list_tools(target_id, user_id).await?;
+    let plan = bundle.plan(&existing);
+    assert_eq!(plan.new_tools, vec!["forecast"]);
+    assert_eq!(plan.conflicts, vec!["current"]);
+    assert!(plan.is_valid());
+
+    // Skipping keeps the existing tool as it was
+    let summary = bundle
+        .import(
+            &services.toolkits,
+            &services.tools,
+            user_id,
+            &BundleTarget::Merge {
+                toolkit_id: target_id,
+                replace: false,
+            },
+        )
+        .await?;
+    assert_eq!(summary.created, vec!["forecast"]);
+    assert_eq!(summary.skipped, vec!["current"]);
+    let current = services
+        .tools
+        .list_tools(target_id, user_id)
+        .await?
+        .into_iter()
+        .find(|t| t.name == "current")
+        .expect("current tool");
+    assert_eq!(current.url.as_deref(), Some("https://old.example.com/now"));
+
+    // Replacing updates the template and the parameter metadata
+    let summary = bundle
+        .import(
+            &services.toolkits,
+            &services.tools,
+            user_id,
+            &BundleTarget::Merge {
+                toolkit_id: target_id,
+                replace: true,
+            },
+        )
+        .await?;
+    assert_eq!(summary.replaced, vec!["current", "forecast"]);
+    assert!(summary.created.is_empty());
+
+    let tools = services.tools.list_tools(target_id, user_id).await?;
+    assert_eq!(tools.len(), 2);
+    let current = tools.iter().find(|t| t.name == "current").expect("current");
+    assert_eq!(
+        current.url.as_deref(),
+        Some("https://v2.example.com/now?q={{string:city}}")
+    );
+    assert_eq!(
+        current.parameter_metadata_map()["city"]
+            .description
+            .as_deref(),
+        Some("Town")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_bundle_creates_nothing() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let toolkit_id = weather_toolkit(&services, user_id).await?;
+    let mut bundle = export(&services, user_id, toolkit_id).await?;
+    bundle.tools.sort_by(|a, b| a.name.cmp(&b.name));
+    bundle.tools[1].body = Some("{\"days\": ".to_string());
+    bundle.tools[1].method = "POST".to_string();
+
+    let plan = bundle.plan(&[]);
+    assert!(!plan.is_valid());
+    assert!(plan.errors[0].starts_with("forecast: "));
+
+    let result = bundle
+        .import(
+            &services.toolkits,
+            &services.tools,
+            user_id,
+            &BundleTarget::NewToolkit {
+                title: "Broken".to_string(),
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let toolkits = services.toolkits.list_toolkits(user_id).await?;
+    assert_eq!(toolkits.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,292 @@
+//! Tests for creating toolkits from imported API descriptions
+
+use saramcp::error::AppError;
+use saramcp::models::ParameterMetadata;
+use saramcp::services::{curl_import, har_import, openapi_import, postman_import, ImportOptions};
+use saramcp::test_utils::test_helpers;
+
+const ORDERS_API: &str = r#"{
+  "openapi": "3.1.0",
+  "info": {"title": "Orders", "description": "Order management"},
+  "servers": [{"url": "https://orders.example.com"}],
+  "components": {
+    "securitySchemes": {"apiKey": {"type": "apiKey", "in": "header", "name": "X-API-Key"}}
+  },
+  "security": [{"apiKey": []}],
+  "paths": {
+    "/orders": {
+      "get": {
+        "operationId": "listOrders",
+        "parameters": [{
+          "name": "status", "in": "query", "required": true,
+          "schema": {"type": "string", "enum": ["open", "closed"]},
+          "description": "Only orders with this status"
+        }]
+      },
+      "post": {
+        "operationId": "createOrder",
+        "requestBody": {"content": {"application/json": {"schema": {
+          "type": "object", "required": ["sku", "quantity"],
+          "properties": {"sku": {"type": "string"}, "quantity": {"type": "integer"}}
+        }}}}
+      }
+    }
+  }
+}"#;
+
+const SHOP_COLLECTION: &str = r#"{
+  "info": {
+    "name": "Shop",
+    "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+  },
+  "variable": [{"key": "baseUrl", "value": "https://shop.example.com"}],
+  "item": [
+    {"name": "Orders", "item": [{
+      "name": "Create order",
+      "request": {
+        "method": "POST",
+        "url": "{{baseUrl}}/orders",
+        "body": {"mode": "raw", "raw": "{\"sku\": \"{{sku}}\"}", "options": {"raw": {"language": "json"}}}
+      }
+    }]},
+    {"name": "Customers", "item": [{
+      "name": "Find customer",
+      "request": {
+        "method": "POST",
+        "url": "{{baseUrl}}/customers/search",
+        "body": {"mode": "urlencoded", "urlencoded": [{"key": "email", "value": "{{email}}"}]}
+      }
+    }]}
+  ]
+}"#;
+
+#[tokio::test]
+async fn test_import_selected_operations() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let import = openapi_import::parse(ORDERS_API, ImportOptions::default())?;
+
+    let toolkit_ids = import
+        .create_toolkits(
+            &services.toolkits,
+            &services.tools,
+            user_id,
+            "My Orders",
+            Some(&["GET /orders".to_string()]),
+        )
+        .await?;
+    assert_eq!(toolkit_ids.len(), 1);
+    let toolkit_id = toolkit_ids[0];
+
+    let toolkit = services.toolkits.get_toolkit(toolkit_id, user_id).await?;
+    assert_eq!(toolkit.title, "My Orders");
+    assert_eq!(toolkit.description.as_deref(), Some("Order management"));
+    assert_eq!(toolkit.visibility, "private");
+
+    let tools = services.tools.list_tools(toolkit_id, user_id).await?;
+    assert_eq!(tools.len(), 1);
+    let tool = &tools[0];
+    assert_eq!(tool.name, "list_orders");
+    assert_eq!(
+        tool.url.as_deref(),
+        Some("https://orders.example.com/orders?status={{string:status}}")
+    );
+    assert_eq!(
+        tool.headers.as_deref(),
+        Some(r#"{"X-API-Key":"{{string:api_key}}"}"#)
+    );
+
+    let metadata = tool.parameter_metadata_map();
+    assert_eq!(
+        metadata["status"],
+        ParameterMetadata {
+            description: Some("Only orders with this status".to_string()),
+            enum_values: vec!["open".into(), "closed".into()],
+            lookup: None,
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_typed_json_body_passes_validation() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let import = openapi_import::parse(ORDERS_API, ImportOptions::default())?;
+
+    let toolkit_id = import
+        .create_toolkits(&services.toolkits, &services.tools, user_id, "Orders", None)
+        .await?[0];
+
+    let tools = services.tools.list_tools(toolkit_id, user_id).await?;
+    let create = tools.iter().find(|t| t.name == "create_order").unwrap();
+    assert_eq!(
+        create.body.as_deref(),
+        Some("{\n  \"quantity\": {{integer:quantity}},\n  \"sku\": \"{{string:sku}}\"\n}")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_without_selection_creates_nothing() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let import = openapi_import::parse(ORDERS_API, ImportOptions::default())?;
+
+    let result = import
+        .create_toolkits(
+            &services.toolkits,
+            &services.tools,
+            user_id,
+            "Orders",
+            Some(&[]),
+        )
+        .await;
+
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    assert!(services.toolkits.list_toolkits(user_id).await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_suggested_globals_saved_on_server() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let (server_id, _) =
+        test_helpers::create_test_server(&services.pool, user_id, "Server", None).await?;
+
+    services
+        .servers
+        .set_server_globals(
+            server_id,
+            user_id,
+            &[("region".to_string(), "eu".to_string(), false)],
+        )
+        .await?;
+    services
+        .servers
+        .set_server_globals(
+            server_id,
+            user_id,
+            &[("api_key".to_string(), "s3cret".to_string(), true)],
+        )
+        .await?;
+
+    // Other globals are kept, secrets are stored encrypted
+    let stored = services.servers.get_server_globals(server_id).await?;
+    assert_eq!(stored.len(), 2);
+    let api_key = stored.iter().find(|g| g.key == "api_key").unwrap();
+    assert_ne!(api_key.value, "s3cret");
+
+    let decrypted = services
+        .servers
+        .get_server_globals_decrypted(server_id)
+        .await?;
+    let api_key = decrypted.iter().find(|g| g.key == "api_key").unwrap();
+    assert_eq!(api_key.value, "s3cret");
+    assert_eq!(api_key.is_secret, Some(true));
+
+    // Another user's server is refused
+    let other =
+        test_helpers::insert_test_user(&services.pool, "other@example.com", "pw", true).await?;
+    assert!(services
+        .servers
+        .set_server_globals(server_id, other, &[])
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_postman_folders_as_toolkits() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let options = ImportOptions {
+        folders_as_toolkits: true,
+        ..Default::default()
+    };
+    let import = postman_import::parse(SHOP_COLLECTION, options)?;
+
+    let toolkit_ids = import
+        .create_toolkits(&services.toolkits, &services.tools, user_id, "Shop", None)
+        .await?;
+    assert_eq!(toolkit_ids.len(), 2);
+
+    let mut titles = Vec::new();
+    for toolkit_id in &toolkit_ids {
+        titles.push(
+            services
+                .toolkits
+                .get_toolkit(*toolkit_id, user_id)
+                .await?
+                .title,
+        );
+        let tools = services.tools.list_tools(*toolkit_id, user_id).await?;
+        assert_eq!(tools.len(), 1);
+        assert!(tools[0]
+            .url
+            .as_deref()
+            .unwrap_or("")
+            .starts_with("{{url:baseUrl}}"));
+    }
+    assert_eq!(titles, ["Shop - Orders", "Shop - Customers"]);
+
+    assert_eq!(import.globals.len(), 1);
+    assert_eq!(import.globals[0].value, "https://shop.example.com");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_curl_tool_passes_validation() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let toolkit_id = test_helpers::create_test_toolkit(&services.pool, user_id, "Shop").await?;
+
+    let import = curl_import::parse(
+        "curl -X POST https://shop.example.com/orders -H 'Content-Type: application/json' -u ada:pw --data-raw '{\"sku\": \"A1\"}'",
+    )?;
+    let tool_id = services
+        .tools
+        .create_tool(toolkit_id, user_id, import.tool.to_request())
+        .await?;
+
+    let (tool, _) = services.tools.get_tool(tool_id, user_id).await?;
+    assert_eq!(tool.name, "post_orders");
+    assert_eq!(tool.method, "POST");
+    assert_eq!(tool.body.as_deref(), Some(r#"{"sku": "A1"}"#));
+    assert!(tool.headers.unwrap_or_default().contains("Basic YWRhOnB3"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_har_endpoints_pass_validation() -> anyhow::Result<()> {
+    let (services, user_id) = test_helpers::setup_services().await?;
+    let har = serde_json::json!({"log": {"version": "1.2", "entries": [
+        {"_resourceType": "xhr", "request": {
+            "method": "GET", "url": "https://intranet.example.com/api/tickets/101?view=full",
+            "headers": [{"name": "Cookie", "value": "sid=abc"}]
+        }},
+        {"_resourceType": "xhr", "request": {
+            "method": "GET", "url": "https://intranet.example.com/api/tickets/102?view=full",
+            "headers": [{"name": "Cookie", "value": "sid=abc"}]
+        }}
+    ]}});
+    let import = har_import::parse(&har.to_string(), ImportOptions::default())?;
+
+    let toolkit_id = import
+        .create_toolkits(
+            &services.toolkits,
+            &services.tools,
+            user_id,
+            "Intranet",
+            None,
+        )
+        .await?[0];
+
+    let tools = services.tools.list_tools(toolkit_id, user_id).await?;
+    assert_eq!(tools.len(), 1);
+    assert_eq!(
+        tools[0].url.as_deref(),
+        Some("{{url:base_url}}/api/tickets/{{integer:ticket_id}}?view=full")
+    );
+    assert!(tools[0]
+        .headers
+        .as_deref()
+        .unwrap_or_default()
+        .contains("{{string:cookie}}"));
+    Ok(())
+}